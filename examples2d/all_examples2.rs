@@ -18,6 +18,7 @@ mod broad_phase_filter2;
 mod capsules2;
 mod ccd2;
 mod ccd_trigger2;
+mod character_controller2;
 mod collision_groups2;
 mod compound2;
 mod constraints2;
@@ -80,6 +81,7 @@ fn main() {
         ("Capsules", capsules2::init_world),
         ("CCD", ccd2::init_world),
         ("CCD Trigger", ccd_trigger2::init_world),
+        ("Character Controller", character_controller2::init_world),
         ("Collision Groups", collision_groups2::init_world),
         ("Compound Shapes", cross2::init_world),
         //        ("Compound Shapes", compound2::init_world),
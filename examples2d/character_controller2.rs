@@ -0,0 +1,134 @@
+extern crate nalgebra as na;
+
+use na::{Point2, RealField, Vector2};
+use ncollide2d::shape::{Capsule, Cuboid, ShapeHandle};
+use nphysics2d::control::KinematicCharacterController;
+use nphysics2d::force_generator::DefaultForceGeneratorSet;
+use nphysics2d::joint::DefaultJointConstraintSet;
+use nphysics2d::object::{
+    BodyPartHandle, BodyStatus, ColliderDesc, DefaultBodySet, DefaultColliderSet, Ground,
+    RigidBodyDesc,
+};
+use nphysics2d::world::{DefaultGeometricalWorld, DefaultMechanicalWorld};
+use nphysics_testbed2d::Testbed;
+
+/*
+ * NOTE: The `r` macro is only here to convert from f64 to the `N` scalar type.
+ * This simplifies experimentation with various scalar types (f32, fixed-point numbers, etc.)
+ */
+pub fn init_world<N: RealField>(testbed: &mut Testbed<N>) {
+    /*
+     * World
+     */
+    let mechanical_world = DefaultMechanicalWorld::new(Vector2::new(r!(0.0), r!(-9.81)));
+    let geometrical_world = DefaultGeometricalWorld::new();
+    let mut bodies = DefaultBodySet::new();
+    let mut colliders = DefaultColliderSet::new();
+    let joint_constraints = DefaultJointConstraintSet::new();
+    let force_generators = DefaultForceGeneratorSet::new();
+
+    /*
+     * Ground
+     */
+    let ground_thickness = r!(0.2);
+    let ground_shape = ShapeHandle::new(Cuboid::new(Vector2::new(r!(15.0), ground_thickness)));
+
+    let ground_handle = bodies.insert(Ground::new());
+    let co = ColliderDesc::new(ground_shape)
+        .translation(Vector2::y() * -ground_thickness)
+        .build(BodyPartHandle(ground_handle, 0));
+    colliders.insert(co);
+
+    /*
+     * Stairs the character can climb.
+     */
+    let step_height = r!(0.15);
+    let step_shape = ShapeHandle::new(Cuboid::new(Vector2::new(r!(0.25), r!(0.5))));
+
+    for i in 0..8 {
+        let height = step_height * r!(i as f64 + 1.0);
+        let co = ColliderDesc::new(step_shape.clone())
+            .translation(Vector2::new(
+                r!(2.0) + r!(0.5) * r!(i as f64),
+                height - r!(0.5),
+            ))
+            .build(BodyPartHandle(ground_handle, 0));
+        colliders.insert(co);
+    }
+
+    /*
+     * A gentle slope the character can walk on, and a steep wall it cannot climb.
+     */
+    let slope_shape = ShapeHandle::new(Cuboid::new(Vector2::new(r!(3.0), r!(0.1))));
+    let co = ColliderDesc::new(slope_shape.clone())
+        .translation(Vector2::new(r!(-5.0), r!(0.5)))
+        .rotation(r!(-0.3))
+        .build(BodyPartHandle(ground_handle, 0));
+    colliders.insert(co);
+
+    let co = ColliderDesc::new(slope_shape)
+        .translation(Vector2::new(r!(-9.0), r!(1.0)))
+        .rotation(r!(-1.2))
+        .build(BodyPartHandle(ground_handle, 0));
+    colliders.insert(co);
+
+    /*
+     * The character.
+     */
+    let character_body = RigidBodyDesc::new()
+        .translation(Vector2::new(r!(0.0), r!(1.0)))
+        .status(BodyStatus::Kinematic)
+        .build();
+    let character_handle = bodies.insert(character_body);
+    let character_shape = ShapeHandle::new(Capsule::new(r!(0.4), r!(0.3)));
+    let character_collider =
+        ColliderDesc::new(character_shape).build(BodyPartHandle(character_handle, 0));
+    let character_collider_handle = colliders.insert(character_collider);
+
+    /*
+     * Make the character walk back and forth through the stairs and the slopes.
+     */
+    let controller = KinematicCharacterController::new();
+    let mut vertical_speed = r!(0.0);
+
+    testbed.add_callback(
+        move |mechanical_world, geometrical_world, bodies, colliders, _, time| {
+            let dt = mechanical_world.integration_parameters.dt();
+            let direction = Vector2::x() * (time * r!(0.3)).cos();
+
+            vertical_speed += mechanical_world.gravity.y * dt;
+            let desired = direction * r!(4.0) * dt + Vector2::y() * vertical_speed * dt;
+
+            if let Some(movement) = controller.move_body(
+                geometrical_world,
+                bodies,
+                colliders,
+                character_collider_handle,
+                &desired,
+            ) {
+                if movement.grounded {
+                    vertical_speed = r!(0.0);
+                }
+            }
+        },
+    );
+
+    /*
+     * Set up the testbed.
+     */
+    testbed.set_ground_handle(Some(ground_handle));
+    testbed.set_world(
+        mechanical_world,
+        geometrical_world,
+        bodies,
+        colliders,
+        joint_constraints,
+        force_generators,
+    );
+    testbed.look_at(Point2::new(0.0, 2.0), 40.0);
+}
+
+fn main() {
+    let testbed = Testbed::<f32>::from_builders(0, vec![("Character controller", init_world)]);
+    testbed.run()
+}
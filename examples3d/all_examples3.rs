@@ -20,6 +20,7 @@ mod boxes3;
 mod broad_phase_filter3;
 mod capsules3;
 mod ccd3;
+mod character_controller3;
 mod collision_groups3;
 mod compound3;
 mod constraints3;
@@ -82,6 +83,7 @@ fn main() {
         ("Broad-phase filter", broad_phase_filter3::init_world),
         ("Capsules", capsules3::init_world),
         ("CCD", ccd3::init_world),
+        ("Character Controller", character_controller3::init_world),
         ("Collision Groups", collision_groups3::init_world),
         ("Compound Shapes", cross3::init_world),
         //        ("Compound Shapes", compound3::init_world::<Real),
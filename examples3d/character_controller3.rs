@@ -0,0 +1,153 @@
+extern crate nalgebra as na;
+
+use kiss3d::event::Key;
+use na::{Point3, RealField, Vector3};
+use ncollide3d::shape::{Capsule, Cuboid, ShapeHandle};
+use nphysics3d::control::KinematicCharacterController;
+use nphysics3d::force_generator::DefaultForceGeneratorSet;
+use nphysics3d::joint::DefaultJointConstraintSet;
+use nphysics3d::object::{
+    BodyPartHandle, BodyStatus, ColliderDesc, DefaultBodySet, DefaultColliderSet, Ground,
+    RigidBodyDesc,
+};
+use nphysics3d::world::{DefaultGeometricalWorld, DefaultMechanicalWorld};
+use nphysics_testbed3d::Testbed;
+
+/*
+ * NOTE: The `r` macro is only here to convert from f64 to the `N` scalar type.
+ * This simplifies experimentation with various scalar types (f32, fixed-point numbers, etc.)
+ */
+pub fn init_world<N: RealField>(testbed: &mut Testbed<N>) {
+    /*
+     * World
+     */
+    let mechanical_world = DefaultMechanicalWorld::new(Vector3::new(r!(0.0), r!(-9.81), r!(0.0)));
+    let geometrical_world = DefaultGeometricalWorld::new();
+    let mut bodies = DefaultBodySet::new();
+    let mut colliders = DefaultColliderSet::new();
+    let joint_constraints = DefaultJointConstraintSet::new();
+    let force_generators = DefaultForceGeneratorSet::new();
+
+    /*
+     * Ground.
+     */
+    let ground_thickness = r!(0.2);
+    let ground_shape = ShapeHandle::new(Cuboid::new(Vector3::new(
+        r!(15.0),
+        ground_thickness,
+        r!(15.0),
+    )));
+
+    let ground_handle = bodies.insert(Ground::new());
+    let co = ColliderDesc::new(ground_shape)
+        .translation(Vector3::y() * -ground_thickness)
+        .build(BodyPartHandle(ground_handle, 0));
+    colliders.insert(co);
+
+    /*
+     * Stairs the character can climb.
+     */
+    let step_height = r!(0.15);
+    let step_shape = ShapeHandle::new(Cuboid::new(Vector3::new(r!(0.25), r!(0.5), r!(1.0))));
+
+    for i in 0..8 {
+        let height = step_height * r!(i as f64 + 1.0);
+        let co = ColliderDesc::new(step_shape.clone())
+            .translation(Vector3::new(
+                r!(2.0) + r!(0.5) * r!(i as f64),
+                height - r!(0.5),
+                r!(0.0),
+            ))
+            .build(BodyPartHandle(ground_handle, 0));
+        colliders.insert(co);
+    }
+
+    /*
+     * A gentle slope the character can walk on, and a steep one it slides along.
+     */
+    let slope_shape = ShapeHandle::new(Cuboid::new(Vector3::new(r!(3.0), r!(0.1), r!(1.0))));
+    let slopes = [(r!(4.0), r!(0.4)), (r!(-4.0), r!(1.1))];
+
+    for (z, angle) in slopes.iter() {
+        let co = ColliderDesc::new(slope_shape.clone())
+            .translation(Vector3::new(r!(5.0), r!(0.0), *z))
+            .rotation(Vector3::z() * *angle)
+            .build(BodyPartHandle(ground_handle, 0));
+        colliders.insert(co);
+    }
+
+    /*
+     * The character.
+     */
+    let character_body = RigidBodyDesc::new()
+        .translation(Vector3::new(r!(-3.0), r!(1.0), r!(0.0)))
+        .status(BodyStatus::Kinematic)
+        .build();
+    let character_handle = bodies.insert(character_body);
+    let character_shape = ShapeHandle::new(Capsule::new(r!(0.4), r!(0.3)));
+    let character_collider =
+        ColliderDesc::new(character_shape).build(BodyPartHandle(character_handle, 0));
+    let character_collider_handle = colliders.insert(character_collider);
+
+    /*
+     * Move the character with the WASD keys: W and S walk toward the stairs and away from them,
+     * A and D walk toward the steep slope and the gentle one.
+     */
+    let controller = KinematicCharacterController::new();
+    let mut vertical_speed = r!(0.0);
+
+    testbed.add_callback_with_keys(
+        move |mechanical_world, geometrical_world, bodies, colliders, keys, _| {
+            let dt = mechanical_world.integration_parameters.dt();
+            let mut direction = Vector3::<N>::zeros();
+
+            for key in keys {
+                match key {
+                    Key::W => direction += Vector3::x(),
+                    Key::S => direction -= Vector3::x(),
+                    Key::A => direction -= Vector3::z(),
+                    Key::D => direction += Vector3::z(),
+                    _ => {}
+                }
+            }
+
+            if direction.norm() > r!(0.0) {
+                direction = direction.normalize();
+            }
+
+            vertical_speed += mechanical_world.gravity.y * dt;
+            let desired = direction * r!(3.0) * dt + Vector3::y() * vertical_speed * dt;
+
+            if let Some(movement) = controller.move_body(
+                geometrical_world,
+                bodies,
+                colliders,
+                character_collider_handle,
+                &desired,
+            ) {
+                if movement.grounded {
+                    vertical_speed = r!(0.0);
+                }
+            }
+        },
+    );
+
+    /*
+     * Run the simulation.
+     */
+    testbed.set_ground_handle(Some(ground_handle));
+    testbed.set_world(
+        mechanical_world,
+        geometrical_world,
+        bodies,
+        colliders,
+        joint_constraints,
+        force_generators,
+    );
+    testbed.look_at(Point3::new(-10.0, 8.0, -10.0), Point3::new(0.0, 0.0, 0.0));
+}
+
+fn main() {
+    let testbed = Testbed::<f32>::from_builders(0, vec![("Character controller", init_world)]);
+    testbed.run()
+}
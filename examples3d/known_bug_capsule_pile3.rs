@@ -0,0 +1,116 @@
+/*!
+ * # Expected behaviour:
+ * The capsules lying on their side fall on the ground and on each other, then come to rest and
+ * fall asleep without drifting.
+ *
+ * # Symptoms:
+ * Capsules resting on the ground or on another capsule jitter and never settle, unlike cuboids.
+ *
+ * # Cause:
+ * The contact manifold generators of ncollide produce a single contact point between a capsule
+ * and a flat surface or another parallel capsule. This point moves from one end of the capsule to
+ * the other from one step to the next instead of yielding a stable two-points manifold.
+ *
+ * # Solution:
+ * Must be fixed in the capsule-capsule and capsule-convex manifold generators of ncollide.
+ */
+
+extern crate nalgebra as na;
+
+use na::{Point3, RealField, UnitQuaternion, Vector3};
+use ncollide3d::shape::{Capsule, Cuboid, ShapeHandle};
+use nphysics3d::force_generator::DefaultForceGeneratorSet;
+use nphysics3d::joint::DefaultJointConstraintSet;
+use nphysics3d::object::{
+    BodyPartHandle, ColliderDesc, DefaultBodySet, DefaultColliderSet, Ground, RigidBodyDesc,
+};
+use nphysics3d::world::{DefaultGeometricalWorld, DefaultMechanicalWorld};
+use nphysics_testbed3d::Testbed;
+
+/*
+ * NOTE: The `r` macro is only here to convert from f64 to the `N` scalar type.
+ * This simplifies experimentation with various scalar types (f32, fixed-point numbers, etc.)
+ */
+fn init_world<N: RealField>(testbed: &mut Testbed<N>) {
+    /*
+     * World
+     */
+    let mechanical_world = DefaultMechanicalWorld::new(Vector3::new(r!(0.0), r!(-9.81), r!(0.0)));
+    let geometrical_world = DefaultGeometricalWorld::new();
+    let mut bodies = DefaultBodySet::new();
+    let mut colliders = DefaultColliderSet::new();
+    let joint_constraints = DefaultJointConstraintSet::new();
+    let force_generators = DefaultForceGeneratorSet::new();
+
+    /*
+     * Ground.
+     */
+    let ground_thickness = r!(0.2);
+    let ground_shape = ShapeHandle::new(Cuboid::new(Vector3::new(
+        r!(5.0),
+        ground_thickness,
+        r!(5.0),
+    )));
+
+    let ground_handle = bodies.insert(Ground::new());
+    let co = ColliderDesc::new(ground_shape)
+        .translation(Vector3::y() * -ground_thickness)
+        .build(BodyPartHandle(ground_handle, 0));
+    colliders.insert(co);
+
+    /*
+     * Create the capsules, lying on their side, each layer rotated by 90 degrees.
+     */
+    let num = 4;
+    let rad = r!(0.2);
+    let half_height = r!(0.6);
+    let capsule = ShapeHandle::new(Capsule::new(half_height, rad));
+
+    let shift = (rad + ColliderDesc::<N>::default_margin()) * r!(2.0);
+    let center = shift * r!(num as f64 - 1.0) / r!(2.0);
+
+    for j in 0usize..6 {
+        let angle = if j % 2 == 0 { r!(0.0) } else { N::frac_pi_2() };
+        let layer_rotation = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), angle);
+        let lying = UnitQuaternion::from_axis_angle(&Vector3::x_axis(), N::frac_pi_2());
+        let rotation = layer_rotation * lying;
+
+        for i in 0usize..num {
+            let offset =
+                layer_rotation * Vector3::new(r!(i as f64) * shift - center, r!(0.0), r!(0.0));
+            let y = rad + r!(j as f64) * shift;
+
+            // Build the rigid body.
+            let rb = RigidBodyDesc::new()
+                .translation(offset + Vector3::y() * y)
+                .rotation(rotation.scaled_axis())
+                .build();
+            let rb_handle = bodies.insert(rb);
+
+            // Build the collider.
+            let co = ColliderDesc::new(capsule.clone())
+                .density(r!(1.0))
+                .build(BodyPartHandle(rb_handle, 0));
+            colliders.insert(co);
+        }
+    }
+
+    /*
+     * Set up the testbed.
+     */
+    testbed.set_ground_handle(Some(ground_handle));
+    testbed.set_world(
+        mechanical_world,
+        geometrical_world,
+        bodies,
+        colliders,
+        joint_constraints,
+        force_generators,
+    );
+    testbed.look_at(Point3::new(-5.0, 3.0, -5.0), Point3::new(0.0, 1.0, 0.0));
+}
+
+fn main() {
+    let testbed = Testbed::<f32>::from_builders(0, vec![("Capsule pile", init_world)]);
+    testbed.run()
+}
@@ -2,10 +2,11 @@ use std::mem;
 use std::ops::{Add, AddAssign, Mul, Neg};
 
 use crate::algebra::{Force2, Velocity2};
-use na::{self, Isometry2, Matrix1, Matrix3, RealField, Vector3};
+use na::{self, Isometry2, Matrix1, Matrix3, RealField, Vector2, Vector3};
 
 /// The inertia of a rigid body grouping both its mass and its angular inertia.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct Inertia2<N: RealField> {
     /// The linear part (mass) of the inertia.
     pub linear: N,
@@ -64,6 +65,16 @@ impl<N: RealField> Inertia2<N> {
         *self
     }
 
+    /// Compute the inertia wrt. the point located at `shift` from the center of mass.
+    ///
+    /// This applies the parallel axis theorem to the angular inertia.
+    pub fn shifted(&self, shift: &Vector2<N>) -> Self {
+        Inertia2::new(
+            self.linear,
+            self.angular + shift.norm_squared() * self.linear,
+        )
+    }
+
     /// Inverts this inetia matrix.
     ///
     /// Sets the angular part to zero if it is not invertible.
@@ -1,10 +1,11 @@
 use std::ops::{Add, AddAssign, Mul, Neg};
 
 use crate::algebra::{Force3, Velocity3};
-use na::{self, Isometry3, Matrix3, Matrix6, RealField, U3};
+use na::{self, Isometry3, Matrix3, Matrix6, RealField, Vector3, U3};
 
 /// The inertia of a rigid body grouping both its mass and its angular inertia.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct Inertia3<N: RealField> {
     /// The linear part (mass) of the inertia.
     pub linear: N,
@@ -70,6 +71,15 @@ impl<N: RealField> Inertia3<N> {
         Inertia3::new(self.linear, rot * self.angular * rot.inverse())
     }
 
+    /// Compute the inertia wrt. the point located at `shift` from the center of mass.
+    ///
+    /// This applies the parallel axis theorem to the angular inertia.
+    pub fn shifted(&self, shift: &Vector3<N>) -> Self {
+        let diag = Matrix3::from_diagonal_element(shift.norm_squared());
+        let angular = self.angular + (diag - shift * shift.transpose()) * self.linear;
+        Inertia3::new(self.linear, angular)
+    }
+
     /// Inverts this inertia matrix.
     ///
     /// Sets the angular part to zero if it is not invertible.
@@ -0,0 +1,329 @@
+//! Regression tests comparing simple simulations with their analytic solutions.
+//!
+//! The `Scenario` fixture they are built upon is shared with the tests of the other modules.
+
+use na::RealField;
+use ncollide::shape::{Ball, Capsule, Cuboid, ShapeHandle};
+
+use crate::force_generator::DefaultForceGeneratorSet;
+use crate::joint::{BallConstraint, DefaultJointConstraintSet};
+use crate::material::{BasicMaterial, MaterialHandle};
+use crate::math::{Point, Vector, Velocity, ANGULAR_DIM};
+use crate::object::{
+    BodyPart, BodyPartHandle, BodyStatus, ColliderDesc, DefaultBodyHandle, DefaultBodySet,
+    DefaultColliderSet, Ground, RigidBody, RigidBodyDesc,
+};
+use crate::world::{DefaultGeometricalWorld, DefaultMechanicalWorld};
+
+pub(crate) const GRAVITY: f64 = 9.81;
+
+/// A world with a ground body, on which canonical scenarios are set up.
+pub(crate) struct Scenario {
+    pub(crate) mechanical_world: DefaultMechanicalWorld<f64>,
+    pub(crate) geometrical_world: DefaultGeometricalWorld<f64>,
+    pub(crate) bodies: DefaultBodySet<f64>,
+    pub(crate) colliders: DefaultColliderSet<f64>,
+    pub(crate) joint_constraints: DefaultJointConstraintSet<f64>,
+    pub(crate) force_generators: DefaultForceGeneratorSet<f64>,
+    pub(crate) ground: DefaultBodyHandle,
+}
+
+impl Scenario {
+    pub(crate) fn new(dt: f64) -> Self {
+        let mut mechanical_world = DefaultMechanicalWorld::new(-Vector::y() * GRAVITY);
+        mechanical_world.integration_parameters.set_dt(dt);
+        let mut bodies = DefaultBodySet::new();
+        let ground = bodies.insert(Ground::new());
+
+        Scenario {
+            mechanical_world,
+            geometrical_world: DefaultGeometricalWorld::new(),
+            bodies,
+            colliders: DefaultColliderSet::new(),
+            joint_constraints: DefaultJointConstraintSet::new(),
+            force_generators: DefaultForceGeneratorSet::new(),
+            ground,
+        }
+    }
+
+    /// Adds to the ground a large flat floor with its top at `y = 0`.
+    pub(crate) fn add_floor(&mut self, restitution: f64, friction: f64) {
+        self.add_floor_to(self.ground, restitution, friction)
+    }
+
+    /// Adds to a new static rigid body a large flat floor with its top at `y = 0`.
+    pub(crate) fn add_static_floor(&mut self, restitution: f64, friction: f64) {
+        let desc = RigidBodyDesc::new().status(BodyStatus::Static);
+        let handle = self.bodies.insert(desc.build());
+        self.add_floor_to(handle, restitution, friction)
+    }
+
+    /// Adds to a new heavy dynamic body, unaffected by gravity, a large flat floor with its top at `y = 0`.
+    pub(crate) fn add_dynamic_floor(
+        &mut self,
+        restitution: f64,
+        friction: f64,
+    ) -> DefaultBodyHandle {
+        let desc = RigidBodyDesc::new().gravity_enabled(false).mass(1.0e6);
+        let handle = self.bodies.insert(desc.build());
+        self.add_floor_to(handle, restitution, friction);
+        handle
+    }
+
+    pub(crate) fn add_floor_to(
+        &mut self,
+        body: DefaultBodyHandle,
+        restitution: f64,
+        friction: f64,
+    ) {
+        let mut half_extents = Vector::repeat(50.0);
+        half_extents.y = 1.0;
+        let co = ColliderDesc::new(ShapeHandle::new(Cuboid::new(half_extents)))
+            .translation(-Vector::y())
+            .material(MaterialHandle::new(BasicMaterial::new(
+                restitution,
+                friction,
+            )))
+            .build(BodyPartHandle(body, 0));
+        let _ = self.colliders.insert(co);
+    }
+
+    /// Adds a dynamic body with one collider of unit density.
+    pub(crate) fn add_body(
+        &mut self,
+        desc: &RigidBodyDesc<f64>,
+        shape: ShapeHandle<f64>,
+        restitution: f64,
+        friction: f64,
+    ) -> DefaultBodyHandle {
+        let handle = self.bodies.insert(desc.build());
+        let co = ColliderDesc::new(shape)
+            .density(1.0)
+            .material(MaterialHandle::new(BasicMaterial::new(
+                restitution,
+                friction,
+            )))
+            .build(BodyPartHandle(handle, 0));
+        let _ = self.colliders.insert(co);
+        handle
+    }
+
+    pub(crate) fn step(&mut self) {
+        self.mechanical_world.step(
+            &mut self.geometrical_world,
+            &mut self.bodies,
+            &mut self.colliders,
+            &mut self.joint_constraints,
+            &mut self.force_generators,
+        )
+    }
+
+    pub(crate) fn time(&self) -> f64 {
+        self.mechanical_world.integration_parameters.t
+    }
+
+    pub(crate) fn rigid_body(&self, handle: DefaultBodyHandle) -> &RigidBody<f64> {
+        self.bodies.rigid_body(handle).unwrap()
+    }
+
+    /// The sum of the kinetic energy and gravitational potential energy of the given body.
+    pub(crate) fn mechanical_energy(&self, handle: DefaultBodyHandle) -> f64 {
+        let rb = self.rigid_body(handle);
+        let height = rb.position().translation.vector.y;
+        rb.kinetic_energy() + rb.augmented_mass().mass() * GRAVITY * height
+    }
+}
+
+pub(crate) fn assert_relative_error(actual: f64, expected: f64, tolerance: f64) {
+    assert!(
+        ((actual - expected) / expected).abs() <= tolerance,
+        "Expected {} within a relative tolerance of {}, found {}.",
+        expected,
+        tolerance,
+        actual
+    );
+}
+
+/// The abscissas at which the sampled `(abscissa, value)` curve crosses zero going down, linearly interpolated.
+pub(crate) fn downward_zero_crossings(samples: &[(f64, f64)]) -> Vec<f64> {
+    samples
+        .windows(2)
+        .filter(|w| w[0].1 > 0.0 && w[1].1 <= 0.0)
+        .map(|w| {
+            let ((t0, s0), (t1, s1)) = (w[0], w[1]);
+            t0 + (t1 - t0) * s0 / (s0 - s1)
+        })
+        .collect()
+}
+
+#[test]
+fn frictionless_bounce_conserves_energy() {
+    let mut scenario = Scenario::new(1.0 / 240.0);
+    scenario.add_floor(1.0, 0.0);
+
+    let radius = 0.5;
+    let desc = RigidBodyDesc::new().translation(Vector::y() * 3.0);
+    let ball = scenario.add_body(&desc, ShapeHandle::new(Ball::new(radius)), 1.0, 0.0);
+    let initial_energy = scenario.mechanical_energy(ball);
+
+    let mut bounced = false;
+    let mut max_height_after_bounce = 0.0;
+
+    while scenario.time() < 3.0 {
+        scenario.step();
+        let rb = scenario.rigid_body(ball);
+
+        if rb.velocity().linear.y > 0.0 {
+            bounced = true;
+        }
+
+        if bounced {
+            max_height_after_bounce = rb
+                .position()
+                .translation
+                .vector
+                .y
+                .max(max_height_after_bounce);
+        }
+    }
+
+    assert!(bounced, "The ball did not bounce.");
+    assert_relative_error(max_height_after_bounce - radius, 3.0 - radius, 0.1);
+
+    let final_energy = scenario.mechanical_energy(ball);
+    assert_relative_error(final_energy, initial_energy, 0.1);
+}
+
+#[test]
+fn pendulum_period() {
+    let mut scenario = Scenario::new(1.0 / 240.0);
+
+    let length = 1.0;
+    let angle: f64 = 0.1;
+    let radius = 0.05;
+    let position = Vector::x() * (length * angle.sin()) - Vector::y() * (length * angle.cos());
+    let desc = RigidBodyDesc::new().translation(position);
+    let bob = scenario.add_body(&desc, ShapeHandle::new(Ball::new(radius)), 0.0, 0.0);
+
+    let joint = BallConstraint::new(
+        BodyPartHandle(scenario.ground, 0),
+        BodyPartHandle(bob, 0),
+        Point::origin(),
+        Point::from(-position),
+    );
+    let _ = scenario.joint_constraints.insert(joint);
+
+    // Physical pendulum: the bob is a small ball, not a point mass.
+    let inertia = scenario.rigid_body(bob).local_inertia();
+    let gyration2 = inertia.angular_matrix()[(ANGULAR_DIM - 1, ANGULAR_DIM - 1)] / inertia.mass();
+    let expected_period =
+        f64::two_pi() * ((length * length + gyration2) / (GRAVITY * length)).sqrt();
+
+    let mut samples = Vec::new();
+
+    while scenario.time() < expected_period * 2.5 {
+        scenario.step();
+        let x = scenario.rigid_body(bob).position().translation.vector.x;
+        samples.push((scenario.time(), x));
+    }
+
+    let crossings = downward_zero_crossings(&samples);
+    assert!(crossings.len() >= 2, "The pendulum did not oscillate.");
+    assert_relative_error(crossings[1] - crossings[0], expected_period, 0.02);
+}
+
+#[test]
+fn projectile_range() {
+    let mut scenario = Scenario::new(1.0 / 240.0);
+
+    let speed = 10.0;
+    let angle = f64::frac_pi_4();
+    let mut velocity = Velocity::zero();
+    velocity.linear = (Vector::x() * angle.cos() + Vector::y() * angle.sin()) * speed;
+    let desc = RigidBodyDesc::new().velocity(velocity);
+    let projectile = scenario.add_body(&desc, ShapeHandle::new(Ball::new(0.1)), 0.0, 0.0);
+
+    let mut samples = Vec::new();
+
+    while scenario.time() < 3.0 {
+        scenario.step();
+        let pos = scenario
+            .rigid_body(projectile)
+            .position()
+            .translation
+            .vector;
+        samples.push((pos.x, pos.y));
+    }
+
+    // The horizontal distance travelled when the projectile comes back to its initial height.
+    let range = downward_zero_crossings(&samples)[0];
+    let expected_range = speed * speed * (2.0 * angle).sin() / GRAVITY;
+    assert_relative_error(range, expected_range, 0.01);
+}
+
+#[test]
+fn box_sliding_distance() {
+    let mut scenario = Scenario::new(1.0 / 240.0);
+    let friction = 0.5;
+    scenario.add_floor(0.0, friction);
+
+    let speed = 5.0;
+    let mut velocity = Velocity::zero();
+    velocity.linear = Vector::x() * speed;
+    let desc = RigidBodyDesc::new()
+        .translation(Vector::y() * 0.5)
+        .velocity(velocity);
+    let cuboid = ShapeHandle::new(Cuboid::new(Vector::repeat(0.5)));
+    let block = scenario.add_body(&desc, cuboid, 0.0, friction);
+
+    while scenario.time() < 2.0 {
+        scenario.step();
+    }
+
+    let rb = scenario.rigid_body(block);
+    let distance = rb.position().translation.vector.x;
+    let expected_distance = speed * speed / (2.0 * friction * GRAVITY);
+    assert!(rb.kinetic_energy() < 1.0e-3, "The box did not stop.");
+    assert_relative_error(distance, expected_distance, 0.1);
+}
+
+// FIXME: the capsule-cuboid manifold generator of ncollide yields a single contact point that
+// moves from one end of the capsule to the other instead of a stable two-points manifold. Enable
+// this test once this is fixed upstream.
+#[test]
+#[ignore]
+fn capsule_lying_on_a_floor_comes_to_rest() {
+    let mut scenario = Scenario::new(1.0 / 60.0);
+    scenario.add_floor(0.0, 0.5);
+
+    // A capsule lying on its side, dropped from a small height.
+    let radius = 0.25;
+    #[cfg(feature = "dim2")]
+    let desc = RigidBodyDesc::new()
+        .translation(Vector::y() * 1.0)
+        .rotation(f64::frac_pi_2());
+    #[cfg(feature = "dim3")]
+    let desc = RigidBodyDesc::new()
+        .translation(Vector::y() * 1.0)
+        .rotation(Vector::z() * f64::frac_pi_2());
+    let capsule = ShapeHandle::new(Capsule::new(1.0, radius));
+    let handle = scenario.add_body(&desc, capsule, 0.0, 0.5);
+
+    while scenario.time() < 2.0 {
+        scenario.step();
+    }
+
+    let rest = *scenario.rigid_body(handle).position();
+    assert_relative_error(rest.translation.vector.y, radius, 0.05);
+
+    while scenario.time() < 4.0 {
+        scenario.step();
+        let rb = scenario.rigid_body(handle);
+        let velocity = rb.velocity();
+        assert!(velocity.linear.norm() < 1.0e-3 && velocity.angular_vector().norm() < 1.0e-3);
+    }
+
+    let pos = scenario.rigid_body(handle).position();
+    let drift = (pos.translation.vector - rest.translation.vector).norm();
+    assert!(drift < 1.0e-3, "The capsule drifted by {}.", drift);
+}
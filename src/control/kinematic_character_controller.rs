@@ -0,0 +1,271 @@
+use na::{RealField, Unit};
+
+use crate::math::{Isometry, Vector};
+use crate::object::{BodyHandle, BodySet, ColliderHandle, ColliderSet, RigidBody};
+use crate::world::GeometricalWorld;
+
+/// The result of the motion of a character by a `KinematicCharacterController`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CharacterMovement<N: RealField> {
+    /// The translation actually applied to the character.
+    pub translation: Vector<N>,
+    /// Whether the character is standing on a surface it can walk on after the motion.
+    pub grounded: bool,
+}
+
+/// A controller moving a character made of a single collider (typically a capsule) through the world.
+///
+/// The controller does not apply any force: it computes how far the character can go along a
+/// desired translation using shape-casts, sliding along walls, climbing small ledges, and
+/// sticking to slopes. The character is expected to be attached to a kinematic rigid body which
+/// is moved directly by `move_body`.
+#[derive(Clone, Debug)]
+pub struct KinematicCharacterController<N: RealField> {
+    /// The direction pointing up from the character's point of view (default: the `y` axis).
+    pub up: Unit<Vector<N>>,
+    /// The maximum angle, in radians, between `up` and a surface the character can walk on (default: `π / 4`).
+    ///
+    /// The character slides along steeper surfaces without climbing them.
+    pub max_slope_angle: N,
+    /// The maximum height of the ledges the character can automatically step on (default: `0.25`).
+    pub step_height: N,
+    /// The maximum distance the character is moved down to stick to the ground when walking down a slope (default: `0.2`).
+    pub snap_to_ground: N,
+    /// The gap kept between the character and the obstacles it touches (default: `0.01`).
+    pub offset: N,
+    /// The maximum number of times the character slides along an obstacle during one motion (default: `4`).
+    pub max_iterations: usize,
+}
+
+impl<N: RealField> KinematicCharacterController<N> {
+    /// Creates a character controller with default parameters.
+    pub fn new() -> Self {
+        KinematicCharacterController {
+            up: Vector::y_axis(),
+            max_slope_angle: N::frac_pi_4(),
+            step_height: na::convert(0.25),
+            snap_to_ground: na::convert(0.2),
+            offset: na::convert(0.01),
+            max_iterations: 4,
+        }
+    }
+
+    /// Computes the translation of the collider `character` that best follows `desired_translation`.
+    ///
+    /// This does not modify the character. Other colliders attached to the same body as
+    /// `character`, as well as sensors, are ignored. Returns `None` if the collider `character`
+    /// does not exist.
+    pub fn move_character<Handle, CollHandle, Colliders>(
+        &self,
+        gworld: &GeometricalWorld<N, Handle, CollHandle>,
+        colliders: &Colliders,
+        character: CollHandle,
+        desired_translation: &Vector<N>,
+    ) -> Option<CharacterMovement<N>>
+    where
+        Handle: BodyHandle,
+        CollHandle: ColliderHandle,
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        let collider = colliders.get(character)?;
+        let start = *collider.position();
+        let up = self.up.into_inner();
+        let mut position = start;
+        let mut remaining = *desired_translation;
+        let mut grounded = false;
+
+        for _ in 0..self.max_iterations {
+            if remaining.norm() <= N::default_epsilon() {
+                break;
+            }
+
+            let (toi, normal) = match self.cast(gworld, colliders, character, &position, &remaining)
+            {
+                Some(hit) => hit,
+                None => {
+                    position.translation.vector += remaining;
+                    break;
+                }
+            };
+
+            position.translation.vector += remaining * toi;
+            remaining *= N::one() - toi;
+
+            if self.is_walkable(&normal) {
+                grounded = true;
+            } else if let Some((stepped, left)) =
+                self.step_up(gworld, colliders, character, &position, &remaining)
+            {
+                position = stepped;
+                remaining = left;
+                grounded = true;
+                continue;
+            }
+
+            // Slide along the obstacle.
+            let along_normal = remaining.dot(&normal);
+
+            if along_normal < N::zero() {
+                remaining -= normal * along_normal;
+            }
+
+            // Don't climb steep slopes by sliding along them.
+            if !self.is_walkable(&normal) {
+                let climb = remaining.dot(&up);
+
+                if climb > N::zero() && climb > desired_translation.dot(&up) {
+                    remaining -= up * climb;
+                }
+            }
+        }
+
+        if desired_translation.dot(&up) <= N::zero() {
+            let down = -up * (self.snap_to_ground + self.offset);
+
+            if let Some((toi, normal)) = self.cast(gworld, colliders, character, &position, &down) {
+                if self.is_walkable(&normal) {
+                    position.translation.vector += down * toi;
+                    grounded = true;
+                }
+            }
+        }
+
+        Some(CharacterMovement {
+            translation: position.translation.vector - start.translation.vector,
+            grounded,
+        })
+    }
+
+    /// Moves the kinematic rigid body attached to the collider `character` to follow `desired_translation`.
+    ///
+    /// The body is moved directly, without affecting its velocity. Returns `None` if the collider
+    /// `character` does not exist or is not attached to a rigid body.
+    pub fn move_body<Handle, CollHandle, Colliders>(
+        &self,
+        gworld: &GeometricalWorld<N, Handle, CollHandle>,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+        colliders: &Colliders,
+        character: CollHandle,
+        desired_translation: &Vector<N>,
+    ) -> Option<CharacterMovement<N>>
+    where
+        Handle: BodyHandle,
+        CollHandle: ColliderHandle,
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        let body = colliders.get(character)?.body();
+        let movement = self.move_character(gworld, colliders, character, desired_translation)?;
+        let rb = bodies.get_mut(body)?.downcast_mut::<RigidBody<N>>()?;
+        let mut position = *rb.position();
+        position.translation.vector += movement.translation;
+        rb.set_position(position);
+
+        Some(movement)
+    }
+
+    fn is_walkable(&self, normal: &Vector<N>) -> bool {
+        normal.dot(&self.up) >= self.max_slope_angle.cos()
+    }
+
+    // Tries to climb the obstacle blocking `translation` if it is not higher than `self.step_height`.
+    // Returns the position on top of the step and the translation left to perform.
+    fn step_up<Handle, CollHandle, Colliders>(
+        &self,
+        gworld: &GeometricalWorld<N, Handle, CollHandle>,
+        colliders: &Colliders,
+        character: CollHandle,
+        position: &Isometry<N>,
+        translation: &Vector<N>,
+    ) -> Option<(Isometry<N>, Vector<N>)>
+    where
+        Handle: BodyHandle,
+        CollHandle: ColliderHandle,
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        if self.step_height <= N::zero() {
+            return None;
+        }
+
+        let up = self.up.into_inner();
+        let horizontal = translation - up * translation.dot(&up);
+
+        if horizontal.norm() <= N::default_epsilon() {
+            return None;
+        }
+
+        let mut stepped = *position;
+
+        // Go up, unless a ceiling is in the way.
+        let lift = up * self.step_height;
+        let lift_toi = self
+            .cast(gworld, colliders, character, &stepped, &lift)
+            .map(|hit| hit.0)
+            .unwrap_or(N::one());
+        stepped.translation.vector += lift * lift_toi;
+
+        // Go forward.
+        let forward_toi = self
+            .cast(gworld, colliders, character, &stepped, &horizontal)
+            .map(|hit| hit.0)
+            .unwrap_or(N::one());
+
+        if forward_toi <= N::zero() {
+            return None;
+        }
+
+        stepped.translation.vector += horizontal * forward_toi;
+
+        // Go back down onto the step.
+        let down = -lift * lift_toi;
+        let (down_toi, normal) = self.cast(gworld, colliders, character, &stepped, &down)?;
+
+        if !self.is_walkable(&normal) {
+            return None;
+        }
+
+        stepped.translation.vector += down * down_toi;
+        Some((stepped, horizontal * (N::one() - forward_toi)))
+    }
+
+    // The fraction of `translation` the character can travel from `position`, and the normal of
+    // the obstacle it hits.
+    fn cast<Handle, CollHandle, Colliders>(
+        &self,
+        gworld: &GeometricalWorld<N, Handle, CollHandle>,
+        colliders: &Colliders,
+        character: CollHandle,
+        position: &Isometry<N>,
+        translation: &Vector<N>,
+    ) -> Option<(N, Vector<N>)>
+    where
+        Handle: BodyHandle,
+        CollHandle: ColliderHandle,
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        let collider = colliders.get(character)?;
+        let body = collider.body();
+        let (_, _, toi) = gworld.cast_shape_filtered(
+            colliders,
+            collider.shape(),
+            position,
+            translation,
+            self.offset,
+            collider.collision_groups(),
+            |handle| {
+                colliders
+                    .get(handle)
+                    .map(|c| c.body() != body)
+                    .unwrap_or(false)
+            },
+        )?;
+
+        // `normal1` is the outward normal of the character, in its local-space.
+        Some((toi.toi, -(position.rotation * toi.normal1.into_inner())))
+    }
+}
+
+impl<N: RealField> Default for KinematicCharacterController<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
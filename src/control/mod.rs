@@ -0,0 +1,8 @@
+//! Controllers moving bodies without going through the force-based dynamics, and helpers for
+//! writing controllers, e.g., for balancing robots.
+
+pub use self::kinematic_character_controller::{CharacterMovement, KinematicCharacterController};
+pub use self::support_polygon::SupportPolygon;
+
+mod kinematic_character_controller;
+mod support_polygon;
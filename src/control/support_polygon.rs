@@ -0,0 +1,312 @@
+use na::{RealField, Unit};
+
+use crate::math::{Point, Vector};
+use crate::object::{Body, BodyHandle, BodySet, Collider, ColliderHandle, ColliderSet};
+use crate::world::GeometricalWorld;
+
+/// The support polygon of a body, i.e., the convex hull of its contact points with the ground.
+///
+/// In 2D, the support polygon is the segment spanned by the contact points.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SupportPolygon<N: RealField> {
+    /// The world-space contact points on the vertices of the support polygon.
+    ///
+    /// In 3D, they are ordered along the boundary of the polygon. In 2D, these are the two
+    /// endpoints of the support segment, or a single point if they coincide.
+    pub vertices: Vec<Point<N>>,
+    /// The world-space center of mass of the body.
+    pub center_of_mass: Point<N>,
+    /// The signed distance from the projection of the center of mass to the boundary of the support polygon.
+    ///
+    /// Both are projected along the up direction. This is positive if the projection of the center of
+    /// mass lies strictly inside of the support polygon, and negative if it lies outside.
+    pub stability_margin: N,
+}
+
+impl<N: RealField> SupportPolygon<N> {
+    /// Computes the support polygon of `body` from its contacts with the colliders accepted by `is_ground`.
+    ///
+    /// The contact points are projected along the `up` direction, pointing away from the ground.
+    /// Returns `None` if `body` does not exist or has no contact with the ground.
+    pub fn compute<Handle, CollHandle, Colliders>(
+        gworld: &GeometricalWorld<N, Handle, CollHandle>,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        colliders: &Colliders,
+        body: Handle,
+        up: &Unit<Vector<N>>,
+        is_ground: impl Fn(CollHandle, &Collider<N, Handle>) -> bool,
+    ) -> Option<Self>
+    where
+        Handle: BodyHandle,
+        CollHandle: ColliderHandle,
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        let center_of_mass = body_center_of_mass(bodies.get(body)?)?;
+        let mut points = Vec::new();
+
+        for (h1, c1, h2, c2, _, manifold) in gworld.contacts_with_body(colliders, body, true) {
+            let (ground, ground_collider, first) = if c1.body() == body {
+                (h2, c2, true)
+            } else {
+                (h1, c1, false)
+            };
+
+            if ground_collider.body() == body || !is_ground(ground, ground_collider) {
+                continue;
+            }
+
+            for c in manifold.contacts() {
+                points.push(if first {
+                    c.contact.world1
+                } else {
+                    c.contact.world2
+                });
+            }
+        }
+
+        if points.is_empty() {
+            return None;
+        }
+
+        let basis = plane_basis(up);
+        let project = |pt: &Point<N>| -> Vec<N> {
+            basis
+                .iter()
+                .map(|axis| (pt - center_of_mass).dot(axis))
+                .collect()
+        };
+        let projected: Vec<_> = points.iter().map(project).collect();
+        let (hull, stability_margin) = hull_and_margin(&projected);
+
+        Some(SupportPolygon {
+            vertices: hull.into_iter().map(|i| points[i]).collect(),
+            center_of_mass,
+            stability_margin,
+        })
+    }
+
+    /// Whether the projection of the center of mass lies strictly inside of the support polygon.
+    pub fn is_statically_stable(&self) -> bool {
+        self.stability_margin > N::zero()
+    }
+}
+
+// The mass-weighted center of mass of all the parts of the body.
+fn body_center_of_mass<N: RealField>(body: &dyn Body<N>) -> Option<Point<N>> {
+    let mut total_mass = N::zero();
+    let mut weighted = Vector::zeros();
+
+    for i in 0..body.num_parts() {
+        let part = body.part(i)?;
+        let mass = part.inertia().mass();
+        total_mass += mass;
+        weighted += part.center_of_mass().coords * mass;
+    }
+
+    if total_mass > N::zero() {
+        Some(Point::from(weighted / total_mass))
+    } else {
+        body.part(0).map(|part| part.center_of_mass())
+    }
+}
+
+#[cfg(feature = "dim2")]
+fn plane_basis<N: RealField>(up: &Unit<Vector<N>>) -> Vec<Vector<N>> {
+    vec![Vector::new(up.y, -up.x)]
+}
+
+#[cfg(feature = "dim3")]
+fn plane_basis<N: RealField>(up: &Unit<Vector<N>>) -> Vec<Vector<N>> {
+    let mut basis = Vec::with_capacity(2);
+    Vector::orthonormal_subspace_basis(&[up.into_inner()], |v| {
+        basis.push(*v);
+        true
+    });
+    basis
+}
+
+// The indices of the extremal points, and the signed distance from the origin to the segment they span.
+#[cfg(feature = "dim2")]
+fn hull_and_margin<N: RealField>(points: &[Vec<N>]) -> (Vec<usize>, N) {
+    let mut min = 0;
+    let mut max = 0;
+
+    for (i, pt) in points.iter().enumerate() {
+        if pt[0] < points[min][0] {
+            min = i;
+        }
+        if pt[0] > points[max][0] {
+            max = i;
+        }
+    }
+
+    let margin = (-points[min][0]).min(points[max][0]);
+
+    if points[min][0] == points[max][0] {
+        (vec![min], margin)
+    } else {
+        (vec![min, max], margin)
+    }
+}
+
+// The indices of the convex hull vertices, and the signed distance from the origin to its boundary.
+#[cfg(feature = "dim3")]
+fn hull_and_margin<N: RealField>(points: &[Vec<N>]) -> (Vec<usize>, N) {
+    let cross = |o: usize, a: usize, b: usize| {
+        (points[a][0] - points[o][0]) * (points[b][1] - points[o][1])
+            - (points[a][1] - points[o][1]) * (points[b][0] - points[o][0])
+    };
+
+    // Andrew's monotone chain.
+    let mut sorted: Vec<usize> = (0..points.len()).collect();
+    sorted.sort_by(|a, b| {
+        let (pa, pb) = (&points[*a], &points[*b]);
+        (pa[0], pa[1])
+            .partial_cmp(&(pb[0], pb[1]))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    sorted.dedup_by(|a, b| points[*a] == points[*b]);
+
+    let mut hull: Vec<usize> = Vec::with_capacity(sorted.len() + 1);
+
+    for pass in 0..2 {
+        let start = hull.len();
+
+        for &i in sorted.iter() {
+            while hull.len() >= start + 2
+                && cross(hull[hull.len() - 2], hull[hull.len() - 1], i) <= N::zero()
+            {
+                let _ = hull.pop();
+            }
+
+            hull.push(i);
+        }
+
+        let _ = hull.pop();
+
+        if pass == 0 {
+            sorted.reverse();
+        }
+    }
+
+    if hull.is_empty() {
+        hull.push(sorted[0]);
+    }
+
+    // The distance from the origin to the segment `[a, b]`.
+    let segment_distance = |a: &[N], b: &[N]| {
+        let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+        let len2 = dx * dx + dy * dy;
+        let t = if len2 > N::zero() {
+            ((-a[0]) * dx + (-a[1]) * dy) / len2
+        } else {
+            N::zero()
+        };
+        let t = t.max(N::zero()).min(N::one());
+        let (px, py) = (a[0] + dx * t, a[1] + dy * t);
+        (px * px + py * py).sqrt()
+    };
+
+    let n = hull.len();
+
+    if n < 3 {
+        let a = &points[hull[0]];
+        let b = &points[hull[n - 1]];
+        return (hull, -segment_distance(a, b));
+    }
+
+    let mut inside = true;
+    let mut distance = N::max_value();
+
+    for k in 0..n {
+        let a = &points[hull[k]];
+        let b = &points[hull[(k + 1) % n]];
+        let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+        let side = (dx * (-a[1]) - dy * (-a[0])) / (dx * dx + dy * dy).sqrt();
+
+        if side < N::zero() {
+            inside = false;
+        }
+
+        distance = distance.min(segment_distance(a, b));
+    }
+
+    if inside {
+        (hull, distance)
+    } else {
+        (hull, -distance)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::hull_and_margin;
+
+    #[cfg(feature = "dim2")]
+    #[test]
+    fn margin_is_the_distance_to_the_closest_segment_endpoint() {
+        let points = vec![vec![-1.0], vec![0.5], vec![2.0]];
+        let (hull, margin) = hull_and_margin(&points);
+        assert_eq!(hull, vec![0, 2]);
+        assert_relative_eq!(margin, 1.0);
+
+        let points = vec![vec![3.0], vec![1.0]];
+        let (hull, margin) = hull_and_margin(&points);
+        assert_eq!(hull, vec![1, 0]);
+        assert_relative_eq!(margin, -1.0);
+    }
+
+    #[cfg(feature = "dim2")]
+    #[test]
+    fn coincident_points_span_a_single_vertex() {
+        let points = vec![vec![0.5], vec![0.5]];
+        let (hull, margin) = hull_and_margin(&points);
+        assert_eq!(hull.len(), 1);
+        assert_relative_eq!(margin, -0.5);
+    }
+
+    #[cfg(feature = "dim3")]
+    #[test]
+    fn center_of_mass_inside_the_polygon_has_a_positive_margin() {
+        let points = vec![
+            vec![-1.0, -1.0],
+            vec![1.0, -1.0],
+            vec![0.0, 0.5],
+            vec![1.0, 1.0],
+            vec![-1.0, 1.0],
+        ];
+        let (mut hull, margin) = hull_and_margin(&points);
+        hull.sort();
+        assert_eq!(hull, vec![0, 1, 3, 4]);
+        assert_relative_eq!(margin, 1.0);
+    }
+
+    #[cfg(feature = "dim3")]
+    #[test]
+    fn center_of_mass_outside_the_polygon_has_a_negative_margin() {
+        let points = vec![
+            vec![1.0, -1.0],
+            vec![3.0, -1.0],
+            vec![3.0, 1.0],
+            vec![1.0, 1.0],
+        ];
+        let (hull, margin) = hull_and_margin(&points);
+        assert_eq!(hull.len(), 4);
+        assert_relative_eq!(margin, -1.0);
+    }
+
+    #[cfg(feature = "dim3")]
+    #[test]
+    fn collinear_points_span_a_degenerate_segment() {
+        let points = vec![vec![-1.0, 1.0], vec![0.0, 1.0], vec![1.0, 1.0]];
+        let (mut hull, margin) = hull_and_margin(&points);
+        hull.sort();
+        assert_eq!(hull, vec![0, 2]);
+        assert_relative_eq!(margin, -1.0);
+
+        let points = vec![vec![2.0, 0.0], vec![2.0, 0.0]];
+        let (hull, margin) = hull_and_margin(&points);
+        assert_eq!(hull.len(), 1);
+        assert_relative_eq!(margin, -2.0);
+    }
+}
@@ -110,6 +110,11 @@ impl Counters {
         self.solver.ncontacts = n;
     }
 
+    /// Set the number of bodies that were active during the last step.
+    pub fn set_nactive_bodies(&mut self, n: usize) {
+        self.solver.nactive_bodies = n;
+    }
+
     /// Set the number of contact pairs generated.
     pub fn set_ncontact_pairs(&mut self, n: usize) {
         self.cd.ncontact_pairs = n;
@@ -8,6 +8,8 @@ pub struct SolverCounters {
     pub nconstraints: usize,
     /// Number of contacts found.
     pub ncontacts: usize,
+    /// Number of active bodies.
+    pub nactive_bodies: usize,
     /// Time spent for the resolution of the constraints (force computation).
     pub velocity_resolution_time: Timer,
     /// Time spent for the assembly of all the constraints into a linear complentarity problem.
@@ -24,6 +26,7 @@ impl SolverCounters {
         SolverCounters {
             nconstraints: 0,
             ncontacts: 0,
+            nactive_bodies: 0,
             assembly_time: Timer::new(),
             velocity_resolution_time: Timer::new(),
             velocity_update_time: Timer::new(),
@@ -36,6 +39,7 @@ impl Display for SolverCounters {
     fn fmt(&self, f: &mut Formatter) -> Result {
         writeln!(f, "Number of contacts: {}", self.ncontacts)?;
         writeln!(f, "Number of constraints: {}", self.nconstraints)?;
+        writeln!(f, "Number of active bodies: {}", self.nactive_bodies)?;
         writeln!(f, "Assembly time: {}", self.assembly_time)?;
         writeln!(
             f,
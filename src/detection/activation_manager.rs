@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+use std::mem;
+
 use crate::joint::{JointConstraint, JointConstraintSet};
 use crate::object::{Body, BodyHandle, BodySet, ColliderSet};
 use crate::utils::union_find;
@@ -15,6 +18,13 @@ pub struct ActivationManager<N: RealField, Handle: BodyHandle> {
     can_deactivate: Vec<bool>,
     to_activate: Vec<Handle>,
     id_to_body: Vec<Handle>,
+    root_to_island: Vec<usize>,
+    islands: Vec<Vec<Handle>>,
+    island_ids: Vec<usize>,
+    anchor_to_island_id: HashMap<Handle, usize>,
+    prev_anchor_to_island_id: HashMap<Handle, usize>,
+    body_to_island_id: HashMap<Handle, usize>,
+    next_island_id: usize,
 }
 
 impl<N: RealField, Handle: BodyHandle> ActivationManager<N, Handle> {
@@ -35,6 +45,13 @@ impl<N: RealField, Handle: BodyHandle> ActivationManager<N, Handle> {
             can_deactivate: Vec::new(),
             to_activate: Vec::new(),
             id_to_body: Vec::new(),
+            root_to_island: Vec::new(),
+            islands: Vec::new(),
+            island_ids: Vec::new(),
+            anchor_to_island_id: HashMap::new(),
+            prev_anchor_to_island_id: HashMap::new(),
+            body_to_island_id: HashMap::new(),
+            next_island_id: 0,
         }
     }
 
@@ -44,6 +61,32 @@ impl<N: RealField, Handle: BodyHandle> ActivationManager<N, Handle> {
         self.to_activate.push(handle);
     }
 
+    /// The active islands computed during the last update.
+    ///
+    /// Each island contains the handles of the non-kinematic bodies interacting with each other,
+    /// directly or indirectly, through contacts or joints. Islands that have been put to sleep
+    /// are not included.
+    pub fn islands(&self) -> &[Vec<Handle>] {
+        &self.islands[..]
+    }
+
+    /// The persistent identifiers of the active islands computed during the last update.
+    ///
+    /// The i-th element of this slice is the identifier of the i-th island returned by
+    /// `self.islands()`.
+    pub fn island_ids(&self) -> &[usize] {
+        &self.island_ids[..]
+    }
+
+    /// The persistent identifier of the active island containing the given body.
+    ///
+    /// An island keeps the same identifier from one update to the next as long as its set of
+    /// bodies does not change. Returns `None` if the body is sleeping, kinematic, static,
+    /// or does not exist.
+    pub fn island_id(&self, handle: Handle) -> Option<usize> {
+        self.body_to_island_id.get(&handle).cloned()
+    }
+
     fn update_energy(&self, body: &mut (impl Body<N> + ?Sized)) {
         // FIXME: avoid the Copy when NLL lands ?
         let status = *body.activation_status();
@@ -176,6 +219,10 @@ impl<N: RealField, Handle: BodyHandle> ActivationManager<N, Handle> {
         }
 
         // Activate/deactivate islands.
+        self.islands.clear();
+        self.root_to_island.clear();
+        self.root_to_island.resize(self.ufind.len(), usize::max_value());
+
         for i in 0usize..self.ufind.len() {
             let root = union_find::find(i, &mut self.ufind[..]);
             let handle = self.id_to_body[i];
@@ -190,6 +237,13 @@ impl<N: RealField, Handle: BodyHandle> ActivationManager<N, Handle> {
                 // Everybody in this set must be reactivated.
                 active_bodies.push(handle);
 
+                if self.root_to_island[root] == usize::max_value() {
+                    self.root_to_island[root] = self.islands.len();
+                    self.islands.push(Vec::new());
+                }
+
+                self.islands[self.root_to_island[root]].push(handle);
+
                 // FIXME: avoid the Copy when NLL lands ?
                 let status = *body.activation_status();
 
@@ -198,5 +252,38 @@ impl<N: RealField, Handle: BodyHandle> ActivationManager<N, Handle> {
                 }
             }
         }
+
+        self.assign_island_ids();
+    }
+
+    // An island inherits the identifier of the island of the previous update which had the same
+    // first body. Because bodies are enumerated in a deterministic order, an island whose
+    // membership did not change always keeps its identifier.
+    fn assign_island_ids(&mut self) {
+        mem::swap(
+            &mut self.anchor_to_island_id,
+            &mut self.prev_anchor_to_island_id,
+        );
+        self.anchor_to_island_id.clear();
+        self.body_to_island_id.clear();
+        self.island_ids.clear();
+
+        for island in &self.islands {
+            let anchor = island[0];
+            let id = match self.prev_anchor_to_island_id.get(&anchor) {
+                Some(id) => *id,
+                None => {
+                    self.next_island_id += 1;
+                    self.next_island_id - 1
+                }
+            };
+
+            let _ = self.anchor_to_island_id.insert(anchor, id);
+            self.island_ids.push(id);
+
+            for handle in island {
+                let _ = self.body_to_island_id.insert(*handle, id);
+            }
+        }
     }
 }
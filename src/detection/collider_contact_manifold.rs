@@ -1,9 +1,24 @@
-use na::RealField;
+use std::cmp::Ordering;
+
+use na::{self, RealField};
 use ncollide::query::{ContactManifold, TrackedContact};
 use ncollide::shape::FeatureId;
 
+use crate::math::Point;
 use crate::object::{BodyHandle, BodyPartHandle, Collider, ColliderAnchor, ColliderHandle};
 
+/// The strategy used to select the contacts kept when a contact manifold is reduced.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ContactReduction {
+    /// Keep the deepest contacts.
+    Deepest,
+    /// Keep the deepest contact, then the contacts maximizing the area covered by the kept contacts.
+    ///
+    /// This yields contacts well-distributed along the contact surface, e.g., the four corners of
+    /// a box resting on a flat floor.
+    MaximumArea,
+}
+
 /// A contact manifold between two bodies.
 #[derive(Clone)]
 pub struct ColliderContactManifold<'a, N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
@@ -18,6 +33,8 @@ pub struct ColliderContactManifold<'a, N: RealField, Handle: BodyHandle, CollHan
     pub collider2: &'a Collider<N, Handle>,
     /// The contact manifold.
     pub manifold: &'a ContactManifold<N>,
+    // The indices of the contacts of `manifold` kept after a reduction.
+    selection: Option<Vec<usize>>,
 }
 
 impl<'a, N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
@@ -37,17 +54,103 @@ impl<'a, N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
             handle2,
             collider2,
             manifold,
+            selection: None,
+        }
+    }
+
+    /// The maximum normal impulse applied by each contact of this manifold.
+    ///
+    /// This is the smallest maximum contact impulse of the two colliders, if any.
+    pub fn max_contact_impulse(&self) -> Option<N> {
+        match (
+            self.collider1.max_contact_impulse(),
+            self.collider2.max_contact_impulse(),
+        ) {
+            (Some(max1), Some(max2)) => Some(max1.min(max2)),
+            (max1, max2) => max1.or(max2),
         }
     }
 
     /// The number of contacts on the manifold.
     pub fn len(&self) -> usize {
-        self.manifold.len()
+        match &self.selection {
+            Some(selection) => selection.len(),
+            None => self.manifold.len(),
+        }
     }
 
     /// Get all the contacts from the manifold.
+    ///
+    /// If this manifold has been reduced, only the contacts kept by the reduction are returned.
     pub fn contacts(&self) -> impl Iterator<Item = &TrackedContact<N>> {
-        self.manifold.contacts()
+        let selection = self.selection.as_ref();
+        self.manifold
+            .contacts()
+            .enumerate()
+            .filter(move |(i, _)| selection.map_or(true, |s| s.contains(i)))
+            .map(|(_, c)| c)
+    }
+
+    /// Reduces the number of contacts of this manifold to at most `max_contacts`.
+    ///
+    /// The contacts are selected using the given strategy. The deepest contact is always kept.
+    /// This does not modify the underlying contact manifold: only the contacts returned by
+    /// `self.contacts()` are affected.
+    pub fn reduce(&mut self, max_contacts: usize, reduction: ContactReduction) {
+        if self.len() <= max_contacts {
+            return;
+        }
+
+        let contacts: Vec<_> = self.contacts().collect();
+        let mut kept: Vec<usize> = Vec::with_capacity(max_contacts);
+
+        match reduction {
+            ContactReduction::Deepest => {
+                let mut ids: Vec<usize> = (0..contacts.len()).collect();
+                ids.sort_by(|a, b| {
+                    let (da, db) = (contacts[*a].contact.depth, contacts[*b].contact.depth);
+                    db.partial_cmp(&da).unwrap_or(Ordering::Equal)
+                });
+                kept.extend_from_slice(&ids[..max_contacts]);
+            }
+            ContactReduction::MaximumArea => {
+                let mut points: Vec<Point<N>> = Vec::with_capacity(max_contacts);
+
+                while kept.len() < max_contacts {
+                    let mut best = None;
+                    let mut best_score = -N::max_value();
+
+                    for (i, c) in contacts.iter().enumerate() {
+                        if kept.contains(&i) {
+                            continue;
+                        }
+
+                        let score = if points.is_empty() {
+                            c.contact.depth
+                        } else {
+                            coverage_increase(&points, &c.contact.world1)
+                        };
+
+                        if score > best_score {
+                            best = Some(i);
+                            best_score = score;
+                        }
+                    }
+
+                    let best = try_ret!(best);
+                    kept.push(best);
+                    points.push(contacts[best].contact.world1);
+                }
+            }
+        }
+
+        // Map the indices back to the contacts of the underlying manifold.
+        let mut selection: Vec<usize> = match &self.selection {
+            Some(selection) => kept.iter().map(|i| selection[*i]).collect(),
+            None => kept,
+        };
+        selection.sort();
+        self.selection = Some(selection);
     }
 
     /// Get the deepest contact, if any, from the manifold.
@@ -113,3 +216,96 @@ impl<'a, N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         self.collider2.anchor()
     }
 }
+
+// How much adding `pt` increases the extent covered by the already selected `points`.
+#[cfg(feature = "dim2")]
+fn coverage_increase<N: RealField>(points: &[Point<N>], pt: &Point<N>) -> N {
+    points
+        .iter()
+        .map(|p| na::distance_squared(p, pt))
+        .fold(N::max_value(), |a, b| a.min(b))
+}
+
+// How much adding `pt` increases the area covered by the already selected `points`.
+//
+// This is the largest area of the triangles formed by `pt` and two selected points, or the
+// distance to the only selected point.
+#[cfg(feature = "dim3")]
+fn coverage_increase<N: RealField>(points: &[Point<N>], pt: &Point<N>) -> N {
+    if points.len() == 1 {
+        return na::distance_squared(&points[0], pt);
+    }
+
+    let mut best = N::zero();
+
+    for (i, a) in points.iter().enumerate() {
+        for b in &points[i + 1..] {
+            best = best.max((b - a).cross(&(pt - a)).norm_squared());
+        }
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod test {
+    use ncollide::shape::{Cuboid, ShapeHandle};
+
+    use super::{ColliderContactManifold, ContactReduction};
+    use crate::analytic_tests::{assert_relative_error, Scenario};
+    use crate::math::{Vector, DIM};
+    use crate::object::RigidBodyDesc;
+
+    #[test]
+    fn resting_box_manifold_reduced_to_its_corners() {
+        let mut scenario = Scenario::new(1.0 / 60.0);
+        scenario
+            .mechanical_world
+            .integration_parameters
+            .max_contacts_per_manifold = Some(4);
+        scenario.add_floor(0.0, 0.5);
+
+        let half_extent = 0.5;
+        let desc = RigidBodyDesc::new().translation(Vector::y() * 1.0);
+        let cuboid = ShapeHandle::new(Cuboid::new(Vector::repeat(half_extent)));
+        let _ = scenario.add_body(&desc, cuboid, 0.0, 0.5);
+
+        while scenario.time() < 2.0 {
+            scenario.step();
+        }
+
+        let (h1, c1, h2, c2, _, manifold) = scenario
+            .geometrical_world
+            .contact_pairs(&scenario.colliders, false)
+            .next()
+            .expect("The box is not resting on the floor.");
+        let mut manifold = ColliderContactManifold::new(h1, c1, h2, c2, manifold);
+        manifold.reduce(4, ContactReduction::MaximumArea);
+
+        // Two contacts in 2D, four in 3D: one at each corner of the bottom face of the box.
+        let num_corners = 1 << (DIM - 1);
+        assert_eq!(manifold.len(), num_corners);
+
+        let mut corners = Vec::new();
+
+        for c in manifold.contacts() {
+            let pt = c.contact.world1;
+            let mut corner = 0;
+
+            for i in (0..DIM).filter(|i| *i != 1) {
+                assert_relative_error(pt[i].abs(), half_extent, 0.05);
+                corner = corner * 2 + (pt[i] > 0.0) as usize;
+            }
+
+            corners.push(corner);
+        }
+
+        corners.sort();
+        corners.dedup();
+        assert_eq!(
+            corners.len(),
+            num_corners,
+            "The contacts are not at distinct corners."
+        );
+    }
+}
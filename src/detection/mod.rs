@@ -1,7 +1,7 @@
 //! Collision detection information.
 
 pub use self::activation_manager::ActivationManager;
-pub use self::collider_contact_manifold::ColliderContactManifold;
+pub use self::collider_contact_manifold::{ColliderContactManifold, ContactReduction};
 
 mod activation_manager;
 mod collider_contact_manifold;
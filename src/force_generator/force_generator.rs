@@ -2,9 +2,11 @@
 
 use downcast_rs::Downcast;
 use generational_arena::Arena;
-use na::RealField;
+use na::{RealField, Unit};
 
-use crate::object::{BodyHandle, BodySet, DefaultBodyHandle};
+use crate::force_generator::ImpulseRecorder;
+use crate::math::{Point, Vector};
+use crate::object::{BodyHandle, BodyPartHandle, BodySet, DefaultBodyHandle};
 use crate::solver::IntegrationParameters;
 
 /// Default force generator set based on an arena with generational indices.
@@ -68,6 +70,23 @@ impl<N: RealField, Handle: BodyHandle> ForceGeneratorSet<N, Handle>
 /// The handle of a force generator.
 pub type DefaultForceGeneratorHandle = generational_arena::Index;
 
+/// A contact point between two body parts, given to the force generators that need them.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BodyContact<N: RealField, Handle: BodyHandle> {
+    /// The first body part in contact.
+    pub body_part1: BodyPartHandle<Handle>,
+    /// The second body part in contact.
+    pub body_part2: BodyPartHandle<Handle>,
+    /// The world-space contact point on the first body part.
+    pub point1: Point<N>,
+    /// The world-space contact point on the second body part.
+    pub point2: Point<N>,
+    /// The world-space contact normal, pointing from the first body part toward the second.
+    pub normal: Unit<Vector<N>>,
+    /// The penetration depth of the contact, negative if the body parts are not touching yet.
+    pub depth: N,
+}
+
 /// A persistent force generator.
 ///
 /// A force generator applies a force to one or several bodies at each step of the simulation.
@@ -78,6 +97,34 @@ pub trait ForceGenerator<N: RealField, Handle: BodyHandle>: Downcast + Send + Sy
         parameters: &IntegrationParameters<N>,
         bodies: &mut dyn BodySet<N, Handle = Handle>,
     );
+
+    /// Whether this force generator reads the contacts given to `self.apply_with_contacts`.
+    ///
+    /// The contacts are only collected if at least one force generator needs them.
+    fn needs_contacts(&self) -> bool {
+        false
+    }
+
+    /// Apply forces to some bodies, depending on the contacts between them.
+    ///
+    /// This is the method actually called by the mechanical world at each step. `contacts` are
+    /// the contacts with a non-zero number of points computed during the previous timestep, and
+    /// is empty unless `self.needs_contacts()` returns `true` for some force generator. The
+    /// default implementation ignores the contacts and calls `self.apply`.
+    fn apply_with_contacts(
+        &mut self,
+        parameters: &IntegrationParameters<N>,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+        contacts: &[BodyContact<N, Handle>],
+    ) {
+        let _ = contacts;
+        self.apply(parameters, bodies)
+    }
+
+    /// The impulses applied by this force generator during the last step, if it records them.
+    fn impulse_recorder(&self) -> Option<&ImpulseRecorder<N, Handle>> {
+        None
+    }
 }
 
 impl_downcast!(ForceGenerator<N, Handle> where N: RealField, Handle: BodyHandle);
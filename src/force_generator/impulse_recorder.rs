@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use na::RealField;
+
+use crate::math::{Force, ForceType, Velocity};
+use crate::object::{BodyHandle, BodyPartHandle, BodySet};
+use crate::solver::IntegrationParameters;
+
+/// Applies forces to body parts while recording the total impulse each one received.
+///
+/// This is an opt-in helper for force generators which need to report the forces they applied,
+/// e.g., for telemetry or fuel accounting. A force generator using it should call `clear` at the
+/// beginning of each call to its `ForceGenerator::apply` method, and expose it through
+/// `ForceGenerator::impulse_recorder`.
+///
+/// All the impulses are expressed in world-space at the center of mass of the body part they
+/// were applied to.
+pub struct ImpulseRecorder<N: RealField, Handle: BodyHandle> {
+    impulses: HashMap<BodyPartHandle<Handle>, Force<N>>,
+}
+
+impl<N: RealField, Handle: BodyHandle> ImpulseRecorder<N, Handle> {
+    /// Creates a new recorder with no impulse recorded.
+    pub fn new() -> Self {
+        ImpulseRecorder {
+            impulses: HashMap::new(),
+        }
+    }
+
+    /// Forgets all the impulses recorded so far.
+    pub fn clear(&mut self) {
+        self.impulses.clear()
+    }
+
+    /// Applies a world-space force at the center of mass of a body part and records the
+    /// corresponding impulse.
+    ///
+    /// Acceleration and velocity changes are converted to impulses using the inertia of the
+    /// body part. Returns `false` if the body part does not exist.
+    pub fn apply_force(
+        &mut self,
+        parameters: &IntegrationParameters<N>,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+        part: BodyPartHandle<Handle>,
+        force: &Force<N>,
+        force_type: ForceType,
+        auto_wake_up: bool,
+    ) -> bool {
+        let body = try_ret!(bodies.get_mut(part.0), false);
+
+        let impulse = {
+            let body_part = try_ret!(body.part(part.1), false);
+            let change = || body_part.inertia() * Velocity::from_vector(force.as_vector());
+
+            match force_type {
+                ForceType::Force => *force * parameters.dt(),
+                ForceType::Impulse => *force,
+                ForceType::AccelerationChange => change() * parameters.dt(),
+                ForceType::VelocityChange => change(),
+            }
+        };
+
+        body.apply_force(part.1, force, force_type, auto_wake_up);
+        *self.impulses.entry(part).or_insert_with(Force::zero) += impulse;
+
+        true
+    }
+
+    /// The total impulse recorded for the given body part.
+    pub fn impulse(&self, part: BodyPartHandle<Handle>) -> Option<&Force<N>> {
+        self.impulses.get(&part)
+    }
+
+    /// Iterates through all the body parts and the total impulse recorded for them.
+    pub fn impulses(&self) -> impl Iterator<Item = (BodyPartHandle<Handle>, &Force<N>)> {
+        self.impulses.iter().map(|(part, impulse)| (*part, impulse))
+    }
+}
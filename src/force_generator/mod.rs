@@ -2,10 +2,13 @@
 
 pub use self::constant_acceleration::ConstantAcceleration;
 pub use self::force_generator::{
-    DefaultForceGeneratorHandle, DefaultForceGeneratorSet, ForceGenerator, ForceGeneratorSet,
+    BodyContact, DefaultForceGeneratorHandle, DefaultForceGeneratorSet, ForceGenerator,
+    ForceGeneratorSet,
 };
+pub use self::impulse_recorder::ImpulseRecorder;
 pub use self::spring::Spring;
 
 mod constant_acceleration;
 mod force_generator;
+mod impulse_recorder;
 mod spring;
@@ -1,7 +1,7 @@
-use na::{DVector, RealField};
+use na::{self, DVector, RealField};
 use std::ops::Range;
 
-use crate::joint::JointConstraint;
+use crate::joint::{JointConstraint, JointPositionError};
 use crate::math::{Point, Vector, DIM};
 use crate::object::{BodyHandle, BodyPartHandle, BodySet};
 use crate::solver::helper;
@@ -76,6 +76,32 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for BallConstr
         (self.b1, self.b2)
     }
 
+    fn can_scale_space(&self) -> bool {
+        true
+    }
+
+    fn scale_space(&mut self, factor: N) {
+        self.anchor1 *= factor;
+        self.anchor2 *= factor;
+        self.impulses *= factor;
+        self.break_force_squared *= factor * factor;
+    }
+
+    fn position_error(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> JointPositionError<N> {
+        let body1 = try_ret!(bodies.get(self.b1.0), JointPositionError::zero());
+        let body2 = try_ret!(bodies.get(self.b2.0), JointPositionError::zero());
+        let part1 = try_ret!(body1.part(self.b1.1), JointPositionError::zero());
+        let part2 = try_ret!(body2.part(self.b2.1), JointPositionError::zero());
+
+        let anchor1 = body1.world_point_at_material_point(part1, &self.anchor1);
+        let anchor2 = body2.world_point_at_material_point(part2, &self.anchor2);
+
+        JointPositionError {
+            linear: na::distance(&anchor1, &anchor2),
+            angular: N::zero(),
+        }
+    }
+
     fn velocity_constraints(
         &mut self,
         _: &IntegrationParameters<N>,
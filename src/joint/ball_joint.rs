@@ -1,20 +1,56 @@
 use na::{
-    self, DVectorSliceMut, Isometry3, Matrix3, RealField, Translation3, UnitQuaternion, Vector3,
-    VectorSlice3, U3,
+    self, DVector, DVectorSlice, DVectorSliceMut, Isometry3, Matrix3, RealField, Translation3,
+    Unit, UnitQuaternion, Vector3, VectorSlice3, U3,
 };
 
 use crate::joint::Joint;
 use crate::math::{JacobianSliceMut, Velocity};
-use crate::solver::IntegrationParameters;
+use crate::object::{BodyPartHandle, Multibody, MultibodyLink};
+use crate::solver::{
+    BilateralGroundConstraint, ConstraintSet, GenericNonlinearConstraint, ImpulseLimits,
+    IntegrationParameters, UnilateralGroundConstraint,
+};
 use crate::utils::GeneralizedCross;
 
 /// A joint that allows only all rotational degrees of freedom between two multibody links.
+///
+/// A spring and a damper pulling the joint toward a target relative orientation can be enabled by
+/// setting a non-zero stiffness or damping. They exert a restoring torque without locking any
+/// degree of freedom, e.g., to give some muscle tone to a ragdoll.
+///
+/// An orientation motor can also be enabled to actively drive the joint toward a desired relative
+/// orientation with a bounded torque. Coulomb friction can be set to resist the rotation of the
+/// joint with a bounded torque, holding it at rest under small loads.
+///
+/// The rotation range can be restricted with cone-twist limits. The relative rotation is decomposed
+/// into a twist around the limit axis of the child link, followed by a swing moving this axis away
+/// from its rest direction. The swing angle can be bounded, restricting the limit axis to a cone,
+/// and the twist angle can be bounded independently, e.g., to model a shoulder or a hip.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct BallJoint<N: RealField> {
     rot: UnitQuaternion<N>,
+    angvel: Vector3<N>,
 
     jacobian_v: Matrix3<N>,
     jacobian_dot_v: Matrix3<N>,
+
+    target_rotation: UnitQuaternion<N>,
+    stiffness: N,
+    damping: N,
+
+    orientation_motor_enabled: bool,
+    desired_orientation: UnitQuaternion<N>,
+    max_orientation_motor_torque: N,
+    // The stiffness and damping of the orientation motor, if it acts as a position drive.
+    orientation_motor_gains: Option<(N, N)>,
+
+    friction_torque: N,
+
+    limit_axis: Unit<Vector3<N>>,
+    max_swing_angle: Option<N>,
+    min_twist_angle: Option<N>,
+    max_twist_angle: Option<N>,
 }
 
 impl<N: RealField> BallJoint<N> {
@@ -22,10 +58,338 @@ impl<N: RealField> BallJoint<N> {
     pub fn new(axisangle: Vector3<N>) -> Self {
         BallJoint {
             rot: UnitQuaternion::new(axisangle),
+            angvel: na::zero(),
             jacobian_v: na::zero(),
             jacobian_dot_v: na::zero(),
+            target_rotation: UnitQuaternion::identity(),
+            stiffness: N::zero(),
+            damping: N::zero(),
+            orientation_motor_enabled: false,
+            desired_orientation: UnitQuaternion::identity(),
+            max_orientation_motor_torque: N::max_value(),
+            orientation_motor_gains: None,
+            friction_torque: N::zero(),
+            limit_axis: Vector3::x_axis(),
+            max_swing_angle: None,
+            min_twist_angle: None,
+            max_twist_angle: None,
+        }
+    }
+
+    /// The relative rotation of the attached multibody links.
+    pub fn rotation(&self) -> &UnitQuaternion<N> {
+        &self.rot
+    }
+
+    /// The relative rotation the spring of this joint pulls the attached multibody links toward.
+    pub fn target_rotation(&self) -> &UnitQuaternion<N> {
+        &self.target_rotation
+    }
+
+    /// Sets the relative rotation the spring of this joint pulls the attached multibody links toward.
+    pub fn set_target_rotation(&mut self, rotation: UnitQuaternion<N>) {
+        self.target_rotation = rotation
+    }
+
+    /// The stiffness of the spring pulling this joint toward its target rotation.
+    pub fn stiffness(&self) -> N {
+        self.stiffness
+    }
+
+    /// Sets the stiffness of the spring pulling this joint toward its target rotation.
+    ///
+    /// The spring is disabled if both the stiffness and the damping are zero.
+    pub fn set_stiffness(&mut self, stiffness: N) {
+        self.stiffness = stiffness
+    }
+
+    /// The damping coefficient of the damper opposing the relative angular velocity of this joint.
+    pub fn damping(&self) -> N {
+        self.damping
+    }
+
+    /// Sets the damping coefficient of the damper opposing the relative angular velocity of this joint.
+    ///
+    /// The spring is disabled if both the stiffness and the damping are zero.
+    pub fn set_damping(&mut self, damping: N) {
+        self.damping = damping
+    }
+
+    /// Returns `true` if the stiffness or the damping of the spring of this joint is non-zero.
+    pub fn is_spring_enabled(&self) -> bool {
+        !self.stiffness.is_zero() || !self.damping.is_zero()
+    }
+
+    /// The torque currently exerted by the spring and the damper.
+    ///
+    /// This is expressed in the local coordinate system of the parent multibody link, and computed
+    /// from the joint velocity at the last kinematic update of the multibody.
+    pub fn spring_torque(&self) -> Vector3<N> {
+        self.torque(&self.angvel)
+    }
+
+    /// Enables the motor driving this joint toward its desired orientation.
+    pub fn enable_orientation_motor(&mut self) {
+        self.orientation_motor_enabled = true
+    }
+
+    /// Disables the motor driving this joint toward its desired orientation.
+    pub fn disable_orientation_motor(&mut self) {
+        self.orientation_motor_enabled = false
+    }
+
+    /// Returns `true` if the motor driving this joint toward its desired orientation is enabled.
+    pub fn is_orientation_motor_enabled(&self) -> bool {
+        self.orientation_motor_enabled
+    }
+
+    /// The relative rotation the orientation motor drives the attached multibody links toward.
+    pub fn desired_orientation(&self) -> &UnitQuaternion<N> {
+        &self.desired_orientation
+    }
+
+    /// Sets the relative rotation the orientation motor drives the attached multibody links toward.
+    pub fn set_desired_orientation(&mut self, orientation: UnitQuaternion<N>) {
+        self.desired_orientation = orientation
+    }
+
+    /// The maximum torque the orientation motor can apply along each axis.
+    pub fn max_orientation_motor_torque(&self) -> N {
+        self.max_orientation_motor_torque
+    }
+
+    /// Sets the maximum torque the orientation motor can apply along each axis.
+    pub fn set_max_orientation_motor_torque(&mut self, max_torque: N) {
+        self.max_orientation_motor_torque = max_torque
+    }
+
+    /// The stiffness and damping of the orientation motor, if it acts as a position drive.
+    pub fn orientation_motor_gains(&self) -> Option<(N, N)> {
+        self.orientation_motor_gains
+    }
+
+    /// Enables the orientation motor and makes it drive this joint toward `target` like a spring
+    /// with the given stiffness and damping, delivering at most the torque `max_torque` along each axis.
+    ///
+    /// Unlike the passive spring of this joint, the drive is solved implicitly by the constraint
+    /// solver so it remains stable for arbitrarily large stiffnesses.
+    pub fn set_orientation_motor_position(
+        &mut self,
+        target: UnitQuaternion<N>,
+        stiffness: N,
+        damping: N,
+        max_torque: N,
+    ) {
+        self.orientation_motor_enabled = true;
+        self.desired_orientation = target;
+        self.max_orientation_motor_torque = max_torque;
+        self.orientation_motor_gains = Some((stiffness, damping));
+    }
+
+    /// Makes the orientation motor reach its desired orientation in one step again, instead of acting as a position drive.
+    pub fn disable_orientation_motor_position(&mut self) {
+        self.orientation_motor_gains = None
+    }
+
+    /// The maximum torque the Coulomb friction of this joint can apply along each axis to oppose its rotation.
+    pub fn angular_friction_torque(&self) -> N {
+        self.friction_torque
+    }
+
+    /// Set the maximum torque the Coulomb friction of this joint can apply along each axis to oppose its rotation.
+    ///
+    /// Unlike damping, friction is able to keep the joint at rest under loads requiring a torque
+    /// smaller than this value. Set it to zero to disable friction.
+    pub fn set_angular_friction_torque(&mut self, torque: N) {
+        self.friction_torque = torque;
+    }
+
+    /// The axis of the child link the cone-twist limits are defined with respect to, in its local-space.
+    ///
+    /// At rest, i.e., when the relative rotation is the identity, this axis is the center of the
+    /// swing cone. It defaults to the `x` axis.
+    pub fn limit_axis(&self) -> &Unit<Vector3<N>> {
+        &self.limit_axis
+    }
+
+    /// Sets the axis of the child link the cone-twist limits are defined with respect to, in its local-space.
+    pub fn set_limit_axis(&mut self, axis: Unit<Vector3<N>>) {
+        self.limit_axis = axis
+    }
+
+    /// The half-angle of the cone the limit axis is restricted to, if any.
+    pub fn max_swing_angle(&self) -> Option<N> {
+        self.max_swing_angle
+    }
+
+    /// Enable and set the half-angle of the cone the limit axis is restricted to.
+    pub fn enable_max_swing_angle(&mut self, limit: N) {
+        assert!(
+            limit >= N::zero(),
+            "BallJoint joint limits: the max swing angle must be positive."
+        );
+        self.max_swing_angle = Some(limit);
+    }
+
+    /// Disable the swing limit of this joint.
+    pub fn disable_max_swing_angle(&mut self) {
+        self.max_swing_angle = None;
+    }
+
+    /// The lower limit of the twist angle.
+    pub fn min_twist_angle(&self) -> Option<N> {
+        self.min_twist_angle
+    }
+
+    /// The upper limit of the twist angle.
+    pub fn max_twist_angle(&self) -> Option<N> {
+        self.max_twist_angle
+    }
+
+    /// Enable and set the lower limit of the twist angle.
+    pub fn enable_min_twist_angle(&mut self, limit: N) {
+        self.min_twist_angle = Some(limit);
+        self.assert_limits();
+    }
+
+    /// Enable and set the upper limit of the twist angle.
+    pub fn enable_max_twist_angle(&mut self, limit: N) {
+        self.max_twist_angle = Some(limit);
+        self.assert_limits();
+    }
+
+    /// Disable the lower limit of the twist angle.
+    pub fn disable_min_twist_angle(&mut self) {
+        self.min_twist_angle = None;
+    }
+
+    /// Disable the upper limit of the twist angle.
+    pub fn disable_max_twist_angle(&mut self) {
+        self.max_twist_angle = None;
+    }
+
+    /// The angle between the limit axis and its rest direction, in `[0, pi]`.
+    pub fn swing_angle(&self) -> N {
+        self.limit_axis.angle(&self.twist_axis())
+    }
+
+    /// The angle of the rotation around the limit axis, in `[-pi, pi]`.
+    pub fn twist_angle(&self) -> N {
+        let axis = self.limit_axis.as_ref();
+        let q = self.rot.as_ref();
+        let half_angle = q.imag().dot(axis).atan2(q.scalar());
+        let angle = half_angle + half_angle;
+
+        if angle > N::pi() {
+            angle - N::two_pi()
+        } else if angle < -N::pi() {
+            angle + N::two_pi()
+        } else {
+            angle
+        }
+    }
+
+    fn assert_limits(&self) {
+        if let (Some(min_angle), Some(max_angle)) = (self.min_twist_angle, self.max_twist_angle) {
+            assert!(
+                min_angle <= max_angle,
+                "BallJoint joint limits: the min twist angle must be smaller than (or equal to) the max twist angle.");
         }
     }
+
+    // The rotation axis along which the swing angle increases, if it is well-defined.
+    fn swing_axis(&self) -> Option<Unit<Vector3<N>>> {
+        Unit::try_new(
+            self.limit_axis.cross(&self.twist_axis()),
+            N::default_epsilon(),
+        )
+    }
+
+    // The axis, in the frame of the parent link, along which the twist angle increases.
+    fn twist_axis(&self) -> Vector3<N> {
+        self.rot * self.limit_axis.into_inner()
+    }
+
+    // The limited axes along which the relative angular velocity must be non-negative, with the
+    // positional error of the corresponding limit and its impulse offset. The spring and the motor
+    // only use the first two impulses of each degree of freedom, so the limits use the third ones.
+    fn active_limits(&self) -> [Option<(Vector3<N>, N, usize)>; 3] {
+        let mut limits = [None; 3];
+
+        if let Some(max_swing_angle) = self.max_swing_angle {
+            let err = self.swing_angle() - max_swing_angle;
+
+            if err >= N::zero() {
+                if let Some(axis) = self.swing_axis() {
+                    limits[0] = Some((-axis.into_inner(), err, 2));
+                }
+            }
+        }
+
+        let twist_angle = self.twist_angle();
+
+        if let Some(min_twist_angle) = self.min_twist_angle {
+            let err = min_twist_angle - twist_angle;
+
+            if err >= N::zero() {
+                limits[1] = Some((self.twist_axis(), err, 5));
+            }
+        }
+
+        if let Some(max_twist_angle) = self.max_twist_angle {
+            let err = twist_angle - max_twist_angle;
+
+            if err >= N::zero() {
+                limits[2] = Some((-self.twist_axis(), err, 8));
+            }
+        }
+
+        limits
+    }
+
+    // Computes `M^-1 J^t` for the jacobian `J` applying the unit torque `axis` at this joint, and returns `J M^-1 J^t`.
+    fn inv_mass_mul_torque(
+        multibody: &Multibody<N>,
+        link: &MultibodyLink<N>,
+        dof_id: usize,
+        axis: &Vector3<N>,
+        out: &mut [N],
+    ) -> N {
+        let mut force = DVector::zeros(link.joint().ndofs());
+        force.fixed_rows_mut::<U3>(dof_id).copy_from(axis);
+        multibody.inv_mass_mul_joint_force(
+            link,
+            DVectorSlice::from_slice(force.as_slice(), force.len()),
+            out,
+        );
+
+        axis.dot(&VectorSlice3::from_slice(&out[link.assembly_id + dof_id..]))
+    }
+
+    // The angular velocity the orientation motor attempts to reach during the next resolution and
+    // the constraint force mixing softening it, like `JointMotor::drive`.
+    fn orientation_drive(&self, dt: N) -> Option<(Vector3<N>, N)> {
+        let error = (self.desired_orientation * self.rot.inverse()).scaled_axis();
+
+        match self.orientation_motor_gains {
+            Some((stiffness, damping)) => {
+                let denominator = damping + dt * stiffness;
+
+                if denominator.is_zero() {
+                    None
+                } else {
+                    let angvel = error * (stiffness / denominator);
+                    Some((angvel, N::one() / (dt * denominator)))
+                }
+            }
+            None => Some((error / dt, N::zero())),
+        }
+    }
+
+    fn torque(&self, angvel: &Vector3<N>) -> Vector3<N> {
+        let error = (self.rot * self.target_rotation.inverse()).scaled_axis();
+        error * -self.stiffness - angvel * self.damping
+    }
 }
 
 impl<N: RealField> Joint<N> for BallJoint<N> {
@@ -43,6 +407,8 @@ impl<N: RealField> Joint<N> for BallJoint<N> {
         let shift = self.rot * -body_shift;
         let angvel = VectorSlice3::from_slice(vels);
 
+        self.angvel = angvel.into_owned();
+
         self.jacobian_v = shift.gcross_matrix_tr();
         self.jacobian_dot_v = angvel.cross(&shift).gcross_matrix_tr();
     }
@@ -105,4 +471,345 @@ impl<N: RealField> Joint<N> for BallJoint<N> {
     fn clone(&self) -> Box<dyn Joint<N>> {
         Box::new(*self)
     }
+
+    fn num_velocity_constraints(&self) -> usize {
+        let mut num = 0;
+
+        if self.is_spring_enabled() {
+            num += 3;
+        }
+
+        if self.orientation_motor_enabled {
+            num += 3;
+        }
+
+        if self.friction_torque > N::zero() {
+            num += 3;
+        }
+
+        if self.max_swing_angle.is_some() {
+            num += 1;
+        }
+
+        if self.min_twist_angle.is_some() {
+            num += 1;
+        }
+
+        if self.max_twist_angle.is_some() {
+            num += 1;
+        }
+
+        num
+    }
+
+    fn velocity_constraints(
+        &self,
+        parameters: &IntegrationParameters<N>,
+        multibody: &Multibody<N>,
+        link: &MultibodyLink<N>,
+        assembly_id: usize,
+        dof_id: usize,
+        ext_vels: &[N],
+        ground_j_id: &mut usize,
+        jacobians: &mut [N],
+        constraints: &mut ConstraintSet<N, (), (), usize>,
+    ) {
+        let ndofs = multibody.ndofs();
+        let angvel = multibody
+            .joint_velocity(link)
+            .fixed_rows::<U3>(dof_id)
+            .into_owned();
+
+        /*
+         * The orientation motor, reaching the desired orientation in one step unless this
+         * requires a torque larger than the maximum. A position drive is instead the soft
+         * constraint of an implicit torsion spring.
+         */
+        let orientation_drive = if self.orientation_motor_enabled {
+            self.orientation_drive(parameters.dt())
+        } else {
+            None
+        };
+
+        if let Some((desired_angvel, cfm)) = orientation_drive {
+            let impulses = multibody.impulses();
+            let max_impulse = self.max_orientation_motor_torque * parameters.dt();
+
+            for i in 0..3 {
+                let dvel = angvel[i] + ext_vels[link.assembly_id + dof_id + i];
+
+                DVectorSliceMut::from_slice(&mut jacobians[*ground_j_id..], ndofs).fill(N::zero());
+                jacobians[*ground_j_id + link.assembly_id + dof_id + i] = N::one();
+
+                let wj_id = *ground_j_id + ndofs;
+                multibody.inv_mass_mul_unit_joint_force(
+                    link,
+                    dof_id + i,
+                    N::one(),
+                    &mut jacobians[wj_id..],
+                );
+
+                let inv_r = jacobians[wj_id + link.assembly_id + dof_id + i]; // = J^t * M^-1 J
+                let impulse_id = link.impulse_id + (dof_id + i) * 3 + 1;
+
+                let constraint = BilateralGroundConstraint {
+                    impulse: impulses[impulse_id] * parameters.warmstart_coeff,
+                    r: N::one() / inv_r,
+                    rhs: dvel - desired_angvel[i],
+                    cfm,
+                    limits: ImpulseLimits::Independent {
+                        min: -max_impulse,
+                        max: max_impulse,
+                    },
+                    impulse_id,
+                    assembly_id,
+                    j_id: *ground_j_id,
+                    wj_id,
+                    ndofs,
+                };
+
+                constraints.velocity.bilateral_ground.push(constraint);
+                *ground_j_id += 2 * ndofs;
+            }
+        }
+
+        /*
+         * The Coulomb friction, attempting to stop the joint motion with a bounded torque.
+         */
+        if self.friction_torque > N::zero() {
+            let impulses = multibody.impulses();
+            let max_impulse = self.friction_torque * parameters.dt();
+
+            for i in 0..3 {
+                let dvel = angvel[i] + ext_vels[link.assembly_id + dof_id + i];
+
+                DVectorSliceMut::from_slice(&mut jacobians[*ground_j_id..], ndofs).fill(N::zero());
+                jacobians[*ground_j_id + link.assembly_id + dof_id + i] = N::one();
+
+                let wj_id = *ground_j_id + ndofs;
+                multibody.inv_mass_mul_unit_joint_force(
+                    link,
+                    dof_id + i,
+                    N::one(),
+                    &mut jacobians[wj_id..],
+                );
+
+                let inv_r = jacobians[wj_id + link.assembly_id + dof_id + i]; // = J^t * M^-1 J
+
+                // The friction impulses are stored after the motor and limit impulses of every degree of freedom of the joint.
+                let impulse_id = link.impulse_id + self.ndofs() * 3 + dof_id + i;
+
+                let constraint = BilateralGroundConstraint {
+                    impulse: impulses[impulse_id] * parameters.warmstart_coeff,
+                    r: N::one() / inv_r,
+                    rhs: dvel,
+                    cfm: N::zero(),
+                    limits: ImpulseLimits::Independent {
+                        min: -max_impulse,
+                        max: max_impulse,
+                    },
+                    impulse_id,
+                    assembly_id,
+                    j_id: *ground_j_id,
+                    wj_id,
+                    ndofs,
+                };
+
+                constraints.velocity.bilateral_ground.push(constraint);
+                *ground_j_id += 2 * ndofs;
+            }
+        }
+
+        /*
+         * The cone-twist limits, preventing the relative angular velocity from moving the joint
+         * further beyond them.
+         */
+        for (axis, _, impulse_offset) in self.active_limits().iter().filter_map(|l| *l) {
+            let dvel = axis.dot(&angvel)
+                + axis.dot(&VectorSlice3::from_slice(
+                    &ext_vels[link.assembly_id + dof_id..],
+                ));
+
+            DVectorSliceMut::from_slice(&mut jacobians[*ground_j_id..], ndofs).fill(N::zero());
+            jacobians[*ground_j_id + link.assembly_id + dof_id..][..3]
+                .copy_from_slice(axis.as_slice());
+
+            let wj_id = *ground_j_id + ndofs;
+            let inv_r =
+                Self::inv_mass_mul_torque(multibody, link, dof_id, &axis, &mut jacobians[wj_id..]); // = J^t * M^-1 J
+            let impulse_id = link.impulse_id + dof_id * 3 + impulse_offset;
+
+            let constraint = UnilateralGroundConstraint {
+                impulse: multibody.impulses()[impulse_id] * parameters.warmstart_coeff,
+                max_impulse: N::max_value(),
+                r: N::one() / inv_r,
+                rhs: dvel,
+                impulse_id,
+                assembly_id,
+                j_id: *ground_j_id,
+                wj_id,
+                ndofs,
+            };
+
+            constraints.velocity.unilateral_ground.push(constraint);
+            *ground_j_id += 2 * ndofs;
+        }
+
+        if !self.is_spring_enabled() {
+            return;
+        }
+
+        /*
+         * The spring torque, applied as constraints with fixed impulses.
+         */
+        let torque = self.torque(&angvel);
+
+        for i in 0..3 {
+            let impulse = torque[i] * parameters.dt();
+
+            DVectorSliceMut::from_slice(&mut jacobians[*ground_j_id..], ndofs).fill(N::zero());
+            jacobians[*ground_j_id + link.assembly_id + dof_id + i] = N::one();
+
+            let wj_id = *ground_j_id + ndofs;
+            multibody.inv_mass_mul_unit_joint_force(
+                link,
+                dof_id + i,
+                N::one(),
+                &mut jacobians[wj_id..],
+            );
+
+            let inv_r = jacobians[wj_id + link.assembly_id + dof_id + i]; // = J^t * M^-1 J
+
+            let constraint = BilateralGroundConstraint {
+                impulse,
+                r: N::one() / inv_r,
+                rhs: N::zero(),
+                cfm: N::zero(),
+                limits: ImpulseLimits::Independent {
+                    min: impulse,
+                    max: impulse,
+                },
+                impulse_id: link.impulse_id + (dof_id + i) * 3,
+                assembly_id,
+                j_id: *ground_j_id,
+                wj_id,
+                ndofs,
+            };
+
+            constraints.velocity.bilateral_ground.push(constraint);
+            *ground_j_id += 2 * ndofs;
+        }
+    }
+
+    fn num_position_constraints(&self) -> usize {
+        if self.max_swing_angle.is_some()
+            || self.min_twist_angle.is_some()
+            || self.max_twist_angle.is_some()
+        {
+            3
+        } else {
+            0
+        }
+    }
+
+    fn position_constraint(
+        &self,
+        i: usize,
+        multibody: &Multibody<N>,
+        link: &MultibodyLink<N>,
+        handle: BodyPartHandle<()>,
+        dof_id: usize,
+        jacobians: &mut [N],
+    ) -> Option<GenericNonlinearConstraint<N, ()>> {
+        let (axis, err, _) = self.active_limits()[i]?;
+
+        if err.is_zero() {
+            return None;
+        }
+
+        let inv_r = Self::inv_mass_mul_torque(multibody, link, dof_id, &axis, jacobians); // = J^t * M^-1 J
+
+        Some(GenericNonlinearConstraint::new(
+            handle,
+            None,
+            true,
+            multibody.ndofs(),
+            0,
+            0,
+            0,
+            -err,
+            N::one() / inv_r,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use na::{UnitQuaternion, Vector3};
+    use ncollide::shape::Ball;
+
+    use super::BallJoint;
+    use crate::force_generator::DefaultForceGeneratorSet;
+    use crate::joint::{DefaultJointConstraintSet, FixedJoint};
+    use crate::math::Isometry;
+    use crate::object::{DefaultBodySet, DefaultColliderSet, MultibodyDesc};
+    use crate::volumetric::Volumetric;
+    use crate::world::{DefaultGeometricalWorld, DefaultMechanicalWorld};
+
+    // Drives the ball joint toward `target` with a bounded position drive for two seconds.
+    fn drive_toward(mut joint: BallJoint<f64>, target: UnitQuaternion<f64>) -> BallJoint<f64> {
+        let mut mechanical_world = DefaultMechanicalWorld::new(Vector3::zeros());
+        let mut geometrical_world = DefaultGeometricalWorld::new();
+        let mut bodies = DefaultBodySet::new();
+        let mut colliders = DefaultColliderSet::new();
+        let mut constraints = DefaultJointConstraintSet::new();
+        let mut forces = DefaultForceGeneratorSet::new();
+
+        joint.set_orientation_motor_position(target, 100.0, 10.0, 5.0);
+
+        let mut desc = MultibodyDesc::new(FixedJoint::new(Isometry::identity()));
+        let _ = desc
+            .add_child(joint)
+            .set_local_inertia(Ball::new(0.5).inertia(1.0));
+        let handle = bodies.insert(desc.build());
+
+        for _ in 0..120 {
+            mechanical_world.step(
+                &mut geometrical_world,
+                &mut bodies,
+                &mut colliders,
+                &mut constraints,
+                &mut forces,
+            );
+        }
+
+        let link = bodies.multibody(handle).unwrap().link(1).unwrap();
+        *link.joint().downcast_ref::<BallJoint<f64>>().unwrap()
+    }
+
+    #[test]
+    fn swing_is_clamped_to_the_cone() {
+        let mut joint = BallJoint::new(Vector3::zeros());
+        joint.enable_max_swing_angle(0.5);
+
+        // Swinging the limit axis `x` by one radian around `z`.
+        let target = UnitQuaternion::from_axis_angle(&Vector3::z_axis(), 1.0);
+        let swing = drive_toward(joint, target).swing_angle();
+        assert_relative_eq!(swing, 0.5, epsilon = 1.0e-2);
+    }
+
+    #[test]
+    fn twist_is_clamped_to_its_limits() {
+        let mut joint = BallJoint::new(Vector3::zeros());
+        joint.enable_min_twist_angle(-0.3);
+        joint.enable_max_twist_angle(0.3);
+
+        // Twisting around the limit axis `x` by one radian in both directions.
+        for &angle in &[1.0, -1.0] {
+            let target = UnitQuaternion::from_axis_angle(&Vector3::x_axis(), angle);
+            let joint = drive_toward(joint, target);
+            assert!(joint.swing_angle() < 0.01);
+            assert_relative_eq!(joint.twist_angle(), 0.3 * angle, epsilon = 1.0e-2);
+        }
+    }
 }
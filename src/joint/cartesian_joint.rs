@@ -7,6 +7,7 @@ use crate::solver::IntegrationParameters;
 
 /// A joint that allows only all the translational degrees of freedom between two multibody links.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct CartesianJoint<N: RealField> {
     position: Vector<N>,
 }
@@ -1,7 +1,7 @@
 use na::{DVector, RealField};
 use std::ops::Range;
 
-use crate::joint::JointConstraint;
+use crate::joint::{JointConstraint, JointPositionError};
 use crate::math::{AngularVector, Point, Rotation, Vector, DIM, SPATIAL_DIM};
 use crate::object::{BodyHandle, BodyPartHandle, BodySet};
 use crate::solver::helper;
@@ -10,6 +10,11 @@ use crate::solver::{
     NonlinearConstraintGenerator,
 };
 
+/// A constraint welding two body parts together.
+///
+/// This is an alias for `FixedConstraint`.
+pub type WeldConstraint<N, Handle> = FixedConstraint<N, Handle>;
+
 /// A constraint that removes all degrees of freedom between two body parts.
 pub struct FixedConstraint<N: RealField, Handle: BodyHandle> {
     b1: BodyPartHandle<Handle>,
@@ -57,6 +62,36 @@ impl<N: RealField, Handle: BodyHandle> FixedConstraint<N, Handle> {
         }
     }
 
+    /// Create a fixed constraint welding two body parts at their current relative position.
+    ///
+    /// Returns `None` if one of the body parts does not exist. The joint frame is located at the
+    /// origin of the first body part. The constraint can be broken (see `set_break_force`) or
+    /// removed from its joint constraint set to separate the two body parts again.
+    pub fn weld(
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        b1: BodyPartHandle<Handle>,
+        b2: BodyPartHandle<Handle>,
+    ) -> Option<Self> {
+        let body1 = bodies.get(b1.0)?;
+        let body2 = bodies.get(b2.0)?;
+        let part1 = body1.part(b1.1)?;
+        let part2 = body2.part(b2.1)?;
+
+        let anchor1 = Point::origin();
+        let world_anchor = body1.world_point_at_material_point(part1, &anchor1);
+        let anchor2 = body2.material_point_at_world_point(part2, &world_anchor);
+        let ref_frame2 = part2.position().rotation.inverse() * part1.position().rotation;
+
+        Some(Self::new(
+            b1,
+            b2,
+            anchor1,
+            Rotation::identity(),
+            anchor2,
+            ref_frame2,
+        ))
+    }
+
     /// Changes the reference frame for the first body part.
     pub fn set_reference_frame_1(&mut self, ref_frame1: Rotation<N>) {
         self.ref_frame1 = ref_frame1
@@ -101,6 +136,35 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for FixedConst
         (self.b1, self.b2)
     }
 
+    fn can_scale_space(&self) -> bool {
+        true
+    }
+
+    fn scale_space(&mut self, factor: N) {
+        let factor2 = factor * factor;
+        self.anchor1 *= factor;
+        self.anchor2 *= factor;
+        self.lin_impulses *= factor;
+        self.ang_impulses *= factor2;
+        self.break_force_squared *= factor2;
+        self.break_torque_squared *= factor2 * factor2;
+    }
+
+    fn position_error(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> JointPositionError<N> {
+        let body1 = try_ret!(bodies.get(self.b1.0), JointPositionError::zero());
+        let body2 = try_ret!(bodies.get(self.b2.0), JointPositionError::zero());
+        let part1 = try_ret!(body1.part(self.b1.1), JointPositionError::zero());
+        let part2 = try_ret!(body2.part(self.b2.1), JointPositionError::zero());
+
+        let pos1 = body1.position_at_material_point(part1, &self.anchor1) * self.ref_frame1;
+        let pos2 = body2.position_at_material_point(part2, &self.anchor2) * self.ref_frame2;
+
+        JointPositionError {
+            linear: (pos2.translation.vector - pos1.translation.vector).norm(),
+            angular: (pos2.rotation / pos1.rotation).scaled_axis().norm(),
+        }
+    }
+
     fn velocity_constraints(
         &mut self,
         _: &IntegrationParameters<N>,
@@ -9,6 +9,7 @@ use crate::solver::IntegrationParameters;
 ///
 /// This joint can only be added between a `Ground` body (as parent) and any other body.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct FreeJoint<N: RealField> {
     position: Isometry<N>,
 }
@@ -0,0 +1,447 @@
+use na::{DVector, DVectorSlice, DVectorSliceMut, RealField};
+use std::ops::Range;
+
+use crate::joint::{JointConstraint, JointPositionError, PrismaticJoint, RevoluteJoint, UnitJoint};
+use crate::object::{Body, BodyHandle, BodyPartHandle, BodySet, Multibody};
+use crate::solver::{
+    BilateralConstraint, BilateralGroundConstraint, ConstraintGeometry, GenericNonlinearConstraint,
+    ImpulseLimits, IntegrationParameters, LinearConstraints, NonlinearConstraintGenerator,
+};
+
+/// A constraint coupling the generalized coordinates of the joints of two multibody links.
+///
+/// The velocity of the joint attaching the second link to its parent is constrained to be equal to
+/// `ratio` times the velocity of the joint attaching the first link to its parent, e.g., to
+/// simulate a gearbox. Use a negative ratio for meshing gears rotating in opposite directions.
+/// Both joints must be revolute or prismatic joints, and the links may belong to the same
+/// multibody or to different ones. The coupling is relative to the joint coordinates at the time
+/// the constraint is first solved.
+pub struct GearConstraint<N: RealField, Handle: BodyHandle> {
+    b1: BodyPartHandle<Handle>,
+    b2: BodyPartHandle<Handle>,
+    ratio: N,
+    reference: Option<(N, N)>,
+    impulse: N,
+    break_force: N,
+    broken: bool,
+    bilateral_ground_rng: Range<usize>,
+    bilateral_rng: Range<usize>,
+}
+
+impl<N: RealField, Handle: BodyHandle> GearConstraint<N, Handle> {
+    /// Creates a constraint making the joint of the link `b2` move `ratio` times as fast as the joint of the link `b1`.
+    pub fn new(b1: BodyPartHandle<Handle>, b2: BodyPartHandle<Handle>, ratio: N) -> Self {
+        GearConstraint {
+            b1,
+            b2,
+            ratio,
+            reference: None,
+            impulse: N::zero(),
+            break_force: N::max_value(),
+            broken: false,
+            bilateral_ground_rng: 0..0,
+            bilateral_rng: 0..0,
+        }
+    }
+
+    /// The ratio between the velocities of the second and the first joints.
+    pub fn ratio(&self) -> N {
+        self.ratio
+    }
+
+    /// Sets the ratio between the velocities of the second and the first joints.
+    ///
+    /// The coupling is reset to be relative to the current joint coordinates.
+    pub fn set_ratio(&mut self, ratio: N) {
+        self.ratio = ratio;
+        self.reference = None;
+    }
+
+    /// The maximum generalized force this joint can absorb before breaking.
+    pub fn set_break_force(&mut self, break_force: N) {
+        self.break_force = break_force;
+    }
+
+    // The current coordinates of both joints, or `None` if one of them is not a unit joint.
+    fn coordinates(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> Option<(N, N)> {
+        Some((
+            link_coordinate(bodies.get(self.b1.0)?, self.b1.1)?,
+            link_coordinate(bodies.get(self.b2.0)?, self.b2.1)?,
+        ))
+    }
+
+    // How far the coordinate of the second joint is from satisfying the coupling.
+    fn coupling_error(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> Option<N> {
+        let (ref1, ref2) = self.reference?;
+        let (coord1, coord2) = self.coordinates(bodies)?;
+        Some(coord2 - ref2 - (coord1 - ref1) * self.ratio)
+    }
+
+    // Fills the jacobians of the coupling, scaled by `sign`, and returns its geometry.
+    fn coupling_geometry(
+        &self,
+        body1: &dyn Body<N>,
+        body2: &dyn Body<N>,
+        sign: N,
+        ground_j_id: &mut usize,
+        j_id: &mut usize,
+        jacobians: &mut [N],
+        ext_vels: Option<&DVector<N>>,
+        out_vel: Option<&mut N>,
+    ) -> Option<ConstraintGeometry<N>> {
+        let mut geom = ConstraintGeometry::new();
+        geom.ndofs1 = body1.status_dependent_ndofs();
+        geom.ndofs2 = body2.status_dependent_ndofs();
+
+        let out_j_id = if geom.is_ground_constraint() {
+            ground_j_id
+        } else {
+            j_id
+        };
+
+        geom.j_id1 = *out_j_id;
+        geom.j_id2 = geom.j_id1 + geom.ndofs1;
+        geom.wj_id1 = geom.j_id2 + geom.ndofs2;
+        geom.wj_id2 = geom.wj_id1 + geom.ndofs1;
+
+        let mut inv_r = N::zero();
+        let mut vel = N::zero();
+        let ext_vels1 = ext_vels.map(|v| v.rows(body1.companion_id(), geom.ndofs1));
+        let ext_vels2 = ext_vels.map(|v| v.rows(body2.companion_id(), geom.ndofs2));
+
+        fill_link_dof_jacobian(
+            body1,
+            self.b1.1,
+            -self.ratio * sign,
+            geom.ndofs1,
+            geom.j_id1,
+            geom.wj_id1,
+            jacobians,
+            &mut inv_r,
+            ext_vels1.as_ref(),
+            &mut vel,
+        )?;
+        fill_link_dof_jacobian(
+            body2,
+            self.b2.1,
+            sign,
+            geom.ndofs2,
+            geom.j_id2,
+            geom.wj_id2,
+            jacobians,
+            &mut inv_r,
+            ext_vels2.as_ref(),
+            &mut vel,
+        )?;
+
+        if self.b1.0 == self.b2.0 {
+            let j1 = DVectorSlice::from_slice(&jacobians[geom.j_id1..], geom.ndofs1);
+            let j2 = DVectorSlice::from_slice(&jacobians[geom.j_id2..], geom.ndofs2);
+            let invm_j1 = DVectorSlice::from_slice(&jacobians[geom.wj_id1..], geom.ndofs1);
+            let invm_j2 = DVectorSlice::from_slice(&jacobians[geom.wj_id2..], geom.ndofs2);
+
+            inv_r += j2.dot(&invm_j1) + j1.dot(&invm_j2);
+        }
+
+        geom.r = if inv_r.is_zero() {
+            N::one()
+        } else {
+            N::one() / inv_r
+        };
+
+        if let Some(out_vel) = out_vel {
+            *out_vel = vel;
+        }
+
+        *out_j_id += (geom.ndofs1 + geom.ndofs2) * 2;
+        Some(geom)
+    }
+}
+
+// The generalized coordinate of the unit joint attaching the given link to its parent.
+fn link_coordinate<N: RealField>(body: &dyn Body<N>, link_id: usize) -> Option<N> {
+    let joint = body.downcast_ref::<Multibody<N>>()?.link(link_id)?.joint();
+
+    if let Some(revolute) = joint.downcast_ref::<RevoluteJoint<N>>() {
+        Some(revolute.position())
+    } else {
+        joint
+            .downcast_ref::<PrismaticJoint<N>>()
+            .map(|prismatic| prismatic.position())
+    }
+}
+
+// Whether the joint attaching the given link to its parent is a prismatic joint.
+fn link_is_prismatic<N: RealField>(body: &dyn Body<N>, link_id: usize) -> bool {
+    body.downcast_ref::<Multibody<N>>()
+        .and_then(|multibody| multibody.link(link_id))
+        .map(|link| link.joint().is::<PrismaticJoint<N>>())
+        .unwrap_or(false)
+}
+
+// Fills the jacobian of a constraint acting on the first degree of freedom of the joint attaching
+// the given link to its parent.
+fn fill_link_dof_jacobian<N: RealField>(
+    body: &dyn Body<N>,
+    link_id: usize,
+    coeff: N,
+    ndofs: usize,
+    j_id: usize,
+    wj_id: usize,
+    jacobians: &mut [N],
+    inv_r: &mut N,
+    ext_vels: Option<&DVectorSlice<N>>,
+    out_vel: &mut N,
+) -> Option<()> {
+    if ndofs == 0 {
+        return Some(());
+    }
+
+    let multibody = body.downcast_ref::<Multibody<N>>()?;
+    let link = multibody.link(link_id)?;
+    let dof_id = link.assembly_id();
+
+    DVectorSliceMut::from_slice(&mut jacobians[j_id..], ndofs).fill(N::zero());
+    jacobians[j_id + dof_id] = coeff;
+    multibody.inv_mass_mul_unit_joint_force(link, 0, coeff, &mut jacobians[wj_id..]);
+
+    *inv_r += coeff * jacobians[wj_id + dof_id];
+    *out_vel += coeff * multibody.generalized_velocity()[dof_id];
+
+    if let Some(ext_vels) = ext_vels {
+        *out_vel += coeff * ext_vels[dof_id];
+    }
+
+    Some(())
+}
+
+impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for GearConstraint<N, Handle> {
+    fn is_broken(&self) -> bool {
+        self.broken
+    }
+
+    fn num_velocity_constraints(&self) -> usize {
+        1
+    }
+
+    fn anchors(&self) -> (BodyPartHandle<Handle>, BodyPartHandle<Handle>) {
+        (self.b1, self.b2)
+    }
+
+    fn position_error(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> JointPositionError<N> {
+        let error = try_ret!(self.coupling_error(bodies), JointPositionError::zero()).abs();
+        let body2 = try_ret!(bodies.get(self.b2.0), JointPositionError::zero());
+
+        if link_is_prismatic(body2, self.b2.1) {
+            JointPositionError {
+                linear: error,
+                angular: N::zero(),
+            }
+        } else {
+            JointPositionError {
+                linear: N::zero(),
+                angular: error,
+            }
+        }
+    }
+
+    fn velocity_constraints(
+        &mut self,
+        _: &IntegrationParameters<N>,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        ext_vels: &DVector<N>,
+        ground_j_id: &mut usize,
+        j_id: &mut usize,
+        jacobians: &mut [N],
+        constraints: &mut LinearConstraints<N, usize>,
+    ) {
+        let body1 = try_ret!(bodies.get(self.b1.0));
+        let body2 = try_ret!(bodies.get(self.b2.0));
+
+        if self.reference.is_none() {
+            self.reference = self.coordinates(bodies);
+        }
+
+        let first_bilateral_ground = constraints.bilateral_ground.len();
+        let first_bilateral = constraints.bilateral.len();
+
+        let assembly_id1 = body1.companion_id();
+        let assembly_id2 = body2.companion_id();
+        let limits = ImpulseLimits::Independent {
+            min: -N::max_value(),
+            max: N::max_value(),
+        };
+        let mut rhs = N::zero();
+
+        let geom = try_ret!(self.coupling_geometry(
+            body1,
+            body2,
+            N::one(),
+            ground_j_id,
+            j_id,
+            jacobians,
+            Some(ext_vels),
+            Some(&mut rhs),
+        ));
+
+        if geom.is_ground_constraint() {
+            if geom.ndofs1 != 0 || geom.ndofs2 != 0 {
+                constraints
+                    .bilateral_ground
+                    .push(BilateralGroundConstraint::new(
+                        geom,
+                        assembly_id1,
+                        assembly_id2,
+                        limits,
+                        rhs,
+                        self.impulse,
+                        0,
+                    ));
+            }
+        } else {
+            constraints.bilateral.push(BilateralConstraint::new(
+                geom,
+                assembly_id1,
+                assembly_id2,
+                limits,
+                rhs,
+                self.impulse,
+                0,
+            ));
+        }
+
+        self.bilateral_ground_rng = first_bilateral_ground..constraints.bilateral_ground.len();
+        self.bilateral_rng = first_bilateral..constraints.bilateral.len();
+    }
+
+    fn cache_impulses(&mut self, constraints: &LinearConstraints<N, usize>, inv_dt: N) {
+        for c in &constraints.bilateral_ground[self.bilateral_ground_rng.clone()] {
+            self.impulse = c.impulse;
+        }
+
+        for c in &constraints.bilateral[self.bilateral_rng.clone()] {
+            self.impulse = c.impulse;
+        }
+
+        if self.impulse.abs() * inv_dt > self.break_force {
+            self.broken = true;
+        }
+    }
+}
+
+impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
+    for GearConstraint<N, Handle>
+{
+    fn num_position_constraints(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> usize {
+        if self.is_active(bodies) {
+            1
+        } else {
+            0
+        }
+    }
+
+    fn position_constraint(
+        &self,
+        parameters: &IntegrationParameters<N>,
+        _: usize,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+        jacobians: &mut [N],
+    ) -> Option<GenericNonlinearConstraint<N, Handle>> {
+        let error = self.coupling_error(bodies)?;
+        let body1 = bodies.get(self.b1.0)?;
+        let body2 = bodies.get(self.b2.0)?;
+        let is_angular = !link_is_prismatic(body2, self.b2.1);
+        let allowed_error = if is_angular {
+            parameters.allowed_angular_error
+        } else {
+            parameters.allowed_linear_error
+        };
+
+        if error.abs() <= allowed_error {
+            return None;
+        }
+
+        // Orient the jacobian so that a positive displacement reduces the error.
+        let sign = if error > N::zero() {
+            -N::one()
+        } else {
+            N::one()
+        };
+        let geom =
+            self.coupling_geometry(body1, body2, sign, &mut 0, &mut 0, jacobians, None, None)?;
+
+        Some(GenericNonlinearConstraint::new(
+            self.b1,
+            Some(self.b2),
+            is_angular,
+            geom.ndofs1,
+            geom.ndofs2,
+            geom.wj_id1,
+            geom.wj_id2,
+            -error.abs(),
+            geom.r,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::GearConstraint;
+    use crate::force_generator::DefaultForceGeneratorSet;
+    use crate::joint::{DefaultJointConstraintSet, FixedJoint, RevoluteJoint};
+    use crate::math::{Isometry, Vector};
+    use crate::object::{BodyPartHandle, DefaultBodySet, DefaultColliderSet, MultibodyDesc};
+    use crate::world::{DefaultGeometricalWorld, DefaultMechanicalWorld};
+
+    #[test]
+    fn driven_gear_turns_at_the_gear_ratio() {
+        let mut mechanical_world = DefaultMechanicalWorld::new(Vector::zeros());
+        let mut geometrical_world = DefaultGeometricalWorld::new();
+        let mut bodies = DefaultBodySet::new();
+        let mut colliders = DefaultColliderSet::new();
+        let mut constraints = DefaultJointConstraintSet::new();
+        let mut forces = DefaultForceGeneratorSet::new();
+
+        #[cfg(feature = "dim2")]
+        let hinge = RevoluteJoint::new(0.0);
+        #[cfg(feature = "dim3")]
+        let hinge = RevoluteJoint::new(Vector::z_axis(), 0.0);
+        let mut driver = hinge;
+        driver.enable_angular_motor();
+        driver.set_desired_angular_motor_velocity(1.0);
+
+        // Two wheels hinged on a fixed frame, the first one being driven by a motor.
+        let mut desc = MultibodyDesc::new(FixedJoint::new(Isometry::identity()));
+        let _ = desc
+            .add_child(driver)
+            .set_body_shift(Vector::x())
+            .set_mass(1.0);
+        let _ = desc
+            .add_child(hinge)
+            .set_parent_shift(Vector::x() * 3.0)
+            .set_body_shift(Vector::x())
+            .set_mass(1.0);
+        let handle = bodies.insert(desc.build());
+        let gear = GearConstraint::new(BodyPartHandle(handle, 1), BodyPartHandle(handle, 2), -2.0);
+        let _ = constraints.insert(gear);
+
+        for _ in 0..120 {
+            mechanical_world.step(
+                &mut geometrical_world,
+                &mut bodies,
+                &mut colliders,
+                &mut constraints,
+                &mut forces,
+            );
+        }
+
+        let multibody = bodies.multibody(handle).unwrap();
+        let angle = |link_id| {
+            let joint = multibody.link(link_id).unwrap().joint();
+            joint.downcast_ref::<RevoluteJoint<f64>>().unwrap().angle()
+        };
+
+        assert!(angle(1) > 1.0, "The driving wheel did not turn.");
+        assert_relative_eq!(angle(2), -2.0 * angle(1), epsilon = 1.0e-2);
+    }
+}
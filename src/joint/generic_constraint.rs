@@ -0,0 +1,623 @@
+use na::{DVector, RealField, Unit};
+use std::ops::Range;
+
+use crate::joint::{JointConstraint, JointMotor, JointPositionError};
+use crate::math::{
+    AngularVector, Isometry, Point, Rotation, SpatialVector, Vector, DIM, SPATIAL_DIM,
+};
+use crate::object::{Body, BodyHandle, BodyPart, BodyPartHandle, BodySet};
+use crate::solver::helper;
+use crate::solver::{
+    BilateralConstraint, BilateralGroundConstraint, ForceDirection, GenericNonlinearConstraint,
+    ImpulseLimits, IntegrationParameters, LinearConstraints, NonlinearConstraintGenerator,
+};
+
+/// One of the degrees of freedom of a generic joint, expressed in the joint frame of the first body part.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum JointAxis {
+    /// The translation along the `x` axis.
+    LinX,
+    /// The translation along the `y` axis.
+    LinY,
+    /// The translation along the `z` axis.
+    #[cfg(feature = "dim3")]
+    LinZ,
+    /// The rotation about the `x` axis.
+    #[cfg(feature = "dim3")]
+    AngX,
+    /// The rotation about the `y` axis.
+    #[cfg(feature = "dim3")]
+    AngY,
+    /// The rotation about the `z` axis.
+    AngZ,
+}
+
+impl JointAxis {
+    /// All the degrees of freedom, in the order of their indices.
+    #[cfg(feature = "dim2")]
+    pub const ALL: [JointAxis; SPATIAL_DIM] = [JointAxis::LinX, JointAxis::LinY, JointAxis::AngZ];
+
+    /// All the degrees of freedom, in the order of their indices.
+    #[cfg(feature = "dim3")]
+    pub const ALL: [JointAxis; SPATIAL_DIM] = [
+        JointAxis::LinX,
+        JointAxis::LinY,
+        JointAxis::LinZ,
+        JointAxis::AngX,
+        JointAxis::AngY,
+        JointAxis::AngZ,
+    ];
+
+    /// The index of this degree of freedom.
+    ///
+    /// Translational degrees of freedom come first, followed by the rotational ones.
+    pub fn index(self) -> usize {
+        match self {
+            JointAxis::LinX => 0,
+            JointAxis::LinY => 1,
+            #[cfg(feature = "dim3")]
+            JointAxis::LinZ => 2,
+            #[cfg(feature = "dim3")]
+            JointAxis::AngX => 3,
+            #[cfg(feature = "dim3")]
+            JointAxis::AngY => 4,
+            JointAxis::AngZ => SPATIAL_DIM - 1,
+        }
+    }
+
+    /// Whether this is a translational degree of freedom.
+    pub fn is_linear(self) -> bool {
+        self.index() < DIM
+    }
+}
+
+/// The way one degree of freedom of a generic joint is constrained.
+#[derive(Copy, Clone, Debug)]
+pub enum JointAxisState<N: RealField> {
+    /// The degree of freedom is not constrained.
+    Free,
+    /// The degree of freedom is completely removed.
+    Locked,
+    /// The relative position along this degree of freedom is restricted to `[min, max]`.
+    Limited {
+        /// The lower limit.
+        min: N,
+        /// The upper limit.
+        max: N,
+    },
+    /// The relative velocity along this degree of freedom is driven by a motor.
+    Motorized(JointMotor<N, N>),
+}
+
+impl<N: RealField> JointAxisState<N> {
+    /// The range the relative position along this degree of freedom is restricted to, if any.
+    pub fn position_range(&self) -> Option<(N, N)> {
+        match *self {
+            JointAxisState::Locked => Some((N::zero(), N::zero())),
+            JointAxisState::Limited { min, max } => Some((min, max)),
+            JointAxisState::Free | JointAxisState::Motorized(_) => None,
+        }
+    }
+}
+
+/// A constraint where each degree of freedom can be independently locked, limited, motorized, or left free.
+///
+/// The degrees of freedom are expressed in the joint frame attached to the first body part.
+/// Relative rotations are measured using the components of the scaled rotation axis of the
+/// second joint frame wrt. the first one. This coincides with the angle about each axis as long
+/// as at most one rotational degree of freedom is free or the relative rotation remains small.
+pub struct GenericConstraint<N: RealField, Handle: BodyHandle> {
+    b1: BodyPartHandle<Handle>,
+    b2: BodyPartHandle<Handle>,
+    anchor1: Point<N>,
+    ref_frame1: Rotation<N>,
+    anchor2: Point<N>,
+    ref_frame2: Rotation<N>,
+    axes: [JointAxisState<N>; SPATIAL_DIM],
+    impulses: SpatialVector<N>,
+    break_force_squared: N,
+    break_torque_squared: N,
+    broken: bool,
+    bilateral_ground_rng: Range<usize>,
+    bilateral_rng: Range<usize>,
+}
+
+impl<N: RealField, Handle: BodyHandle> GenericConstraint<N, Handle> {
+    /// Create a generic constraint between two body parts with all its degrees of freedom locked.
+    ///
+    /// The joint frames are located at `anchor1` and `anchor2` with the orientations `ref_frame1`
+    /// and `ref_frame2` wrt. the body parts `b1` and `b2` respectively.
+    pub fn new(
+        b1: BodyPartHandle<Handle>,
+        b2: BodyPartHandle<Handle>,
+        anchor1: Point<N>,
+        ref_frame1: Rotation<N>,
+        anchor2: Point<N>,
+        ref_frame2: Rotation<N>,
+    ) -> Self {
+        GenericConstraint {
+            b1,
+            b2,
+            anchor1,
+            ref_frame1,
+            anchor2,
+            ref_frame2,
+            axes: [JointAxisState::Locked; SPATIAL_DIM],
+            impulses: SpatialVector::zeros(),
+            break_force_squared: N::max_value(),
+            break_torque_squared: N::max_value(),
+            broken: false,
+            bilateral_ground_rng: 0..0,
+            bilateral_rng: 0..0,
+        }
+    }
+
+    /// The state of the given degree of freedom.
+    pub fn axis_state(&self, axis: JointAxis) -> &JointAxisState<N> {
+        &self.axes[axis.index()]
+    }
+
+    /// Sets the state of the given degree of freedom.
+    pub fn set_axis_state(&mut self, axis: JointAxis, state: JointAxisState<N>) {
+        if let JointAxisState::Limited { min, max } = state {
+            assert!(
+                min <= max,
+                "GenericConstraint limits: the min limit must be smaller than (or equal to) the max limit."
+            );
+        }
+
+        self.axes[axis.index()] = state;
+    }
+
+    /// Removes the given degree of freedom.
+    pub fn lock_axis(&mut self, axis: JointAxis) {
+        self.set_axis_state(axis, JointAxisState::Locked)
+    }
+
+    /// Leaves the given degree of freedom unconstrained.
+    pub fn free_axis(&mut self, axis: JointAxis) {
+        self.set_axis_state(axis, JointAxisState::Free)
+    }
+
+    /// Restricts the relative position along the given degree of freedom to `[min, max]`.
+    pub fn set_limits(&mut self, axis: JointAxis, min: N, max: N) {
+        self.set_axis_state(axis, JointAxisState::Limited { min, max })
+    }
+
+    /// Drives the relative velocity along the given degree of freedom to `desired_velocity`
+    /// using at most the force (or torque) `max_force`.
+    ///
+    /// If this degree of freedom was already motorized, its acceleration limit is kept and the
+    /// velocity targeted by the motor ramps from its current value.
+    pub fn set_motor(&mut self, axis: JointAxis, desired_velocity: N, max_force: N) {
+        let mut motor = self.motor(axis);
+        motor.desired_velocity = desired_velocity;
+        motor.max_force = max_force;
+        motor.target_position = None;
+        self.set_axis_state(axis, JointAxisState::Motorized(motor))
+    }
+
+    /// Drives the relative position along the given degree of freedom toward `target_position`
+    /// like a spring of the given stiffness and damping, using at most the force (or torque) `max_force`.
+    ///
+    /// The drive is solved by the constraint solver, and remains stable for any stiffness. The
+    /// relative velocity it aims for is the desired velocity of the motor, zero by default.
+    pub fn set_motor_position(
+        &mut self,
+        axis: JointAxis,
+        target_position: N,
+        stiffness: N,
+        damping: N,
+        max_force: N,
+    ) {
+        let mut motor = self.motor(axis);
+        motor.set_position_drive(target_position, stiffness, damping, max_force);
+        self.set_axis_state(axis, JointAxisState::Motorized(motor))
+    }
+
+    // The enabled motor of the given degree of freedom, keeping its current state if it is already motorized.
+    fn motor(&self, axis: JointAxis) -> JointMotor<N, N> {
+        match self.axes[axis.index()] {
+            JointAxisState::Motorized(motor) => JointMotor {
+                enabled: true,
+                ..motor
+            },
+            _ => JointMotor {
+                enabled: true,
+                ..JointMotor::new()
+            },
+        }
+    }
+
+    /// Limits the rate of change of the velocity targeted by the motor of the given degree of freedom.
+    ///
+    /// The velocity targeted by the motor then ramps toward its desired velocity instead of
+    /// jumping to it. This has no effect if the given degree of freedom is not motorized.
+    pub fn set_motor_max_acceleration(&mut self, axis: JointAxis, max_acceleration: Option<N>) {
+        if let JointAxisState::Motorized(motor) = &mut self.axes[axis.index()] {
+            motor.max_acceleration = max_acceleration;
+        }
+    }
+
+    /// The velocity the motor of the given degree of freedom attempts to reach, if it is motorized.
+    ///
+    /// This differs from its desired velocity while it ramps toward it.
+    pub fn effective_motor_velocity(&self, axis: JointAxis) -> Option<N> {
+        match &self.axes[axis.index()] {
+            JointAxisState::Motorized(motor) => Some(motor.target_velocity()),
+            _ => None,
+        }
+    }
+
+    /// Changes the reference frame for the first body part.
+    pub fn set_reference_frame_1(&mut self, ref_frame1: Rotation<N>) {
+        self.ref_frame1 = ref_frame1
+    }
+
+    /// Changes the reference frame for the second body part.
+    pub fn set_reference_frame_2(&mut self, ref_frame2: Rotation<N>) {
+        self.ref_frame2 = ref_frame2
+    }
+
+    /// Changes the attached material point from the first body part.
+    pub fn set_anchor_1(&mut self, anchor1: Point<N>) {
+        self.anchor1 = anchor1
+    }
+
+    /// Changes the attached material point from the second body part.
+    pub fn set_anchor_2(&mut self, anchor2: Point<N>) {
+        self.anchor2 = anchor2
+    }
+
+    /// The maximum force this joint can absorb before breaking.
+    pub fn set_break_force(&mut self, break_force: N) {
+        self.break_force_squared = break_force * break_force;
+    }
+
+    /// The maximum torque this joint can absorb before breaking.
+    pub fn set_break_torque(&mut self, break_torque: N) {
+        self.break_torque_squared = break_torque * break_torque;
+    }
+
+    fn joint_frames(
+        &self,
+        body1: &dyn Body<N>,
+        part1: &dyn BodyPart<N>,
+        body2: &dyn Body<N>,
+        part2: &dyn BodyPart<N>,
+    ) -> (Isometry<N>, Isometry<N>) {
+        let pos1 = body1.position_at_material_point(part1, &self.anchor1) * self.ref_frame1;
+        let pos2 = body2.position_at_material_point(part2, &self.anchor2) * self.ref_frame2;
+        (pos1, pos2)
+    }
+}
+
+/// The world-space direction of the `i`-th degree of freedom, and the relative position along it.
+fn dof_geometry<N: RealField>(
+    pos1: &Isometry<N>,
+    pos2: &Isometry<N>,
+    i: usize,
+) -> (ForceDirection<N>, N) {
+    if i < DIM {
+        let axis = pos1 * Unit::new_unchecked(Vector::ith(i, N::one()));
+        let offset = axis.dot(&(pos2.translation.vector - pos1.translation.vector));
+        (ForceDirection::Linear(axis), offset)
+    } else {
+        let axis = angular_axis(pos1, i - DIM);
+        let angle = axis.dot(&(pos2.rotation / pos1.rotation).scaled_axis());
+        (ForceDirection::Angular(axis), angle)
+    }
+}
+
+#[cfg(feature = "dim2")]
+fn angular_axis<N: RealField>(_: &Isometry<N>, _: usize) -> Unit<AngularVector<N>> {
+    Unit::new_unchecked(AngularVector::x())
+}
+
+#[cfg(feature = "dim3")]
+fn angular_axis<N: RealField>(pos1: &Isometry<N>, i: usize) -> Unit<AngularVector<N>> {
+    pos1 * Unit::new_unchecked(AngularVector::ith(i, N::one()))
+}
+
+impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for GenericConstraint<N, Handle> {
+    fn is_broken(&self) -> bool {
+        self.broken
+    }
+
+    fn num_velocity_constraints(&self) -> usize {
+        self.axes
+            .iter()
+            .filter(|state| match state {
+                JointAxisState::Free => false,
+                JointAxisState::Motorized(motor) => motor.enabled,
+                JointAxisState::Locked | JointAxisState::Limited { .. } => true,
+            })
+            .count()
+    }
+
+    fn anchors(&self) -> (BodyPartHandle<Handle>, BodyPartHandle<Handle>) {
+        (self.b1, self.b2)
+    }
+
+    fn position_error(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> JointPositionError<N> {
+        let body1 = try_ret!(bodies.get(self.b1.0), JointPositionError::zero());
+        let body2 = try_ret!(bodies.get(self.b2.0), JointPositionError::zero());
+        let part1 = try_ret!(body1.part(self.b1.1), JointPositionError::zero());
+        let part2 = try_ret!(body2.part(self.b2.1), JointPositionError::zero());
+
+        let (pos1, pos2) = self.joint_frames(body1, part1, body2, part2);
+        let mut lin_error2 = N::zero();
+        let mut ang_error2 = N::zero();
+
+        for (dof, state) in self.axes.iter().enumerate() {
+            if let Some((min, max)) = state.position_range() {
+                let (_, offset) = dof_geometry(&pos1, &pos2, dof);
+                let error = (min - offset).max(offset - max).max(N::zero());
+
+                if dof < DIM {
+                    lin_error2 += error * error;
+                } else {
+                    ang_error2 += error * error;
+                }
+            }
+        }
+
+        JointPositionError {
+            linear: lin_error2.sqrt(),
+            angular: ang_error2.sqrt(),
+        }
+    }
+
+    fn velocity_constraints(
+        &mut self,
+        parameters: &IntegrationParameters<N>,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        ext_vels: &DVector<N>,
+        ground_j_id: &mut usize,
+        j_id: &mut usize,
+        jacobians: &mut [N],
+        constraints: &mut LinearConstraints<N, usize>,
+    ) {
+        for axis in self.axes.iter_mut() {
+            if let JointAxisState::Motorized(motor) = axis {
+                if motor.enabled {
+                    motor.update_effective_velocity(parameters.dt());
+                }
+            }
+        }
+
+        let body1 = try_ret!(bodies.get(self.b1.0));
+        let body2 = try_ret!(bodies.get(self.b2.0));
+        let part1 = try_ret!(body1.part(self.b1.1));
+        let part2 = try_ret!(body2.part(self.b2.1));
+
+        let (pos1, pos2) = self.joint_frames(body1, part1, body2, part2);
+
+        let anchor1 = Point::from(pos1.translation.vector);
+        let anchor2 = Point::from(pos2.translation.vector);
+
+        let assembly_id1 = body1.companion_id();
+        let assembly_id2 = body2.companion_id();
+
+        let first_bilateral_ground = constraints.bilateral_ground.len();
+        let first_bilateral = constraints.bilateral.len();
+
+        let unbounded = ImpulseLimits::Independent {
+            min: -N::max_value(),
+            max: N::max_value(),
+        };
+        let unilateral = ImpulseLimits::Independent {
+            min: N::zero(),
+            max: N::max_value(),
+        };
+
+        for i in 0..SPATIAL_DIM {
+            let (dir, offset) = dof_geometry(&pos1, &pos2, i);
+
+            // The solver drives `rhs` to zero, where `rhs` is the relative velocity of the
+            // body parts along `-dir`. Motors thus use the negated direction so that
+            // the desired velocity is expressed along the actual joint axis.
+            let (dir, limits, (desired_vel, cfm)) = match self.axes[i] {
+                JointAxisState::Free => continue,
+                JointAxisState::Locked => (dir, unbounded, (N::zero(), N::zero())),
+                JointAxisState::Limited { min, max } => {
+                    if relative_eq!(min, max) {
+                        (dir, unbounded, (N::zero(), N::zero()))
+                    } else if offset <= min {
+                        (-dir, unilateral, (N::zero(), N::zero()))
+                    } else if offset >= max {
+                        (dir, unilateral, (N::zero(), N::zero()))
+                    } else {
+                        continue;
+                    }
+                }
+                JointAxisState::Motorized(motor) => {
+                    if !motor.enabled {
+                        continue;
+                    }
+
+                    match motor.drive(offset, parameters.dt()) {
+                        Some(drive) => (-dir, motor.impulse_limits(), drive),
+                        None => continue,
+                    }
+                }
+            };
+
+            let (ext_vels1, ext_vels2) =
+                helper::split_ext_vels(body1, body2, assembly_id1, assembly_id2, ext_vels);
+            let mut rhs = N::zero();
+            let geom = helper::constraint_pair_geometry(
+                body1,
+                part1,
+                self.b1,
+                body2,
+                part2,
+                self.b2,
+                &anchor1,
+                &anchor2,
+                &dir,
+                ground_j_id,
+                j_id,
+                jacobians,
+                Some(&ext_vels1),
+                Some(&ext_vels2),
+                Some(&mut rhs),
+            );
+
+            rhs -= desired_vel;
+
+            if geom.ndofs1 == 0 || geom.ndofs2 == 0 {
+                constraints.bilateral_ground.push(
+                    BilateralGroundConstraint::new(
+                        geom,
+                        assembly_id1,
+                        assembly_id2,
+                        limits,
+                        rhs,
+                        self.impulses[i],
+                        i,
+                    )
+                    .with_cfm(cfm),
+                );
+            } else {
+                constraints.bilateral.push(
+                    BilateralConstraint::new(
+                        geom,
+                        assembly_id1,
+                        assembly_id2,
+                        limits,
+                        rhs,
+                        self.impulses[i],
+                        i,
+                    )
+                    .with_cfm(cfm),
+                );
+            }
+        }
+
+        self.bilateral_ground_rng = first_bilateral_ground..constraints.bilateral_ground.len();
+        self.bilateral_rng = first_bilateral..constraints.bilateral.len();
+    }
+
+    fn cache_impulses(&mut self, constraints: &LinearConstraints<N, usize>, inv_dt: N) {
+        // Limits that are no longer active must not keep their impulse for warmstarting.
+        self.impulses.fill(N::zero());
+
+        for c in &constraints.bilateral_ground[self.bilateral_ground_rng.clone()] {
+            self.impulses[c.impulse_id] = c.impulse;
+        }
+
+        for c in &constraints.bilateral[self.bilateral_rng.clone()] {
+            self.impulses[c.impulse_id] = c.impulse;
+        }
+
+        let inv_dt2 = inv_dt * inv_dt;
+        let lin_impulses = self.impulses.rows(0, DIM).norm_squared();
+        let ang_impulses = self.impulses.rows(DIM, SPATIAL_DIM - DIM).norm_squared();
+
+        if lin_impulses * inv_dt2 > self.break_force_squared
+            || ang_impulses * inv_dt2 > self.break_torque_squared
+        {
+            self.broken = true;
+        }
+    }
+}
+
+impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
+    for GenericConstraint<N, Handle>
+{
+    fn num_position_constraints(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> usize {
+        // FIXME: calling this at each iteration of the non-linear resolution is costly.
+        if self.is_active(bodies) {
+            self.axes
+                .iter()
+                .filter(|state| state.position_range().is_some())
+                .count()
+        } else {
+            0
+        }
+    }
+
+    fn position_constraint(
+        &self,
+        parameters: &IntegrationParameters<N>,
+        i: usize,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+        jacobians: &mut [N],
+    ) -> Option<GenericNonlinearConstraint<N, Handle>> {
+        // Find the degree of freedom corresponding to the i-th position constraint.
+        let (dof, (min, max)) = self
+            .axes
+            .iter()
+            .enumerate()
+            .filter_map(|(dof, state)| state.position_range().map(|range| (dof, range)))
+            .nth(i)?;
+
+        let body1 = bodies.get(self.b1.0)?;
+        let body2 = bodies.get(self.b2.0)?;
+        let part1 = body1.part(self.b1.1)?;
+        let part2 = body2.part(self.b2.1)?;
+
+        let (pos1, pos2) = self.joint_frames(body1, part1, body2, part2);
+
+        let anchor1 = Point::from(pos1.translation.vector);
+        let anchor2 = Point::from(pos2.translation.vector);
+
+        let (dir, offset) = dof_geometry(&pos1, &pos2, dof);
+
+        let (error, dir) = if offset < min {
+            (min - offset, -dir)
+        } else if offset > max {
+            (offset - max, dir)
+        } else {
+            return None;
+        };
+
+        let is_angular = dof >= DIM;
+        let allowed_error = if is_angular {
+            parameters.allowed_angular_error
+        } else {
+            parameters.allowed_linear_error
+        };
+
+        if error > allowed_error {
+            let mut j_id = 0;
+            let mut ground_j_id = 0;
+
+            let geom = helper::constraint_pair_geometry(
+                body1,
+                part1,
+                self.b1,
+                body2,
+                part2,
+                self.b2,
+                &anchor1,
+                &anchor2,
+                &dir,
+                &mut ground_j_id,
+                &mut j_id,
+                jacobians,
+                None,
+                None,
+                None,
+            );
+
+            let rhs = -error;
+            let constraint = GenericNonlinearConstraint::new(
+                self.b1,
+                Some(self.b2),
+                is_angular,
+                geom.ndofs1,
+                geom.ndofs2,
+                geom.wj_id1,
+                geom.wj_id2,
+                rhs,
+                geom.r,
+            );
+
+            Some(constraint)
+        } else {
+            None
+        }
+    }
+}
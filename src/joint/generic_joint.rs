@@ -0,0 +1,458 @@
+use na::{DVectorSliceMut, Isometry3, RealField, Translation3, Unit, UnitQuaternion, Vector3};
+
+use crate::joint::{Joint, JointAxis, JointAxisState, PrismaticJoint, RevoluteJoint};
+use crate::math::{JacobianSliceMut, Velocity, SPATIAL_DIM};
+use crate::object::{BodyPartHandle, Multibody, MultibodyLink};
+use crate::solver::{ConstraintSet, GenericNonlinearConstraint, IntegrationParameters};
+
+/// A joint where each of the six relative degrees of freedom can be individually locked, limited, or motorized.
+///
+/// Locked degrees of freedom are removed from the generalized coordinates of the multibody. The
+/// remaining ones are ordered as in `JointAxis::ALL`: the translations along the free `x`, `y`, and
+/// `z` axes first, followed by the rotations about the free `x`, `y`, and `z` axes. All the axes
+/// are expressed in the local coordinate system of the parent multibody link.
+///
+/// The rotations are applied successively, i.e., the rotational part of this joint is an Euler
+/// angles chain. Thus it suffers from gimbal lock when two of its rotation axes become aligned,
+/// which may only happen if all three rotations are free. Prefer a `BallJoint` in this case.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct GenericJoint<N: RealField> {
+    prisms: [Option<PrismaticJoint<N>>; 3],
+    revos: [Option<RevoluteJoint<N>>; 3],
+    ndofs: usize,
+
+    // The rotation axes in the parent frame, and the body shift rotated by the joint rotation.
+    world_axes: [Vector3<N>; 3],
+    shift: Vector3<N>,
+    jacobians: [Velocity<N>; SPATIAL_DIM],
+    jacobians_dot: [Velocity<N>; SPATIAL_DIM],
+}
+
+impl<N: RealField> GenericJoint<N> {
+    /// Creates a generic joint with the given state for each degree of freedom, in the order of `JointAxis::ALL`.
+    ///
+    /// All the generalized coordinates start at zero. Locked degrees of freedom cannot be unlocked afterwards.
+    pub fn new(axes: [JointAxisState<N>; SPATIAL_DIM]) -> Self {
+        let mut prisms = [None; 3];
+        let mut revos = [None; 3];
+
+        for i in 0..3 {
+            let lin_axis = Unit::new_unchecked(Vector3::ith(i, N::one()));
+
+            prisms[i] = match axes[i] {
+                JointAxisState::Locked => None,
+                JointAxisState::Free => Some(PrismaticJoint::new(lin_axis, N::zero())),
+                JointAxisState::Limited { min, max } => {
+                    let mut prism = PrismaticJoint::new(lin_axis, N::zero());
+                    prism.enable_min_offset(min);
+                    prism.enable_max_offset(max);
+                    Some(prism)
+                }
+                JointAxisState::Motorized(motor) => {
+                    let mut prism = PrismaticJoint::new(lin_axis, N::zero());
+                    prism.enable_linear_motor();
+                    prism.set_desired_linear_motor_velocity(motor.desired_velocity);
+                    prism.set_max_linear_motor_force(motor.max_force);
+                    prism.set_max_linear_motor_acceleration(motor.max_acceleration);
+                    Some(prism)
+                }
+            };
+
+            revos[i] = match axes[3 + i] {
+                JointAxisState::Locked => None,
+                JointAxisState::Free => Some(RevoluteJoint::new(lin_axis, N::zero())),
+                JointAxisState::Limited { min, max } => {
+                    let mut revo = RevoluteJoint::new(lin_axis, N::zero());
+                    revo.enable_min_angle(min);
+                    revo.enable_max_angle(max);
+                    Some(revo)
+                }
+                JointAxisState::Motorized(motor) => {
+                    let mut revo = RevoluteJoint::new(lin_axis, N::zero());
+                    revo.enable_angular_motor();
+                    revo.set_desired_angular_motor_velocity(motor.desired_velocity);
+                    revo.set_max_angular_motor_torque(motor.max_force);
+                    revo.set_max_angular_motor_acceleration(motor.max_acceleration);
+                    Some(revo)
+                }
+            };
+        }
+
+        let ndofs = prisms.iter().filter(|p| p.is_some()).count()
+            + revos.iter().filter(|r| r.is_some()).count();
+
+        GenericJoint {
+            prisms,
+            revos,
+            ndofs,
+            world_axes: [Vector3::zeros(); 3],
+            shift: Vector3::zeros(),
+            jacobians: [Velocity::zero(); SPATIAL_DIM],
+            jacobians_dot: [Velocity::zero(); SPATIAL_DIM],
+        }
+    }
+
+    /// Whether the given degree of freedom is locked.
+    pub fn is_locked(&self, axis: JointAxis) -> bool {
+        self.dof_index(axis).is_none()
+    }
+
+    /// The index of the generalized coordinate of this joint associated to `axis`, or `None` if it is locked.
+    pub fn dof_index(&self, axis: JointAxis) -> Option<usize> {
+        let i = axis.index();
+        let unlocked = if i < 3 {
+            self.prisms[i].is_some()
+        } else {
+            self.revos[i - 3].is_some()
+        };
+
+        if unlocked {
+            Some(self.dof_states().take(i).filter(|s| *s).count())
+        } else {
+            None
+        }
+    }
+
+    /// The current translation or rotation angle along the given degree of freedom.
+    ///
+    /// This is always zero for a locked degree of freedom.
+    pub fn coordinate(&self, axis: JointAxis) -> N {
+        let i = axis.index();
+
+        if i < 3 {
+            self.prisms[i].map(|p| p.offset()).unwrap_or_else(N::zero)
+        } else {
+            self.revos[i - 3].map(|r| r.angle()).unwrap_or_else(N::zero)
+        }
+    }
+
+    /// The unit joint driving the given translational degree of freedom, or `None` if it is locked.
+    ///
+    /// Its limits, motor, friction, and spring can be modified through `self.prismatic_mut(axis)`.
+    pub fn prismatic(&self, axis: JointAxis) -> Option<&PrismaticJoint<N>> {
+        assert!(axis.is_linear(), "The joint axis must be translational.");
+        self.prisms[axis.index()].as_ref()
+    }
+
+    /// The mutable unit joint driving the given translational degree of freedom, or `None` if it is locked.
+    pub fn prismatic_mut(&mut self, axis: JointAxis) -> Option<&mut PrismaticJoint<N>> {
+        assert!(axis.is_linear(), "The joint axis must be translational.");
+        self.prisms[axis.index()].as_mut()
+    }
+
+    /// The unit joint driving the given rotational degree of freedom, or `None` if it is locked.
+    ///
+    /// Its limits, motor, friction, and spring can be modified through `self.revolute_mut(axis)`.
+    pub fn revolute(&self, axis: JointAxis) -> Option<&RevoluteJoint<N>> {
+        assert!(!axis.is_linear(), "The joint axis must be rotational.");
+        self.revos[axis.index() - 3].as_ref()
+    }
+
+    /// The mutable unit joint driving the given rotational degree of freedom, or `None` if it is locked.
+    pub fn revolute_mut(&mut self, axis: JointAxis) -> Option<&mut RevoluteJoint<N>> {
+        assert!(!axis.is_linear(), "The joint axis must be rotational.");
+        self.revos[axis.index() - 3].as_mut()
+    }
+
+    // Whether each degree of freedom is unlocked, in the order of `JointAxis::ALL`.
+    fn dof_states<'a>(&'a self) -> impl Iterator<Item = bool> + 'a {
+        self.prisms
+            .iter()
+            .map(|p| p.is_some())
+            .chain(self.revos.iter().map(|r| r.is_some()))
+    }
+
+    // The unlocked sub-joints, in the order of the generalized coordinates.
+    fn dofs<'a>(&'a self) -> impl Iterator<Item = &'a dyn Joint<N>> + 'a {
+        self.prisms
+            .iter()
+            .filter_map(|p| p.as_ref().map(|p| p as &dyn Joint<N>))
+            .chain(
+                self.revos
+                    .iter()
+                    .filter_map(|r| r.as_ref().map(|r| r as &dyn Joint<N>)),
+            )
+    }
+
+    fn dofs_mut<'a>(&'a mut self) -> impl Iterator<Item = &'a mut dyn Joint<N>> + 'a {
+        self.prisms
+            .iter_mut()
+            .filter_map(|p| p.as_mut().map(|p| p as &mut dyn Joint<N>))
+            .chain(
+                self.revos
+                    .iter_mut()
+                    .filter_map(|r| r.as_mut().map(|r| r as &mut dyn Joint<N>)),
+            )
+    }
+
+    fn num_prismatic(&self) -> usize {
+        self.prisms.iter().filter(|p| p.is_some()).count()
+    }
+
+    fn num_revolute(&self) -> usize {
+        self.ndofs - self.num_prismatic()
+    }
+
+    fn rotation(&self) -> UnitQuaternion<N> {
+        self.revos
+            .iter()
+            .filter_map(|r| r.as_ref())
+            .fold(UnitQuaternion::identity(), |rot, r| rot * r.rotation())
+    }
+
+    // The angular velocity due to the rotations `first..last` of the chain.
+    fn angular_velocity(&self, first: usize, last: usize, vels: &[N]) -> Vector3<N> {
+        (first..last).fold(Vector3::zeros(), |acc, i| {
+            acc + self.world_axes[i] * vels[i]
+        })
+    }
+
+    fn translation(&self) -> Vector3<N> {
+        self.prisms
+            .iter()
+            .filter_map(|p| p.as_ref())
+            .fold(Vector3::zeros(), |t, p| t + p.translation().vector)
+    }
+}
+
+impl<N: RealField> Joint<N> for GenericJoint<N> {
+    #[inline]
+    fn ndofs(&self) -> usize {
+        self.ndofs
+    }
+
+    fn body_to_parent(&self, parent_shift: &Vector3<N>, body_shift: &Vector3<N>) -> Isometry3<N> {
+        let rot = self.rotation();
+        let trans = Translation3::from(parent_shift + self.translation() - rot * body_shift);
+        Isometry3::from_parts(trans, rot)
+    }
+
+    fn update_jacobians(&mut self, body_shift: &Vector3<N>, vels: &[N]) {
+        let nprisms = self.num_prismatic();
+        let nrevos = self.num_revolute();
+        let mut rot = UnitQuaternion::identity();
+
+        for (i, prism) in self.prisms.iter().filter_map(|p| p.as_ref()).enumerate() {
+            self.jacobians[i] = Velocity::new(prism.axis().into_inner(), Vector3::zeros());
+            self.jacobians_dot[i] = Velocity::zero();
+        }
+
+        // Each rotation axis is moved by the rotations preceding it in the chain.
+        for (i, revo) in self.revos.iter().filter_map(|r| r.as_ref()).enumerate() {
+            self.world_axes[i] = rot * revo.axis().into_inner();
+            rot = rot * revo.rotation();
+        }
+
+        self.shift = rot * -body_shift;
+        let angvel = self.angular_velocity(0, nrevos, &vels[nprisms..]);
+
+        for i in 0..nrevos {
+            let axis = self.world_axes[i];
+            let axis_dot = self.angular_velocity(0, i, &vels[nprisms..]).cross(&axis);
+
+            self.jacobians[nprisms + i] = Velocity::new(axis.cross(&self.shift), axis);
+            self.jacobians_dot[nprisms + i] = Velocity::new(
+                axis_dot.cross(&self.shift) + axis.cross(&angvel.cross(&self.shift)),
+                axis_dot,
+            );
+        }
+    }
+
+    fn jacobian(&self, transform: &Isometry3<N>, out: &mut JacobianSliceMut<N>) {
+        for i in 0..self.ndofs {
+            out.column_mut(i)
+                .copy_from(self.jacobians[i].transformed(transform).as_vector())
+        }
+    }
+
+    fn jacobian_dot(&self, transform: &Isometry3<N>, out: &mut JacobianSliceMut<N>) {
+        for i in 0..self.ndofs {
+            out.column_mut(i)
+                .copy_from(self.jacobians_dot[i].transformed(transform).as_vector())
+        }
+    }
+
+    fn jacobian_dot_veldiff_mul_coordinates(
+        &self,
+        transform: &Isometry3<N>,
+        vels: &[N],
+        out: &mut JacobianSliceMut<N>,
+    ) {
+        let nprisms = self.num_prismatic();
+        let nrevos = self.num_revolute();
+        let angvel = self.angular_velocity(0, nrevos, &vels[nprisms..]);
+
+        for i in 0..nprisms {
+            out.column_mut(i).fill(N::zero());
+        }
+
+        for i in 0..nrevos {
+            let axis = self.world_axes[i];
+            let coupling = axis.cross(&self.angular_velocity(i + 1, nrevos, &vels[nprisms..]));
+            let jac = Velocity::new(
+                coupling.cross(&self.shift) + angvel.cross(&axis.cross(&self.shift)),
+                coupling,
+            );
+
+            out.column_mut(nprisms + i)
+                .copy_from(jac.transformed(transform).as_vector())
+        }
+    }
+
+    fn jacobian_mul_coordinates(&self, vels: &[N]) -> Velocity<N> {
+        (0..self.ndofs).fold(Velocity::zero(), |acc, i| acc + self.jacobians[i] * vels[i])
+    }
+
+    fn jacobian_dot_mul_coordinates(&self, vels: &[N]) -> Velocity<N> {
+        (0..self.ndofs).fold(Velocity::zero(), |acc, i| {
+            acc + self.jacobians_dot[i] * vels[i]
+        })
+    }
+
+    fn default_damping(&self, out: &mut DVectorSliceMut<N>) {
+        for (i, dof) in self.dofs().enumerate() {
+            dof.default_damping(&mut out.rows_mut(i, 1));
+        }
+    }
+
+    fn add_passive_forces(&self, vels: &[N], out: &mut [N]) {
+        for (i, dof) in self.dofs().enumerate() {
+            dof.add_passive_forces(&vels[i..i + 1], &mut out[i..i + 1]);
+        }
+    }
+
+    fn integrate(&mut self, parameters: &IntegrationParameters<N>, vels: &[N]) {
+        for (i, dof) in self.dofs_mut().enumerate() {
+            dof.integrate(parameters, &[vels[i]]);
+        }
+    }
+
+    fn apply_displacement(&mut self, disp: &[N]) {
+        for (i, dof) in self.dofs_mut().enumerate() {
+            dof.apply_displacement(&[disp[i]]);
+        }
+    }
+
+    #[inline]
+    fn clone(&self) -> Box<dyn Joint<N>> {
+        Box::new(*self)
+    }
+
+    fn num_velocity_constraints(&self) -> usize {
+        self.dofs().map(|dof| dof.num_velocity_constraints()).sum()
+    }
+
+    fn velocity_constraints(
+        &self,
+        parameters: &IntegrationParameters<N>,
+        multibody: &Multibody<N>,
+        link: &MultibodyLink<N>,
+        assembly_id: usize,
+        dof_id: usize,
+        ext_vels: &[N],
+        ground_j_id: &mut usize,
+        jacobians: &mut [N],
+        constraints: &mut ConstraintSet<N, (), (), usize>,
+    ) {
+        for (i, dof) in self.dofs().enumerate() {
+            dof.velocity_constraints(
+                parameters,
+                multibody,
+                link,
+                assembly_id,
+                dof_id + i,
+                ext_vels,
+                ground_j_id,
+                jacobians,
+                constraints,
+            );
+        }
+    }
+
+    fn num_position_constraints(&self) -> usize {
+        // NOTE: we don't test if constraints exist to simplify indexing.
+        self.ndofs
+    }
+
+    fn position_constraint(
+        &self,
+        i: usize,
+        multibody: &Multibody<N>,
+        link: &MultibodyLink<N>,
+        handle: BodyPartHandle<()>,
+        dof_id: usize,
+        jacobians: &mut [N],
+    ) -> Option<GenericNonlinearConstraint<N, ()>> {
+        self.dofs()
+            .nth(i)?
+            .position_constraint(0, multibody, link, handle, dof_id + i, jacobians)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use na::{Isometry3, Matrix6, Unit, Vector3};
+
+    use super::GenericJoint;
+    use crate::joint::{Joint, JointAxisState, RevoluteJoint};
+
+    // The jacobian of `joint` after displacing its generalized coordinates by `disp`.
+    fn jacobian(joint: &GenericJoint<f64>, shift: &Vector3<f64>, disp: &[f64]) -> Matrix6<f64> {
+        let mut joint = *joint;
+        joint.apply_displacement(disp);
+        joint.update_jacobians(shift, &[0.0; 6]);
+
+        let mut res = Matrix6::zeros();
+        joint.jacobian(&Isometry3::identity(), &mut res.columns_mut(0, 6));
+        res
+    }
+
+    #[test]
+    fn jacobian_dot_matches_finite_differences() {
+        let mut joint = GenericJoint::new([JointAxisState::Free; 6]);
+        joint.apply_displacement(&[0.1, -0.2, 0.3, 0.4, -0.5, 0.6]);
+
+        let shift = Vector3::new(0.3, -1.0, 0.5);
+        let vels = [0.5, 1.0, -1.5, 2.0, -1.0, 3.0];
+        joint.update_jacobians(&shift, &vels);
+
+        let mut jacobian_dot = Matrix6::zeros();
+        joint.jacobian_dot(&Isometry3::identity(), &mut jacobian_dot.columns_mut(0, 6));
+
+        // Central differences of the jacobian along the trajectory with the velocities `vels`.
+        let h = 1.0e-6;
+        let forward: Vec<f64> = vels.iter().map(|v| v * h).collect();
+        let backward: Vec<f64> = vels.iter().map(|v| -v * h).collect();
+        let expected =
+            (jacobian(&joint, &shift, &forward) - jacobian(&joint, &shift, &backward)) / (2.0 * h);
+
+        assert!(relative_eq!(jacobian_dot, expected, epsilon = 1.0e-6));
+    }
+
+    #[test]
+    fn single_rotation_matches_revolute_joint() {
+        let mut axes = [JointAxisState::Locked; 6];
+        axes[5] = JointAxisState::Free;
+        let mut generic = GenericJoint::new(axes);
+        let mut revolute = RevoluteJoint::new(Unit::new_unchecked(Vector3::z()), 0.0);
+
+        let shift = Vector3::new(1.0, -0.5, 0.2);
+        let vels = [2.0];
+
+        generic.apply_displacement(&[0.7]);
+        generic.update_jacobians(&shift, &vels);
+        revolute.apply_displacement(&[0.7]);
+        revolute.update_jacobians(&shift, &vels);
+
+        let mut generic_j = Matrix6::zeros();
+        let mut revolute_j = Matrix6::zeros();
+        generic.jacobian_dot(&Isometry3::identity(), &mut generic_j.columns_mut(0, 1));
+        revolute.jacobian_dot(&Isometry3::identity(), &mut revolute_j.columns_mut(0, 1));
+        assert!(relative_eq!(generic_j, revolute_j, epsilon = 1.0e-10));
+
+        generic.jacobian(&Isometry3::identity(), &mut generic_j.columns_mut(0, 1));
+        revolute.jacobian(&Isometry3::identity(), &mut revolute_j.columns_mut(0, 1));
+        assert!(relative_eq!(generic_j, revolute_j, epsilon = 1.0e-10));
+    }
+}
@@ -10,6 +10,7 @@ use crate::solver::{ConstraintSet, GenericNonlinearConstraint, IntegrationParame
 /// The degree of freedom is the combination of a rotation and a translation along the same axis.
 /// Both rotational and translational motions are coupled to generate a screw motion.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct HelicalJoint<N: RealField> {
     revo: RevoluteJoint<N>,
     pitch: N,
@@ -42,11 +42,16 @@ pub trait Joint<N: RealField>: Downcast + Send + Sync {
     /// Fill `out` with the non-zero entries of a damping that can be applied by default to ensure a good stability of the joint.
     fn default_damping(&self, out: &mut DVectorSliceMut<N>);
 
+    /// Adds to `out` the generalized forces passively applied by this joint, e.g., by a spring.
+    ///
+    /// Both `vels` and `out` contain one element per degree of freedom of this joint.
+    fn add_passive_forces(&self, _vels: &[N], _out: &mut [N]) {}
+
     /// The maximum number of impulses needed by this joints for
     /// its constraints.
     fn nimpulses(&self) -> usize {
         // FIXME: keep this?
-        self.ndofs() * 3
+        self.ndofs() * 4
     }
 
     /// Maximum number of velocity constrains that can be generated by this joint.
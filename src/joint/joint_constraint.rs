@@ -53,6 +53,26 @@ pub trait JointConstraintSet<N: RealField, Handle: BodyHandle> {
     /// A constraint-based joint can be removed automatically by nphysics when one of its attached
     /// bodies is removed from the mechanical world.
     fn remove(&mut self, to_remove: Self::Handle);
+
+    /// Remove a joint that broke during a timestep from this set.
+    ///
+    /// This is called automatically by nphysics for each joint that broke. The default
+    /// implementation simply removes the joint.
+    fn remove_broken(&mut self, to_remove: Self::Handle) {
+        self.remove(to_remove)
+    }
+
+    /// Gets the handle of one joint that broke and has been removed by `remove_broken`.
+    ///
+    /// This method should return a broken joint handle only once. The mechanical world pops all
+    /// the remaining ones at the beginning of each step, so the joints broken during a step can
+    /// be popped until the next one. The default implementation does not keep track of broken
+    /// joints.
+    fn pop_break_event(
+        &mut self,
+    ) -> Option<(Self::Handle, BodyPartHandle<Handle>, BodyPartHandle<Handle>)> {
+        None
+    }
 }
 
 /// A set containing all the joint-constraints added to the world.
@@ -70,6 +90,11 @@ pub struct DefaultJointConstraintSet<N: RealField, Handle: BodyHandle = DefaultB
         BodyPartHandle<Handle>,
         BodyPartHandle<Handle>,
     )>,
+    broken: Vec<(
+        DefaultJointConstraintHandle,
+        BodyPartHandle<Handle>,
+        BodyPartHandle<Handle>,
+    )>,
 }
 
 impl<N: RealField, Handle: BodyHandle> DefaultJointConstraintSet<N, Handle> {
@@ -79,6 +104,7 @@ impl<N: RealField, Handle: BodyHandle> DefaultJointConstraintSet<N, Handle> {
             constraints: Arena::new(),
             inserted: Vec::new(),
             removed: Vec::new(),
+            broken: Vec::new(),
         }
     }
 
@@ -112,6 +138,28 @@ impl<N: RealField, Handle: BodyHandle> DefaultJointConstraintSet<N, Handle> {
         Some(res)
     }
 
+    /// Removes all the joints attached to the given body, and returns their handles.
+    ///
+    /// The joints attached to a removed body are automatically removed by `MechanicalWorld::maintain`,
+    /// which returns their handles. Calling this instead purges them without maintaining the
+    /// mechanical world.
+    pub fn remove_body_constraints(&mut self, body: Handle) -> Vec<DefaultJointConstraintHandle> {
+        let to_remove: Vec<_> = self
+            .iter()
+            .filter(|(_, c)| {
+                let (part1, part2) = c.anchors();
+                part1.0 == body || part2.0 == body
+            })
+            .map(|(h, _)| h)
+            .collect();
+
+        for handle in &to_remove {
+            let _ = self.remove(*handle);
+        }
+
+        to_remove
+    }
+
     /// Check if this set contains a joint identified by `handle`.
     pub fn contains(&self, handle: DefaultJointConstraintHandle) -> bool {
         self.constraints.contains(handle)
@@ -203,11 +251,43 @@ impl<N: RealField, Handle: BodyHandle> JointConstraintSet<N, Handle>
     fn remove(&mut self, to_remove: Self::Handle) {
         let _ = self.remove(to_remove);
     }
+
+    fn remove_broken(&mut self, to_remove: Self::Handle) {
+        if let Some(constraint) = self.remove(to_remove) {
+            let (part1, part2) = constraint.anchors();
+            self.broken.push((to_remove, part1, part2));
+        }
+    }
+
+    fn pop_break_event(
+        &mut self,
+    ) -> Option<(Self::Handle, BodyPartHandle<Handle>, BodyPartHandle<Handle>)> {
+        self.broken.pop()
+    }
 }
 
 /// The handle of a joint on a `DefaultJointConstraintsSet`.
 pub type DefaultJointConstraintHandle = generational_arena::Index;
 
+/// The amount by which the positional constraints of a joint are violated.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct JointPositionError<N: RealField> {
+    /// The distance by which the linear part of the joint is violated.
+    pub linear: N,
+    /// The angle (in radians) by which the angular part of the joint is violated.
+    pub angular: N,
+}
+
+impl<N: RealField> JointPositionError<N> {
+    /// A position error equal to zero, i.e., for a joint that is perfectly satisfied.
+    pub fn zero() -> Self {
+        JointPositionError {
+            linear: N::zero(),
+            angular: N::zero(),
+        }
+    }
+}
+
 /// Trait implemented by joint that operate by generating constraints to restrict the relative motion of two body parts.
 pub trait JointConstraint<N: RealField, Handle: BodyHandle>:
     NonlinearConstraintGenerator<N, Handle> + Downcast + Send + Sync
@@ -248,6 +328,81 @@ pub trait JointConstraint<N: RealField, Handle: BodyHandle>:
     fn is_broken(&self) -> bool {
         false // FIXME: we provide a default impl just to avoid a breaking change.
     }
+
+    /// How far the body parts attached to this joint are from satisfying its positional constraints.
+    ///
+    /// This is computed from the current positions of the bodies, e.g., after a timestep. Large
+    /// errors indicate the solver cannot keep up with this joint, in which case increasing the
+    /// number of position iterations of the integration parameters may help.
+    fn position_error(&self, _bodies: &dyn BodySet<N, Handle = Handle>) -> JointPositionError<N> {
+        // FIXME: we provide a default impl just to avoid a breaking change.
+        JointPositionError::zero()
+    }
+
+    /// Whether `scale_space` is supported by this joint.
+    ///
+    /// `MechanicalWorld::scale_space` fails without modifying anything if this returns `false`
+    /// for any joint, which is the default.
+    fn can_scale_space(&self) -> bool {
+        false
+    }
+
+    /// Scales the anchors of this joint, and the other quantities depending on the length unit,
+    /// by `factor`.
+    ///
+    /// This is called by `MechanicalWorld::scale_space`, which leaves the mass of each body
+    /// unchanged. Thus linear impulses and forces are scaled by `factor`, and angular ones by
+    /// `factor * factor`.
+    fn scale_space(&mut self, _factor: N) {}
 }
 
 impl_downcast!(JointConstraint<N, Handle> where N: RealField, Handle: BodyHandle);
+
+#[cfg(test)]
+mod test {
+    use ncollide::shape::{Ball, ShapeHandle};
+
+    use super::JointConstraintSet;
+    use crate::analytic_tests::{Scenario, GRAVITY};
+    use crate::joint::BallConstraint;
+    use crate::math::{Point, Vector};
+    use crate::object::{BodyPartHandle, RigidBodyDesc};
+    use crate::world::PhysicsEvent;
+
+    #[test]
+    fn overloaded_joint_breaks_and_is_removed() {
+        let mut scenario = Scenario::new(1.0 / 240.0);
+
+        let desc = RigidBodyDesc::new().translation(-Vector::y());
+        let bob = scenario.add_body(&desc, ShapeHandle::new(Ball::new(0.05)), 0.0, 0.0);
+        let weight = scenario.rigid_body(bob).augmented_mass().mass() * GRAVITY;
+
+        let mut joint = BallConstraint::new(
+            BodyPartHandle(scenario.ground, 0),
+            BodyPartHandle(bob, 0),
+            Point::origin(),
+            Point::from(Vector::y()),
+        );
+        joint.set_break_force(weight * 0.5);
+        let handle = scenario.joint_constraints.insert(joint);
+
+        scenario.step();
+
+        assert!(!scenario.joint_constraints.contains(handle));
+        assert_eq!(
+            scenario
+                .joint_constraints
+                .pop_break_event()
+                .map(|(broken, _, _)| broken),
+            Some(handle)
+        );
+        assert!(scenario
+            .mechanical_world
+            .drain_events()
+            .iter()
+            .any(|event| match event {
+                PhysicsEvent::JointBroken(_, part2) => part2.0 == bob,
+                _ => false,
+            }));
+    }
+}
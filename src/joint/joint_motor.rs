@@ -4,6 +4,7 @@ use num::Zero;
 
 /// Description of a motor applied to a joint.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct JointMotor<V, N: RealField> {
     /// The velocity the motor will attempt to reach.
     pub desired_velocity: V,
@@ -11,6 +12,18 @@ pub struct JointMotor<V, N: RealField> {
     pub max_force: N,
     /// Whether or not the motor is active.
     pub enabled: bool,
+    /// The maximum rate of change of the velocity the motor attempts to reach, if it is limited.
+    pub max_acceleration: Option<N>,
+    /// The velocity the motor actually attempts to reach when its acceleration is limited.
+    ///
+    /// It ramps toward `desired_velocity` over time instead of changing instantaneously.
+    pub effective_velocity: V,
+    /// The position the motor drives the joint toward, if it acts as a position drive.
+    pub target_position: Option<V>,
+    /// The stiffness of the position drive, i.e., the force applied per unit of position error.
+    pub stiffness: N,
+    /// The damping of the position drive, i.e., the force applied per unit of velocity error.
+    pub damping: N,
 }
 
 impl<V: Zero, N: RealField> JointMotor<V, N> {
@@ -22,9 +35,30 @@ impl<V: Zero, N: RealField> JointMotor<V, N> {
             desired_velocity: V::zero(),
             max_force: N::max_value(),
             enabled: false,
+            max_acceleration: None,
+            effective_velocity: V::zero(),
+            target_position: None,
+            stiffness: N::zero(),
+            damping: N::zero(),
         }
     }
 
+    /// Limits the rate of change of the velocity the motor attempts to reach.
+    pub fn with_max_acceleration(mut self, max_acceleration: Option<N>) -> Self {
+        self.max_acceleration = max_acceleration;
+        self
+    }
+
+    /// Turns this motor into a position drive pulling the joint toward `target_position`.
+    ///
+    /// This does not enable the motor nor change its maximum force.
+    pub fn with_position_drive(mut self, target_position: V, stiffness: N, damping: N) -> Self {
+        self.target_position = Some(target_position);
+        self.stiffness = stiffness;
+        self.damping = damping;
+        self
+    }
+
     /// The limits of the impulse applicable by the motor on the body parts.
     pub fn impulse_limits(&self) -> ImpulseLimits<N> {
         ImpulseLimits::Independent {
@@ -34,8 +68,120 @@ impl<V: Zero, N: RealField> JointMotor<V, N> {
     }
 }
 
+impl<N: RealField> JointMotor<N, N> {
+    /// The velocity the motor attempts to reach during the next resolution.
+    ///
+    /// This is the effective velocity if the acceleration of the motor is limited, and the
+    /// desired velocity otherwise.
+    pub fn target_velocity(&self) -> N {
+        if self.max_acceleration.is_some() {
+            self.effective_velocity
+        } else {
+            self.desired_velocity
+        }
+    }
+
+    /// The velocity the motor attempts to reach during the next resolution, for a joint currently
+    /// at `position`, and the constraint force mixing softening it.
+    ///
+    /// Without a target position, this is the target velocity, reached rigidly. Otherwise, the motor
+    /// is the soft constraint equivalent to the force `stiffness * (target_position - position) +
+    /// damping * (target_velocity - velocity)` evaluated at the end of a timestep of length `dt`:
+    /// its constraint force mixing is `1 / (dt * (damping + dt * stiffness))`, so the joint
+    /// oscillates at the natural frequency of the spring for any mass it moves, and remains stable
+    /// for any stiffness. Returns `None` if this position drive has zero stiffness and damping.
+    pub fn drive(&self, position: N, dt: N) -> Option<(N, N)> {
+        match self.target_position {
+            Some(target_position) => {
+                let denominator = self.damping + dt * self.stiffness;
+
+                if denominator.is_zero() {
+                    None
+                } else {
+                    let velocity = (self.stiffness * (target_position - position)
+                        + self.damping * self.target_velocity())
+                        / denominator;
+                    Some((velocity, N::one() / (dt * denominator)))
+                }
+            }
+            None => Some((self.target_velocity(), N::zero())),
+        }
+    }
+
+    /// Turns this motor into a position drive pulling the joint toward `target_position`.
+    ///
+    /// The motor is enabled and delivers at most the force `max_force`.
+    pub fn set_position_drive(
+        &mut self,
+        target_position: N,
+        stiffness: N,
+        damping: N,
+        max_force: N,
+    ) {
+        *self = self.with_position_drive(target_position, stiffness, damping);
+        self.enabled = true;
+        self.max_force = max_force;
+    }
+
+    /// Moves the effective velocity toward the desired velocity by at most `max_acceleration * dt`.
+    pub fn update_effective_velocity(&mut self, dt: N) {
+        match self.max_acceleration {
+            Some(max_acceleration) => {
+                let max_change = max_acceleration * dt;
+                let change = (self.desired_velocity - self.effective_velocity)
+                    .max(-max_change)
+                    .min(max_change);
+                self.effective_velocity += change;
+            }
+            None => self.effective_velocity = self.desired_velocity,
+        }
+    }
+}
+
 impl<V: Zero, N: RealField> Default for JointMotor<V, N> {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use na::RealField;
+
+    use crate::analytic_tests::{assert_relative_error, downward_zero_crossings, Scenario};
+    use crate::joint::{FixedJoint, PrismaticJoint};
+    use crate::math::{Isometry, Vector};
+    use crate::object::{BodyPart, MultibodyDesc};
+
+    #[test]
+    fn position_drive_oscillates_at_its_natural_frequency() {
+        let stiffness = 100.0;
+
+        for &mass in &[1.0, 4.0] {
+            let mut scenario = Scenario::new(1.0 / 240.0);
+
+            // A horizontal slider released away from the target of its undamped position drive.
+            let mut slider = PrismaticJoint::new(Vector::x_axis(), 0.5);
+            slider.set_linear_motor_position(0.0, stiffness, 0.0, f64::max_value());
+            let mut desc = MultibodyDesc::new(FixedJoint::new(Isometry::identity()));
+            let _ = desc.add_child(slider).set_mass(mass);
+            let handle = scenario.bodies.insert(desc.build());
+
+            let expected_period = f64::two_pi() * (mass / stiffness).sqrt();
+            let mut samples = Vec::new();
+
+            while scenario.time() < expected_period * 2.5 {
+                scenario.step();
+                let link = scenario.bodies.multibody(handle).unwrap().link(1).unwrap();
+                samples.push((scenario.time(), link.position().translation.vector.x));
+            }
+
+            let crossings = downward_zero_crossings(&samples);
+            assert!(
+                crossings.len() >= 2,
+                "The position drive did not oscillate."
+            );
+            assert_relative_error(crossings[1] - crossings[0], expected_period, 0.02);
+        }
+    }
+}
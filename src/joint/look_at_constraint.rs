@@ -0,0 +1,314 @@
+use na::{DVector, RealField, Unit};
+
+use crate::joint::{JointConstraint, JointPositionError};
+use crate::math::{AngularVector, Point, Rotation, Vector};
+use crate::object::{BodyHandle, BodyPartHandle, BodySet};
+use crate::solver::{
+    helper, BilateralConstraint, BilateralGroundConstraint, ForceDirection, ImpulseLimits,
+};
+use crate::solver::{
+    GenericNonlinearConstraint, IntegrationParameters, LinearConstraints,
+    NonlinearConstraintGenerator,
+};
+
+// The number of rotational degrees of freedom constrained to aim at the target.
+#[cfg(feature = "dim2")]
+const NUM_AIMING_AXES: usize = 1;
+#[cfg(feature = "dim3")]
+const NUM_AIMING_AXES: usize = 2;
+
+/// A constraint rotating a body part so that one of its axis points toward a target.
+///
+/// The rotation is driven by a motor with a bounded angular velocity and torque, so the body part
+/// turns smoothly toward the target instead of snapping to it. This is typically used for turrets
+/// and cameras attached to the ground or to a vehicle. The rotation around the aiming axis itself
+/// is left free.
+pub struct LookAtConstraint<N: RealField, Handle: BodyHandle> {
+    b1: BodyPartHandle<Handle>,
+    b2: BodyPartHandle<Handle>,
+    target: Point<N>,
+    forward: Unit<Vector<N>>,
+    max_angular_velocity: N,
+    max_torque: N,
+    angle_limit: Option<(Unit<Vector<N>>, N)>,
+}
+
+impl<N: RealField, Handle: BodyHandle> LookAtConstraint<N, Handle> {
+    /// Creates a constraint making the axis `forward` of `b2` point toward the point `target` of `b1`.
+    ///
+    /// The `target` is expressed in the local-space of `b1` (typically the ground, in which case
+    /// this is a world-space point) and `forward` in the local-space of `b2`. The angular velocity
+    /// and the torque of the motor are not limited by default.
+    pub fn new(
+        b1: BodyPartHandle<Handle>,
+        b2: BodyPartHandle<Handle>,
+        target: Point<N>,
+        forward: Unit<Vector<N>>,
+    ) -> Self {
+        LookAtConstraint {
+            b1,
+            b2,
+            target,
+            forward,
+            max_angular_velocity: N::max_value(),
+            max_torque: N::max_value(),
+            angle_limit: None,
+        }
+    }
+
+    /// The point to look at, in the local-space of the first body part.
+    pub fn target(&self) -> &Point<N> {
+        &self.target
+    }
+
+    /// Sets the point to look at, in the local-space of the first body part.
+    pub fn set_target(&mut self, target: Point<N>) {
+        self.target = target
+    }
+
+    /// The axis pointed toward the target, in the local-space of the second body part.
+    pub fn forward(&self) -> &Unit<Vector<N>> {
+        &self.forward
+    }
+
+    /// Sets the axis pointed toward the target, in the local-space of the second body part.
+    pub fn set_forward(&mut self, forward: Unit<Vector<N>>) {
+        self.forward = forward
+    }
+
+    /// The maximum angular velocity at which the second body part turns toward the target.
+    pub fn max_angular_velocity(&self) -> N {
+        self.max_angular_velocity
+    }
+
+    /// Sets the maximum angular velocity at which the second body part turns toward the target.
+    pub fn set_max_angular_velocity(&mut self, max_angular_velocity: N) {
+        self.max_angular_velocity = max_angular_velocity
+    }
+
+    /// The maximum torque applied to turn the second body part toward the target.
+    pub fn max_torque(&self) -> N {
+        self.max_torque
+    }
+
+    /// Sets the maximum torque applied to turn the second body part toward the target.
+    pub fn set_max_torque(&mut self, max_torque: N) {
+        self.max_torque = max_torque
+    }
+
+    /// The axis, in the local-space of the first body part, and the maximum angle the aiming axis
+    /// may deviate from it.
+    pub fn angle_limit(&self) -> Option<&(Unit<Vector<N>>, N)> {
+        self.angle_limit.as_ref()
+    }
+
+    /// Prevents the aiming axis from deviating from `rest_axis` by more than `max_angle` radians.
+    ///
+    /// The `rest_axis` is expressed in the local-space of the first body part. When the target lies
+    /// outside of this cone, the second body part aims at the point of the cone closest to it.
+    pub fn set_angle_limit(&mut self, rest_axis: Unit<Vector<N>>, max_angle: N) {
+        self.angle_limit = Some((rest_axis, max_angle))
+    }
+
+    /// Removes the angle limit of the aiming axis.
+    pub fn disable_angle_limit(&mut self) {
+        self.angle_limit = None
+    }
+
+    // The world-space rotation bringing the aiming axis of the second body part onto the direction
+    // of the target, expressed as an axis scaled by an angle.
+    fn aiming_error(
+        &self,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+    ) -> Option<(Unit<Vector<N>>, AngularVector<N>)> {
+        let body1 = bodies.get(self.b1.0)?;
+        let body2 = bodies.get(self.b2.0)?;
+        let pos1 = body1.part(self.b1.1)?.position();
+        let pos2 = body2.part(self.b2.1)?.position();
+
+        let forward = pos2.rotation * self.forward;
+        let origin = Point::from(pos2.translation.vector);
+        let mut dir = Unit::try_new(pos1 * self.target - origin, N::default_epsilon())?;
+
+        if let Some((rest_axis, max_angle)) = self.angle_limit {
+            let rest_axis = pos1.rotation * rest_axis;
+            let cos = rest_axis.dot(&dir);
+
+            if cos < max_angle.cos() {
+                let (sin_max, cos_max) = max_angle.sin_cos();
+                dir = match Unit::try_new(*dir - *rest_axis * cos, N::default_epsilon()) {
+                    Some(perp) => Unit::new_normalize(*rest_axis * cos_max + *perp * sin_max),
+                    None => rest_axis,
+                };
+            }
+        }
+
+        Some((forward, scaled_rotation_between(&forward, &dir)))
+    }
+}
+
+#[cfg(feature = "dim2")]
+fn scaled_rotation_between<N: RealField>(
+    a: &Unit<Vector<N>>,
+    b: &Unit<Vector<N>>,
+) -> AngularVector<N> {
+    AngularVector::new(Rotation::rotation_between_axis(a, b).angle())
+}
+
+#[cfg(feature = "dim3")]
+fn scaled_rotation_between<N: RealField>(
+    a: &Unit<Vector<N>>,
+    b: &Unit<Vector<N>>,
+) -> AngularVector<N> {
+    match Rotation::rotation_between_axis(a, b) {
+        Some(rot) => rot.scaled_axis(),
+        // The axis are opposite: turn around any axis orthogonal to `a`.
+        None => {
+            let mut axis = AngularVector::zeros();
+            Vector::orthonormal_subspace_basis(&[a.into_inner()], |v| {
+                axis = *v;
+                false
+            });
+            axis * N::pi()
+        }
+    }
+}
+
+impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for LookAtConstraint<N, Handle> {
+    fn num_velocity_constraints(&self) -> usize {
+        NUM_AIMING_AXES
+    }
+
+    fn anchors(&self) -> (BodyPartHandle<Handle>, BodyPartHandle<Handle>) {
+        (self.b1, self.b2)
+    }
+
+    fn position_error(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> JointPositionError<N> {
+        let (_, error) = try_ret!(self.aiming_error(bodies), JointPositionError::zero());
+
+        JointPositionError {
+            linear: N::zero(),
+            angular: error.norm(),
+        }
+    }
+
+    fn velocity_constraints(
+        &mut self,
+        parameters: &IntegrationParameters<N>,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        ext_vels: &DVector<N>,
+        ground_j_id: &mut usize,
+        j_id: &mut usize,
+        jacobians: &mut [N],
+        constraints: &mut LinearConstraints<N, usize>,
+    ) {
+        let (forward, error) = try_ret!(self.aiming_error(bodies));
+        let body1 = try_ret!(bodies.get(self.b1.0));
+        let body2 = try_ret!(bodies.get(self.b2.0));
+        let part1 = try_ret!(body1.part(self.b1.1));
+        let part2 = try_ret!(body2.part(self.b2.1));
+
+        let anchor1 = Point::from(part1.position().translation.vector);
+        let anchor2 = Point::from(part2.position().translation.vector);
+
+        let assembly_id1 = body1.companion_id();
+        let assembly_id2 = body2.companion_id();
+
+        // Reach the target in one step, unless this exceeds the maximum angular velocity.
+        let mut desired_angvel = error * parameters.inv_dt();
+        let angvel = desired_angvel.norm();
+
+        if angvel > self.max_angular_velocity {
+            desired_angvel *= self.max_angular_velocity / angvel;
+        }
+
+        let max_impulse = self.max_torque * parameters.dt();
+        let limits = ImpulseLimits::Independent {
+            min: -max_impulse,
+            max: max_impulse,
+        };
+
+        let mut constrain_axis = |axis: &AngularVector<N>| {
+            let (ext_vels1, ext_vels2) =
+                helper::split_ext_vels(body1, body2, assembly_id1, assembly_id2, ext_vels);
+            // The solver drives to zero the relative velocity along the opposite of the force direction.
+            let dir = ForceDirection::Angular(Unit::new_unchecked(-*axis));
+            let mut rhs = N::zero();
+            let geom = helper::constraint_pair_geometry(
+                body1,
+                part1,
+                self.b1,
+                body2,
+                part2,
+                self.b2,
+                &anchor1,
+                &anchor2,
+                &dir,
+                ground_j_id,
+                j_id,
+                jacobians,
+                Some(&ext_vels1),
+                Some(&ext_vels2),
+                Some(&mut rhs),
+            );
+
+            rhs -= desired_angvel.dot(axis);
+
+            if geom.ndofs1 == 0 || geom.ndofs2 == 0 {
+                constraints
+                    .bilateral_ground
+                    .push(BilateralGroundConstraint::new(
+                        geom,
+                        assembly_id1,
+                        assembly_id2,
+                        limits,
+                        rhs,
+                        N::zero(),
+                        0,
+                    ));
+            } else {
+                constraints.bilateral.push(BilateralConstraint::new(
+                    geom,
+                    assembly_id1,
+                    assembly_id2,
+                    limits,
+                    rhs,
+                    N::zero(),
+                    0,
+                ));
+            }
+        };
+
+        // The rotation around the aiming axis is left free.
+        #[cfg(feature = "dim2")]
+        {
+            let _ = forward;
+            constrain_axis(&AngularVector::x());
+        }
+        #[cfg(feature = "dim3")]
+        Vector::orthonormal_subspace_basis(&[forward.into_inner()], |axis| {
+            constrain_axis(axis);
+            true
+        });
+    }
+
+    fn cache_impulses(&mut self, _: &LinearConstraints<N, usize>, _: N) {}
+}
+
+impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
+    for LookAtConstraint<N, Handle>
+{
+    fn num_position_constraints(&self, _: &dyn BodySet<N, Handle = Handle>) -> usize {
+        0
+    }
+
+    fn position_constraint(
+        &self,
+        _: &IntegrationParameters<N>,
+        _: usize,
+        _: &mut dyn BodySet<N, Handle = Handle>,
+        _: &mut [N],
+    ) -> Option<GenericNonlinearConstraint<N, Handle>> {
+        None
+    }
+}
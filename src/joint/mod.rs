@@ -5,9 +5,10 @@ pub use self::fixed_joint::FixedJoint;
 pub use self::free_joint::FreeJoint;
 pub use self::joint::Joint;
 pub use self::prismatic_joint::PrismaticJoint;
-pub use self::revolute_joint::RevoluteJoint;
+pub use self::revolute_joint::{RevoluteJoint, RevoluteSpring};
+pub use self::spring_joint::SpringJoint;
 pub use self::unit_joint::{
-    unit_joint_num_velocity_constraints, unit_joint_position_constraint,
+    unit_joint_limits_reached, unit_joint_num_velocity_constraints, unit_joint_position_constraint,
     unit_joint_velocity_constraints, UnitJoint,
 };
 
@@ -16,6 +17,8 @@ pub use self::ball_joint::BallJoint;
 #[cfg(feature = "dim3")]
 pub use self::cylindrical_joint::CylindricalJoint;
 #[cfg(feature = "dim3")]
+pub use self::generic_joint::GenericJoint;
+#[cfg(feature = "dim3")]
 pub use self::helical_joint::HelicalJoint;
 #[cfg(feature = "dim3")]
 pub use self::pin_slot_joint::PinSlotJoint;
@@ -27,14 +30,20 @@ pub use self::rectangular_joint::RectangularJoint;
 pub use self::universal_joint::UniversalJoint;
 
 pub use self::cartesian_constraint::CartesianConstraint;
-pub use self::fixed_constraint::FixedConstraint;
+pub use self::fixed_constraint::{FixedConstraint, WeldConstraint};
+pub use self::gear_constraint::GearConstraint;
+pub use self::generic_constraint::{GenericConstraint, JointAxis, JointAxisState};
 pub use self::joint_constraint::{
     DefaultJointConstraintHandle, DefaultJointConstraintSet, JointConstraint, JointConstraintSet,
+    JointPositionError,
 };
 pub use self::joint_motor::JointMotor;
+pub use self::look_at_constraint::LookAtConstraint;
 pub use self::mouse_constraint::MouseConstraint;
 pub use self::prismatic_constraint::PrismaticConstraint;
+pub use self::rack_and_pinion_constraint::RackAndPinionConstraint;
 pub use self::revolute_constraint::RevoluteConstraint;
+pub use self::rope_constraint::RopeConstraint;
 
 #[cfg(feature = "dim3")]
 pub use self::ball_constraint::BallConstraint;
@@ -45,6 +54,8 @@ pub use self::pin_slot_constraint::PinSlotConstraint;
 #[cfg(feature = "dim3")]
 pub use self::planar_constraint::PlanarConstraint;
 #[cfg(feature = "dim3")]
+pub use self::planar_lock_constraint::PlanarLockConstraint;
+#[cfg(feature = "dim3")]
 pub use self::rectangular_constraint::RectangularConstraint;
 #[cfg(feature = "dim3")]
 pub use self::universal_constraint::UniversalConstraint;
@@ -55,6 +66,7 @@ mod free_joint;
 mod joint;
 mod prismatic_joint;
 mod revolute_joint;
+mod spring_joint;
 mod unit_joint;
 
 #[cfg(feature = "dim3")]
@@ -62,6 +74,8 @@ mod ball_joint;
 #[cfg(feature = "dim3")]
 mod cylindrical_joint;
 #[cfg(feature = "dim3")]
+mod generic_joint;
+#[cfg(feature = "dim3")]
 mod helical_joint;
 #[cfg(feature = "dim3")]
 mod pin_slot_joint;
@@ -74,11 +88,16 @@ mod universal_joint;
 
 mod cartesian_constraint;
 mod fixed_constraint;
+mod gear_constraint;
+mod generic_constraint;
 mod joint_constraint;
 mod joint_motor;
+mod look_at_constraint;
 mod mouse_constraint;
 mod prismatic_constraint;
+mod rack_and_pinion_constraint;
 mod revolute_constraint;
+mod rope_constraint;
 mod unit_constraint;
 
 #[cfg(feature = "dim3")]
@@ -90,6 +109,8 @@ mod pin_slot_constraint;
 #[cfg(feature = "dim3")]
 mod planar_constraint;
 #[cfg(feature = "dim3")]
+mod planar_lock_constraint;
+#[cfg(feature = "dim3")]
 mod rectangular_constraint;
 #[cfg(feature = "dim3")]
 mod universal_constraint;
@@ -1,6 +1,6 @@
-use na::{DVector, RealField, Unit};
+use na::{self, DVector, RealField, Unit};
 
-use crate::joint::JointConstraint;
+use crate::joint::{JointConstraint, JointPositionError};
 use crate::math::{Point, Vector, DIM};
 use crate::object::{BodyHandle, BodyPartHandle, BodySet};
 use crate::solver::{
@@ -12,19 +12,26 @@ use crate::solver::{
 };
 
 /// A spring-like constraint to be used to drag a body part with the mouse.
+///
+/// This attaches a point of a body part to a target point, typically attached to the ground and
+/// updated each frame with `set_target`. The constraint is soft: only a fraction (the stiffness)
+/// of the distance between both points is corrected at each timestep, and the impulse it applies
+/// is bounded so that dragging a heavy body part does not make the simulation unstable.
 pub struct MouseConstraint<N: RealField, Handle: BodyHandle> {
     b1: BodyPartHandle<Handle>,
     b2: BodyPartHandle<Handle>,
     anchor1: Point<N>,
     anchor2: Point<N>,
     limit: N,
+    stiffness: Option<N>,
 }
 
 impl<N: RealField, Handle: BodyHandle> MouseConstraint<N, Handle> {
-    /// Initialize a mouse constraint between two bodies.getPartHandle
+    /// Initialize a mouse constraint between two bodies.
     ///
     /// Typically, `b1` will be the ground and the anchor the position of the mouse.
     /// Both anchors are expressed in the local coordinate frames of the corresponding body parts.
+    /// The `limit` is the maximum impulse the constraint can apply along each axis at each timestep.
     pub fn new(
         b1: BodyPartHandle<Handle>,
         b2: BodyPartHandle<Handle>,
@@ -38,15 +45,64 @@ impl<N: RealField, Handle: BodyHandle> MouseConstraint<N, Handle> {
             anchor1,
             anchor2,
             limit,
+            stiffness: None,
         }
     }
 
+    /// The first anchor, expressed in the local space of the first body part.
+    pub fn anchor_1(&self) -> &Point<N> {
+        &self.anchor1
+    }
+
+    /// The second anchor, expressed in the local space of the second body part.
+    pub fn anchor_2(&self) -> &Point<N> {
+        &self.anchor2
+    }
+
+    /// Sets the point the second anchor is dragged toward.
+    ///
+    /// This is the same as `self.set_anchor_1(target)`, hence `target` is expressed in world-space
+    /// only if the first body part is the ground.
+    pub fn set_target(&mut self, target: Point<N>) {
+        self.anchor1 = target;
+    }
+
+    /// The maximum impulse this constraint can apply along each axis at each timestep.
+    pub fn max_impulse(&self) -> N {
+        self.limit
+    }
+
+    /// Sets the maximum impulse this constraint can apply along each axis at each timestep.
+    pub fn set_max_impulse(&mut self, limit: N) {
+        self.limit = limit;
+    }
+
+    /// Sets the maximum impulse this constraint can apply to a force applied during `dt` seconds.
+    pub fn set_max_force(&mut self, max_force: N, dt: N) {
+        self.limit = max_force * dt;
+    }
+
+    /// The fraction of the distance between both anchors corrected at each timestep.
+    ///
+    /// If `None`, the error reduction parameter of the integration parameters is used.
+    pub fn stiffness(&self) -> Option<N> {
+        self.stiffness
+    }
+
+    /// Sets the fraction, in `[0, 1]`, of the distance between both anchors corrected at each timestep.
+    ///
+    /// Lower values make the dragging softer. If `None`, the error reduction parameter of the
+    /// integration parameters is used.
+    pub fn set_stiffness(&mut self, stiffness: Option<N>) {
+        self.stiffness = stiffness;
+    }
+
     /// Change the first anchor, expressed in the local space of the first body part.
     pub fn set_anchor_1(&mut self, anchor1: Point<N>) {
         self.anchor1 = anchor1;
     }
 
-    /// Change the first anchor, expressed in the local space of the second body part.
+    /// Change the second anchor, expressed in the local space of the second body part.
     pub fn set_anchor_2(&mut self, anchor2: Point<N>) {
         self.anchor2 = anchor2;
     }
@@ -61,6 +117,21 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for MouseConst
         (self.b1, self.b2)
     }
 
+    fn position_error(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> JointPositionError<N> {
+        let body1 = try_ret!(bodies.get(self.b1.0), JointPositionError::zero());
+        let body2 = try_ret!(bodies.get(self.b2.0), JointPositionError::zero());
+        let part1 = try_ret!(body1.part(self.b1.1), JointPositionError::zero());
+        let part2 = try_ret!(body2.part(self.b2.1), JointPositionError::zero());
+
+        let anchor1 = body1.world_point_at_material_point(part1, &self.anchor1);
+        let anchor2 = body2.world_point_at_material_point(part2, &self.anchor2);
+
+        JointPositionError {
+            linear: na::distance(&anchor1, &anchor2),
+            angular: N::zero(),
+        }
+    }
+
     fn velocity_constraints(
         &mut self,
         parameters: &IntegrationParameters<N>,
@@ -93,6 +164,7 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for MouseConst
         };
 
         let error = anchor2 - anchor1;
+        let stiffness = self.stiffness.unwrap_or(parameters.erp);
         let (ext_vels1, ext_vels2) =
             helper::split_ext_vels(body1, body2, assembly_id1, assembly_id2, ext_vels);
 
@@ -103,7 +175,7 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for MouseConst
 
         for dir in &canonical_basis {
             let fdir = ForceDirection::Linear(Unit::new_unchecked(*dir));
-            let mut rhs = -error.dot(&*dir) * parameters.erp * parameters.inv_dt();
+            let mut rhs = -error.dot(&*dir) * stiffness * parameters.inv_dt();
             let geom = helper::constraint_pair_geometry(
                 body1,
                 part1,
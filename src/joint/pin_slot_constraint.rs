@@ -1,7 +1,7 @@
 use na::{DVector, RealField, Unit};
 use std::ops::Range;
 
-use crate::joint::JointConstraint;
+use crate::joint::{JointConstraint, JointPositionError};
 use crate::math::{AngularVector, Point, Vector, DIM, SPATIAL_DIM};
 use crate::object::{BodyHandle, BodyPartHandle, BodySet};
 use crate::solver::helper;
@@ -130,6 +130,26 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for PinSlotCon
         (self.b1, self.b2)
     }
 
+    fn position_error(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> JointPositionError<N> {
+        let body1 = try_ret!(bodies.get(self.b1.0), JointPositionError::zero());
+        let body2 = try_ret!(bodies.get(self.b2.0), JointPositionError::zero());
+        let part1 = try_ret!(body1.part(self.b1.1), JointPositionError::zero());
+        let part2 = try_ret!(body2.part(self.b2.1), JointPositionError::zero());
+
+        let pos1 = part1.position();
+        let pos2 = part2.position();
+
+        let axis_v1 = pos1 * self.axis_v1;
+        let axis_w1 = pos1 * self.axis_w1;
+        let axis_w2 = pos2 * self.axis_w2;
+        let dpt = pos2 * self.anchor2 - pos1 * self.anchor1;
+
+        JointPositionError {
+            linear: (dpt - axis_v1.into_inner() * axis_v1.dot(&dpt)).norm(),
+            angular: axis_w1.angle(axis_w2.as_ref()),
+        }
+    }
+
     fn velocity_constraints(
         &mut self,
         _: &IntegrationParameters<N>,
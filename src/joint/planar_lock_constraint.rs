@@ -0,0 +1,139 @@
+use na::{DVector, RealField, Unit};
+
+use crate::joint::{JointConstraint, JointPositionError, PlanarConstraint};
+use crate::math::{Point, Vector};
+use crate::object::{BodyHandle, BodyPartHandle, BodySet};
+use crate::solver::{
+    GenericNonlinearConstraint, IntegrationParameters, LinearConstraints,
+    NonlinearConstraintGenerator,
+};
+
+/// A constraint locking a body part to an arbitrary plane.
+///
+/// The body part can only translate along the plane and rotate around the plane normal. This is
+/// typically used to confine the physics of a 3D scene to a possibly tilted plane, e.g., for a
+/// side-scroller. The plane is attached to another body part, usually the ground.
+pub struct PlanarLockConstraint<N: RealField, Handle: BodyHandle> {
+    plane_point: Point<N>,
+    plane_normal: Unit<Vector<N>>,
+    body_normal: Unit<Vector<N>>,
+    constraint: PlanarConstraint<N, Handle>,
+}
+
+impl<N: RealField, Handle: BodyHandle> PlanarLockConstraint<N, Handle> {
+    /// Creates a constraint locking the origin of `b2` to the plane with the given point and normal.
+    ///
+    /// The plane is expressed in the local-space of `b1` (typically the ground, in which case this
+    /// is a world-space plane). `body_normal` is the axis of `b2`, expressed in its local-space,
+    /// that is kept aligned with the plane normal.
+    pub fn new(
+        b1: BodyPartHandle<Handle>,
+        b2: BodyPartHandle<Handle>,
+        plane_point: Point<N>,
+        plane_normal: Unit<Vector<N>>,
+        body_normal: Unit<Vector<N>>,
+    ) -> Self {
+        PlanarLockConstraint {
+            plane_point,
+            plane_normal,
+            body_normal,
+            constraint: PlanarConstraint::new(
+                b1,
+                b2,
+                plane_point,
+                plane_normal,
+                Point::origin(),
+                body_normal,
+            ),
+        }
+    }
+
+    /// A point of the plane, in the local-space of the first body part.
+    pub fn plane_point(&self) -> &Point<N> {
+        &self.plane_point
+    }
+
+    /// The normal of the plane, in the local-space of the first body part.
+    pub fn plane_normal(&self) -> &Unit<Vector<N>> {
+        &self.plane_normal
+    }
+
+    /// The axis of the second body part kept aligned with the plane normal, in its local-space.
+    pub fn body_normal(&self) -> &Unit<Vector<N>> {
+        &self.body_normal
+    }
+
+    /// The maximum force this joint can absorb before breaking.
+    pub fn set_break_force(&mut self, break_force: N) {
+        self.constraint.set_break_force(break_force)
+    }
+
+    /// The maximum torque this joint can absorb before breaking.
+    pub fn set_break_torque(&mut self, break_torque: N) {
+        self.constraint.set_break_torque(break_torque)
+    }
+}
+
+impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
+    for PlanarLockConstraint<N, Handle>
+{
+    fn is_broken(&self) -> bool {
+        self.constraint.is_broken()
+    }
+
+    fn num_velocity_constraints(&self) -> usize {
+        self.constraint.num_velocity_constraints()
+    }
+
+    fn anchors(&self) -> (BodyPartHandle<Handle>, BodyPartHandle<Handle>) {
+        self.constraint.anchors()
+    }
+
+    fn position_error(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> JointPositionError<N> {
+        self.constraint.position_error(bodies)
+    }
+
+    fn velocity_constraints(
+        &mut self,
+        parameters: &IntegrationParameters<N>,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        ext_vels: &DVector<N>,
+        ground_j_id: &mut usize,
+        j_id: &mut usize,
+        jacobians: &mut [N],
+        constraints: &mut LinearConstraints<N, usize>,
+    ) {
+        self.constraint.velocity_constraints(
+            parameters,
+            bodies,
+            ext_vels,
+            ground_j_id,
+            j_id,
+            jacobians,
+            constraints,
+        )
+    }
+
+    fn cache_impulses(&mut self, constraints: &LinearConstraints<N, usize>, inv_dt: N) {
+        self.constraint.cache_impulses(constraints, inv_dt)
+    }
+}
+
+impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
+    for PlanarLockConstraint<N, Handle>
+{
+    fn num_position_constraints(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> usize {
+        self.constraint.num_position_constraints(bodies)
+    }
+
+    fn position_constraint(
+        &self,
+        parameters: &IntegrationParameters<N>,
+        i: usize,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+        jacobians: &mut [N],
+    ) -> Option<GenericNonlinearConstraint<N, Handle>> {
+        self.constraint
+            .position_constraint(parameters, i, bodies, jacobians)
+    }
+}
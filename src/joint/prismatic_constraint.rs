@@ -1,7 +1,7 @@
 use na::{DVector, RealField, Unit};
 use std::ops::Range;
 
-use crate::joint::{unit_constraint, JointConstraint};
+use crate::joint::{unit_constraint, JointConstraint, JointPositionError};
 use crate::math::{AngularVector, Point, Vector, DIM, SPATIAL_DIM};
 use crate::object::{BodyHandle, BodyPartHandle, BodySet};
 use crate::solver::helper;
@@ -129,6 +129,36 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
         (self.b1, self.b2)
     }
 
+    fn position_error(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> JointPositionError<N> {
+        let body1 = try_ret!(bodies.get(self.b1.0), JointPositionError::zero());
+        let body2 = try_ret!(bodies.get(self.b2.0), JointPositionError::zero());
+        let part1 = try_ret!(body1.part(self.b1.1), JointPositionError::zero());
+        let part2 = try_ret!(body2.part(self.b2.1), JointPositionError::zero());
+
+        let pos1 = body1.position_at_material_point(part1, &self.anchor1);
+        let pos2 = body2.position_at_material_point(part2, &self.anchor2);
+
+        let axis = pos1 * self.axis1;
+        let dpt = pos2.translation.vector - pos1.translation.vector;
+        let offset = axis.dot(&dpt);
+        let off_axis = dpt - axis.into_inner() * offset;
+
+        let mut limit_error = N::zero();
+
+        if let Some(min) = self.min_offset {
+            limit_error = limit_error.max(min - offset);
+        }
+
+        if let Some(max) = self.max_offset {
+            limit_error = limit_error.max(offset - max);
+        }
+
+        JointPositionError {
+            linear: (off_axis.norm_squared() + limit_error * limit_error).sqrt(),
+            angular: (pos2.rotation / pos1.rotation).scaled_axis().norm(),
+        }
+    }
+
     fn velocity_constraints(
         &mut self,
         _: &IntegrationParameters<N>,
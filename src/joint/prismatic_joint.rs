@@ -9,6 +9,7 @@ use crate::solver::{ConstraintSet, GenericNonlinearConstraint, IntegrationParame
 
 /// A unit joint that allows only one translational degree on freedom.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct PrismaticJoint<N: RealField> {
     axis: Unit<Vector<N>>,
     jacobian: Velocity<N>,
@@ -17,7 +18,10 @@ pub struct PrismaticJoint<N: RealField> {
 
     min_offset: Option<N>,
     max_offset: Option<N>,
+    at_lower_limit: bool,
+    at_upper_limit: bool,
     motor: JointMotor<N, N>,
+    friction_force: N,
 }
 
 impl<N: RealField> PrismaticJoint<N> {
@@ -32,7 +36,10 @@ impl<N: RealField> PrismaticJoint<N> {
             offset,
             min_offset: None,
             max_offset: None,
+            at_lower_limit: false,
+            at_upper_limit: false,
             motor: JointMotor::new(),
+            friction_force: N::zero(),
         }
     }
 
@@ -47,10 +54,18 @@ impl<N: RealField> PrismaticJoint<N> {
             offset: offset,
             min_offset: None,
             max_offset: None,
+            at_lower_limit: false,
+            at_upper_limit: false,
             motor: JointMotor::new(),
+            friction_force: N::zero(),
         }
     }
 
+    /// The axis of the translational degree of freedom.
+    pub fn axis(&self) -> Unit<Vector<N>> {
+        self.axis
+    }
+
     /// The relative displacement of the attached multibody links along the joint axis.
     pub fn offset(&self) -> N {
         self.offset
@@ -93,6 +108,20 @@ impl<N: RealField> PrismaticJoint<N> {
         self.assert_limits();
     }
 
+    /// Whether the lower limit of the relative displacement was reached during the last timestep.
+    ///
+    /// This is `true` if the lower limit generated a constraint during the last resolution.
+    pub fn at_lower_limit(&self) -> bool {
+        self.at_lower_limit
+    }
+
+    /// Whether the upper limit of the relative displacement was reached during the last timestep.
+    ///
+    /// This is `true` if the upper limit generated a constraint during the last resolution.
+    pub fn at_upper_limit(&self) -> bool {
+        self.at_upper_limit
+    }
+
     /// Returns `true` if the joint motor is enabled.
     pub fn is_linear_motor_enabled(&self) -> bool {
         self.motor.enabled
@@ -128,6 +157,68 @@ impl<N: RealField> PrismaticJoint<N> {
         self.motor.max_force = force;
     }
 
+    /// The maximum acceleration of the velocity targeted by the joint motor, if it is limited.
+    pub fn max_linear_motor_acceleration(&self) -> Option<N> {
+        self.motor.max_acceleration
+    }
+
+    /// Limits the acceleration of the velocity targeted by the joint motor.
+    ///
+    /// If set, the relative velocity targeted by the motor ramps toward the desired velocity over
+    /// time instead of jumping to it, so the motor speeds up smoothly.
+    pub fn set_max_linear_motor_acceleration(&mut self, max_acceleration: Option<N>) {
+        self.motor.max_acceleration = max_acceleration;
+    }
+
+    /// The relative velocity the joint motor attempts to reach during the next timestep.
+    ///
+    /// This differs from the desired velocity while it ramps toward it.
+    pub fn effective_linear_motor_velocity(&self) -> N {
+        self.motor.target_velocity()
+    }
+
+    /// The offset the joint motor drives this joint toward, if it acts as a position drive.
+    pub fn linear_motor_position(&self) -> Option<N> {
+        self.motor.target_position
+    }
+
+    /// Enables the linear motor and makes it drive this joint toward `target_offset`.
+    ///
+    /// The motor behaves like a spring with the given stiffness and damping, delivering at most the
+    /// force `max_force`. It is solved implicitly by the constraint solver so it remains stable for
+    /// arbitrarily large stiffnesses. The relative velocity it aims for at the target is the desired
+    /// linear velocity of the motor.
+    pub fn set_linear_motor_position(
+        &mut self,
+        target_offset: N,
+        stiffness: N,
+        damping: N,
+        max_force: N,
+    ) {
+        self.motor
+            .set_position_drive(target_offset, stiffness, damping, max_force)
+    }
+
+    /// Stops the linear motor from driving this joint toward a target offset.
+    ///
+    /// The motor, if enabled, then only drives the joint toward its desired linear velocity.
+    pub fn disable_linear_motor_position(&mut self) {
+        self.motor.target_position = None
+    }
+
+    /// The maximum force the Coulomb friction of this joint can apply to oppose its translation.
+    pub fn linear_friction_force(&self) -> N {
+        self.friction_force
+    }
+
+    /// Set the maximum force the Coulomb friction of this joint can apply to oppose its translation.
+    ///
+    /// Unlike damping, friction is able to keep the joint at rest under loads requiring a force
+    /// smaller than this value. Set it to zero to disable friction.
+    pub fn set_linear_friction_force(&mut self, force: N) {
+        self.friction_force = force;
+    }
+
     fn assert_limits(&self) {
         if let (Some(min_offset), Some(max_offset)) = (self.min_offset, self.max_offset) {
             assert!(
@@ -176,7 +267,18 @@ impl<N: RealField> Joint<N> for PrismaticJoint<N> {
     fn default_damping(&self, _: &mut DVectorSliceMut<N>) {}
 
     fn integrate(&mut self, parameters: &IntegrationParameters<N>, vels: &[N]) {
-        self.offset += vels[0] * parameters.dt()
+        // The limits constrained the velocities we are integrating if they were reached.
+        let (at_lower_limit, at_upper_limit) = joint::unit_joint_limits_reached(self);
+        self.at_lower_limit = at_lower_limit;
+        self.at_upper_limit = at_upper_limit;
+        self.offset += vels[0] * parameters.dt();
+
+        // A disabled motor starts from the current velocity once enabled.
+        if self.motor.enabled {
+            self.motor.update_effective_velocity(parameters.dt());
+        } else {
+            self.motor.effective_velocity = vels[0];
+        }
     }
 
     fn apply_displacement(&mut self, disp: &[N]) {
@@ -265,6 +367,10 @@ impl<N: RealField> UnitJoint<N> for PrismaticJoint<N> {
     fn max_position(&self) -> Option<N> {
         self.max_offset
     }
+
+    fn friction(&self) -> N {
+        self.friction_force
+    }
 }
 
 #[cfg(feature = "dim3")]
@@ -279,13 +385,20 @@ macro_rules! prismatic_motor_limit_methods (
             disable_max_offset,
             enable_min_offset,
             enable_max_offset,
+            at_lower_linear_limit,
+            at_upper_linear_limit,
             is_linear_motor_enabled,
             enable_linear_motor,
             disable_linear_motor,
             desired_linear_motor_velocity,
             set_desired_linear_motor_velocity,
             max_linear_motor_force,
-            set_max_linear_motor_force);
+            set_max_linear_motor_force,
+            max_linear_motor_acceleration,
+            set_max_linear_motor_acceleration,
+            effective_linear_motor_velocity,
+            linear_friction_force,
+            set_linear_friction_force);
     }
 );
 
@@ -301,13 +414,20 @@ macro_rules! prismatic_motor_limit_methods_1 (
             disable_max_offset_1,
             enable_min_offset_1,
             enable_max_offset_1,
+            at_lower_linear_limit_1,
+            at_upper_linear_limit_1,
             is_linear_motor_enabled_1,
             enable_linear_motor_1,
             disable_linear_motor_1,
             desired_linear_motor_velocity_1,
             set_desired_linear_motor_velocity_1,
             max_linear_motor_force_1,
-            set_max_linear_motor_force_1);
+            set_max_linear_motor_force_1,
+            max_linear_motor_acceleration_1,
+            set_max_linear_motor_acceleration_1,
+            effective_linear_motor_velocity_1,
+            linear_friction_force_1,
+            set_linear_friction_force_1);
     }
 );
 
@@ -323,13 +443,20 @@ macro_rules! prismatic_motor_limit_methods_2 (
             disable_max_offset_2,
             enable_min_offset_2,
             enable_max_offset_2,
+            at_lower_linear_limit_2,
+            at_upper_linear_limit_2,
             is_linear_motor_enabled_2,
             enable_linear_motor_2,
             disable_linear_motor_2,
             desired_linear_motor_velocity_2,
             set_desired_linear_motor_velocity_2,
             max_linear_motor_force2,
-            set_max_linear_motor_force_2);
+            set_max_linear_motor_force_2,
+            max_linear_motor_acceleration_2,
+            set_max_linear_motor_acceleration_2,
+            effective_linear_motor_velocity_2,
+            linear_friction_force_2,
+            set_linear_friction_force_2);
     }
 );
 
@@ -342,13 +469,20 @@ macro_rules! _prismatic_motor_limit_methods (
      $disable_max_offset: ident,
      $enable_min_offset:  ident,
      $enable_max_offset:  ident,
+     $at_lower_limit:    ident,
+     $at_upper_limit:    ident,
      $is_motor_enabled:  ident,
      $enable_motor:      ident,
      $disable_motor:     ident,
      $desired_motor_velocity:     ident,
      $set_desired_motor_velocity: ident,
      $max_motor_force:           ident,
-     $set_max_motor_force:       ident
+     $set_max_motor_force:       ident,
+     $max_motor_acceleration:     ident,
+     $set_max_motor_acceleration: ident,
+     $effective_motor_velocity:   ident,
+     $friction_force:            ident,
+     $set_friction_force:        ident
      ) => {
         impl<N: RealField> $ty<N> {
             /// The lower limit of the relative translational displacement of the attached multibody links along the joint axis.
@@ -381,6 +515,16 @@ macro_rules! _prismatic_motor_limit_methods (
                 self.$prism.enable_max_offset(limit)
             }
 
+            /// Whether the lower limit of the relative translational displacement was reached during the last timestep.
+            pub fn $at_lower_limit(&self) -> bool {
+                self.$prism.at_lower_limit()
+            }
+
+            /// Whether the upper limit of the relative translational displacement was reached during the last timestep.
+            pub fn $at_upper_limit(&self) -> bool {
+                self.$prism.at_upper_limit()
+            }
+
             /// Returns `true` if the joint translational motor is enabled.
             pub fn $is_motor_enabled(&self) -> bool {
                 self.$prism.is_linear_motor_enabled()
@@ -415,6 +559,31 @@ macro_rules! _prismatic_motor_limit_methods (
             pub fn $set_max_motor_force(&mut self, force: N) {
                 self.$prism.set_max_linear_motor_force(force)
             }
+
+            /// The maximum acceleration of the velocity targeted by the joint translational motor, if it is limited.
+            pub fn $max_motor_acceleration(&self) -> Option<N> {
+                self.$prism.max_linear_motor_acceleration()
+            }
+
+            /// Limits the acceleration of the velocity targeted by the joint translational motor.
+            pub fn $set_max_motor_acceleration(&mut self, max_acceleration: Option<N>) {
+                self.$prism.set_max_linear_motor_acceleration(max_acceleration)
+            }
+
+            /// The relative translational velocity the joint motor attempts to reach during the next timestep.
+            pub fn $effective_motor_velocity(&self) -> N {
+                self.$prism.effective_linear_motor_velocity()
+            }
+
+            /// The maximum force the Coulomb friction of this joint can apply to oppose its translation.
+            pub fn $friction_force(&self) -> N {
+                self.$prism.linear_friction_force()
+            }
+
+            /// Set the maximum force the Coulomb friction of this joint can apply to oppose its translation.
+            pub fn $set_friction_force(&mut self, force: N) {
+                self.$prism.set_linear_friction_force(force)
+            }
         }
     }
 );
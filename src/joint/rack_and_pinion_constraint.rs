@@ -0,0 +1,238 @@
+use na::{DVector, RealField};
+
+use crate::joint::{
+    GearConstraint, Joint, JointConstraint, JointPositionError, PrismaticJoint, RevoluteJoint,
+};
+use crate::object::{BodyHandle, BodyPartHandle, BodySet, Multibody};
+use crate::solver::{
+    GenericNonlinearConstraint, IntegrationParameters, LinearConstraints,
+    NonlinearConstraintGenerator,
+};
+
+/// A constraint coupling the offset of a prismatic joint to the angle of a revolute joint.
+///
+/// This models a pinion rotating with a revolute joint and meshing with a rack sliding along a
+/// prismatic joint, e.g., for steering mechanisms and linear actuators. Each radian of rotation of
+/// the pinion translates the rack by `pitch` along its axis. Use a negative pitch to reverse the
+/// direction of the translation. The pinion must be attached to its parent multibody link by a
+/// `RevoluteJoint`, and the rack by a `PrismaticJoint`. Otherwise, this constraint has no effect.
+pub struct RackAndPinionConstraint<N: RealField, Handle: BodyHandle> {
+    constraint: GearConstraint<N, Handle>,
+}
+
+impl<N: RealField, Handle: BodyHandle> RackAndPinionConstraint<N, Handle> {
+    /// Creates a constraint between the revolute joint of the link `pinion` and the prismatic joint of the link `rack`.
+    ///
+    /// The coupling is relative to the joint coordinates at the time the constraint is first solved.
+    pub fn new(pinion: BodyPartHandle<Handle>, rack: BodyPartHandle<Handle>, pitch: N) -> Self {
+        RackAndPinionConstraint {
+            constraint: GearConstraint::new(pinion, rack, pitch),
+        }
+    }
+
+    /// The translation of the rack per radian of rotation of the pinion.
+    pub fn pitch(&self) -> N {
+        self.constraint.ratio()
+    }
+
+    /// Sets the translation of the rack per radian of rotation of the pinion.
+    ///
+    /// The coupling is reset to be relative to the current joint coordinates.
+    pub fn set_pitch(&mut self, pitch: N) {
+        self.constraint.set_ratio(pitch)
+    }
+
+    /// The maximum force this joint can absorb before breaking.
+    pub fn set_break_force(&mut self, break_force: N) {
+        self.constraint.set_break_force(break_force)
+    }
+
+    // Whether the pinion is attached by a revolute joint and the rack by a prismatic joint.
+    fn has_valid_joints(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> bool {
+        let (pinion, rack) = self.constraint.anchors();
+        link_joint_is::<N, RevoluteJoint<N>, _>(bodies, pinion)
+            && link_joint_is::<N, PrismaticJoint<N>, _>(bodies, rack)
+    }
+}
+
+// Whether the given link is attached to its parent by a joint of type `J`.
+fn link_joint_is<N: RealField, J: Joint<N>, Handle: BodyHandle>(
+    bodies: &dyn BodySet<N, Handle = Handle>,
+    link: BodyPartHandle<Handle>,
+) -> bool {
+    bodies
+        .get(link.0)
+        .and_then(|body| body.downcast_ref::<Multibody<N>>())
+        .and_then(|multibody| multibody.link(link.1))
+        .map(|link| link.joint().is::<J>())
+        .unwrap_or(false)
+}
+
+impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
+    for RackAndPinionConstraint<N, Handle>
+{
+    fn is_broken(&self) -> bool {
+        self.constraint.is_broken()
+    }
+
+    fn num_velocity_constraints(&self) -> usize {
+        self.constraint.num_velocity_constraints()
+    }
+
+    fn anchors(&self) -> (BodyPartHandle<Handle>, BodyPartHandle<Handle>) {
+        self.constraint.anchors()
+    }
+
+    fn position_error(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> JointPositionError<N> {
+        if !self.has_valid_joints(bodies) {
+            return JointPositionError::zero();
+        }
+
+        self.constraint.position_error(bodies)
+    }
+
+    fn velocity_constraints(
+        &mut self,
+        parameters: &IntegrationParameters<N>,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        ext_vels: &DVector<N>,
+        ground_j_id: &mut usize,
+        j_id: &mut usize,
+        jacobians: &mut [N],
+        constraints: &mut LinearConstraints<N, usize>,
+    ) {
+        if !self.has_valid_joints(bodies) {
+            return;
+        }
+
+        self.constraint.velocity_constraints(
+            parameters,
+            bodies,
+            ext_vels,
+            ground_j_id,
+            j_id,
+            jacobians,
+            constraints,
+        )
+    }
+
+    fn cache_impulses(&mut self, constraints: &LinearConstraints<N, usize>, inv_dt: N) {
+        self.constraint.cache_impulses(constraints, inv_dt)
+    }
+}
+
+impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
+    for RackAndPinionConstraint<N, Handle>
+{
+    fn num_position_constraints(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> usize {
+        if !self.has_valid_joints(bodies) {
+            return 0;
+        }
+
+        self.constraint.num_position_constraints(bodies)
+    }
+
+    fn position_constraint(
+        &self,
+        parameters: &IntegrationParameters<N>,
+        i: usize,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+        jacobians: &mut [N],
+    ) -> Option<GenericNonlinearConstraint<N, Handle>> {
+        self.constraint
+            .position_constraint(parameters, i, bodies, jacobians)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RackAndPinionConstraint;
+    use crate::force_generator::DefaultForceGeneratorSet;
+    use crate::joint::{
+        DefaultJointConstraintSet, FixedJoint, Joint, PrismaticJoint, RevoluteJoint,
+    };
+    use crate::math::{Isometry, Vector};
+    use crate::object::{BodyPartHandle, DefaultBodySet, DefaultColliderSet, MultibodyDesc};
+    use crate::world::{DefaultGeometricalWorld, DefaultMechanicalWorld};
+
+    // Drives a pinion with a motor on a fixed frame, next to a second link attached by `joint`, and
+    // returns the angle of the pinion and the coordinate of the second link after two seconds.
+    fn drive_pinion(joint: impl Joint<f64>, pitch: f64) -> (f64, f64) {
+        let mut mechanical_world = DefaultMechanicalWorld::new(Vector::zeros());
+        let mut geometrical_world = DefaultGeometricalWorld::new();
+        let mut bodies = DefaultBodySet::new();
+        let mut colliders = DefaultColliderSet::new();
+        let mut constraints = DefaultJointConstraintSet::new();
+        let mut forces = DefaultForceGeneratorSet::new();
+
+        #[cfg(feature = "dim2")]
+        let mut pinion = RevoluteJoint::new(0.0);
+        #[cfg(feature = "dim3")]
+        let mut pinion = RevoluteJoint::new(Vector::z_axis(), 0.0);
+        pinion.enable_angular_motor();
+        pinion.set_desired_angular_motor_velocity(1.0);
+
+        let mut desc = MultibodyDesc::new(FixedJoint::new(Isometry::identity()));
+        let _ = desc
+            .add_child(pinion)
+            .set_body_shift(Vector::x())
+            .set_mass(1.0);
+        let _ = desc
+            .add_child(joint)
+            .set_parent_shift(Vector::y() * 3.0)
+            .set_body_shift(Vector::x())
+            .set_mass(1.0);
+        let handle = bodies.insert(desc.build());
+        let rack = RackAndPinionConstraint::new(
+            BodyPartHandle(handle, 1),
+            BodyPartHandle(handle, 2),
+            pitch,
+        );
+        let _ = constraints.insert(rack);
+
+        for _ in 0..120 {
+            mechanical_world.step(
+                &mut geometrical_world,
+                &mut bodies,
+                &mut colliders,
+                &mut constraints,
+                &mut forces,
+            );
+        }
+
+        let multibody = bodies.multibody(handle).unwrap();
+        let pinion = multibody.link(1).unwrap().joint();
+        let other = multibody.link(2).unwrap().joint();
+        let angle = pinion.downcast_ref::<RevoluteJoint<f64>>().unwrap().angle();
+        let coordinate = other
+            .downcast_ref::<PrismaticJoint<f64>>()
+            .map(|prismatic| prismatic.offset())
+            .or_else(|| {
+                other
+                    .downcast_ref::<RevoluteJoint<f64>>()
+                    .map(|revolute| revolute.angle())
+            })
+            .unwrap();
+
+        (angle, coordinate)
+    }
+
+    #[test]
+    fn rack_translates_by_the_pitch_per_radian_of_the_pinion() {
+        let (angle, offset) = drive_pinion(PrismaticJoint::new(Vector::x_axis(), 0.0), 0.5);
+        assert!(angle > 1.0, "The pinion did not turn.");
+        assert_relative_eq!(offset, 0.5 * angle, epsilon = 1.0e-2);
+    }
+
+    #[test]
+    fn coupling_without_a_prismatic_rack_has_no_effect() {
+        #[cfg(feature = "dim2")]
+        let hinge = RevoluteJoint::new(0.0);
+        #[cfg(feature = "dim3")]
+        let hinge = RevoluteJoint::new(Vector::z_axis(), 0.0);
+
+        let (angle, other_angle) = drive_pinion(hinge, 0.5);
+        assert!(angle > 1.0, "The pinion did not turn.");
+        assert_relative_eq!(other_angle, 0.0);
+    }
+}
@@ -1,7 +1,7 @@
 use na::{DVector, RealField, Unit, Vector3};
 use std::ops::Range;
 
-use crate::joint::JointConstraint;
+use crate::joint::{JointConstraint, JointPositionError};
 use crate::math::{AngularVector, Point};
 use crate::object::{BodyHandle, BodyPartHandle, BodySet};
 use crate::solver::helper;
@@ -80,6 +80,24 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
         (self.b1, self.b2)
     }
 
+    fn position_error(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> JointPositionError<N> {
+        let body1 = try_ret!(bodies.get(self.b1.0), JointPositionError::zero());
+        let body2 = try_ret!(bodies.get(self.b2.0), JointPositionError::zero());
+        let part1 = try_ret!(body1.part(self.b1.1), JointPositionError::zero());
+        let part2 = try_ret!(body2.part(self.b2.1), JointPositionError::zero());
+
+        let pos1 = body1.position_at_material_point(part1, &self.anchor1);
+        let pos2 = body2.position_at_material_point(part2, &self.anchor2);
+
+        let axis1 = pos1 * self.axis1;
+        let dpt = pos2.translation.vector - pos1.translation.vector;
+
+        JointPositionError {
+            linear: axis1.dot(&dpt).abs(),
+            angular: (pos2.rotation / pos1.rotation).scaled_axis().norm(),
+        }
+    }
+
     fn velocity_constraints(
         &mut self,
         _: &IntegrationParameters<N>,
@@ -7,6 +7,7 @@ use crate::solver::{ConstraintSet, GenericNonlinearConstraint, IntegrationParame
 
 /// A joint that allows two translational degrees of freedom.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct RectangularJoint<N: RealField> {
     prism1: PrismaticJoint<N>,
     prism2: PrismaticJoint<N>,
@@ -3,7 +3,7 @@ use na::Unit;
 use na::{DVector, RealField};
 use std::ops::Range;
 
-use crate::joint::JointConstraint;
+use crate::joint::{JointConstraint, JointPositionError};
 use crate::math::{AngularVector, Point, Vector, DIM, SPATIAL_DIM};
 use crate::object::{BodyHandle, BodyPartHandle, BodySet};
 use crate::solver::helper;
@@ -175,6 +175,40 @@ impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle>
         (self.b1, self.b2)
     }
 
+    fn can_scale_space(&self) -> bool {
+        true
+    }
+
+    fn scale_space(&mut self, factor: N) {
+        let factor2 = factor * factor;
+        self.anchor1 *= factor;
+        self.anchor2 *= factor;
+        self.lin_impulses *= factor;
+        self.ang_impulses *= factor2;
+        self.break_force_squared *= factor2;
+        self.break_torque_squared *= factor2 * factor2;
+    }
+
+    fn position_error(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> JointPositionError<N> {
+        let body1 = try_ret!(bodies.get(self.b1.0), JointPositionError::zero());
+        let body2 = try_ret!(bodies.get(self.b2.0), JointPositionError::zero());
+        let part1 = try_ret!(body1.part(self.b1.1), JointPositionError::zero());
+        let part2 = try_ret!(body2.part(self.b2.1), JointPositionError::zero());
+
+        let pos1 = body1.position_at_material_point(part1, &self.anchor1);
+        let pos2 = body2.position_at_material_point(part2, &self.anchor2);
+
+        #[cfg(feature = "dim2")]
+        let angular = N::zero();
+        #[cfg(feature = "dim3")]
+        let angular = (pos1 * self.axis1).angle((pos2 * self.axis2).as_ref());
+
+        JointPositionError {
+            linear: (pos2.translation.vector - pos1.translation.vector).norm(),
+            angular,
+        }
+    }
+
     fn velocity_constraints(
         &mut self,
         _: &IntegrationParameters<N>,
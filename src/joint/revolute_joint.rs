@@ -12,6 +12,7 @@ use crate::utils::GeneralizedCross;
 
 /// A unit joint that allows only one relative rotational degree of freedom between two multibody links.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 pub struct RevoluteJoint<N: RealField> {
     axis: Unit<AngularVector<N>>,
     jacobian: Velocity<N>,
@@ -23,7 +24,23 @@ pub struct RevoluteJoint<N: RealField> {
 
     min_angle: Option<N>,
     max_angle: Option<N>,
+    at_lower_limit: bool,
+    at_upper_limit: bool,
     motor: JointMotor<N, N>,
+    friction_torque: N,
+    spring: Option<RevoluteSpring<N>>,
+}
+
+/// A torsion spring pulling a revolute joint toward a rest angle.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct RevoluteSpring<N: RealField> {
+    /// The angle at which the spring applies no torque.
+    pub rest_angle: N,
+    /// The torque applied by the spring per radian away from the rest angle.
+    pub stiffness: N,
+    /// The torque opposing the joint rotation per unit of angular velocity.
+    pub damping: N,
 }
 
 impl<N: RealField> RevoluteJoint<N> {
@@ -39,7 +56,11 @@ impl<N: RealField> RevoluteJoint<N> {
             angle: angle,
             min_angle: None,
             max_angle: None,
+            at_lower_limit: false,
+            at_upper_limit: false,
             motor: JointMotor::new(),
+            friction_torque: N::zero(),
+            spring: None,
         }
     }
 
@@ -58,7 +79,11 @@ impl<N: RealField> RevoluteJoint<N> {
             angle: angle,
             min_angle: None,
             max_angle: None,
+            at_lower_limit: false,
+            at_upper_limit: false,
             motor: JointMotor::new(),
+            friction_torque: N::zero(),
+            spring: None,
         }
     }
 
@@ -134,6 +159,20 @@ impl<N: RealField> RevoluteJoint<N> {
         self.assert_limits();
     }
 
+    /// Whether the lower limit of the rotation angle was reached during the last timestep.
+    ///
+    /// This is `true` if the lower limit generated a constraint during the last resolution.
+    pub fn at_lower_limit(&self) -> bool {
+        self.at_lower_limit
+    }
+
+    /// Whether the upper limit of the rotation angle was reached during the last timestep.
+    ///
+    /// This is `true` if the upper limit generated a constraint during the last resolution.
+    pub fn at_upper_limit(&self) -> bool {
+        self.at_upper_limit
+    }
+
     /// Return `true` if the angular motor of this joint is enabled.
     pub fn is_angular_motor_enabled(&self) -> bool {
         self.motor.enabled
@@ -169,6 +208,91 @@ impl<N: RealField> RevoluteJoint<N> {
         self.motor.max_force = torque;
     }
 
+    /// The maximum angular acceleration of the velocity targeted by the joint motor, if it is limited.
+    pub fn max_angular_motor_acceleration(&self) -> Option<N> {
+        self.motor.max_acceleration
+    }
+
+    /// Limits the angular acceleration of the velocity targeted by the joint motor.
+    ///
+    /// If set, the angular velocity targeted by the motor ramps toward the desired velocity over
+    /// time instead of jumping to it, so the motor spins up smoothly.
+    pub fn set_max_angular_motor_acceleration(&mut self, max_acceleration: Option<N>) {
+        self.motor.max_acceleration = max_acceleration;
+    }
+
+    /// The angular velocity the joint motor attempts to reach during the next timestep.
+    ///
+    /// This differs from the desired velocity while it ramps toward it.
+    pub fn effective_angular_motor_velocity(&self) -> N {
+        self.motor.target_velocity()
+    }
+
+    /// The angle the joint motor drives this joint toward, if it acts as a position drive.
+    pub fn angular_motor_position(&self) -> Option<N> {
+        self.motor.target_position
+    }
+
+    /// Enables the angular motor and makes it drive this joint toward `target_angle`.
+    ///
+    /// The motor behaves like a torsion spring with the given stiffness and damping, delivering at
+    /// most the torque `max_torque`. Unlike `set_spring`, it is solved implicitly by the constraint
+    /// solver so it remains stable for arbitrarily large stiffnesses. The angular velocity it aims
+    /// for at the target is the desired angular velocity of the motor.
+    pub fn set_angular_motor_position(
+        &mut self,
+        target_angle: N,
+        stiffness: N,
+        damping: N,
+        max_torque: N,
+    ) {
+        self.motor
+            .set_position_drive(target_angle, stiffness, damping, max_torque)
+    }
+
+    /// Stops the angular motor from driving this joint toward a target angle.
+    ///
+    /// The motor, if enabled, then only drives the joint toward its desired angular velocity.
+    pub fn disable_angular_motor_position(&mut self) {
+        self.motor.target_position = None
+    }
+
+    /// The maximum torque the Coulomb friction of this joint can apply to oppose its rotation.
+    pub fn angular_friction_torque(&self) -> N {
+        self.friction_torque
+    }
+
+    /// Set the maximum torque the Coulomb friction of this joint can apply to oppose its rotation.
+    ///
+    /// Unlike damping, friction is able to keep the joint at rest under loads requiring a torque
+    /// smaller than this value. Set it to zero to disable friction.
+    pub fn set_angular_friction_torque(&mut self, torque: N) {
+        self.friction_torque = torque;
+    }
+
+    /// The torsion spring of this joint, if any.
+    pub fn spring(&self) -> Option<&RevoluteSpring<N>> {
+        self.spring.as_ref()
+    }
+
+    /// Sets a torsion spring pulling this joint toward `rest_angle`.
+    ///
+    /// The spring applies the torque `stiffness * (rest_angle - angle) - damping * angular_velocity`
+    /// at each timestep. It acts passively in addition to the joint limits, but is ignored while
+    /// the angular motor of this joint is enabled.
+    pub fn set_spring(&mut self, rest_angle: N, stiffness: N, damping: N) {
+        self.spring = Some(RevoluteSpring {
+            rest_angle,
+            stiffness,
+            damping,
+        })
+    }
+
+    /// Removes the torsion spring of this joint.
+    pub fn disable_spring(&mut self) {
+        self.spring = None
+    }
+
     fn assert_limits(&self) {
         if let (Some(min_angle), Some(max_angle)) = (self.min_angle, self.max_angle) {
             assert!(
@@ -223,14 +347,34 @@ impl<N: RealField> Joint<N> for RevoluteJoint<N> {
     }
 
     fn integrate(&mut self, parameters: &IntegrationParameters<N>, vels: &[N]) {
+        // The limits constrained the velocities we are integrating if they were reached.
+        let (at_lower_limit, at_upper_limit) = joint::unit_joint_limits_reached(self);
+        self.at_lower_limit = at_lower_limit;
+        self.at_upper_limit = at_upper_limit;
         self.angle += vels[0] * parameters.dt();
         self.update_rot();
+
+        // A disabled motor starts from the current velocity once enabled.
+        if self.motor.enabled {
+            self.motor.update_effective_velocity(parameters.dt());
+        } else {
+            self.motor.effective_velocity = vels[0];
+        }
     }
 
     fn default_damping(&self, out: &mut DVectorSliceMut<N>) {
         out.fill(na::convert(0.1f64))
     }
 
+    fn add_passive_forces(&self, vels: &[N], out: &mut [N]) {
+        if let Some(spring) = &self.spring {
+            if !self.motor.enabled {
+                out[0] +=
+                    spring.stiffness * (spring.rest_angle - self.angle) - spring.damping * vels[0];
+            }
+        }
+    }
+
     fn apply_displacement(&mut self, disp: &[N]) {
         self.angle += disp[0];
         self.update_rot();
@@ -318,6 +462,10 @@ impl<N: RealField> UnitJoint<N> for RevoluteJoint<N> {
     fn max_position(&self) -> Option<N> {
         self.max_angle
     }
+
+    fn friction(&self) -> N {
+        self.friction_torque
+    }
 }
 
 #[cfg(feature = "dim3")]
@@ -332,13 +480,20 @@ macro_rules! revolute_motor_limit_methods (
             disable_max_angle,
             enable_min_angle,
             enable_max_angle,
+            at_lower_angular_limit,
+            at_upper_angular_limit,
             is_angular_motor_enabled,
             enable_angular_motor,
             disable_angular_motor,
             desired_angular_motor_velocity,
             set_desired_angular_motor_velocity,
             max_angular_motor_torque,
-            set_max_angular_motor_torque);
+            set_max_angular_motor_torque,
+            max_angular_motor_acceleration,
+            set_max_angular_motor_acceleration,
+            effective_angular_motor_velocity,
+            angular_friction_torque,
+            set_angular_friction_torque);
     }
 );
 
@@ -354,13 +509,20 @@ macro_rules! revolute_motor_limit_methods_1 (
             disable_max_angle_1,
             enable_min_angle_1,
             enable_max_angle_1,
+            at_lower_angular_limit_1,
+            at_upper_angular_limit_1,
             is_angular_motor_enabled_1,
             enable_angular_motor_1,
             disable_angular_motor_1,
             desired_angular_motor_velocity_1,
             set_desired_angular_motor_velocity_1,
             max_angular_motor_torque_1,
-            set_max_angular_motor_torque_1);
+            set_max_angular_motor_torque_1,
+            max_angular_motor_acceleration_1,
+            set_max_angular_motor_acceleration_1,
+            effective_angular_motor_velocity_1,
+            angular_friction_torque_1,
+            set_angular_friction_torque_1);
     }
 );
 
@@ -376,13 +538,20 @@ macro_rules! revolute_motor_limit_methods_2 (
             disable_max_angle_2,
             enable_min_angle_2,
             enable_max_angle_2,
+            at_lower_angular_limit_2,
+            at_upper_angular_limit_2,
             is_angular_motor_enabled_2,
             enable_angular_motor_2,
             disable_angular_motor_2,
             desired_angular_motor_velocity_2,
             set_desired_angular_motor_velocity_2,
             max_angular_motor_torque_2,
-            set_max_angular_motor_torque_2);
+            set_max_angular_motor_torque_2,
+            max_angular_motor_acceleration_2,
+            set_max_angular_motor_acceleration_2,
+            effective_angular_motor_velocity_2,
+            angular_friction_torque_2,
+            set_angular_friction_torque_2);
     }
 );
 
@@ -395,13 +564,20 @@ macro_rules! _revolute_motor_limit_methods (
      $disable_max_angle: ident,
      $enable_min_angle:  ident,
      $enable_max_angle:  ident,
+     $at_lower_limit:    ident,
+     $at_upper_limit:    ident,
      $is_motor_enabled:  ident,
      $enable_motor:      ident,
      $disable_motor:     ident,
      $desired_motor_velocity:     ident,
      $set_desired_motor_velocity: ident,
      $max_motor_torque:           ident,
-     $set_max_motor_torque:       ident
+     $set_max_motor_torque:       ident,
+     $max_motor_acceleration:     ident,
+     $set_max_motor_acceleration: ident,
+     $effective_motor_velocity:   ident,
+     $friction_torque:            ident,
+     $set_friction_torque:        ident
      ) => {
         impl<N: RealField> $ty<N> {
             /// The lower limit of the rotation angle.
@@ -434,6 +610,16 @@ macro_rules! _revolute_motor_limit_methods (
                 self.$revo.enable_max_angle(limit)
             }
 
+            /// Whether the lower limit of the rotation angle was reached during the last timestep.
+            pub fn $at_lower_limit(&self) -> bool {
+                self.$revo.at_lower_limit()
+            }
+
+            /// Whether the upper limit of the rotation angle was reached during the last timestep.
+            pub fn $at_upper_limit(&self) -> bool {
+                self.$revo.at_upper_limit()
+            }
+
             /// Return `true` if the angular motor of this joint is enabled.
             pub fn $is_motor_enabled(&self) -> bool {
                 self.$revo.is_angular_motor_enabled()
@@ -468,6 +654,31 @@ macro_rules! _revolute_motor_limit_methods (
             pub fn $set_max_motor_torque(&mut self, torque: N) {
                 self.$revo.set_max_angular_motor_torque(torque)
             }
+
+            /// The maximum angular acceleration of the velocity targeted by the joint motor, if it is limited.
+            pub fn $max_motor_acceleration(&self) -> Option<N> {
+                self.$revo.max_angular_motor_acceleration()
+            }
+
+            /// Limits the angular acceleration of the velocity targeted by the joint motor.
+            pub fn $set_max_motor_acceleration(&mut self, max_acceleration: Option<N>) {
+                self.$revo.set_max_angular_motor_acceleration(max_acceleration)
+            }
+
+            /// The angular velocity the joint motor attempts to reach during the next timestep.
+            pub fn $effective_motor_velocity(&self) -> N {
+                self.$revo.effective_angular_motor_velocity()
+            }
+
+            /// The maximum torque the Coulomb friction of this joint can apply to oppose its rotation.
+            pub fn $friction_torque(&self) -> N {
+                self.$revo.angular_friction_torque()
+            }
+
+            /// Set the maximum torque the Coulomb friction of this joint can apply to oppose its rotation.
+            pub fn $set_friction_torque(&mut self, torque: N) {
+                self.$revo.set_angular_friction_torque(torque)
+            }
         }
     }
 );
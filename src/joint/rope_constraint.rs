@@ -0,0 +1,340 @@
+use na::{DVector, RealField, Unit};
+use std::ops::Range;
+
+use crate::joint::{unit_constraint, JointConstraint, JointPositionError};
+use crate::math::{Point, Vector};
+use crate::object::{BodyHandle, BodyPartHandle, BodySet};
+use crate::solver::helper;
+use crate::solver::{
+    BilateralConstraint, BilateralGroundConstraint, ForceDirection, GenericNonlinearConstraint,
+    ImpulseLimits, IntegrationParameters, LinearConstraints, NonlinearConstraintGenerator,
+};
+
+/// A constraint preventing the distance between two anchors from exceeding a maximum length.
+///
+/// The constraint is inactive while the anchors are closer than this length, i.e., while the rope
+/// is slack. Once the rope is taut, an optional damping opposes the relative velocity of the
+/// anchors along the rope.
+pub struct RopeConstraint<N: RealField, Handle: BodyHandle> {
+    b1: BodyPartHandle<Handle>,
+    b2: BodyPartHandle<Handle>,
+    anchor1: Point<N>,
+    anchor2: Point<N>,
+    length: N,
+    damping: N,
+    impulses: [N; 2],
+    break_force: N,
+    broken: bool,
+    bilateral_ground_rng: Range<usize>,
+    bilateral_rng: Range<usize>,
+}
+
+impl<N: RealField, Handle: BodyHandle> RopeConstraint<N, Handle> {
+    /// Creates a rope of the given maximum length between `anchor1` and `anchor2`.
+    ///
+    /// The anchors are expressed in the local-space of `b1` and `b2` respectively. The rope is not damped by default.
+    pub fn new(
+        b1: BodyPartHandle<Handle>,
+        b2: BodyPartHandle<Handle>,
+        anchor1: Point<N>,
+        anchor2: Point<N>,
+        length: N,
+    ) -> Self {
+        RopeConstraint {
+            b1,
+            b2,
+            anchor1,
+            anchor2,
+            length,
+            damping: N::zero(),
+            impulses: [N::zero(); 2],
+            break_force: N::max_value(),
+            broken: false,
+            bilateral_ground_rng: 0..0,
+            bilateral_rng: 0..0,
+        }
+    }
+
+    /// The maximum distance between the two anchors.
+    pub fn length(&self) -> N {
+        self.length
+    }
+
+    /// Sets the maximum distance between the two anchors.
+    pub fn set_length(&mut self, length: N) {
+        self.length = length
+    }
+
+    /// The force opposing the relative velocity of the anchors along the rope, per unit of velocity, while it is taut.
+    pub fn damping(&self) -> N {
+        self.damping
+    }
+
+    /// Sets the force opposing the relative velocity of the anchors along the rope, per unit of velocity, while it is taut.
+    pub fn set_damping(&mut self, damping: N) {
+        self.damping = damping
+    }
+
+    /// The maximum force this joint can absorb before breaking.
+    pub fn set_break_force(&mut self, break_force: N) {
+        self.break_force = break_force
+    }
+
+    // The world-space anchors, and the direction from the first to the second one.
+    fn world_anchors(
+        &self,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+    ) -> Option<(Point<N>, Point<N>, Option<Unit<Vector<N>>>)> {
+        let body1 = bodies.get(self.b1.0)?;
+        let body2 = bodies.get(self.b2.0)?;
+        let part1 = body1.part(self.b1.1)?;
+        let part2 = body2.part(self.b2.1)?;
+
+        let anchor1 = body1.world_point_at_material_point(part1, &self.anchor1);
+        let anchor2 = body2.world_point_at_material_point(part2, &self.anchor2);
+        let dir = Unit::try_new(anchor2 - anchor1, N::default_epsilon());
+
+        Some((anchor1, anchor2, dir))
+    }
+}
+
+impl<N: RealField, Handle: BodyHandle> JointConstraint<N, Handle> for RopeConstraint<N, Handle> {
+    fn is_broken(&self) -> bool {
+        self.broken
+    }
+
+    fn num_velocity_constraints(&self) -> usize {
+        2
+    }
+
+    fn anchors(&self) -> (BodyPartHandle<Handle>, BodyPartHandle<Handle>) {
+        (self.b1, self.b2)
+    }
+
+    fn position_error(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> JointPositionError<N> {
+        let (anchor1, anchor2, _) =
+            try_ret!(self.world_anchors(bodies), JointPositionError::zero());
+
+        JointPositionError {
+            linear: (na::distance(&anchor1, &anchor2) - self.length).max(N::zero()),
+            angular: N::zero(),
+        }
+    }
+
+    fn velocity_constraints(
+        &mut self,
+        parameters: &IntegrationParameters<N>,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        ext_vels: &DVector<N>,
+        ground_j_id: &mut usize,
+        j_id: &mut usize,
+        jacobians: &mut [N],
+        constraints: &mut LinearConstraints<N, usize>,
+    ) {
+        let first_bilateral_ground = constraints.bilateral_ground.len();
+        let first_bilateral = constraints.bilateral.len();
+        self.bilateral_ground_rng = first_bilateral_ground..first_bilateral_ground;
+        self.bilateral_rng = first_bilateral..first_bilateral;
+
+        let (anchor1, anchor2, dir) = try_ret!(self.world_anchors(bodies));
+        let dir = try_ret!(dir);
+        let body1 = try_ret!(bodies.get(self.b1.0));
+        let body2 = try_ret!(bodies.get(self.b2.0));
+        let part1 = try_ret!(body1.part(self.b1.1));
+        let part2 = try_ret!(body2.part(self.b2.1));
+
+        let assembly_id1 = body1.companion_id();
+        let assembly_id2 = body2.companion_id();
+
+        unit_constraint::build_linear_limits_velocity_constraint(
+            body1,
+            part1,
+            self.b1,
+            body2,
+            part2,
+            self.b2,
+            assembly_id1,
+            assembly_id2,
+            &anchor1,
+            &anchor2,
+            &dir,
+            None,
+            Some(self.length),
+            ext_vels,
+            self.impulses[0],
+            0,
+            ground_j_id,
+            j_id,
+            jacobians,
+            constraints,
+        );
+
+        if !self.damping.is_zero() && na::distance(&anchor1, &anchor2) >= self.length {
+            let (ext_vels1, ext_vels2) =
+                helper::split_ext_vels(body1, body2, assembly_id1, assembly_id2, ext_vels);
+            let mut rhs = N::zero();
+            let geom = helper::constraint_pair_geometry(
+                body1,
+                part1,
+                self.b1,
+                body2,
+                part2,
+                self.b2,
+                &anchor1,
+                &anchor2,
+                &ForceDirection::Linear(dir),
+                ground_j_id,
+                j_id,
+                jacobians,
+                Some(&ext_vels1),
+                Some(&ext_vels2),
+                Some(&mut rhs),
+            );
+
+            // The damping impulse cannot exceed the one a viscous damper would apply during this timestep.
+            let max_impulse = self.damping * rhs.abs() * parameters.dt();
+            let limits = ImpulseLimits::Independent {
+                min: -max_impulse,
+                max: max_impulse,
+            };
+
+            if geom.is_ground_constraint() {
+                constraints
+                    .bilateral_ground
+                    .push(BilateralGroundConstraint::new(
+                        geom,
+                        assembly_id1,
+                        assembly_id2,
+                        limits,
+                        rhs,
+                        self.impulses[1],
+                        1,
+                    ));
+            } else {
+                constraints.bilateral.push(BilateralConstraint::new(
+                    geom,
+                    assembly_id1,
+                    assembly_id2,
+                    limits,
+                    rhs,
+                    self.impulses[1],
+                    1,
+                ));
+            }
+        } else {
+            self.impulses[1] = N::zero();
+        }
+
+        self.bilateral_ground_rng = first_bilateral_ground..constraints.bilateral_ground.len();
+        self.bilateral_rng = first_bilateral..constraints.bilateral.len();
+    }
+
+    fn cache_impulses(&mut self, constraints: &LinearConstraints<N, usize>, inv_dt: N) {
+        self.impulses = [N::zero(); 2];
+
+        for c in &constraints.bilateral_ground[self.bilateral_ground_rng.clone()] {
+            self.impulses[c.impulse_id] = c.impulse;
+        }
+
+        for c in &constraints.bilateral[self.bilateral_rng.clone()] {
+            self.impulses[c.impulse_id] = c.impulse;
+        }
+
+        if (self.impulses[0] + self.impulses[1]).abs() * inv_dt > self.break_force {
+            self.broken = true;
+        }
+    }
+}
+
+impl<N: RealField, Handle: BodyHandle> NonlinearConstraintGenerator<N, Handle>
+    for RopeConstraint<N, Handle>
+{
+    fn num_position_constraints(&self, bodies: &dyn BodySet<N, Handle = Handle>) -> usize {
+        if self.is_active(bodies) {
+            1
+        } else {
+            0
+        }
+    }
+
+    fn position_constraint(
+        &self,
+        parameters: &IntegrationParameters<N>,
+        _: usize,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+        jacobians: &mut [N],
+    ) -> Option<GenericNonlinearConstraint<N, Handle>> {
+        let (anchor1, anchor2, dir) = self.world_anchors(bodies)?;
+        let body1 = bodies.get(self.b1.0)?;
+        let body2 = bodies.get(self.b2.0)?;
+        let part1 = body1.part(self.b1.1)?;
+        let part2 = body2.part(self.b2.1)?;
+
+        unit_constraint::build_linear_limits_position_constraint(
+            parameters,
+            body1,
+            part1,
+            self.b1,
+            body2,
+            part2,
+            self.b2,
+            &anchor1,
+            &anchor2,
+            &dir?,
+            None,
+            Some(self.length),
+            jacobians,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ncollide::shape::{Ball, ShapeHandle};
+
+    use super::RopeConstraint;
+    use crate::analytic_tests::{Scenario, GRAVITY};
+    use crate::math::{Point, Vector};
+    use crate::object::{BodyPartHandle, RigidBodyDesc};
+
+    #[test]
+    fn rope_limits_the_distance_only_when_taut() {
+        let mut scenario = Scenario::new(1.0 / 240.0);
+
+        // A ball hanging from a slack rope attached to the origin.
+        let length = 1.0;
+        let desc = RigidBodyDesc::new().translation(Vector::x() * 0.5);
+        let bob = scenario.add_body(&desc, ShapeHandle::new(Ball::new(0.05)), 0.0, 0.0);
+        let rope = RopeConstraint::new(
+            BodyPartHandle(scenario.ground, 0),
+            BodyPartHandle(bob, 0),
+            Point::origin(),
+            Point::origin(),
+            length,
+        );
+        let _ = scenario.joint_constraints.insert(rope);
+
+        let mut taut = false;
+
+        while scenario.time() < 3.0 {
+            scenario.step();
+            let rb = scenario.rigid_body(bob);
+            let distance = rb.position().translation.vector.norm();
+            assert!(
+                distance < length + 0.01,
+                "The rope stretched to {}.",
+                distance
+            );
+
+            // The ball falls freely until the rope becomes taut.
+            taut = taut || distance > length * 0.99;
+
+            if !taut {
+                let expected = -Vector::y() * GRAVITY * scenario.time();
+                assert!((rb.velocity().linear - expected).norm() < 1.0e-6);
+            }
+        }
+
+        assert!(taut, "The rope never became taut.");
+    }
+}
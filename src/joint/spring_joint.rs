@@ -0,0 +1,326 @@
+use na::{self, DVectorSliceMut, RealField, Unit};
+
+use crate::joint::{self, Joint, JointMotor, UnitJoint};
+use crate::math::{Dim, Isometry, JacobianSliceMut, Rotation, Translation, Vector, Velocity};
+use crate::object::{BodyPartHandle, Multibody, MultibodyLink};
+use crate::solver::{ConstraintSet, GenericNonlinearConstraint, IntegrationParameters};
+
+/// A unit joint that allows only one translational degree of freedom, driven by a spring and a damper.
+///
+/// This behaves like a prismatic joint where the relative displacement of the attached multibody
+/// links is pulled back toward a rest length instead of being left free. Hard travel stops can be
+/// set with `enable_min_offset` and `enable_max_offset`. This is typically used to model the
+/// suspension of a vehicle.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct SpringJoint<N: RealField> {
+    axis: Unit<Vector<N>>,
+    offset: N,
+    velocity: N,
+
+    rest_length: N,
+    stiffness: N,
+    damping: N,
+    preload: N,
+
+    min_offset: Option<N>,
+    max_offset: Option<N>,
+    // Never enabled, but required by the `UnitJoint` trait.
+    motor: JointMotor<N, N>,
+}
+
+impl<N: RealField> SpringJoint<N> {
+    /// Create a new spring joint along the provided axis, initially at its rest length.
+    ///
+    /// The axis is expressed in the local coordinate system of the two multibody links attached to this joint.
+    pub fn new(axis: Unit<Vector<N>>, rest_length: N, stiffness: N, damping: N) -> Self {
+        SpringJoint {
+            axis,
+            offset: rest_length,
+            velocity: N::zero(),
+            rest_length,
+            stiffness,
+            damping,
+            preload: N::zero(),
+            min_offset: None,
+            max_offset: None,
+            motor: JointMotor::new(),
+        }
+    }
+
+    /// The relative displacement of the attached multibody links along the joint axis.
+    pub fn offset(&self) -> N {
+        self.offset
+    }
+
+    /// The relative translation of the attached multibody links along the joint axis.
+    pub fn translation(&self) -> Translation<N> {
+        Translation::from(*self.axis * self.offset)
+    }
+
+    /// The displacement along the joint axis at which the spring does not exert any force.
+    pub fn rest_length(&self) -> N {
+        self.rest_length
+    }
+
+    /// Sets the displacement along the joint axis at which the spring does not exert any force.
+    pub fn set_rest_length(&mut self, rest_length: N) {
+        self.rest_length = rest_length
+    }
+
+    /// The stiffness of the spring.
+    pub fn stiffness(&self) -> N {
+        self.stiffness
+    }
+
+    /// Sets the stiffness of the spring.
+    pub fn set_stiffness(&mut self, stiffness: N) {
+        self.stiffness = stiffness
+    }
+
+    /// The damping coefficient of the damper.
+    pub fn damping(&self) -> N {
+        self.damping
+    }
+
+    /// Sets the damping coefficient of the damper.
+    pub fn set_damping(&mut self, damping: N) {
+        self.damping = damping
+    }
+
+    /// The constant force added to the spring force, pushing the attached multibody links apart.
+    pub fn preload(&self) -> N {
+        self.preload
+    }
+
+    /// Sets the constant force added to the spring force, pushing the attached multibody links apart.
+    pub fn set_preload(&mut self, preload: N) {
+        self.preload = preload
+    }
+
+    /// How much the spring is compressed wrt. its rest length.
+    ///
+    /// This is negative if the spring is extended.
+    pub fn compression(&self) -> N {
+        self.rest_length - self.offset
+    }
+
+    /// The force currently exerted by the spring and the damper along the joint axis.
+    ///
+    /// A positive value pushes the attached multibody links apart. This is computed from the
+    /// joint velocity at the last kinematic update of the multibody.
+    pub fn spring_force(&self) -> N {
+        self.preload + self.stiffness * self.compression() - self.damping * self.velocity
+    }
+
+    /// The lower limit of the relative displacement of the attached multibody links along the joint axis.
+    pub fn min_offset(&self) -> Option<N> {
+        self.min_offset
+    }
+
+    /// The upper limit of the relative displacement of the attached multibody links along the joint axis.
+    pub fn max_offset(&self) -> Option<N> {
+        self.max_offset
+    }
+
+    /// Disable the lower limit of the relative displacement of the attached multibody links along the joint axis.
+    pub fn disable_min_offset(&mut self) {
+        self.min_offset = None;
+    }
+
+    /// Disable the upper limit of the relative displacement of the attached multibody links along the joint axis.
+    pub fn disable_max_offset(&mut self) {
+        self.max_offset = None;
+    }
+
+    /// Set the lower limit of the relative displacement of the attached multibody links along the joint axis.
+    pub fn enable_min_offset(&mut self, limit: N) {
+        self.min_offset = Some(limit);
+        self.assert_limits();
+    }
+
+    /// Set the upper limit of the relative displacement of the attached multibody links along the joint axis.
+    pub fn enable_max_offset(&mut self, limit: N) {
+        self.max_offset = Some(limit);
+        self.assert_limits();
+    }
+
+    fn assert_limits(&self) {
+        if let (Some(min_offset), Some(max_offset)) = (self.min_offset, self.max_offset) {
+            assert!(
+                min_offset <= max_offset,
+                "SpringJoint joint limits: the min offset must be smaller than (or equal to) the max offset.");
+        }
+    }
+}
+
+impl<N: RealField> Joint<N> for SpringJoint<N> {
+    #[inline]
+    fn ndofs(&self) -> usize {
+        1
+    }
+
+    fn body_to_parent(&self, parent_shift: &Vector<N>, body_shift: &Vector<N>) -> Isometry<N> {
+        let trans = Translation::from(parent_shift - body_shift + self.axis.as_ref() * self.offset);
+        Isometry::from_parts(trans, Rotation::identity())
+    }
+
+    fn update_jacobians(&mut self, _: &Vector<N>, vels: &[N]) {
+        self.velocity = vels[0];
+    }
+
+    fn jacobian(&self, transform: &Isometry<N>, out: &mut JacobianSliceMut<N>) {
+        let transformed_axis = transform * self.axis;
+        out.fixed_rows_mut::<Dim>(0)
+            .copy_from(transformed_axis.as_ref())
+    }
+
+    fn jacobian_dot(&self, _: &Isometry<N>, _: &mut JacobianSliceMut<N>) {}
+
+    fn jacobian_dot_veldiff_mul_coordinates(
+        &self,
+        _: &Isometry<N>,
+        _: &[N],
+        _: &mut JacobianSliceMut<N>,
+    ) {
+    }
+
+    fn default_damping(&self, _: &mut DVectorSliceMut<N>) {}
+
+    fn add_passive_forces(&self, vels: &[N], out: &mut [N]) {
+        out[0] += self.preload + self.stiffness * self.compression() - self.damping * vels[0];
+    }
+
+    fn integrate(&mut self, parameters: &IntegrationParameters<N>, vels: &[N]) {
+        self.offset += vels[0] * parameters.dt()
+    }
+
+    fn apply_displacement(&mut self, disp: &[N]) {
+        self.offset += disp[0]
+    }
+
+    fn jacobian_mul_coordinates(&self, acc: &[N]) -> Velocity<N> {
+        Velocity::new(self.axis.as_ref() * acc[0], na::zero())
+    }
+
+    fn jacobian_dot_mul_coordinates(&self, _: &[N]) -> Velocity<N> {
+        Velocity::zero()
+    }
+
+    #[inline]
+    fn clone(&self) -> Box<dyn Joint<N>> {
+        Box::new(*self)
+    }
+
+    fn num_velocity_constraints(&self) -> usize {
+        joint::unit_joint_num_velocity_constraints(self)
+    }
+
+    fn velocity_constraints(
+        &self,
+        parameters: &IntegrationParameters<N>,
+        multibody: &Multibody<N>,
+        link: &MultibodyLink<N>,
+        assembly_id: usize,
+        dof_id: usize,
+        ext_vels: &[N],
+        ground_j_id: &mut usize,
+        jacobians: &mut [N],
+        constraints: &mut ConstraintSet<N, (), (), usize>,
+    ) {
+        joint::unit_joint_velocity_constraints(
+            self,
+            parameters,
+            multibody,
+            link,
+            assembly_id,
+            dof_id,
+            ext_vels,
+            ground_j_id,
+            jacobians,
+            constraints,
+        )
+    }
+
+    fn num_position_constraints(&self) -> usize {
+        if self.min_offset.is_some() || self.max_offset.is_some() {
+            1
+        } else {
+            0
+        }
+    }
+
+    fn position_constraint(
+        &self,
+        _: usize,
+        multibody: &Multibody<N>,
+        link: &MultibodyLink<N>,
+        handle: BodyPartHandle<()>,
+        dof_id: usize,
+        jacobians: &mut [N],
+    ) -> Option<GenericNonlinearConstraint<N, ()>> {
+        joint::unit_joint_position_constraint(
+            self, multibody, link, handle, dof_id, false, jacobians,
+        )
+    }
+}
+
+impl<N: RealField> UnitJoint<N> for SpringJoint<N> {
+    fn position(&self) -> N {
+        self.offset
+    }
+
+    fn motor(&self) -> &JointMotor<N, N> {
+        &self.motor
+    }
+
+    fn min_position(&self) -> Option<N> {
+        self.min_offset
+    }
+
+    fn max_position(&self) -> Option<N> {
+        self.max_offset
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use na::RealField;
+
+    use super::SpringJoint;
+    use crate::analytic_tests::{
+        assert_relative_error, downward_zero_crossings, Scenario, GRAVITY,
+    };
+    use crate::joint::FixedJoint;
+    use crate::math::{Isometry, Vector};
+    use crate::object::{BodyPart, MultibodyDesc};
+
+    #[test]
+    fn spring_joint_period() {
+        let mut scenario = Scenario::new(1.0 / 240.0);
+
+        let mass = 1.0;
+        let stiffness = 100.0;
+        let spring = SpringJoint::new(Vector::y_axis(), 0.0, stiffness, 0.0);
+
+        // A vertical spring released at its rest length, oscillating around its loaded length.
+        let mut desc = MultibodyDesc::new(FixedJoint::new(Isometry::identity()));
+        let _ = desc.add_child(spring).set_mass(mass);
+        let handle = scenario.bodies.insert(desc.build());
+
+        let expected_period = f64::two_pi() * (mass / stiffness).sqrt();
+        let equilibrium = -mass * GRAVITY / stiffness;
+        let mut samples = Vec::new();
+
+        while scenario.time() < expected_period * 2.5 {
+            scenario.step();
+            let link = scenario.bodies.multibody(handle).unwrap().link(1).unwrap();
+            let y = link.position().translation.vector.y;
+            samples.push((scenario.time(), y - equilibrium));
+        }
+
+        let crossings = downward_zero_crossings(&samples);
+        assert!(crossings.len() >= 2, "The spring did not oscillate.");
+        assert_relative_error(crossings[1] - crossings[0], expected_period, 0.02);
+    }
+}
@@ -5,8 +5,8 @@ use na::{DVectorSliceMut, RealField};
 use crate::joint::{Joint, JointMotor};
 use crate::object::{Body, BodyPartHandle, Multibody, MultibodyLink};
 use crate::solver::{
-    BilateralGroundConstraint, ConstraintSet, GenericNonlinearConstraint, IntegrationParameters,
-    UnilateralGroundConstraint,
+    BilateralGroundConstraint, ConstraintSet, GenericNonlinearConstraint, ImpulseLimits,
+    IntegrationParameters, UnilateralGroundConstraint,
 };
 
 /// Trait implemented by joints using the reduced-coordinates approach and allowing only one degree of freedom.
@@ -19,10 +19,26 @@ pub trait UnitJoint<N: RealField>: Joint<N> {
     fn min_position(&self) -> Option<N>;
     /// The upper limit, if any, set to the generalized coordinate of this unit joint.
     fn max_position(&self) -> Option<N>;
+    /// The maximum generalized force the Coulomb friction of this unit joint can apply.
+    fn friction(&self) -> N {
+        N::zero()
+    }
 }
 
 impl_downcast!(UnitJoint<N> where N: RealField);
 
+/// Whether the lower and upper limits of the given unit joint are reached.
+///
+/// A limit is reached when the generalized coordinate of the joint lies on or beyond it, in which
+/// case the limit generates a constraint during the next resolution.
+pub fn unit_joint_limits_reached<N: RealField, J: UnitJoint<N>>(joint: &J) -> (bool, bool) {
+    let position = joint.position();
+    let at_lower = joint.min_position().map(|min| position <= min);
+    let at_upper = joint.max_position().map(|max| position >= max);
+
+    (at_lower.unwrap_or(false), at_upper.unwrap_or(false))
+}
+
 /// Computes the maximum number of velocity constraints to be applied by the given unit joint.
 pub fn unit_joint_num_velocity_constraints<N: RealField, J: UnitJoint<N>>(joint: &J) -> usize {
     // FIXME: don't always keep the constraints active.
@@ -31,6 +47,9 @@ pub fn unit_joint_num_velocity_constraints<N: RealField, J: UnitJoint<N>>(joint:
     if joint.motor().enabled {
         nconstraints += 1;
     }
+    if joint.friction() > N::zero() {
+        nconstraints += 1;
+    }
     if joint.min_position().is_some() {
         nconstraints += 1;
     }
@@ -60,7 +79,14 @@ pub fn unit_joint_velocity_constraints<N: RealField, J: UnitJoint<N>>(
     let mut is_min_constraint_active = false;
     let joint_velocity = multibody.joint_velocity(link);
 
-    if joint.motor().enabled {
+    let motor = joint.motor();
+    let drive = if motor.enabled {
+        motor.drive(joint.position(), parameters.dt())
+    } else {
+        None
+    };
+
+    if let Some((target_velocity, cfm)) = drive {
         let dvel = joint_velocity[dof_id] + ext_vels[link.assembly_id];
 
         DVectorSliceMut::from_slice(&mut jacobians[*ground_j_id..], ndofs).fill(N::zero());
@@ -70,14 +96,51 @@ pub fn unit_joint_velocity_constraints<N: RealField, J: UnitJoint<N>>(
         multibody.inv_mass_mul_unit_joint_force(link, dof_id, N::one(), &mut jacobians[wj_id..]);
 
         let inv_r = jacobians[wj_id + link.assembly_id + dof_id]; // = J^t * M^-1 J
-        let rhs = dvel - joint.motor().desired_velocity;
-        let limits = joint.motor().impulse_limits();
+        let rhs = dvel - target_velocity;
+        let limits = motor.impulse_limits();
         let impulse_id = link.impulse_id + dof_id * 3;
 
         let constraint = BilateralGroundConstraint {
             impulse: impulses[impulse_id] * parameters.warmstart_coeff,
             r: N::one() / inv_r,
             rhs,
+            cfm,
+            limits,
+            impulse_id,
+            assembly_id,
+            j_id: *ground_j_id,
+            wj_id: *ground_j_id + ndofs,
+            ndofs,
+        };
+
+        constraints.velocity.bilateral_ground.push(constraint);
+        *ground_j_id += 2 * ndofs;
+    }
+
+    if joint.friction() > N::zero() {
+        let dvel = joint_velocity[dof_id] + ext_vels[link.assembly_id + dof_id];
+
+        DVectorSliceMut::from_slice(&mut jacobians[*ground_j_id..], ndofs).fill(N::zero());
+        jacobians[*ground_j_id + link.assembly_id + dof_id] = N::one();
+
+        let wj_id = *ground_j_id + ndofs;
+        multibody.inv_mass_mul_unit_joint_force(link, dof_id, N::one(), &mut jacobians[wj_id..]);
+
+        let inv_r = jacobians[wj_id + link.assembly_id + dof_id]; // = J^t * M^-1 J
+        let max_impulse = joint.friction() * parameters.dt();
+        let limits = ImpulseLimits::Independent {
+            min: -max_impulse,
+            max: max_impulse,
+        };
+        // The friction impulses are stored after the motor and limit impulses of every degree of freedom of the joint.
+        let impulse_id = link.impulse_id + link.joint().ndofs() * 3 + dof_id;
+
+        // Attempts to stop the joint motion, like a motor with a zero desired velocity.
+        let constraint = BilateralGroundConstraint {
+            impulse: impulses[impulse_id] * parameters.warmstart_coeff,
+            r: N::one() / inv_r,
+            rhs: dvel,
+            cfm: N::zero(),
             limits,
             impulse_id,
             assembly_id,
@@ -112,6 +175,7 @@ pub fn unit_joint_velocity_constraints<N: RealField, J: UnitJoint<N>>(
             let impulse_id = link.impulse_id + dof_id * 3 + 1;
             let constraint = UnilateralGroundConstraint {
                 impulse: impulses[impulse_id] * parameters.warmstart_coeff,
+                max_impulse: N::max_value(),
                 r: N::one() / inv_r,
                 rhs: dvel,
                 impulse_id,
@@ -154,6 +218,7 @@ pub fn unit_joint_velocity_constraints<N: RealField, J: UnitJoint<N>>(
             let impulse_id = link.impulse_id + dof_id * 3 + 2;
             let constraint = UnilateralGroundConstraint {
                 impulse: impulses[impulse_id] * parameters.warmstart_coeff,
+                max_impulse: N::max_value(),
                 r: N::one() / inv_r,
                 rhs: dvel,
                 impulse_id,
@@ -86,6 +86,9 @@ extern crate ncollide2d as ncollide;
 #[cfg(feature = "dim3")]
 extern crate ncollide3d as ncollide;
 extern crate num_traits as num;
+#[cfg(feature = "serde-serialize")]
+#[macro_use]
+extern crate serde;
 
 //#[cfg(test)]
 //extern crate test;
@@ -191,12 +194,16 @@ macro_rules! desc_getters(
 macro_rules! user_data_accessors(
     () => {
         /// Retrieves a reference to the user-defined user-data attached to this object.
+        ///
+        /// Use `.downcast_ref::<T>()` on the result to retrieve the user-data with its concrete type `T`.
         #[inline]
         pub fn user_data(&self) -> Option<&(dyn Any + Send + Sync)> {
             self.user_data.as_ref().map(|d| &**d)
         }
 
         /// Retrieves a mutable reference to the user-defined user-data attached to this object.
+        ///
+        /// Use `.downcast_mut::<T>()` on the result to retrieve the user-data with its concrete type `T`.
         #[inline]
         pub fn user_data_mut(&mut self) -> Option<&mut (dyn Any + Send + Sync)> {
             self.user_data.as_mut().map(|d| &mut **d)
@@ -241,6 +248,7 @@ const NOT_REGISTERED_ERROR: &'static str =
     "This collider has not been registered into a world (proxy indexes are None).";
 
 pub mod algebra;
+pub mod control;
 pub mod counters;
 pub mod detection;
 pub mod force_generator;
@@ -253,6 +261,9 @@ pub mod volumetric;
 pub mod world;
 // mod tests;
 
+#[cfg(test)]
+mod analytic_tests;
+
 pub use nalgebra;
 #[cfg(feature = "dim2")]
 pub use ncollide2d;
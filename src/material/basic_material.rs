@@ -1,6 +1,8 @@
 use na::RealField;
 
-use crate::material::{LocalMaterialProperties, Material, MaterialCombineMode, MaterialContext};
+use crate::material::{
+    LocalMaterialProperties, Material, MaterialCombineMode, MaterialContext, RestitutionCurve,
+};
 
 use crate::math::Vector;
 
@@ -16,6 +18,11 @@ pub struct BasicMaterial<N: RealField> {
     pub id: Option<u32>,
     /// Restitution coefficient of the surface.
     pub restitution: N,
+    /// Optional restitution coefficient depending on the impact velocity.
+    ///
+    /// If set, this replaces `restitution` for the contacts involving this material, unless
+    /// their restitution coefficient is set by a material lookup table.
+    pub restitution_curve: Option<RestitutionCurve<N>>,
     /// Friction coefficient of the surface.
     pub friction: N,
     /// The fictitious velocity at the surface of this material.
@@ -34,6 +41,7 @@ impl<N: RealField> BasicMaterial<N> {
         BasicMaterial {
             id: None,
             restitution,
+            restitution_curve: None,
             friction,
             surface_velocity: None,
             restitution_combine_mode: MaterialCombineMode::Average,
@@ -44,15 +52,18 @@ impl<N: RealField> BasicMaterial<N> {
 
 impl<N: RealField> Material<N> for BasicMaterial<N> {
     fn local_properties(&self, context: MaterialContext<N>) -> LocalMaterialProperties<N> {
-        LocalMaterialProperties {
-            id: self.id,
-            restitution: (self.restitution, self.restitution_combine_mode),
-            friction: (self.friction, self.friction_combine_mode),
-            surface_velocity: self
-                .surface_velocity
-                .map(|v| context.position * v)
-                .unwrap_or(Vector::zeros()),
-        }
+        let surface_velocity = self
+            .surface_velocity
+            .map(|v| context.position * v)
+            .unwrap_or(Vector::zeros());
+
+        LocalMaterialProperties::new(
+            self.id,
+            (self.friction, self.friction_combine_mode),
+            (self.restitution, self.restitution_combine_mode),
+            surface_velocity,
+        )
+        .with_restitution_curve(self.restitution_curve)
     }
 }
 
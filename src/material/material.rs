@@ -86,6 +86,76 @@ impl MaterialCombineMode {
     }
 }
 
+/// A restitution coefficient that depends on the impact velocity.
+///
+/// The restitution coefficient is linearly interpolated from `low_speed_restitution`, for a
+/// relative normal velocity of zero, to `high_speed_restitution`, for a relative normal velocity
+/// greater than or equal to `crossover_speed`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RestitutionCurve<N: RealField> {
+    /// The restitution coefficient for impacts with a velocity close to zero.
+    pub low_speed_restitution: N,
+    /// The restitution coefficient for impacts with a velocity greater than `crossover_speed`.
+    pub high_speed_restitution: N,
+    /// The impact velocity from which the restitution coefficient is `high_speed_restitution`.
+    pub crossover_speed: N,
+}
+
+impl<N: RealField> RestitutionCurve<N> {
+    /// Initializes a restitution curve ramping from `low_speed_restitution` to `high_speed_restitution`.
+    pub fn new(low_speed_restitution: N, high_speed_restitution: N, crossover_speed: N) -> Self {
+        RestitutionCurve {
+            low_speed_restitution,
+            high_speed_restitution,
+            crossover_speed,
+        }
+    }
+
+    /// The restitution coefficient for an impact with the given relative normal velocity.
+    #[inline]
+    pub fn restitution(&self, impact_speed: N) -> N {
+        if impact_speed >= self.crossover_speed {
+            self.high_speed_restitution
+        } else if impact_speed <= N::zero() {
+            self.low_speed_restitution
+        } else {
+            let t = impact_speed / self.crossover_speed;
+            self.low_speed_restitution * (N::one() - t) + self.high_speed_restitution * t
+        }
+    }
+
+    fn combine(
+        a: (Option<Self>, N, MaterialCombineMode),
+        b: (Option<Self>, N, MaterialCombineMode),
+    ) -> Option<Self> {
+        let (curve1, crossover1) = match a.0 {
+            Some(curve) => (curve, Some(curve.crossover_speed)),
+            None => (RestitutionCurve::new(a.1, a.1, N::zero()), None),
+        };
+        let (curve2, crossover2) = match b.0 {
+            Some(curve) => (curve, Some(curve.crossover_speed)),
+            None => (RestitutionCurve::new(b.1, b.1, N::zero()), None),
+        };
+
+        let crossover_speed = match (crossover1, crossover2) {
+            (Some(s1), Some(s2)) => (s1 + s2) * na::convert(0.5),
+            (Some(s), None) | (None, Some(s)) => s,
+            (None, None) => return None,
+        };
+
+        let low = MaterialCombineMode::combine(
+            (curve1.low_speed_restitution, a.2),
+            (curve2.low_speed_restitution, b.2),
+        );
+        let high = MaterialCombineMode::combine(
+            (curve1.high_speed_restitution, a.2),
+            (curve2.high_speed_restitution, b.2),
+        );
+
+        Some(RestitutionCurve::new(low.0, high.0, crossover_speed))
+    }
+}
+
 /// Computed material properties at a contact point.
 pub struct LocalMaterialProperties<N: RealField> {
     /// The optional material identifier used for pairwise material coefficient lookup table.
@@ -94,8 +164,53 @@ pub struct LocalMaterialProperties<N: RealField> {
     pub friction: (N, MaterialCombineMode),
     /// The restitution coefficient and its combination mode.
     pub restitution: (N, MaterialCombineMode),
+    /// The restitution coefficient as a function of the impact velocity.
+    ///
+    /// If set, this is used by the contact solver instead of the constant `restitution` coefficient.
+    pub restitution_curve: Option<RestitutionCurve<N>>,
     /// The surface velocity at this point.
     pub surface_velocity: Vector<N>,
+    /// If set, the relative normal velocity the solver will attempt to reach at this contact,
+    /// instead of the one resulting from `restitution`.
+    ///
+    /// This is never set by the combination of two materials. It is meant to be set by a
+    /// `ContactMaterialModifier` to implement non-physical behaviors, e.g., a bumper always
+    /// bouncing objects at the same speed. The contact can only push the colliders apart, so the
+    /// resulting separating velocity will be at least this value.
+    pub target_normal_velocity: Option<N>,
+    /// If set, the maximum normal impulse the solver will apply at this contact.
+    ///
+    /// This is set by the combination of two materials only if one of the colliders has a
+    /// maximum contact impulse. Such contacts are not corrected at the position level, so the
+    /// colliders may remain penetrating.
+    pub max_normal_impulse: Option<N>,
+}
+
+impl<N: RealField> LocalMaterialProperties<N> {
+    /// Initializes local material properties without restitution curve, target normal velocity,
+    /// nor maximum normal impulse.
+    pub fn new(
+        id: Option<MaterialId>,
+        friction: (N, MaterialCombineMode),
+        restitution: (N, MaterialCombineMode),
+        surface_velocity: Vector<N>,
+    ) -> Self {
+        LocalMaterialProperties {
+            id,
+            friction,
+            restitution,
+            restitution_curve: None,
+            surface_velocity,
+            target_normal_velocity: None,
+            max_normal_impulse: None,
+        }
+    }
+
+    /// Sets the restitution coefficient as a function of the impact velocity.
+    pub fn with_restitution_curve(mut self, curve: Option<RestitutionCurve<N>>) -> Self {
+        self.restitution_curve = curve;
+        self
+    }
 }
 
 /// An utility trait to clone material trait-objects.
@@ -145,6 +260,7 @@ impl<N: RealField> dyn Material<N> {
         let props1 = material1.local_properties(context1);
         let props2 = material2.local_properties(context2);
         let restitution;
+        let mut restitution_curve = None;
         let friction;
 
         match (props1.id, props2.id) {
@@ -153,6 +269,7 @@ impl<N: RealField> dyn Material<N> {
                     .restitution_coefficient(id1, id2)
                     .map(|coeff| (coeff, MaterialCombineMode::Lookup))
                     .unwrap_or_else(|| {
+                        restitution_curve = combine_restitution_curves(&props1, &props2);
                         MaterialCombineMode::combine(props1.restitution, props2.restitution)
                     });
                 friction = table
@@ -164,19 +281,39 @@ impl<N: RealField> dyn Material<N> {
             }
             _ => {
                 restitution = MaterialCombineMode::combine(props1.restitution, props2.restitution);
+                restitution_curve = combine_restitution_curves(&props1, &props2);
                 friction = MaterialCombineMode::combine(props1.friction, props2.friction);
             }
         }
 
-        LocalMaterialProperties {
-            id: None,
+        LocalMaterialProperties::new(
+            None,
             friction,
             restitution,
-            surface_velocity: props1.surface_velocity - props2.surface_velocity,
-        }
+            props1.surface_velocity - props2.surface_velocity,
+        )
+        .with_restitution_curve(restitution_curve)
     }
 }
 
+fn combine_restitution_curves<N: RealField>(
+    props1: &LocalMaterialProperties<N>,
+    props2: &LocalMaterialProperties<N>,
+) -> Option<RestitutionCurve<N>> {
+    RestitutionCurve::combine(
+        (
+            props1.restitution_curve,
+            props1.restitution.0,
+            props1.restitution.1,
+        ),
+        (
+            props2.restitution_curve,
+            props2.restitution.0,
+            props2.restitution.1,
+        ),
+    )
+}
+
 /// A shared handle to an abstract shape.
 ///
 /// This can be mutated using COW.
@@ -210,3 +347,110 @@ impl<N: RealField> Deref for MaterialHandle<N> {
         &**self.0.deref()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use ncollide::shape::{Ball, Cuboid, ShapeHandle};
+
+    use super::{MaterialCombineMode, RestitutionCurve};
+    use crate::analytic_tests::{assert_relative_error, Scenario, GRAVITY};
+    use crate::material::{BasicMaterial, MaterialHandle};
+    use crate::math::Vector;
+    use crate::object::{BodyPartHandle, ColliderDesc, RigidBodyDesc};
+
+    #[test]
+    fn restitution_curve_is_interpolated_up_to_the_crossover_speed() {
+        let curve = RestitutionCurve::new(0.8, 0.2, 4.0);
+
+        assert_eq!(curve.restitution(-1.0), 0.8);
+        assert_eq!(curve.restitution(0.0), 0.8);
+        assert_relative_eq!(curve.restitution(1.0), 0.65);
+        assert_relative_eq!(curve.restitution(2.0), 0.5);
+        assert_eq!(curve.restitution(4.0), 0.2);
+        assert_eq!(curve.restitution(10.0), 0.2);
+    }
+
+    #[test]
+    fn restitution_curve_combined_with_a_constant_coefficient() {
+        let curve = RestitutionCurve::new(0.8, 0.2, 4.0);
+        let average = MaterialCombineMode::Average;
+        let combined = RestitutionCurve::combine((Some(curve), 0.5, average), (None, 0.4, average));
+
+        let combined = combined.expect("The restitution curve was lost by the combination.");
+        assert_relative_eq!(combined.low_speed_restitution, 0.6);
+        assert_relative_eq!(combined.high_speed_restitution, 0.3);
+        assert_eq!(combined.crossover_speed, 4.0);
+        assert_eq!(
+            RestitutionCurve::combine((None, 0.5, average), (None, 0.4, average)),
+            None
+        );
+    }
+
+    #[test]
+    fn restitution_curves_combined_with_their_combine_mode() {
+        let curve1 = RestitutionCurve::new(0.8, 0.2, 4.0);
+        let curve2 = RestitutionCurve::new(0.6, 0.4, 2.0);
+        let combined = RestitutionCurve::combine(
+            (Some(curve1), 0.0, MaterialCombineMode::Min),
+            (Some(curve2), 0.0, MaterialCombineMode::Average),
+        );
+
+        assert_eq!(combined, Some(RestitutionCurve::new(0.6, 0.2, 3.0)));
+    }
+
+    /// The height reached by a ball bouncing, after being dropped from `drop_height`, on a floor with the same material.
+    fn rebound_height(drop_height: f64, curve: RestitutionCurve<f64>) -> f64 {
+        let mut scenario = Scenario::new(1.0 / 240.0);
+        let mut material = BasicMaterial::new(0.0, 0.0);
+        material.restitution_curve = Some(curve);
+
+        let mut half_extents = Vector::repeat(50.0);
+        half_extents.y = 1.0;
+        let floor = ColliderDesc::new(ShapeHandle::new(Cuboid::new(half_extents)))
+            .translation(-Vector::y())
+            .material(MaterialHandle::new(material))
+            .build(BodyPartHandle(scenario.ground, 0));
+        let _ = scenario.colliders.insert(floor);
+
+        let radius = 0.5;
+        let desc = RigidBodyDesc::new().translation(Vector::y() * (drop_height + radius));
+        let ball = scenario.bodies.insert(desc.build());
+        let co = ColliderDesc::new(ShapeHandle::new(Ball::new(radius)))
+            .density(1.0)
+            .material(MaterialHandle::new(material))
+            .build(BodyPartHandle(ball, 0));
+        let _ = scenario.colliders.insert(co);
+
+        let mut bounced = false;
+        let mut max_height_after_bounce = 0.0;
+
+        while scenario.time() < 3.0 {
+            scenario.step();
+            let rb = scenario.rigid_body(ball);
+
+            if rb.velocity().linear.y > 0.0 {
+                bounced = true;
+            }
+
+            if bounced {
+                let height = rb.position().translation.vector.y - radius;
+                max_height_after_bounce = height.max(max_height_after_bounce);
+            }
+        }
+
+        assert!(bounced, "The ball did not bounce.");
+        max_height_after_bounce
+    }
+
+    #[test]
+    fn restitution_curve_depends_on_the_impact_speed() {
+        let curve = RestitutionCurve::new(1.0, 0.0, 20.0);
+
+        for drop_height in &[1.5, 6.0] {
+            let impact_speed = (2.0 * GRAVITY * drop_height).sqrt();
+            let restitution = curve.restitution(impact_speed);
+            let expected = restitution * restitution * drop_height;
+            assert_relative_error(rebound_height(*drop_height, curve), expected, 0.1);
+        }
+    }
+}
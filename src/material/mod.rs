@@ -3,7 +3,7 @@
 pub use self::basic_material::BasicMaterial;
 pub use self::material::{
     LocalMaterialProperties, Material, MaterialCombineMode, MaterialContext, MaterialHandle,
-    MaterialId,
+    MaterialId, RestitutionCurve,
 };
 pub use self::materials_coefficients_table::MaterialsCoefficientsTable;
 
@@ -8,7 +8,7 @@ use ncollide::interpolation::{
 };
 use ncollide::shape::DeformationsType;
 
-use crate::math::{Force, ForceType, Inertia, Isometry, Point, Vector, Velocity};
+use crate::math::{AngularVector, Force, ForceType, Inertia, Isometry, Point, Vector, Velocity};
 
 use crate::solver::{ForceDirection, IntegrationParameters};
 
@@ -315,6 +315,14 @@ pub trait Body<N: RealField>: Downcast + Send + Sync {
         }
     }
 
+    /// Check if this body is dynamic and currently sleeping.
+    ///
+    /// A sleeping body is not simulated until it is woken up, e.g., by a contact with an awake body.
+    #[inline]
+    fn is_sleeping(&self) -> bool {
+        self.is_dynamic() && !self.activation_status().is_active()
+    }
+
     /// Whether or not the status of this body is dynamic.
     #[inline]
     fn is_dynamic(&self) -> bool {
@@ -371,6 +379,32 @@ pub trait Body<N: RealField>: Downcast + Send + Sync {
         auto_wake_up: bool,
     );
 
+    /// Apply a torque to a part of this body.
+    ///
+    /// For multibodies, this only affects the degrees of freedom able to rotate the given link.
+    fn apply_torque_to_part(
+        &mut self,
+        part_id: usize,
+        torque: &AngularVector<N>,
+        force_type: ForceType,
+        auto_wake_up: bool,
+    ) {
+        let force = Force::torque_from_vector(*torque);
+        self.apply_force(part_id, &force, force_type, auto_wake_up)
+    }
+
+    /// Apply a torque expressed in the local-space of a part of this body.
+    fn apply_local_torque_to_part(
+        &mut self,
+        part_id: usize,
+        torque: &AngularVector<N>,
+        force_type: ForceType,
+        auto_wake_up: bool,
+    ) {
+        let force = Force::torque_from_vector(*torque);
+        self.apply_local_force(part_id, &force, force_type, auto_wake_up)
+    }
+
     /// Apply a force at a given point of a part of this body.
     fn apply_force_at_point(
         &mut self,
@@ -1,5 +1,6 @@
-use generational_arena::Arena;
+use generational_arena::{self as arena, Arena};
 use std::hash::Hash;
+use std::iter::Map;
 
 use crate::object::{Body, DefaultColliderHandle, Multibody, RigidBody};
 use crate::world::GeometricalWorld;
@@ -44,6 +45,22 @@ pub trait BodySet<N: RealField> {
     /// Mutable iterate through all the bodies on this set, applying the closure `f` on them.
     fn foreach_mut(&mut self, f: &mut dyn FnMut(Self::Handle, &mut dyn Body<N>));
 
+    /// Remove a body from this set.
+    ///
+    /// This is used by nphysics to remove the bodies that escaped the world bounds (see
+    /// `MechanicalWorld::set_auto_remove_escaped_bodies`). The default implementation does not
+    /// support removal and does nothing, in which case escaped bodies are only reported.
+    fn remove(&mut self, _to_remove: Self::Handle) {}
+
+    /// Adds a body (represented as a boxed trait-object) to this set, and returns its handle.
+    ///
+    /// This is used by nphysics to add the multibody detached when a multibody joint breaks. The
+    /// default implementation does not support insertion and returns `None`, in which case
+    /// multibody joints never break.
+    fn insert_boxed(&mut self, _body: Box<dyn Body<N>>) -> Option<Self::Handle> {
+        None
+    }
+
     /// Gets the handle of one body that has been removed.
     ///
     /// A body set must keep track (using typically a stack or a queue) of every body that has been
@@ -113,6 +130,20 @@ impl<N: RealField> DefaultBodySet<N> {
         self.bodies.iter_mut().map(|b| (b.0, &mut **b.1))
     }
 
+    /// Removes all the bodies for which `f` returns `false`.
+    ///
+    /// The removed bodies are registered as removal events, exactly as if they were removed with `.remove`.
+    pub fn retain(&mut self, mut f: impl FnMut(DefaultBodyHandle, &mut dyn Body<N>) -> bool) {
+        let to_remove: Vec<_> = self
+            .iter_mut()
+            .filter_map(|(handle, body)| if f(handle, body) { None } else { Some(handle) })
+            .collect();
+
+        for handle in to_remove {
+            let _ = self.remove(handle);
+        }
+    }
+
     /// Gets the rigid body identified by `handle`.
     ///
     /// Returns `None` if the body does not exists, of if it exists but is not a rigid body.
@@ -142,6 +173,33 @@ impl<N: RealField> DefaultBodySet<N> {
     }
 }
 
+impl<'a, N: RealField> IntoIterator for &'a DefaultBodySet<N> {
+    type Item = (DefaultBodyHandle, &'a dyn Body<N>);
+    type IntoIter = Map<
+        arena::Iter<'a, Box<dyn Body<N>>>,
+        fn((DefaultBodyHandle, &'a Box<dyn Body<N>>)) -> Self::Item,
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let f: fn((DefaultBodyHandle, &'a Box<dyn Body<N>>)) -> Self::Item = |b| (b.0, &**b.1);
+        self.bodies.iter().map(f)
+    }
+}
+
+impl<'a, N: RealField> IntoIterator for &'a mut DefaultBodySet<N> {
+    type Item = (DefaultBodyHandle, &'a mut dyn Body<N>);
+    type IntoIter = Map<
+        arena::IterMut<'a, Box<dyn Body<N>>>,
+        fn((DefaultBodyHandle, &'a mut Box<dyn Body<N>>)) -> Self::Item,
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let f: fn((DefaultBodyHandle, &'a mut Box<dyn Body<N>>)) -> Self::Item =
+            |b| (b.0, &mut **b.1);
+        self.bodies.iter_mut().map(f)
+    }
+}
+
 impl<N: RealField> BodySet<N> for DefaultBodySet<N> {
     type Handle = DefaultBodyHandle;
 
@@ -169,12 +227,24 @@ impl<N: RealField> BodySet<N> for DefaultBodySet<N> {
         }
     }
 
+    fn remove(&mut self, to_remove: Self::Handle) {
+        let _ = self.remove(to_remove);
+    }
+
+    fn insert_boxed(&mut self, body: Box<dyn Body<N>>) -> Option<Self::Handle> {
+        Some(self.insert_boxed(body))
+    }
+
     fn pop_removal_event(&mut self) -> Option<Self::Handle> {
         self.removed.pop()
     }
 }
 
 /// The body handle used by the `DefaultBodySet`.
+///
+/// This is a generational index: when a body is removed, its slot may be reused by a body
+/// inserted afterward, but with a different generation. Thus a handle to a removed body never
+/// refers to another body, and looking it up on the set returns `None`.
 pub type DefaultBodyHandle = generational_arena::Index;
 /// The body part handle used by the `DefaultBodySet`.
 pub type DefaultBodyPartHandle = BodyPartHandle<DefaultBodyHandle>;
@@ -1,17 +1,18 @@
 use std::f64;
 use std::sync::Arc;
 
-use na::RealField;
+use na::{RealField, Unit};
+use ncollide::bounding_volume::AABB;
 use ncollide::pipeline::{
     BroadPhaseProxyHandle, CollisionGroups, CollisionObject, CollisionObjectGraphIndex,
     CollisionObjectRef, CollisionObjectUpdateFlags, GeometricQueryType,
 };
-use ncollide::shape::{Shape, ShapeHandle};
+use ncollide::shape::{Plane, Shape, ShapeHandle};
 use std::any::Any;
 
 use crate::material::{BasicMaterial, Material, MaterialHandle};
-use crate::math::{Isometry, Rotation, Vector};
-use crate::object::{BodyHandle, BodyPartHandle};
+use crate::math::{Isometry, Point, Rotation, Vector, DIM};
+use crate::object::{BodyHandle, BodyPartHandle, SelfCollisionFilter};
 
 use crate::utils::{UserData, UserDataBox};
 
@@ -57,17 +58,38 @@ pub struct ColliderRemovalData<N: RealField, Handle: BodyHandle> {
     pub(crate) graph_index: CollisionObjectGraphIndex,
 }
 
+/// The properties of the fluid filling the volume of a sensor collider.
+///
+/// Dynamic bodies overlapping a collider filled with a fluid are subject to a buoyancy force, as
+/// well as linear and angular drag forces, proportional to their immersed volume.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FluidProperties<N: RealField> {
+    /// The density of the fluid.
+    pub density: N,
+    /// The coefficient of the force opposing the linear velocity of the immersed bodies.
+    pub linear_drag: N,
+    /// The coefficient of the torque opposing the angular velocity of the immersed bodies.
+    pub angular_drag: N,
+}
+
 /// Data stored in each collider.
 ///
 /// This is needed by nphysics.
 pub struct ColliderData<N: RealField, Handle: BodyHandle> {
     margin: N,
     density: N,
+    // NOTE: needed to restore the query type of a sensor turned back into a solid collider.
+    angular_prediction: N,
     anchor: ColliderAnchor<N, Handle>,
     // NOTE: needed for the collision filter.
     body_status_dependent_ndofs: usize,
+    // NOTE: needed for the collision filter too.
+    self_collision_filter: Option<Arc<SelfCollisionFilter>>,
     material: MaterialHandle<N>,
     ccd_enabled: bool,
+    contact_solving_enabled: bool,
+    max_contact_impulse: Option<N>,
+    fluid: Option<FluidProperties<N>>,
     user_data: Option<Box<dyn Any + Send + Sync>>,
 }
 
@@ -83,10 +105,15 @@ impl<N: RealField, Handle: BodyHandle> ColliderData<N, Handle> {
         ColliderData {
             margin,
             density,
+            angular_prediction: na::convert(f64::consts::PI / 180.0 * 5.0),
             anchor,
             body_status_dependent_ndofs,
+            self_collision_filter: None,
             material,
             ccd_enabled: false,
+            contact_solving_enabled: true,
+            max_contact_impulse: None,
+            fluid: None,
             user_data: None,
         }
     }
@@ -182,12 +209,16 @@ impl<N: RealField, Handle: BodyHandle> Collider<N, Handle> {
      * Methods of ColliderData.
      */
     /// The user-data attached to this collider.
+    ///
+    /// Use `.downcast_ref::<T>()` on the result to retrieve the user-data with its concrete type `T`.
     #[inline]
     pub fn user_data(&self) -> Option<&(dyn Any + Send + Sync)> {
         self.0.data().user_data.as_ref().map(|d| &**d)
     }
 
     /// Mutable reference to the user-data attached to this collider.
+    ///
+    /// Use `.downcast_mut::<T>()` on the result to retrieve the user-data with its concrete type `T`.
     #[inline]
     pub fn user_data_mut(&mut self) -> Option<&mut (dyn Any + Send + Sync)> {
         self.0.data_mut().user_data.as_mut().map(|d| &mut **d)
@@ -215,12 +246,66 @@ impl<N: RealField, Handle: BodyHandle> Collider<N, Handle> {
     }
 
     /// Sets the marging on this collider's shapes.
+    ///
+    /// The distance up to which contacts are generated for this collider is adjusted accordingly,
+    /// and its bounding volume on the broad phase is enlarged or shrunk at the next collision
+    /// detection. Small margins reduce the gap visible between resting objects but make contacts
+    /// appear and disappear more often, which can cause jittering and objects tunneling through
+    /// thin obstacles. Large margins are more stable but objects seem to float above each other.
     #[inline]
     pub fn set_margin(&mut self, margin: N) {
+        let old_margin = self.margin();
+
+        if let GeometricQueryType::Contacts(linear, angular) = self.query_type() {
+            let linear = linear - old_margin + margin;
+            self.set_query_type(GeometricQueryType::Contacts(linear, angular));
+        }
+
         *self.0.update_flags_mut() |= CollisionObjectUpdateFlags::SHAPE_CHANGED;
         self.0.data_mut().margin = margin;
     }
 
+    /// Computes the tight world-space AABB of this collider's shape.
+    ///
+    /// This does not take the margin nor the prediction distances into account. Use
+    /// `GeometricalWorld::broad_phase_aabb` to retrieve the enlarged AABB actually tracked by
+    /// the broad phase.
+    #[inline]
+    pub fn compute_aabb(&self) -> AABB<N> {
+        self.shape().aabb(self.position())
+    }
+
+    /// Checks if the given world-space point is inside of this collider.
+    ///
+    /// Returns `false` if the shape of this collider does not support point queries.
+    #[inline]
+    pub fn contains_point(&self, point: &Point<N>) -> bool {
+        self.shape()
+            .as_point_query()
+            .map(|q| q.contains_point(self.position(), point))
+            .unwrap_or(false)
+    }
+
+    /// The signed distance between the given world-space point and the boundary of this collider.
+    ///
+    /// The distance is negative if the point is inside of this collider. For compound shapes, the
+    /// boundary considered is the one of the closest sub-shape. Returns `None` if the shape of this
+    /// collider does not support point queries.
+    #[inline]
+    pub fn signed_distance_to_point(&self, point: &Point<N>) -> Option<N> {
+        let proj = self
+            .shape()
+            .as_point_query()?
+            .project_point(self.position(), point, false);
+        let dist = na::distance(point, &proj.point);
+
+        if proj.is_inside {
+            Some(-dist)
+        } else {
+            Some(dist)
+        }
+    }
+
     /// Clears all the internal flags tracking changes made to this collider.
     #[inline]
     pub fn clear_update_flags(&mut self) {
@@ -279,6 +364,31 @@ impl<N: RealField, Handle: BodyHandle> Collider<N, Handle> {
         self.query_type().is_proximity_query()
     }
 
+    /// Turns this collider into a sensor, or back into a solid collider.
+    ///
+    /// The interactions involving this collider are re-evaluated at the next collision detection:
+    /// a collider turned into a sensor stops generating contacts (and thus contact constraints)
+    /// and generates proximities instead, and conversely.
+    #[inline]
+    pub fn set_sensor(&mut self, is_sensor: bool) {
+        let margin = self.margin();
+        let query_type = match self.query_type() {
+            GeometricQueryType::Contacts(linear, angular) if is_sensor => {
+                self.0.data_mut().angular_prediction = angular;
+                GeometricQueryType::Proximity(linear - margin)
+            }
+            GeometricQueryType::Proximity(linear) if !is_sensor => {
+                GeometricQueryType::Contacts(linear + margin, self.0.data().angular_prediction)
+            }
+            _ => return,
+        };
+
+        // Force the broad phase to re-dispatch the pairs involving this collider so their
+        // interactions are recreated with the new query type.
+        *self.0.update_flags_mut() |= CollisionObjectUpdateFlags::SHAPE_CHANGED;
+        self.set_query_type(query_type);
+    }
+
     /// Returns `true` if this collider is subjected to Continuous Collision Detection (CCD).
     #[inline]
     pub fn is_ccd_enabled(&self) -> bool {
@@ -291,6 +401,70 @@ impl<N: RealField, Handle: BodyHandle> Collider<N, Handle> {
         self.0.data_mut().ccd_enabled = enabled
     }
 
+    /// Returns `true` if the contacts involving this collider are solved by the constraints solver.
+    #[inline]
+    pub fn is_contact_solving_enabled(&self) -> bool {
+        self.0.data().contact_solving_enabled
+    }
+
+    /// Enables or disables the resolution of the contacts involving this collider.
+    ///
+    /// Unlike a sensor, a collider with contact solving disabled still generates contact manifolds
+    /// and contact events, but the constraints solver ignores them: no contact impulse is computed
+    /// and the bodies are not pushed apart. The contact points, normals and depths can then be used
+    /// to apply a custom response, e.g., from a vehicle controller.
+    #[inline]
+    pub fn enable_contact_solving(&mut self, enabled: bool) {
+        self.0.data_mut().contact_solving_enabled = enabled
+    }
+
+    /// The maximum normal impulse applied by each contact involving this collider.
+    #[inline]
+    pub fn max_contact_impulse(&self) -> Option<N> {
+        self.0.data().max_contact_impulse
+    }
+
+    /// Sets the maximum normal impulse applied by each contact involving this collider.
+    ///
+    /// The colliders in contact with this one can only be pushed apart by impulses up to this
+    /// limit, and the contacts are not corrected at the position level. This lets heavy bodies
+    /// sink into this collider, e.g., to simulate a soft foam cushion, while lighter bodies rest
+    /// partially submerged. The friction force is limited by the clamped normal impulse. Set to
+    /// `None` to remove the limit.
+    #[inline]
+    pub fn set_max_contact_impulse(&mut self, max_impulse: Option<N>) {
+        self.0.data_mut().max_contact_impulse = max_impulse
+    }
+
+    /// The fluid filling this collider, if any.
+    #[inline]
+    pub fn fluid(&self) -> Option<&FluidProperties<N>> {
+        self.0.data().fluid.as_ref()
+    }
+
+    /// Sets the fluid filling this collider.
+    ///
+    /// The fluid only affects the dynamic bodies overlapping this collider if it is a sensor.
+    #[inline]
+    pub fn set_fluid(&mut self, fluid: Option<FluidProperties<N>>) {
+        self.0.data_mut().fluid = fluid
+    }
+
+    // Attaches this collider to another body, keeping the same body parts.
+    pub(crate) fn set_anchor_body(&mut self, new_body: Handle) {
+        match &mut self.0.data_mut().anchor {
+            ColliderAnchor::OnBodyPart { body_part, .. } => body_part.0 = new_body,
+            ColliderAnchor::OnDeformableBody { body, .. } => *body = new_body,
+        }
+    }
+
+    // Attaches this collider to another body part, keeping its relative position.
+    pub(crate) fn set_anchor_body_part(&mut self, new_part: BodyPartHandle<Handle>) {
+        if let ColliderAnchor::OnBodyPart { body_part, .. } = &mut self.0.data_mut().anchor {
+            *body_part = new_part
+        }
+    }
+
     #[inline]
     pub(crate) fn body_status_dependent_ndofs(&self) -> usize {
         self.0.data().body_status_dependent_ndofs
@@ -301,6 +475,53 @@ impl<N: RealField, Handle: BodyHandle> Collider<N, Handle> {
         self.0.data_mut().body_status_dependent_ndofs = ndofs
     }
 
+    /// Replaces the shape of this collider by a version scaled by `factor`, and scales its position accordingly.
+    ///
+    /// The density is adjusted so the mass of this collider is not modified.
+    pub(crate) fn scale(&mut self, factor: N, scaled_shape: ShapeHandle<N>) {
+        {
+            let data = self.0.data_mut();
+            data.density /= factor.powi(DIM as i32);
+
+            if let ColliderAnchor::OnBodyPart {
+                position_wrt_body_part,
+                ..
+            } = &mut data.anchor
+            {
+                position_wrt_body_part.translation.vector *= factor;
+            }
+        }
+
+        let mut pos = *self.position();
+        pos.translation.vector *= factor;
+        self.set_position(pos);
+        self.set_shape(scaled_shape);
+    }
+
+    #[inline]
+    pub(crate) fn self_collision_filter(&self) -> Option<&SelfCollisionFilter> {
+        self.0.data().self_collision_filter.as_ref().map(|f| &**f)
+    }
+
+    /// Sets the self-collision filter of the multibody this collider is attached to.
+    ///
+    /// Returns `false` if this collider already had this filter.
+    #[inline]
+    pub(crate) fn set_self_collision_filter(&mut self, filter: &Arc<SelfCollisionFilter>) -> bool {
+        let data = self.0.data_mut();
+        let changed = data
+            .self_collision_filter
+            .as_ref()
+            .map(|curr| !Arc::ptr_eq(curr, filter))
+            .unwrap_or(true);
+
+        if changed {
+            data.self_collision_filter = Some(filter.clone());
+        }
+
+        changed
+    }
+
     /*
      * Original methods from the CollisionObject.
      */
@@ -450,6 +671,9 @@ pub struct ColliderDesc<N: RealField> {
     angular_prediction: N,
     is_sensor: bool,
     ccd_enabled: bool,
+    contact_solving_enabled: bool,
+    max_contact_impulse: Option<N>,
+    fluid: Option<FluidProperties<N>>,
 }
 
 impl<N: RealField> ColliderDesc<N> {
@@ -470,9 +694,21 @@ impl<N: RealField> ColliderDesc<N> {
             angular_prediction,
             is_sensor: false,
             ccd_enabled: false,
+            contact_solving_enabled: true,
+            max_contact_impulse: None,
+            fluid: None,
         }
     }
 
+    /// Creates a new collider builder with a half-space shape.
+    ///
+    /// The half-space is bounded by the plane passing through the origin of the collider with
+    /// the given outward `normal`, i.e., everything on the opposite side of `normal` is solid.
+    /// This is typically attached to the ground to create an infinite floor.
+    pub fn halfspace(normal: Unit<Vector<N>>) -> Self {
+        Self::new(ShapeHandle::new(Plane::new(normal)))
+    }
+
     /// The default margin surrounding a collider: 0.01
     pub fn default_margin() -> N {
         na::convert(0.01)
@@ -497,6 +733,11 @@ impl<N: RealField> ColliderDesc<N> {
     desc_custom_setters!(
         self.translation, set_translation, vector: Vector<N> | { self.position.translation.vector = vector }
         self.material, set_material, material: MaterialHandle<N> | { self.material = Some(material) }
+        self.fluid, set_fluid, density: N, linear_drag: N, angular_drag: N | {
+            self.is_sensor = true;
+            self.fluid = Some(FluidProperties { density, linear_drag, angular_drag });
+        }
+        self.max_contact_impulse, set_max_contact_impulse, max_impulse: N | { self.max_contact_impulse = Some(max_impulse) }
     );
 
     desc_setters!(
@@ -509,6 +750,7 @@ impl<N: RealField> ColliderDesc<N> {
         sensor, set_is_sensor, is_sensor: bool
         position, set_position, position: Isometry<N>
         ccd_enabled, set_ccd_enabled, ccd_enabled: bool
+        contact_solving_enabled, set_contact_solving_enabled, contact_solving_enabled: bool
     );
 
     #[cfg(feature = "dim3")]
@@ -521,6 +763,7 @@ impl<N: RealField> ColliderDesc<N> {
         self.get_shape: &dyn Shape<N> | { &*self.shape }
         self.get_translation: &Vector<N> | { &self.position.translation.vector }
         self.get_material: Option<&dyn Material<N>> | { self.material.as_ref().map(|m| &**m) }
+        self.get_fluid: Option<&FluidProperties<N>> | { self.fluid.as_ref() }
     );
 
     desc_getters!(
@@ -531,6 +774,8 @@ impl<N: RealField> ColliderDesc<N> {
         [val] get_angular_prediction -> angular_prediction: N
         [val] is_sensor -> is_sensor: bool
         [val] get_ccd_enabled -> ccd_enabled: bool
+        [val] get_contact_solving_enabled -> contact_solving_enabled: bool
+        [val] get_max_contact_impulse -> max_contact_impulse: Option<N>
         [ref] get_position -> position: Isometry<N>
     );
 
@@ -557,7 +802,11 @@ impl<N: RealField> ColliderDesc<N> {
             .clone()
             .unwrap_or_else(|| MaterialHandle::new(BasicMaterial::default()));
         let mut data = ColliderData::new(self.margin, self.density, anchor, 0, material);
+        data.angular_prediction = self.angular_prediction;
         data.ccd_enabled = self.ccd_enabled;
+        data.contact_solving_enabled = self.contact_solving_enabled;
+        data.max_contact_impulse = self.max_contact_impulse;
+        data.fluid = self.fluid;
         data.user_data = self.user_data.as_ref().map(|data| data.0.to_any());
         let co = CollisionObject::new(
             None,
@@ -692,6 +941,7 @@ impl<N: RealField> DeformableColliderDesc<N> {
             .clone()
             .unwrap_or_else(|| MaterialHandle::new(BasicMaterial::default()));
         let mut data = ColliderData::new(self.margin, N::zero(), anchor, 0, material);
+        data.angular_prediction = self.angular_prediction;
         data.ccd_enabled = data.ccd_enabled;
         data.user_data = self.user_data.as_ref().map(|data| data.0.to_any());
 
@@ -707,3 +957,161 @@ impl<N: RealField> DeformableColliderDesc<N> {
         Collider(co)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use na::Unit;
+    use ncollide::pipeline::ContactEvent;
+    use ncollide::shape::{Ball, Cuboid, ShapeHandle};
+
+    use super::ColliderDesc;
+    use crate::analytic_tests::{assert_relative_error, Scenario, GRAVITY};
+    use crate::math::Vector;
+    use crate::object::{BodyPartHandle, RigidBodyDesc};
+    use crate::volumetric::Volumetric;
+
+    #[test]
+    fn heavy_body_sinks_into_a_collider_with_a_maximum_contact_impulse() {
+        let dt = 1.0 / 60.0;
+        let mut scenario = Scenario::new(dt);
+
+        // A ball of unit density rests on a single contact, which supports up to twice its weight.
+        let radius = 0.5;
+        let ball = Ball::new(radius);
+        let max_impulse = 2.0 * ball.inertia(1.0).mass() * GRAVITY * dt;
+        let mut half_extents = Vector::repeat(50.0);
+        half_extents.y = 1.0;
+        let floor = ColliderDesc::new(ShapeHandle::new(Cuboid::new(half_extents)))
+            .translation(-Vector::y())
+            .max_contact_impulse(max_impulse)
+            .build(BodyPartHandle(scenario.ground, 0));
+        let _ = scenario.colliders.insert(floor);
+
+        let mut add_ball = |x: f64, density: f64| {
+            let desc = RigidBodyDesc::new().translation(Vector::x() * x + Vector::y() * radius);
+            let handle = scenario.bodies.insert(desc.build());
+            let co = ColliderDesc::new(ShapeHandle::new(ball))
+                .density(density)
+                .build(BodyPartHandle(handle, 0));
+            let _ = scenario.colliders.insert(co);
+            handle
+        };
+        let light = add_ball(-5.0, 1.0);
+        let heavy = add_ball(5.0, 10.0);
+
+        while scenario.time() < 1.0 {
+            scenario.step();
+        }
+
+        let light_height = scenario.rigid_body(light).position().translation.vector.y;
+        let heavy_height = scenario.rigid_body(heavy).position().translation.vector.y;
+        assert!(
+            light_height > radius * 0.9,
+            "The light ball sank to {}.",
+            light_height
+        );
+        assert!(
+            heavy_height < 0.0,
+            "The heavy ball did not sink, it is at {}.",
+            heavy_height
+        );
+    }
+
+    #[test]
+    fn contact_normal_against_a_tilted_halfspace() {
+        let mut scenario = Scenario::new(1.0 / 60.0);
+
+        let angle = 0.3f64;
+        let normal = Unit::new_normalize(Vector::y() * angle.cos() + Vector::x() * angle.sin());
+        let co = ColliderDesc::halfspace(normal).build(BodyPartHandle(scenario.ground, 0));
+        let halfspace = scenario.colliders.insert(co);
+
+        // A ball slightly penetrating the half-space.
+        let radius = 0.5;
+        let desc = RigidBodyDesc::new()
+            .translation(*normal * radius * 0.9)
+            .gravity_enabled(false);
+        let _ = scenario.add_body(&desc, ShapeHandle::new(Ball::new(radius)), 0.0, 0.0);
+
+        scenario.step();
+
+        let (h1, _, _, _, _, manifold) = scenario
+            .geometrical_world
+            .contact_pairs(&scenario.colliders, false)
+            .next()
+            .expect("The ball does not touch the half-space.");
+        let contact = &manifold.deepest_contact().unwrap().contact;
+
+        // The contact normal points toward the second collider.
+        let expected = if h1 == halfspace { *normal } else { -*normal };
+        assert!((*contact.normal - expected).norm() < 1.0e-6);
+    }
+
+    #[test]
+    fn box_resting_on_a_halfspace_stays_still() {
+        let mut scenario = Scenario::new(1.0 / 60.0);
+
+        let co =
+            ColliderDesc::halfspace(Vector::y_axis()).build(BodyPartHandle(scenario.ground, 0));
+        let _ = scenario.colliders.insert(co);
+
+        let half_extent = 0.5;
+        let desc = RigidBodyDesc::new().translation(Vector::y() * half_extent);
+        let cuboid = ShapeHandle::new(Cuboid::new(Vector::repeat(half_extent)));
+        let handle = scenario.add_body(&desc, cuboid, 0.0, 0.5);
+
+        while scenario.time() < 1.0 {
+            scenario.step();
+        }
+
+        let rest = *scenario.rigid_body(handle).position();
+        assert_relative_error(rest.translation.vector.y, half_extent, 0.01);
+
+        for _ in 0..600 {
+            scenario.step();
+            let rb = scenario.rigid_body(handle);
+            let drift = (rb.position().translation.vector - rest.translation.vector).norm();
+            assert!(drift < 1.0e-3, "The box drifted by {}.", drift);
+            assert!(rb.velocity().linear.norm() < 1.0e-2);
+            assert!(rb.velocity().angular_vector().norm() < 1.0e-2);
+        }
+    }
+
+    #[test]
+    fn collider_without_contact_solving_reports_contacts_and_falls_through() {
+        let mut scenario = Scenario::new(1.0 / 60.0);
+        scenario.add_floor(0.0, 0.5);
+
+        let radius = 0.5;
+        let desc = RigidBodyDesc::new().translation(Vector::y() * radius * 2.0);
+        let ball = scenario.bodies.insert(desc.build());
+        let co = ColliderDesc::new(ShapeHandle::new(Ball::new(radius)))
+            .density(1.0)
+            .contact_solving_enabled(false)
+            .build(BodyPartHandle(ball, 0));
+        let _ = scenario.colliders.insert(co);
+
+        let mut contact_started = false;
+
+        while scenario.time() < 1.0 {
+            scenario.step();
+            contact_started = contact_started
+                || scenario
+                    .geometrical_world
+                    .contact_events()
+                    .iter()
+                    .any(|e| match e {
+                        ContactEvent::Started(..) => true,
+                        ContactEvent::Stopped(..) => false,
+                    });
+        }
+
+        assert!(contact_started, "No contact was reported.");
+        let height = scenario.rigid_body(ball).position().translation.vector.y;
+        assert!(
+            height < 0.0,
+            "The ball did not fall through, it is at {}.",
+            height
+        );
+    }
+}
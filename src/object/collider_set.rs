@@ -1,4 +1,5 @@
-use generational_arena::Arena;
+use generational_arena::{self as arena, Arena};
+use std::collections::HashMap;
 
 use crate::object::{BodyHandle, Collider, ColliderRemovalData, DefaultBodyHandle};
 use na::RealField;
@@ -69,9 +70,20 @@ pub trait ColliderSet<N: RealField, Handle: BodyHandle>:
     ///
     /// A collider can be removed automatically by nphysics when the collider it was attached too has been removed.
     fn remove(&mut self, to_remove: Self::Handle) -> Option<&mut ColliderRemovalData<N, Handle>>;
+
+    /// Called by nphysics after it attached the given collider to another body.
+    ///
+    /// This happens when a multibody joint breaks: the colliders of the detached links are attached
+    /// to the new multibody. A collider set indexing its colliders by body should update this index.
+    /// The default implementation does nothing.
+    fn collider_reattached(&mut self, _handle: Self::Handle, _old_body: Handle) {}
 }
 
 /// The collider handle used by the `DefaultColliderSet`.
+///
+/// This is a generational index: when a collider is removed, its slot may be reused by a collider
+/// inserted afterward, but with a different generation. Thus a handle to a removed collider never
+/// refers to another collider, and looking it up on the set returns `None`.
 pub type DefaultColliderHandle = generational_arena::Index;
 
 /// The default set containing all the colliders added to the world.
@@ -81,6 +93,7 @@ pub struct DefaultColliderSet<N: RealField, Handle: BodyHandle = DefaultBodyHand
     colliders: Arena<Collider<N, Handle>>,
     removed: Vec<(DefaultColliderHandle, ColliderRemovalData<N, Handle>)>,
     inserted: Vec<DefaultColliderHandle>,
+    body_colliders: HashMap<Handle, Vec<DefaultColliderHandle>>,
 }
 
 impl<N: RealField, Handle: BodyHandle> DefaultColliderSet<N, Handle> {
@@ -90,19 +103,26 @@ impl<N: RealField, Handle: BodyHandle> DefaultColliderSet<N, Handle> {
             colliders: Arena::new(),
             removed: Vec::new(),
             inserted: Vec::new(),
+            body_colliders: HashMap::new(),
         }
     }
 
     /// Adds a collider to this set.
     pub fn insert(&mut self, collider: Collider<N, Handle>) -> DefaultColliderHandle {
+        let body = collider.body();
         let res = self.colliders.insert(collider);
         self.inserted.push(res);
+        self.body_colliders
+            .entry(body)
+            .or_insert_with(Vec::new)
+            .push(res);
         res
     }
 
     /// Removes a collider from this set.
     pub fn remove(&mut self, to_remove: DefaultColliderHandle) -> Option<Collider<N, Handle>> {
         let res = self.colliders.remove(to_remove)?;
+        self.unregister_body_collider(res.body(), to_remove);
 
         if let Some(data) = res.removal_data() {
             self.removed.push((to_remove, data));
@@ -111,6 +131,27 @@ impl<N: RealField, Handle: BodyHandle> DefaultColliderSet<N, Handle> {
         Some(res)
     }
 
+    fn unregister_body_collider(&mut self, body: Handle, handle: DefaultColliderHandle) {
+        if let Some(handles) = self.body_colliders.get_mut(&body) {
+            handles.retain(|h| *h != handle);
+
+            if handles.is_empty() {
+                let _ = self.body_colliders.remove(&body);
+            }
+        }
+    }
+
+    /// The handles of all the colliders of this set attached to the given body.
+    ///
+    /// For a multibody, this includes the colliders attached to all its links. This is kept
+    /// up-to-date as colliders are inserted into or removed from this set.
+    pub fn colliders_of(&self, body: Handle) -> &[DefaultColliderHandle] {
+        self.body_colliders
+            .get(&body)
+            .map(|handles| &handles[..])
+            .unwrap_or(&[])
+    }
+
     /// Check if this set contains a collider identified by `handle`.
     fn contains(&self, handle: DefaultColliderHandle) -> bool {
         self.colliders.contains(handle)
@@ -137,6 +178,47 @@ impl<N: RealField, Handle: BodyHandle> DefaultColliderSet<N, Handle> {
     ) -> impl Iterator<Item = (DefaultColliderHandle, &mut Collider<N, Handle>)> {
         self.colliders.iter_mut()
     }
+
+    /// Removes all the colliders for which `f` returns `false`.
+    ///
+    /// The removed colliders are registered as removal events, exactly as if they were removed with `.remove`.
+    pub fn retain(
+        &mut self,
+        mut f: impl FnMut(DefaultColliderHandle, &mut Collider<N, Handle>) -> bool,
+    ) {
+        let to_remove: Vec<_> = self
+            .iter_mut()
+            .filter_map(|(handle, collider)| {
+                if f(handle, collider) {
+                    None
+                } else {
+                    Some(handle)
+                }
+            })
+            .collect();
+
+        for handle in to_remove {
+            let _ = self.remove(handle);
+        }
+    }
+}
+
+impl<'a, N: RealField, Handle: BodyHandle> IntoIterator for &'a DefaultColliderSet<N, Handle> {
+    type Item = (DefaultColliderHandle, &'a Collider<N, Handle>);
+    type IntoIter = arena::Iter<'a, Collider<N, Handle>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.colliders.iter()
+    }
+}
+
+impl<'a, N: RealField, Handle: BodyHandle> IntoIterator for &'a mut DefaultColliderSet<N, Handle> {
+    type Item = (DefaultColliderHandle, &'a mut Collider<N, Handle>);
+    type IntoIter = arena::IterMut<'a, Collider<N, Handle>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.colliders.iter_mut()
+    }
 }
 
 impl<N: RealField, Handle: BodyHandle> CollisionObjectSet<N> for DefaultColliderSet<N, Handle> {
@@ -194,6 +276,7 @@ impl<N: RealField, Handle: BodyHandle> ColliderSet<N, Handle> for DefaultCollide
 
     fn remove(&mut self, to_remove: Self::Handle) -> Option<&mut ColliderRemovalData<N, Handle>> {
         let res = self.colliders.remove(to_remove)?;
+        self.unregister_body_collider(res.body(), to_remove);
         if let Some(data) = res.removal_data() {
             self.removed.push((to_remove, data));
             self.removed.last_mut().map(|r| &mut r.1)
@@ -201,4 +284,13 @@ impl<N: RealField, Handle: BodyHandle> ColliderSet<N, Handle> for DefaultCollide
             None
         }
     }
+
+    fn collider_reattached(&mut self, handle: Self::Handle, old_body: Handle) {
+        let new_body = try_ret!(self.colliders.get(handle)).body();
+        self.unregister_body_collider(old_body, handle);
+        self.body_colliders
+            .entry(new_body)
+            .or_insert_with(Vec::new)
+            .push(handle);
+    }
 }
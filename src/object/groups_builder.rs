@@ -0,0 +1,261 @@
+use ncollide::pipeline::CollisionGroups;
+
+/// The number of collision groups supported by `CollisionGroups`.
+pub const NUM_COLLISION_GROUPS: usize = 30;
+
+/// A registry of names given to collision group indices.
+///
+/// This allows collision groups to be designated by names like `"player"` or `"debris"`
+/// instead of raw indices when building `CollisionGroups` with a `GroupsBuilder`.
+#[derive(Clone, Debug, Default)]
+pub struct GroupNames {
+    names: Vec<(String, usize)>,
+}
+
+impl GroupNames {
+    /// Creates a registry without any named group.
+    pub fn new() -> Self {
+        GroupNames { names: Vec::new() }
+    }
+
+    /// Gives a name to the collision group with the index `group`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `group` is not smaller than `NUM_COLLISION_GROUPS`, or if `name` is already registered.
+    pub fn register(&mut self, name: &str, group: usize) {
+        assert!(
+            group < NUM_COLLISION_GROUPS,
+            "The collision group index must be smaller than {}.",
+            NUM_COLLISION_GROUPS
+        );
+        assert!(
+            self.group(name).is_none(),
+            "The collision group name {:?} is already registered.",
+            name
+        );
+        self.names.push((name.to_string(), group))
+    }
+
+    /// Registers a name for the collision group with the index `group`, returning `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as `register`.
+    pub fn with_name(mut self, name: &str, group: usize) -> Self {
+        self.register(name, group);
+        self
+    }
+
+    /// The index of the collision group registered with the given name.
+    pub fn group(&self, name: &str) -> Option<usize> {
+        self.names.iter().find(|e| e.0 == name).map(|e| e.1)
+    }
+
+    /// The name of the collision group with the index `group`, if any.
+    pub fn name(&self, group: usize) -> Option<&str> {
+        self.names
+            .iter()
+            .find(|e| e.1 == group)
+            .map(|e| e.0.as_str())
+    }
+
+    /// Creates a builder of collision groups that can refer to the groups named by this registry.
+    pub fn builder(&self) -> GroupsBuilder {
+        GroupsBuilder::with_names(self)
+    }
+}
+
+/// A builder of the `CollisionGroups` given to colliders.
+///
+/// By default, the built collision groups are member of every group and can interact with every group.
+/// Calling `.member_of` or `.collides_with` restricts the membership (resp. the whitelist) to the
+/// groups given to all the calls to this method.
+#[derive(Clone, Debug)]
+pub struct GroupsBuilder<'a> {
+    names: Option<&'a GroupNames>,
+    membership: Option<Vec<usize>>,
+    whitelist: Option<Vec<usize>>,
+    blacklist: Vec<usize>,
+}
+
+impl<'a> GroupsBuilder<'a> {
+    /// Creates a builder of collision groups designated by their indices.
+    pub fn new() -> Self {
+        GroupsBuilder {
+            names: None,
+            membership: None,
+            whitelist: None,
+            blacklist: Vec::new(),
+        }
+    }
+
+    /// Creates a builder of collision groups that may also be designated by the names registered in `names`.
+    pub fn with_names(names: &'a GroupNames) -> Self {
+        GroupsBuilder {
+            names: Some(names),
+            ..GroupsBuilder::new()
+        }
+    }
+
+    /// Adds the given groups to the groups the collider is member of.
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of the given groups is not smaller than `NUM_COLLISION_GROUPS`.
+    pub fn member_of(mut self, groups: &[usize]) -> Self {
+        extend_groups(self.membership.get_or_insert_with(Vec::new), groups);
+        self
+    }
+
+    /// Adds the given groups to the groups the collider can interact with.
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of the given groups is not smaller than `NUM_COLLISION_GROUPS`.
+    pub fn collides_with(mut self, groups: &[usize]) -> Self {
+        extend_groups(self.whitelist.get_or_insert_with(Vec::new), groups);
+        self
+    }
+
+    /// Adds the given groups to the groups the collider cannot interact with.
+    ///
+    /// The blacklist has priority over the whitelist.
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of the given groups is not smaller than `NUM_COLLISION_GROUPS`.
+    pub fn blacklist(mut self, groups: &[usize]) -> Self {
+        extend_groups(&mut self.blacklist, groups);
+        self
+    }
+
+    /// Adds the group registered with the given name to the groups the collider is member of.
+    ///
+    /// Returns `None` if this builder has no registry of names, or if `name` is not registered.
+    pub fn member_of_named(self, name: &str) -> Option<Self> {
+        let group = self.named_group(name)?;
+        Some(self.member_of(&[group]))
+    }
+
+    /// Adds the group registered with the given name to the groups the collider can interact with.
+    ///
+    /// Returns `None` if this builder has no registry of names, or if `name` is not registered.
+    pub fn collides_with_named(self, name: &str) -> Option<Self> {
+        let group = self.named_group(name)?;
+        Some(self.collides_with(&[group]))
+    }
+
+    /// Adds the group registered with the given name to the groups the collider cannot interact with.
+    ///
+    /// Returns `None` if this builder has no registry of names, or if `name` is not registered.
+    pub fn blacklist_named(self, name: &str) -> Option<Self> {
+        let group = self.named_group(name)?;
+        Some(self.blacklist(&[group]))
+    }
+
+    /// Builds the collision groups.
+    pub fn build(&self) -> CollisionGroups {
+        let mut groups = CollisionGroups::new().with_blacklist(&self.blacklist);
+
+        if let Some(membership) = &self.membership {
+            groups = groups.with_membership(membership);
+        }
+
+        if let Some(whitelist) = &self.whitelist {
+            groups = groups.with_whitelist(whitelist);
+        }
+
+        groups
+    }
+
+    fn named_group(&self, name: &str) -> Option<usize> {
+        self.names?.group(name)
+    }
+}
+
+impl<'a> Default for GroupsBuilder<'a> {
+    fn default() -> Self {
+        GroupsBuilder::new()
+    }
+}
+
+impl<'a> From<GroupsBuilder<'a>> for CollisionGroups {
+    fn from(builder: GroupsBuilder<'a>) -> Self {
+        builder.build()
+    }
+}
+
+fn extend_groups(out: &mut Vec<usize>, groups: &[usize]) {
+    for group in groups {
+        assert!(
+            *group < NUM_COLLISION_GROUPS,
+            "The collision group index must be smaller than {}.",
+            NUM_COLLISION_GROUPS
+        );
+
+        if !out.contains(group) {
+            out.push(*group)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{GroupNames, GroupsBuilder, NUM_COLLISION_GROUPS};
+
+    #[test]
+    fn default_groups_interact_with_everything() {
+        let groups = GroupsBuilder::new().build();
+
+        for i in 0..NUM_COLLISION_GROUPS {
+            assert!(groups.is_member_of(i));
+            assert!(groups.is_group_whitelisted(i));
+            assert!(!groups.is_group_blacklisted(i));
+        }
+    }
+
+    #[test]
+    fn membership_whitelist_and_blacklist() {
+        let groups = GroupsBuilder::new()
+            .member_of(&[1, 2])
+            .member_of(&[2, 3])
+            .collides_with(&[4, 5])
+            .blacklist(&[5, 6])
+            .build();
+
+        for i in 0..NUM_COLLISION_GROUPS {
+            assert_eq!(groups.is_member_of(i), i >= 1 && i <= 3);
+            assert_eq!(groups.is_group_whitelisted(i), i == 4 || i == 5);
+            assert_eq!(groups.is_group_blacklisted(i), i == 5 || i == 6);
+        }
+    }
+
+    #[test]
+    fn named_groups() {
+        let names = GroupNames::new()
+            .with_name("player", 1)
+            .with_name("debris", 2)
+            .with_name("sensor", 3);
+        assert_eq!(names.group("debris"), Some(2));
+        assert_eq!(names.name(3), Some("sensor"));
+        assert_eq!(names.group("enemy"), None);
+
+        let groups = names
+            .builder()
+            .member_of_named("player")
+            .and_then(|b| b.collides_with_named("debris"))
+            .and_then(|b| b.blacklist_named("sensor"))
+            .unwrap()
+            .build();
+
+        for i in 0..NUM_COLLISION_GROUPS {
+            assert_eq!(groups.is_member_of(i), i == 1);
+            assert_eq!(groups.is_group_whitelisted(i), i == 2);
+            assert_eq!(groups.is_group_blacklisted(i), i == 3);
+        }
+
+        assert!(names.builder().member_of_named("enemy").is_none());
+        assert!(GroupsBuilder::new().collides_with_named("player").is_none());
+    }
+}
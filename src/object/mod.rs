@@ -9,7 +9,7 @@ pub use self::body_set::{
 };
 pub use self::collider::{
     Collider, ColliderAnchor, ColliderData, ColliderDesc, ColliderRemovalData,
-    DeformableColliderDesc,
+    DeformableColliderDesc, FluidProperties,
 };
 pub use self::collider_set::{
     ColliderHandle, ColliderSet, DefaultColliderHandle, DefaultColliderSet,
@@ -20,12 +20,17 @@ pub use self::fem_surface::{FEMSurface, FEMSurfaceDesc};
 #[cfg(feature = "dim3")]
 pub use self::fem_volume::{FEMVolume, FEMVolumeDesc};
 pub use self::ground::Ground;
+pub use self::groups_builder::{GroupNames, GroupsBuilder, NUM_COLLISION_GROUPS};
 pub use self::mass_constraint_system::{MassConstraintSystem, MassConstraintSystemDesc};
 pub use self::mass_spring_system::{MassSpringSystem, MassSpringSystemDesc};
-pub use self::multibody::{Multibody, MultibodyDesc};
+pub(crate) use self::multibody::{LinkLocation, SelfCollisionFilter};
+pub use self::multibody::{InfeasibleVelocityChange, Multibody, MultibodyDesc};
 pub use self::multibody_link::MultibodyLink;
 pub(crate) use self::multibody_link::MultibodyLinkVec;
+pub use self::multibody_loop_closure::LoopClosureJoint;
+pub(crate) use self::multibody_loop_closure::MultibodyLoopClosure;
 pub use self::rigid_body::{RigidBody, RigidBodyDesc};
+pub use self::transfer::{transfer_body, BodyTransfer};
 
 mod body;
 mod body_set;
@@ -37,8 +42,12 @@ mod fem_surface;
 #[cfg(feature = "dim3")]
 mod fem_volume;
 mod ground;
+mod groups_builder;
 mod mass_constraint_system;
 mod mass_spring_system;
 mod multibody;
+mod multibody_conversion;
 mod multibody_link;
+mod multibody_loop_closure;
 mod rigid_body;
+mod transfer;
@@ -1,23 +1,30 @@
 use std::any::Any;
+use std::collections::HashSet;
 use std::ops::MulAssign;
+use std::sync::Arc;
 
-use crate::joint::Joint;
+use crate::joint::{FreeJoint, Joint};
 use crate::math::{
     AngularDim, Dim, Force, ForceType, Inertia, Isometry, Jacobian, Point, SpatialMatrix,
     Translation, Vector, Velocity, DIM,
 };
 use crate::object::{
     ActivationStatus, Body, BodyHandle, BodyPart, BodyPartHandle, BodyStatus, BodyUpdateStatus,
-    ColliderHandle, MultibodyLink, MultibodyLinkVec,
+    ColliderHandle, LoopClosureJoint, MultibodyLink, MultibodyLinkVec, MultibodyLoopClosure,
 };
 use crate::solver::{
-    ConstraintSet, ForceDirection, IntegrationParameters, NonlinearSORProx, SORProx,
+    BilateralGroundConstraint, ConstraintSet, ForceDirection, ImpulseLimits, IntegrationParameters,
+    NonlinearSORProx, SORProx,
 };
 use na::{self, DMatrix, DVector, DVectorSlice, DVectorSliceMut, Dynamic, MatrixMN, RealField, LU};
 use ncollide::shape::DeformationsType;
 
 use crate::utils::{GeneralizedCross, IndexMut2};
 
+/// Error returned by `Multibody::add_link_velocity` if the joints of a multibody cannot yield a velocity change.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InfeasibleVelocityChange;
+
 /// An articulated body simulated using the reduced-coordinates approach.
 pub struct Multibody<N: RealField> {
     rbs: MultibodyLinkVec<N>,
@@ -25,6 +32,8 @@ pub struct Multibody<N: RealField> {
     damping: DVector<N>,
     accelerations: DVector<N>,
     forces: DVector<N>,
+    // The forces applied to each link by the user, used to compute the joint reactions.
+    link_forces: Vec<Force<N>>,
     impulses: DVector<N>,
     body_jacobians: Vec<Jacobian<N>>,
     // FIXME: use sparse matrices.
@@ -37,6 +46,9 @@ pub struct Multibody<N: RealField> {
     ndofs: usize,
     companion_id: usize,
     user_data: Option<Box<dyn Any + Send + Sync>>,
+    // NOTE: shared with the colliders attached to this multibody for the collision filter.
+    self_collision: Arc<SelfCollisionFilter>,
+    loop_closures: Vec<MultibodyLoopClosure<N>>,
 
     /*
      * Workspaces.
@@ -61,6 +73,7 @@ impl<N: RealField> Multibody<N> {
             rbs: MultibodyLinkVec(Vec::new()),
             velocities: DVector::zeros(0),
             forces: DVector::zeros(0),
+            link_forces: Vec::new(),
             damping: DVector::zeros(0),
             accelerations: DVector::zeros(0),
             impulses: DVector::zeros(0),
@@ -79,6 +92,8 @@ impl<N: RealField> Multibody<N> {
             i_coriolis_dt: Jacobian::zeros(0),
             solver_workspace: Some(SolverWorkspace::new()),
             user_data: None,
+            self_collision: Arc::new(SelfCollisionFilter::new()),
+            loop_closures: Vec::new(),
         }
     }
 
@@ -135,6 +150,19 @@ impl<N: RealField> Multibody<N> {
         self.rbs.iter()
     }
 
+    /// Iterator through the kinematic tree of this multibody.
+    ///
+    /// Yields, for each link, its id, the id of its parent (`None` for the root), the joint
+    /// attaching it to its parent, and the number of degrees of freedom of this joint. All
+    /// link are guaranteed to be yielded before its descendant.
+    pub fn kinematic_tree(
+        &self,
+    ) -> impl Iterator<Item = (usize, Option<usize>, &dyn Joint<N>, usize)> {
+        self.rbs
+            .iter()
+            .map(|link| (link.link_id(), link.parent_id(), link.joint(), link.ndofs()))
+    }
+
     /// Mutable iterator through all the links of this multibody.
     ///
     /// All link are guaranteed to be yielded before its descendant.
@@ -177,6 +205,129 @@ impl<N: RealField> Multibody<N> {
         self.rbs[link_id].local_inertia.angular = angular_inertia;
     }
 
+    /// Enables or disables collisions between the links of this multibody (enabled by default).
+    ///
+    /// This does not affect collisions between this multibody and other bodies.
+    pub fn set_self_collision(&mut self, enabled: bool) {
+        Arc::make_mut(&mut self.self_collision).enabled = enabled
+    }
+
+    /// Whether collisions between the links of this multibody are enabled.
+    pub fn self_collision_enabled(&self) -> bool {
+        self.self_collision.enabled
+    }
+
+    /// Disables collisions between the two given links of this multibody.
+    pub fn disable_link_pair_collision(&mut self, link1: usize, link2: usize) {
+        let _ = Arc::make_mut(&mut self.self_collision)
+            .excluded_pairs
+            .insert(sorted_pair(link1, link2));
+    }
+
+    /// Re-enables collisions between the two given links of this multibody.
+    ///
+    /// This has no effect if self-collisions are disabled for the whole multibody.
+    pub fn enable_link_pair_collision(&mut self, link1: usize, link2: usize) {
+        let _ = Arc::make_mut(&mut self.self_collision)
+            .excluded_pairs
+            .remove(&sorted_pair(link1, link2));
+    }
+
+    /// Disables collisions between every link and its parent.
+    pub fn disable_adjacent_links_collision(&mut self) {
+        let filter = Arc::make_mut(&mut self.self_collision);
+
+        for link in self.rbs.iter().filter(|l| !l.is_root()) {
+            let _ = filter
+                .excluded_pairs
+                .insert(sorted_pair(link.parent_internal_id, link.internal_id));
+        }
+    }
+
+    /// Whether the colliders attached to the two given links of this multibody can collide.
+    pub fn can_links_collide(&self, link1: usize, link2: usize) -> bool {
+        self.self_collision.can_collide(link1, link2)
+    }
+
+    /// Closes a kinematic loop by attaching the links `link1` and `link2` of this multibody with the given joint.
+    ///
+    /// The joint is enforced by the constraint solver, so the links should already satisfy it
+    /// approximately. Their anchors are expressed in the local-space of each link.
+    pub fn close_loop(&mut self, link1: usize, link2: usize, joint: LoopClosureJoint<N>) {
+        assert!(
+            link1 < self.rbs.len() && link2 < self.rbs.len(),
+            "Multibody::close_loop: invalid link id."
+        );
+        assert!(
+            link1 != link2,
+            "Multibody::close_loop: a link cannot be attached to itself."
+        );
+
+        let impulse_id = self.impulses.len();
+        self.impulses
+            .resize_vertically_mut(impulse_id + joint.num_constraints(), N::zero());
+        self.loop_closures.push(MultibodyLoopClosure {
+            link1,
+            link2,
+            joint,
+            impulse_id,
+        });
+    }
+
+    /// The joints closing kinematic loops of this multibody, with the ids of the two links each one attaches.
+    pub fn loop_closures(&self) -> impl Iterator<Item = (usize, usize, &LoopClosureJoint<N>)> {
+        self.loop_closures
+            .iter()
+            .map(|closure| (closure.link1, closure.link2, &closure.joint))
+    }
+
+    // Fills the jacobian `J` of the `i`-th constraint of the given loop closure at `j_id`, and
+    // `M^-1 J^t` at `wj_id`. Returns `J M^-1 J^t`, the positional error of the constraint, and
+    // whether it is an angular constraint.
+    fn fill_loop_closure_jacobians(
+        &self,
+        closure: &MultibodyLoopClosure<N>,
+        i: usize,
+        j_id: usize,
+        wj_id: usize,
+        jacobians: &mut [N],
+    ) -> (N, N, bool) {
+        let link1 = &self.rbs[closure.link1];
+        let link2 = &self.rbs[closure.link2];
+        let constraint = closure.joint.constraint(i, link1, link2);
+        let is_angular = match constraint.dir {
+            ForceDirection::Linear(_) => false,
+            ForceDirection::Angular(_) => true,
+        };
+        let force1 = constraint
+            .dir
+            .at_point(&(constraint.point1 - link1.com.coords));
+        let force2 = (-constraint.dir).at_point(&(constraint.point2 - link2.com.coords));
+
+        // J = J1 - J2, using the location of M^-1 J^t as a temporary buffer.
+        self.link_jacobian_mul_force(link1, &force1, &mut jacobians[j_id..]);
+        self.link_jacobian_mul_force(link2, &force2, &mut jacobians[wj_id..]);
+
+        for k in 0..self.ndofs {
+            jacobians[j_id + k] += jacobians[wj_id + k];
+            jacobians[wj_id + k] = jacobians[j_id + k];
+        }
+
+        {
+            let mut out = DVectorSliceMut::from_slice(&mut jacobians[wj_id..], self.ndofs);
+            assert!(self.inv_augmented_mass.solve_mut(&mut out))
+        }
+
+        let j = DVectorSlice::from_slice(&jacobians[j_id..], self.ndofs);
+        let invm_j = DVectorSlice::from_slice(&jacobians[wj_id..], self.ndofs);
+
+        (j.dot(&invm_j), constraint.error, is_angular)
+    }
+
+    pub(crate) fn self_collision_filter(&self) -> &Arc<SelfCollisionFilter> {
+        &self.self_collision
+    }
+
     fn add_link(
         &mut self,
         parent: Option<usize>,
@@ -262,6 +413,7 @@ impl<N: RealField> Multibody<N> {
         self.accelerations
             .resize_vertically_mut(len + ndofs, N::zero());
         self.body_jacobians.push(Jacobian::zeros(0));
+        self.link_forces.push(Force::zero());
 
         let len = self.impulses.len();
         self.impulses
@@ -273,6 +425,10 @@ impl<N: RealField> Multibody<N> {
             return;
         }
 
+        if self.has_breakable_joints() {
+            self.workspace.start_velocities.copy_from(&self.velocities);
+        }
+
         self.accelerations.fill(N::zero());
 
         for i in 0..self.rbs.len() {
@@ -341,6 +497,14 @@ impl<N: RealField> Multibody<N> {
         self.accelerations
             .cmpy(-N::one(), &self.damping, &self.velocities, N::one());
 
+        for rb in self.rbs.iter() {
+            let rng = rb.assembly_id..rb.assembly_id + rb.dof.ndofs();
+            rb.dof.add_passive_forces(
+                &self.velocities.as_slice()[rng.clone()],
+                &mut self.accelerations.as_mut_slice()[rng],
+            );
+        }
+
         assert!(self.inv_augmented_mass.solve_mut(&mut self.accelerations));
     }
 
@@ -722,16 +886,383 @@ impl<N: RealField> Multibody<N> {
         &mut self.forces
     }
 
+    /// Adds a force to the generalized coordinates of this multibody.
+    ///
+    /// The components of `force` follow the same ordering as the generalized velocities of this
+    /// multibody. It is applied during the next timestep, and cleared afterwards like any other
+    /// force applied to this multibody.
+    pub fn apply_generalized_force(&mut self, force: &DVector<N>, auto_wake_up: bool) {
+        assert_eq!(
+            force.len(),
+            self.ndofs,
+            "The generalized force must have as many components as the multibody degrees of freedom."
+        );
+
+        if self.status != BodyStatus::Dynamic {
+            return;
+        }
+
+        if auto_wake_up {
+            self.activate()
+        }
+
+        self.forces += force;
+    }
+
+    /// Adds `delta` to the velocity of the center of mass of the link `link_id`, and wakes this multibody up.
+    ///
+    /// The generalized velocities are changed by the smallest amount yielding this velocity change,
+    /// using the pseudo-inverse of the jacobian of the link. This fails, leaving this multibody
+    /// unchanged, if the joints between the link and the root cannot yield `delta`, e.g., for a
+    /// linear velocity change orthogonal to the axis of a prismatic joint. Like for rigid bodies, the
+    /// velocity change does not depend on the mass, and this has no effect if this multibody is
+    /// not dynamic.
+    pub fn add_link_velocity(
+        &mut self,
+        link_id: usize,
+        delta: &Velocity<N>,
+    ) -> Result<(), InfeasibleVelocityChange> {
+        let link = self.link(link_id).expect("Invalid multibody link handle.");
+
+        if self.status != BodyStatus::Dynamic {
+            return Ok(());
+        }
+
+        if self.ndofs == 0 {
+            return Err(InfeasibleVelocityChange);
+        }
+
+        let jacobian = &self.body_jacobians[link.internal_id];
+        let eps: N = na::convert(1.0e-6);
+        let dvel = jacobian
+            .clone()
+            .svd(true, true)
+            .solve(delta.as_vector(), eps)
+            .map_err(|_| InfeasibleVelocityChange)?;
+        let error = jacobian * &dvel - delta.as_vector();
+
+        if error.norm() > eps * (N::one() + delta.as_vector().norm()) {
+            return Err(InfeasibleVelocityChange);
+        }
+
+        self.activate();
+        self.update_status.set_velocity_changed(true);
+        self.velocities += dvel;
+        Ok(())
+    }
+
     #[inline]
     pub(crate) fn impulses(&self) -> &[N] {
         self.impulses.as_slice()
     }
+
+    // Whether the joint of at least one link of this multibody can break.
+    pub(crate) fn has_breakable_joints(&self) -> bool {
+        self.rbs.iter().any(|l| l.is_breakable())
+    }
+
+    // The wrench applied during the last step by the joint of each link to the subtree rooted at
+    // this link, expressed at the joint anchor.
+    //
+    // It is deduced from the momentum change of the links of the subtree, and from the other
+    // forces applied to them: gravity, the forces applied by the user, the loop closures, and
+    // the given impulses applied to each link by the contacts, expressed at its center of mass.
+    fn joint_reactions(
+        &self,
+        gravity: &Vector<N>,
+        inv_dt: N,
+        contact_impulses: &[Force<N>],
+    ) -> Vec<Force<N>> {
+        let mut impulses = contact_impulses.to_vec();
+
+        if let Some(workspace) = &self.solver_workspace {
+            for c in &workspace.constraints.velocity.bilateral_ground {
+                for closure in &self.loop_closures {
+                    let rng =
+                        closure.impulse_id..closure.impulse_id + closure.joint.num_constraints();
+
+                    if rng.contains(&c.impulse_id) {
+                        let link1 = &self.rbs[closure.link1];
+                        let link2 = &self.rbs[closure.link2];
+                        let i = c.impulse_id - closure.impulse_id;
+                        let constraint = closure.joint.constraint(i, link1, link2);
+                        let force1 = constraint
+                            .dir
+                            .at_point(&(constraint.point1 - link1.com.coords));
+                        let force2 =
+                            (-constraint.dir).at_point(&(constraint.point2 - link2.com.coords));
+                        impulses[closure.link1] += force1 * c.impulse;
+                        impulses[closure.link2] += force2 * c.impulse;
+                    }
+                }
+            }
+        }
+
+        // The wrench missing to each link to explain its momentum change, about the origin.
+        let mut reactions: Vec<Force<N>> = self
+            .rbs
+            .iter()
+            .enumerate()
+            .map(|(i, rb)| {
+                #[allow(unused_mut)] // mut is needed in 3D but not in 2D.
+                let mut momentum_rate = (rb.inertia * self.workspace.link_dvels[i]) * inv_dt;
+
+                #[cfg(feature = "dim3")]
+                {
+                    momentum_rate.angular += rb
+                        .velocity
+                        .angular
+                        .cross(&(rb.inertia.angular * rb.velocity.angular));
+                }
+
+                let mut external = self.link_forces[i] + impulses[i] * inv_dt;
+
+                if self.gravity_enabled {
+                    external.linear += gravity * rb.inertia.mass();
+                }
+
+                let missing = momentum_rate - external;
+                Force::linear_at_point(missing.linear, &rb.com)
+                    + Force::torque_from_vector(missing.angular_vector())
+            })
+            .collect();
+
+        // All links are after their parent.
+        for i in (1..self.rbs.len()).rev() {
+            let reaction = reactions[i];
+            reactions[self.rbs[i].parent_internal_id] += reaction;
+        }
+
+        for (rb, reaction) in self.rbs.iter().zip(reactions.iter_mut()) {
+            let anchor = rb.parent_to_world * Point::from(rb.parent_shift);
+            *reaction = Force::linear_at_point(reaction.linear, &Point::from(-anchor.coords))
+                + Force::torque_from_vector(reaction.angular_vector());
+        }
+
+        reactions
+    }
+
+    // The ids of the links whose joint absorbed, during the last step, a force or torque greater
+    // than its break threshold. A link is omitted if the joint of one of its ancestors is
+    // overloaded too, since it is detached along with this ancestor.
+    pub(crate) fn overloaded_joints(
+        &self,
+        gravity: &Vector<N>,
+        inv_dt: N,
+        contact_impulses: &[Force<N>],
+    ) -> Vec<usize> {
+        if self.status != BodyStatus::Dynamic || !self.has_breakable_joints() {
+            return Vec::new();
+        }
+
+        let reactions = self.joint_reactions(gravity, inv_dt, contact_impulses);
+        let mut overloaded = vec![false; self.rbs.len()];
+        let mut result = Vec::new();
+
+        for rb in self.rbs.iter().skip(1) {
+            let reaction = &reactions[rb.internal_id];
+
+            if overloaded[rb.parent_internal_id] {
+                overloaded[rb.internal_id] = true;
+            } else if rb.is_breakable()
+                && (reaction.linear.norm() > rb.break_force
+                    || reaction.angular_vector().norm() > rb.break_torque)
+            {
+                overloaded[rb.internal_id] = true;
+                result.push(rb.internal_id);
+            }
+        }
+
+        result
+    }
+
+    // The ids of the links kept when the subtree rooted at the given link is removed, the ids of
+    // the links of this subtree, and the new location of each link.
+    fn split_links(&self, link_id: usize) -> (Vec<usize>, Vec<usize>, Vec<LinkLocation>) {
+        assert!(
+            link_id != 0 && link_id < self.rbs.len(),
+            "Multibody: the root link cannot be detached."
+        );
+
+        let mut in_subtree = vec![false; self.rbs.len()];
+        in_subtree[link_id] = true;
+
+        for i in link_id + 1..self.rbs.len() {
+            in_subtree[i] = in_subtree[self.rbs[i].parent_internal_id];
+        }
+
+        let mut kept = Vec::new();
+        let mut detached = Vec::new();
+        let mut locations = Vec::with_capacity(self.rbs.len());
+
+        for (i, is_detached) in in_subtree.iter().enumerate() {
+            if *is_detached {
+                locations.push(LinkLocation::Detached(detached.len()));
+                detached.push(i);
+            } else {
+                locations.push(LinkLocation::Kept(kept.len()));
+                kept.push(i);
+            }
+        }
+
+        (kept, detached, locations)
+    }
+
+    // A new multibody made of the subtree rooted at the given link, attached to the ground by a
+    // free joint with the current position and velocity of this link.
+    //
+    // The kinematics of this multibody must be up-to-date.
+    pub(crate) fn subtree_copy(&self, link_id: usize) -> Multibody<N> {
+        let (_, detached, locations) = self.split_links(link_id);
+        let root = &self.rbs[link_id];
+        let root_joint = FreeJoint::new(root.local_to_world);
+        let root_velocity =
+            Velocity::from_vector(&(&self.body_jacobians[link_id] * &self.velocities));
+
+        self.copy_links(
+            &detached,
+            &locations,
+            Some((Box::new(root_joint), root_velocity)),
+        )
+    }
+
+    // Removes the subtree rooted at the given link from this multibody, and returns the new
+    // location of each link.
+    pub(crate) fn remove_subtree(&mut self, link_id: usize) -> Vec<LinkLocation> {
+        let (kept, _, locations) = self.split_links(link_id);
+        let mut result = self.copy_links(&kept, &locations, None);
+        result.user_data = self.user_data.take();
+        *self = result;
+        locations
+    }
+
+    // A multibody made of copies of the given links, in the same order, with the ids given by
+    // `locations`. The parent of each link, except the first one, must be among the given links.
+    //
+    // The first link is attached to the ground by its original joint, or by the given joint with
+    // the given generalized velocity.
+    fn copy_links(
+        &self,
+        ids: &[usize],
+        locations: &[LinkLocation],
+        mut root_joint: Option<(Box<dyn Joint<N>>, Velocity<N>)>,
+    ) -> Multibody<N> {
+        let mut result = Multibody::new();
+        result.status = self.status;
+        result.gravity_enabled = self.gravity_enabled;
+        result.activation = self.activation;
+
+        for (k, i) in ids.iter().enumerate() {
+            let link = &self.rbs[*i];
+            let parent = if k == 0 {
+                None
+            } else {
+                Some(locations[link.parent_internal_id].id())
+            };
+            let (joint, parent_shift, body_shift, velocity) = match root_joint.take() {
+                Some((joint, velocity)) => {
+                    (joint, Vector::zeros(), Vector::zeros(), Some(velocity))
+                }
+                None => (link.dof.clone(), link.parent_shift, link.body_shift, None),
+            };
+
+            let new_link = result.add_link(
+                parent,
+                joint,
+                parent_shift,
+                body_shift,
+                link.local_inertia,
+                link.local_com,
+            );
+            new_link.name = link.name.clone();
+            new_link.break_force = link.break_force;
+            new_link.break_torque = link.break_torque;
+            let rng = new_link.assembly_id..new_link.assembly_id + new_link.ndofs();
+
+            if let Some(velocity) = velocity {
+                result.velocities.as_mut_slice()[rng].copy_from_slice(velocity.as_slice());
+            } else {
+                let old_rng = link.assembly_id..link.assembly_id + link.ndofs();
+                result.velocities.as_mut_slice()[rng.clone()]
+                    .copy_from_slice(&self.velocities.as_slice()[old_rng.clone()]);
+                result.damping.as_mut_slice()[rng]
+                    .copy_from_slice(&self.damping.as_slice()[old_rng]);
+            }
+        }
+
+        for closure in &self.loop_closures {
+            if ids.contains(&closure.link1) && ids.contains(&closure.link2) {
+                let link1 = locations[closure.link1].id();
+                let link2 = locations[closure.link2].id();
+                result.close_loop(link1, link2, closure.joint);
+            }
+        }
+
+        result.set_self_collision(self.self_collision.enabled);
+
+        for (link1, link2) in &self.self_collision.excluded_pairs {
+            if ids.contains(link1) && ids.contains(link2) {
+                result.disable_link_pair_collision(locations[*link1].id(), locations[*link2].id());
+            }
+        }
+
+        result
+    }
+}
+
+/// The location of a link of a multibody after one of its subtrees was detached.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum LinkLocation {
+    /// The link is still part of the original multibody, with the given id.
+    Kept(usize),
+    /// The link is part of the detached multibody, with the given id.
+    Detached(usize),
+}
+
+impl LinkLocation {
+    pub(crate) fn id(&self) -> usize {
+        match self {
+            LinkLocation::Kept(id) | LinkLocation::Detached(id) => *id,
+        }
+    }
 }
 
 /// A temporary workspace for various updates of the multibody.
 struct MultibodyWorkspace<N: RealField> {
     accs: Vec<Velocity<N>>,
     ndofs_vec: DVector<N>,
+    // The generalized velocities before the constraints resolution, and the resulting velocity
+    // change of each link. Only computed if a link joint can break.
+    start_velocities: DVector<N>,
+    link_dvels: Vec<Velocity<N>>,
+}
+
+/// The rules deciding which links of a multibody can collide with each other.
+#[derive(Clone, Debug)]
+pub(crate) struct SelfCollisionFilter {
+    enabled: bool,
+    excluded_pairs: HashSet<(usize, usize)>,
+}
+
+impl SelfCollisionFilter {
+    fn new() -> Self {
+        SelfCollisionFilter {
+            enabled: true,
+            excluded_pairs: HashSet::new(),
+        }
+    }
+
+    pub(crate) fn can_collide(&self, link1: usize, link2: usize) -> bool {
+        link1 != link2 && self.enabled && !self.excluded_pairs.contains(&sorted_pair(link1, link2))
+    }
+}
+
+fn sorted_pair(a: usize, b: usize) -> (usize, usize) {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
 }
 
 impl<N: RealField> MultibodyWorkspace<N> {
@@ -740,13 +1271,17 @@ impl<N: RealField> MultibodyWorkspace<N> {
         MultibodyWorkspace {
             accs: Vec::new(),
             ndofs_vec: DVector::zeros(0),
+            start_velocities: DVector::zeros(0),
+            link_dvels: Vec::new(),
         }
     }
 
     /// Resize the workspace so it is enough for `nlinks` links.
     pub fn resize(&mut self, nlinks: usize, ndofs: usize) {
         self.accs.resize(nlinks, Velocity::zero());
-        self.ndofs_vec = DVector::zeros(ndofs)
+        self.link_dvels.resize(nlinks, Velocity::zero());
+        self.ndofs_vec = DVector::zeros(ndofs);
+        self.start_velocities = DVector::zeros(ndofs)
     }
 }
 
@@ -805,6 +1340,16 @@ impl<N: RealField> Body<N> for Multibody<N> {
 
     #[inline]
     fn integrate(&mut self, parameters: &IntegrationParameters<N>) {
+        if self.status == BodyStatus::Dynamic && self.has_breakable_joints() {
+            // The body jacobians still match the velocities of the constraints resolution.
+            let dvels = &self.velocities - &self.workspace.start_velocities;
+
+            for i in 0..self.rbs.len() {
+                self.workspace.link_dvels[i] =
+                    Velocity::from_vector(&(&self.body_jacobians[i] * &dvels));
+            }
+        }
+
         self.update_status.set_position_changed(true);
 
         for rb in self.rbs.iter_mut() {
@@ -824,7 +1369,11 @@ impl<N: RealField> Body<N> for Multibody<N> {
     }
 
     fn clear_forces(&mut self) {
-        self.forces.fill(N::zero())
+        self.forces.fill(N::zero());
+
+        for force in &mut self.link_forces {
+            *force = Force::zero()
+        }
     }
 
     fn update_kinematics(&mut self) {
@@ -1025,8 +1574,10 @@ impl<N: RealField> Body<N> for Multibody<N> {
 
     #[inline]
     fn has_active_internal_constraints(&mut self) -> bool {
-        self.links()
-            .any(|link| link.joint().num_velocity_constraints() != 0)
+        !self.loop_closures.is_empty()
+            || self
+                .links()
+                .any(|link| link.joint().num_velocity_constraints() != 0)
     }
 
     #[inline]
@@ -1059,7 +1610,12 @@ impl<N: RealField> Body<N> for Multibody<N> {
             .rbs
             .iter()
             .map(|l| l.joint().num_velocity_constraints())
-            .sum();
+            .sum::<usize>()
+            + self
+                .loop_closures
+                .iter()
+                .map(|l| l.joint.num_constraints())
+                .sum::<usize>();
 
         workspace.resize(nconstraints, self.ndofs);
 
@@ -1077,6 +1633,55 @@ impl<N: RealField> Body<N> for Multibody<N> {
             );
         }
 
+        /*
+         * The loop closures, cancelling the relative velocity of the attached links along each
+         * constrained direction.
+         */
+        for closure in &self.loop_closures {
+            for i in 0..closure.joint.num_constraints() {
+                let j_id = ground_j_id;
+                let wj_id = j_id + self.ndofs;
+                let (inv_r, _, _) = self.fill_loop_closure_jacobians(
+                    closure,
+                    i,
+                    j_id,
+                    wj_id,
+                    workspace.jacobians.as_mut_slice(),
+                );
+
+                if inv_r.is_zero() {
+                    continue;
+                }
+
+                let j =
+                    DVectorSlice::from_slice(&workspace.jacobians.as_slice()[j_id..], self.ndofs);
+                let impulse_id = closure.impulse_id + i;
+
+                let constraint = BilateralGroundConstraint {
+                    impulse: self.impulses[impulse_id] * parameters.warmstart_coeff,
+                    r: N::one() / inv_r,
+                    rhs: j.dot(&self.velocities) + j.dot(ext_vels),
+                    cfm: N::zero(),
+                    limits: ImpulseLimits::Independent {
+                        min: -N::max_value(),
+                        max: N::max_value(),
+                    },
+                    impulse_id,
+                    assembly_id: 0,
+                    j_id,
+                    wj_id,
+                    ndofs: self.ndofs,
+                };
+
+                workspace
+                    .constraints
+                    .velocity
+                    .bilateral_ground
+                    .push(constraint);
+                ground_j_id += 2 * self.ndofs;
+            }
+        }
+
         self.solver_workspace = Some(workspace);
     }
 
@@ -1099,12 +1704,25 @@ impl<N: RealField> Body<N> for Multibody<N> {
         let workspace = self.solver_workspace.as_mut().unwrap();
         for c in &mut workspace.constraints.velocity.unilateral_ground {
             let dim = Dynamic::new(c.ndofs);
-            SORProx::solve_unilateral_ground(c, workspace.jacobians.as_slice(), dvels, dim)
+            SORProx::solve_unilateral_ground(
+                c,
+                workspace.jacobians.as_slice(),
+                dvels,
+                dim,
+                N::zero(),
+            )
         }
 
         for c in &mut workspace.constraints.velocity.bilateral_ground {
             let dim = Dynamic::new(c.ndofs);
-            SORProx::solve_bilateral_ground(c, &[], workspace.jacobians.as_slice(), dvels, dim)
+            SORProx::solve_bilateral_ground(
+                c,
+                &[],
+                workspace.jacobians.as_slice(),
+                dvels,
+                dim,
+                N::zero(),
+            )
         }
     }
 
@@ -1133,7 +1751,12 @@ impl<N: RealField> Body<N> for Multibody<N> {
                 if let Some(c) = c {
                     // FIXME: the following has been copy-pasted from the NonlinearSORProx.
                     // We should refactor the code better.
-                    let rhs = NonlinearSORProx::clamp_rhs(c.rhs, c.is_angular, parameters);
+                    let rhs = NonlinearSORProx::clamp_rhs(
+                        c.rhs,
+                        c.is_angular,
+                        parameters.joint_erp.unwrap_or(parameters.erp),
+                        parameters,
+                    );
 
                     if rhs < N::zero() {
                         let impulse = -rhs * c.r;
@@ -1146,6 +1769,41 @@ impl<N: RealField> Body<N> for Multibody<N> {
                 }
             }
         }
+
+        for closure_id in 0..self.loop_closures.len() {
+            for i in 0..self.loop_closures[closure_id].joint.num_constraints() {
+                let ndofs = self.ndofs;
+                let (inv_r, error, is_angular) = self.fill_loop_closure_jacobians(
+                    &self.loop_closures[closure_id],
+                    i,
+                    0,
+                    ndofs,
+                    jacobians.as_mut_slice(),
+                );
+
+                if inv_r.is_zero() {
+                    continue;
+                }
+
+                let rhs = NonlinearSORProx::clamp_rhs(
+                    -error.abs(),
+                    is_angular,
+                    parameters.joint_erp.unwrap_or(parameters.erp),
+                    parameters,
+                );
+
+                if rhs < N::zero() {
+                    // Moves the links along the jacobian in the direction reducing the error.
+                    let impulse = if error > N::zero() {
+                        rhs / inv_r
+                    } else {
+                        -rhs / inv_r
+                    };
+                    jacobians.rows_mut(ndofs, ndofs).mul_assign(impulse);
+                    self.apply_displacement(jacobians.rows(ndofs, ndofs).as_slice());
+                }
+            }
+        }
         self.solver_workspace = Some(workspace);
         self.update_kinematics();
     }
@@ -1155,6 +1813,7 @@ impl<N: RealField> Body<N> for Multibody<N> {
         self.update_status.set_local_inertia_changed(true);
         let mut link = &mut self.rbs[part_id];
         let mass_sum = link.inertia.linear + inertia.linear;
+        let old_local_com = link.local_com;
 
         // Update center of mass.
         if !mass_sum.is_zero() {
@@ -1166,8 +1825,9 @@ impl<N: RealField> Body<N> for Multibody<N> {
             link.com = link.local_to_world.translation.vector.into();
         }
 
-        // Update inertia.
-        link.local_inertia += inertia;
+        // Update inertia, expressing both terms wrt. the new center of mass.
+        link.local_inertia = link.local_inertia.shifted(&(old_local_com - link.local_com))
+            + inertia.shifted(&(com - link.local_com));
     }
 
     #[inline]
@@ -1193,12 +1853,15 @@ impl<N: RealField> Body<N> for Multibody<N> {
         }
 
         match force_type {
-            ForceType::Force => self.forces.gemv_tr(
-                N::one(),
-                &self.body_jacobians[part_id],
-                force.as_vector(),
-                N::one(),
-            ),
+            ForceType::Force => {
+                self.link_forces[part_id] += *force;
+                self.forces.gemv_tr(
+                    N::one(),
+                    &self.body_jacobians[part_id],
+                    force.as_vector(),
+                    N::one(),
+                )
+            }
             ForceType::Impulse => {
                 self.update_status.set_velocity_changed(true);
                 let dvel = &mut self.workspace.ndofs_vec;
@@ -1213,6 +1876,7 @@ impl<N: RealField> Body<N> for Multibody<N> {
             }
             ForceType::AccelerationChange => {
                 let force = self.rbs[part_id].inertia * *force;
+                self.link_forces[part_id] += Force::from_vector(force.as_vector());
                 self.forces.gemv_tr(
                     N::one(),
                     &self.body_jacobians[part_id],
@@ -1317,6 +1981,7 @@ pub struct MultibodyDesc<N: RealField> {
     local_center_of_mass: Point<N>,
     body_shift: Vector<N>,
     parent_shift: Vector<N>,
+    loop_closures: Vec<(usize, usize, LoopClosureJoint<N>)>,
 }
 
 impl<N: RealField> MultibodyDesc<N> {
@@ -1331,6 +1996,7 @@ impl<N: RealField> MultibodyDesc<N> {
             local_center_of_mass: Point::origin(),
             body_shift: Vector::zeros(),
             parent_shift: Vector::zeros(),
+            loop_closures: Vec::new(),
         }
     }
 
@@ -1342,6 +2008,21 @@ impl<N: RealField> MultibodyDesc<N> {
         self.children.last_mut().unwrap()
     }
 
+    /// Closes a kinematic loop by attaching two links of the multibody described by `self` with the given joint.
+    ///
+    /// The links are identified by their index in the order they are built: the link described by
+    /// `self` has the index 0, and is followed by its children, each one followed by its own
+    /// descendants. When built with `self.build()`, those indices are the link ids of the multibody.
+    pub fn close_loop(
+        &mut self,
+        link1: usize,
+        link2: usize,
+        joint: LoopClosureJoint<N>,
+    ) -> &mut Self {
+        self.loop_closures.push((link1, link2, joint));
+        self
+    }
+
     /// Sets the joint of this multibody builder.
     pub fn set_joint<J: Joint<N>>(&mut self, joint: J) -> &mut Self {
         self.joint = Box::new(joint);
@@ -1438,6 +2119,327 @@ impl<N: RealField> MultibodyDesc<N> {
             let _ = child.do_build_with_parent(multibody, Some(me));
         }
 
+        // The links described by `self` have consecutive ids starting at `me`.
+        for (link1, link2, joint) in &self.loop_closures {
+            multibody.close_loop(me + link1, me + link2, *joint);
+        }
+
         multibody.link_mut(me).unwrap()
     }
 }
+
+#[cfg(feature = "serde-serialize")]
+mod desc_serde {
+    use na::RealField;
+    use serde::de::{Deserialize, Deserializer};
+    use serde::ser::{Error, Serialize, Serializer};
+
+    use super::MultibodyDesc;
+    #[cfg(feature = "dim3")]
+    use crate::joint::{
+        BallJoint, CylindricalJoint, GenericJoint, HelicalJoint, PinSlotJoint, PlanarJoint,
+        RectangularJoint, UniversalJoint,
+    };
+    use crate::joint::{
+        CartesianJoint, FixedJoint, FreeJoint, Joint, PrismaticJoint, RevoluteJoint, SpringJoint,
+    };
+    use crate::math::{Inertia, Point, Vector, Velocity};
+    use crate::object::LoopClosureJoint;
+
+    macro_rules! serializable_joints(
+        ($($(#[$attr: meta])* $variant: ident($joint: ident)),*) => {
+            // The joints supported by the serialization of a multibody description.
+            #[derive(Serialize, Deserialize)]
+            enum SerializableJoint<N: RealField> {
+                $($(#[$attr])* $variant($joint<N>),)*
+            }
+
+            impl<N: RealField> SerializableJoint<N> {
+                fn from_joint(joint: &dyn Joint<N>) -> Option<Self> {
+                    $(
+                        $(#[$attr])*
+                        {
+                            if let Some(joint) = joint.downcast_ref::<$joint<N>>() {
+                                return Some(SerializableJoint::$variant(*joint));
+                            }
+                        }
+                    )*
+
+                    None
+                }
+
+                fn into_joint(self) -> Box<dyn Joint<N>> {
+                    match self {
+                        $($(#[$attr])* SerializableJoint::$variant(joint) => Box::new(joint),)*
+                    }
+                }
+            }
+        }
+    );
+
+    serializable_joints!(
+        Cartesian(CartesianJoint),
+        Fixed(FixedJoint),
+        Free(FreeJoint),
+        Prismatic(PrismaticJoint),
+        Revolute(RevoluteJoint),
+        Spring(SpringJoint),
+        #[cfg(feature = "dim3")]
+        Ball(BallJoint),
+        #[cfg(feature = "dim3")]
+        Cylindrical(CylindricalJoint),
+        #[cfg(feature = "dim3")]
+        Generic(GenericJoint),
+        #[cfg(feature = "dim3")]
+        Helical(HelicalJoint),
+        #[cfg(feature = "dim3")]
+        PinSlot(PinSlotJoint),
+        #[cfg(feature = "dim3")]
+        Planar(PlanarJoint),
+        #[cfg(feature = "dim3")]
+        Rectangular(RectangularJoint),
+        #[cfg(feature = "dim3")]
+        Universal(UniversalJoint)
+    );
+
+    // The serialized form of a multibody description, where the joint is not a trait-object.
+    #[derive(Serialize, Deserialize)]
+    struct SerializableMultibodyDesc<N: RealField> {
+        name: String,
+        children: Vec<SerializableMultibodyDesc<N>>,
+        joint: SerializableJoint<N>,
+        velocity: Velocity<N>,
+        local_inertia: Inertia<N>,
+        local_center_of_mass: Point<N>,
+        body_shift: Vector<N>,
+        parent_shift: Vector<N>,
+        loop_closures: Vec<(usize, usize, LoopClosureJoint<N>)>,
+    }
+
+    impl<N: RealField> SerializableMultibodyDesc<N> {
+        fn from_desc(desc: &MultibodyDesc<N>) -> Option<Self> {
+            Some(SerializableMultibodyDesc {
+                name: desc.name.clone(),
+                children: desc
+                    .children
+                    .iter()
+                    .map(Self::from_desc)
+                    .collect::<Option<_>>()?,
+                joint: SerializableJoint::from_joint(&*desc.joint)?,
+                velocity: desc.velocity,
+                local_inertia: desc.local_inertia,
+                local_center_of_mass: desc.local_center_of_mass,
+                body_shift: desc.body_shift,
+                parent_shift: desc.parent_shift,
+                loop_closures: desc.loop_closures.clone(),
+            })
+        }
+
+        fn into_desc(self) -> MultibodyDesc<N> {
+            MultibodyDesc {
+                name: self.name,
+                children: self.children.into_iter().map(Self::into_desc).collect(),
+                joint: self.joint.into_joint(),
+                velocity: self.velocity,
+                local_inertia: self.local_inertia,
+                local_center_of_mass: self.local_center_of_mass,
+                body_shift: self.body_shift,
+                parent_shift: self.parent_shift,
+                loop_closures: self.loop_closures,
+            }
+        }
+    }
+
+    /// Serializes this multibody description and the description of all its children.
+    ///
+    /// This fails if one of the joints is not one of the joints provided by nphysics.
+    impl<N: RealField + Serialize> Serialize for MultibodyDesc<N> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            SerializableMultibodyDesc::from_desc(self)
+                .ok_or_else(|| S::Error::custom("unsupported multibody joint type"))?
+                .serialize(serializer)
+        }
+    }
+
+    impl<'de, N: RealField + Deserialize<'de>> Deserialize<'de> for MultibodyDesc<N> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            SerializableMultibodyDesc::deserialize(deserializer)
+                .map(SerializableMultibodyDesc::into_desc)
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use crate::joint::PrismaticJoint;
+        use crate::math::Vector;
+        use crate::object::MultibodyDesc;
+
+        #[test]
+        fn multibody_desc_serde_round_trip() {
+            let mut slider = PrismaticJoint::new(Vector::x_axis(), 0.25);
+            slider.enable_min_offset(-1.0);
+            slider.enable_max_offset(2.0);
+            slider.enable_linear_motor();
+            slider.set_desired_linear_motor_velocity(3.0);
+            slider.set_max_linear_motor_force(4.0);
+
+            let mut desc = MultibodyDesc::new(slider).name("robot".to_owned());
+            let _ = desc
+                .add_child(PrismaticJoint::new(Vector::y_axis(), -0.5))
+                .set_parent_shift(Vector::x() * 1.5)
+                .set_body_shift(Vector::y() * 0.5)
+                .set_mass(2.0);
+
+            let json = serde_json::to_string(&desc).unwrap();
+            let copy: MultibodyDesc<f64> = serde_json::from_str(&json).unwrap();
+            assert_eq!(serde_json::to_string(&copy).unwrap(), json);
+
+            let joint = copy.joint.downcast_ref::<PrismaticJoint<f64>>().unwrap();
+            assert_eq!(joint.axis(), Vector::x_axis());
+            assert_eq!(joint.offset(), 0.25);
+            assert_eq!(joint.min_offset(), Some(-1.0));
+            assert_eq!(joint.max_offset(), Some(2.0));
+            assert!(joint.is_linear_motor_enabled());
+            assert_eq!(joint.desired_linear_motor_velocity(), 3.0);
+            assert_eq!(joint.max_linear_motor_force(), 4.0);
+            assert_eq!(copy.get_name(), "robot");
+
+            let child = &copy.children[0];
+            let child_joint = child.joint.downcast_ref::<PrismaticJoint<f64>>().unwrap();
+            assert_eq!(child_joint.axis(), Vector::y_axis());
+            assert_eq!(child_joint.offset(), -0.5);
+            assert_eq!(*child.get_parent_shift(), Vector::x() * 1.5);
+            assert_eq!(*child.get_body_shift(), Vector::y() * 0.5);
+            assert_eq!(child.get_mass(), 2.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ncollide::shape::Ball;
+
+    use super::InfeasibleVelocityChange;
+    use crate::analytic_tests::{Scenario, GRAVITY};
+    use crate::joint::{FixedJoint, PrismaticJoint, RevoluteJoint};
+    use crate::math::{Isometry, Vector, Velocity};
+    use crate::object::{Body, BodyPart, BodyPartHandle, DefaultBodyHandle, MultibodyDesc};
+    use crate::volumetric::Volumetric;
+    use crate::world::PhysicsEvent;
+
+    #[test]
+    fn link_velocity_change_is_mapped_to_the_joint_velocities() {
+        let slider = PrismaticJoint::new(Vector::x_axis(), 0.0);
+        let mut multibody = MultibodyDesc::new(slider).mass(2.0).build();
+        multibody.update_kinematics();
+
+        let along = Velocity::from_vectors(Vector::x() * 3.0, na::zero());
+        assert_eq!(multibody.add_link_velocity(0, &along), Ok(()));
+        assert_relative_eq!(multibody.joint_velocity(multibody.root())[0], 3.0);
+
+        // The prismatic joint cannot move its link along `y`.
+        let across = Velocity::from_vectors(Vector::y() * 3.0, na::zero());
+        assert_eq!(
+            multibody.add_link_velocity(0, &across),
+            Err(InfeasibleVelocityChange)
+        );
+        assert_relative_eq!(multibody.joint_velocity(multibody.root())[0], 3.0);
+    }
+
+    /// Steps until the joint of the link 1 of the given multibody breaks, and returns the new
+    /// multibody made of the detached link.
+    fn step_until_link_detached(
+        scenario: &mut Scenario,
+        multibody: DefaultBodyHandle,
+        duration: f64,
+    ) -> Option<DefaultBodyHandle> {
+        while scenario.time() < duration {
+            scenario.step();
+
+            for event in scenario.mechanical_world.drain_events() {
+                if let PhysicsEvent::JointBroken(part1, part2) = event {
+                    if part1 == BodyPartHandle(multibody, 0) {
+                        return Some(part2.0);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    #[test]
+    fn overloaded_fixed_multibody_joint_detaches_its_link() {
+        for &(break_torque, breaks) in &[(0.5, true), (2.0, false)] {
+            let mut scenario = Scenario::new(1.0 / 240.0);
+            let inertia = Ball::new(0.1).inertia(1.0);
+            let weight = inertia.mass() * GRAVITY;
+
+            // A horizontal cantilever of unit length, mounted on a horizontal slider. Its joint absorbs
+            // its weight, and a torque equal to its weight times its length.
+            let mut desc = MultibodyDesc::new(PrismaticJoint::new(Vector::x_axis(), 0.0));
+            let _ = desc
+                .add_child(FixedJoint::new(Isometry::identity()))
+                .set_body_shift(Vector::x())
+                .set_local_inertia(inertia);
+            let handle = scenario.bodies.insert(desc.build());
+            let link = scenario
+                .bodies
+                .multibody_mut(handle)
+                .unwrap()
+                .link_mut(1)
+                .unwrap();
+            link.set_break_force(weight * 2.0);
+            link.set_break_torque(weight * break_torque);
+
+            let detached = step_until_link_detached(&mut scenario, handle, 0.25);
+            assert_eq!(detached.is_some(), breaks);
+
+            let num_links = scenario.bodies.multibody(handle).unwrap().num_links();
+            assert_eq!(num_links, if breaks { 1 } else { 2 });
+        }
+    }
+
+    #[test]
+    fn overloaded_revolute_multibody_joint_drops_its_weight() {
+        for &(break_force, breaks) in &[(0.5, true), (2.0, false)] {
+            let mut scenario = Scenario::new(1.0 / 240.0);
+            let inertia = Ball::new(0.1).inertia(1.0);
+            let weight = inertia.mass() * GRAVITY;
+
+            #[cfg(feature = "dim2")]
+            let hinge = RevoluteJoint::new(0.0);
+            #[cfg(feature = "dim3")]
+            let hinge = RevoluteJoint::new(Vector::z_axis(), 0.0);
+
+            // A weight hanging at rest below a hinge, which absorbs its weight.
+            let mut desc = MultibodyDesc::new(FixedJoint::new(Isometry::identity()));
+            let _ = desc
+                .add_child(hinge)
+                .set_body_shift(Vector::y())
+                .set_local_inertia(inertia);
+            let handle = scenario.bodies.insert(desc.build());
+            scenario
+                .bodies
+                .multibody_mut(handle)
+                .unwrap()
+                .link_mut(1)
+                .unwrap()
+                .set_break_force(weight * break_force);
+
+            let detached = step_until_link_detached(&mut scenario, handle, 0.25);
+            assert_eq!(detached.is_some(), breaks);
+
+            if let Some(detached) = detached {
+                // The detached weight falls freely.
+                while scenario.time() < 0.25 {
+                    scenario.step();
+                }
+
+                let weight = scenario.bodies.multibody(detached).unwrap().root();
+                assert!(weight.velocity().linear.y < -1.0);
+                assert_eq!(scenario.bodies.multibody(handle).unwrap().num_links(), 1);
+            }
+        }
+    }
+}
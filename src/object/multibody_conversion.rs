@@ -0,0 +1,334 @@
+use na::{RealField, Unit};
+
+#[cfg(feature = "dim3")]
+use crate::joint::{BallConstraint, BallJoint};
+use crate::joint::{
+    CartesianConstraint, CartesianJoint, DefaultJointConstraintSet, FixedConstraint, FixedJoint,
+    FreeJoint, Joint, PrismaticConstraint, PrismaticJoint, RevoluteConstraint, RevoluteJoint,
+};
+#[cfg(feature = "dim3")]
+use crate::math::AngularVector;
+use crate::math::{Isometry, Point, Rotation, Vector};
+use crate::object::{
+    Body, BodyPartHandle, ColliderAnchor, DefaultBodyHandle, DefaultBodySet, DefaultColliderSet,
+    Ground, LoopClosureJoint, Multibody, RigidBodyDesc,
+};
+use crate::volumetric::Volumetric;
+
+// The joint constraint equivalent to a multibody joint.
+enum LinkConstraint<N: RealField> {
+    None,
+    Fixed,
+    Cartesian,
+    #[cfg(feature = "dim2")]
+    Revolute,
+    #[cfg(feature = "dim3")]
+    Revolute(Unit<AngularVector<N>>),
+    Prismatic(Unit<Vector<N>>),
+    #[cfg(feature = "dim3")]
+    Ball,
+}
+
+impl<N: RealField> LinkConstraint<N> {
+    fn from_joint(joint: &dyn Joint<N>) -> Option<Self> {
+        if joint.is::<FreeJoint<N>>() {
+            Some(LinkConstraint::None)
+        } else if joint.is::<FixedJoint<N>>() {
+            Some(LinkConstraint::Fixed)
+        } else if joint.is::<CartesianJoint<N>>() {
+            Some(LinkConstraint::Cartesian)
+        } else if let Some(revolute) = joint.downcast_ref::<RevoluteJoint<N>>() {
+            #[cfg(feature = "dim2")]
+            {
+                let _ = revolute;
+                Some(LinkConstraint::Revolute)
+            }
+            #[cfg(feature = "dim3")]
+            {
+                Some(LinkConstraint::Revolute(revolute.axis()))
+            }
+        } else if let Some(prismatic) = joint.downcast_ref::<PrismaticJoint<N>>() {
+            Some(LinkConstraint::Prismatic(prismatic.axis()))
+        } else {
+            #[cfg(feature = "dim3")]
+            {
+                if joint.is::<BallJoint<N>>() {
+                    return Some(LinkConstraint::Ball);
+                }
+            }
+
+            None
+        }
+    }
+}
+
+impl<N: RealField> Multibody<N> {
+    /// Replaces the multibody identified by `handle` by one rigid body per link, attached together by joint constraints.
+    ///
+    /// Each rigid body has the same position, velocity, and mass properties as the link it replaces,
+    /// and the colliders attached to a link are re-attached to its rigid body. If the root of the
+    /// multibody is not attached to the ground by a free joint, a new `Ground` body is inserted to
+    /// attach it to. The joints closing kinematic loops of the multibody are converted to the
+    /// equivalent joint constraints. Joint limits, motors, and damping are not converted.
+    ///
+    /// Only free, fixed, cartesian, revolute, prismatic, and ball joints are supported. Returns the
+    /// handles of the rigid bodies, in the same order as the multibody links, or `None` (leaving the
+    /// multibody untouched) if `handle` does not identify a multibody or if one of its joints is not supported.
+    pub fn to_rigid_bodies_with_constraints(
+        handle: DefaultBodyHandle,
+        bodies: &mut DefaultBodySet<N>,
+        colliders: &mut DefaultColliderSet<N>,
+        constraints: &mut DefaultJointConstraintSet<N>,
+    ) -> Option<Vec<DefaultBodyHandle>> {
+        let link_constraints = bodies
+            .multibody(handle)?
+            .links()
+            .map(|link| LinkConstraint::from_joint(&*link.dof))
+            .collect::<Option<Vec<_>>>()?;
+
+        let mut body = bodies.remove(handle)?;
+        let multibody = body
+            .downcast_mut::<Multibody<N>>()
+            .expect("The body should be a multibody.");
+
+        // The mass of the colliders is added back to the rigid bodies when the world registers the colliders.
+        let collider_handles: Vec<_> = colliders
+            .iter()
+            .filter(|(_, collider)| collider.body() == handle)
+            .map(|(collider_handle, _)| collider_handle)
+            .collect();
+        let mut moved_colliders = Vec::with_capacity(collider_handles.len());
+
+        for collider_handle in collider_handles {
+            let collider = colliders
+                .remove(collider_handle)
+                .expect("Invalid collider handle.");
+
+            if collider.density() != N::zero() {
+                if let ColliderAnchor::OnBodyPart {
+                    body_part,
+                    position_wrt_body_part,
+                } = collider.anchor()
+                {
+                    let (com, inertia) = collider
+                        .shape()
+                        .transformed_mass_properties(collider.density(), position_wrt_body_part);
+                    multibody.add_local_inertia_and_com(body_part.1, com, -inertia)
+                }
+            }
+
+            moved_colliders.push(collider);
+        }
+
+        let is_active = multibody.is_active();
+        let mut rigid_bodies = Vec::with_capacity(multibody.num_links());
+
+        for link in multibody.links() {
+            let mut rb = RigidBodyDesc::new()
+                .position(link.local_to_world)
+                .velocity(link.velocity)
+                .local_inertia(link.local_inertia)
+                .local_center_of_mass(link.local_com)
+                .status(multibody.status())
+                .gravity_enabled(multibody.gravity_enabled())
+                .build();
+
+            if !is_active {
+                rb.deactivate();
+            }
+
+            rigid_bodies.push(bodies.insert(rb));
+        }
+
+        let mut ground = None;
+
+        for (link, link_constraint) in multibody.links().zip(link_constraints.into_iter()) {
+            let (parent, parent_pos) = if link.is_root() {
+                if let LinkConstraint::None = link_constraint {
+                    continue;
+                }
+
+                let ground = *ground.get_or_insert_with(|| bodies.insert(Ground::new()));
+                (BodyPartHandle(ground, 0), Isometry::identity())
+            } else {
+                (
+                    BodyPartHandle(rigid_bodies[link.parent_internal_id], 0),
+                    link.parent_to_world,
+                )
+            };
+
+            let child = BodyPartHandle(rigid_bodies[link.link_id()], 0);
+            let child_pos = link.local_to_world;
+            let anchor1 = Point::from(link.parent_shift);
+            let anchor2 = child_pos.inverse() * (parent_pos * anchor1);
+            let ref_frame1 = parent_pos.rotation.inverse() * child_pos.rotation;
+            let ref_frame2 = Rotation::identity();
+
+            let _ = match link_constraint {
+                LinkConstraint::None => continue,
+                LinkConstraint::Fixed => constraints.insert(FixedConstraint::new(
+                    parent, child, anchor1, ref_frame1, anchor2, ref_frame2,
+                )),
+                LinkConstraint::Cartesian => constraints.insert(CartesianConstraint::new(
+                    parent, child, anchor1, ref_frame1, anchor2, ref_frame2,
+                )),
+                #[cfg(feature = "dim2")]
+                LinkConstraint::Revolute => {
+                    constraints.insert(RevoluteConstraint::new(parent, child, anchor1, anchor2))
+                }
+                // The rotation of the child is about the joint axis, so the axis is the same in both frames.
+                #[cfg(feature = "dim3")]
+                LinkConstraint::Revolute(axis) => constraints.insert(RevoluteConstraint::new(
+                    parent, child, anchor1, axis, anchor2, axis,
+                )),
+                LinkConstraint::Prismatic(axis) => constraints.insert(PrismaticConstraint::new(
+                    parent, child, anchor1, axis, anchor2,
+                )),
+                #[cfg(feature = "dim3")]
+                LinkConstraint::Ball => {
+                    constraints.insert(BallConstraint::new(parent, child, anchor1, anchor2))
+                }
+            };
+        }
+
+        for (link1, link2, joint) in multibody.loop_closures() {
+            let b1 = BodyPartHandle(rigid_bodies[link1], 0);
+            let b2 = BodyPartHandle(rigid_bodies[link2], 0);
+
+            let _ = match *joint {
+                #[cfg(feature = "dim2")]
+                LoopClosureJoint::Ball { anchor1, anchor2 } => {
+                    constraints.insert(RevoluteConstraint::new(b1, b2, anchor1, anchor2))
+                }
+                #[cfg(feature = "dim3")]
+                LoopClosureJoint::Ball { anchor1, anchor2 } => {
+                    constraints.insert(BallConstraint::new(b1, b2, anchor1, anchor2))
+                }
+                #[cfg(feature = "dim3")]
+                LoopClosureJoint::Revolute {
+                    anchor1,
+                    axis1,
+                    anchor2,
+                    axis2,
+                } => constraints.insert(RevoluteConstraint::new(
+                    b1, b2, anchor1, axis1, anchor2, axis2,
+                )),
+                LoopClosureJoint::Fixed { frame1, frame2 } => {
+                    constraints.insert(FixedConstraint::new(
+                        b1,
+                        b2,
+                        Point::from(frame1.translation.vector),
+                        frame1.rotation,
+                        Point::from(frame2.translation.vector),
+                        frame2.rotation,
+                    ))
+                }
+            };
+        }
+
+        for mut collider in moved_colliders {
+            if let ColliderAnchor::OnBodyPart { body_part, .. } = collider.anchor() {
+                let rigid_body = rigid_bodies[body_part.1];
+                collider.set_anchor_body_part(BodyPartHandle(rigid_body, 0));
+            }
+
+            collider.set_proxy_handle(None);
+            collider.set_graph_index(None);
+            let _ = colliders.insert(collider);
+        }
+
+        Some(rigid_bodies)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ncollide::shape::{Ball, ShapeHandle};
+
+    use crate::force_generator::DefaultForceGeneratorSet;
+    use crate::joint::{DefaultJointConstraintSet, FreeJoint, PrismaticJoint};
+    use crate::math::{AngularVector, Isometry, Vector, Velocity};
+    use crate::object::{
+        BodyPart, BodyPartHandle, ColliderDesc, DefaultBodySet, DefaultColliderSet, Multibody,
+        MultibodyDesc,
+    };
+    use crate::world::{DefaultGeometricalWorld, DefaultMechanicalWorld};
+
+    #[test]
+    fn rigid_bodies_match_the_pose_velocity_and_inertia_of_the_links() {
+        let mut mechanical_world = DefaultMechanicalWorld::new(Vector::zeros());
+        let mut geometrical_world = DefaultGeometricalWorld::new();
+        let mut bodies = DefaultBodySet::new();
+        let mut colliders = DefaultColliderSet::new();
+        let mut constraints = DefaultJointConstraintSet::new();
+        let mut forces = DefaultForceGeneratorSet::new();
+
+        // A free root moving and spinning, with a sliding child carrying an off-center collider.
+        let velocity = Velocity::from_vectors(Vector::x(), AngularVector::repeat(0.5));
+        let mut desc = MultibodyDesc::new(FreeJoint::new(Isometry::identity())).velocity(velocity);
+        let _ = desc
+            .add_child(PrismaticJoint::new(Vector::x_axis(), 0.3))
+            .set_parent_shift(Vector::y());
+        let handle = bodies.insert(desc.build());
+        let collider_desc = ColliderDesc::new(ShapeHandle::new(Ball::new(0.25))).density(1.0);
+        let _ = colliders.insert(collider_desc.build(BodyPartHandle(handle, 0)));
+        let _ = colliders.insert(
+            collider_desc
+                .translation(Vector::x() * 0.5)
+                .build(BodyPartHandle(handle, 1)),
+        );
+
+        mechanical_world.step(
+            &mut geometrical_world,
+            &mut bodies,
+            &mut colliders,
+            &mut constraints,
+            &mut forces,
+        );
+
+        let links: Vec<_> = bodies
+            .multibody(handle)
+            .unwrap()
+            .links()
+            .map(|link| {
+                (
+                    link.local_to_world,
+                    link.velocity,
+                    link.local_inertia,
+                    link.local_com,
+                )
+            })
+            .collect();
+
+        let rigid_bodies = Multibody::to_rigid_bodies_with_constraints(
+            handle,
+            &mut bodies,
+            &mut colliders,
+            &mut constraints,
+        )
+        .unwrap();
+        let _ = mechanical_world.maintain(
+            &mut geometrical_world,
+            &mut bodies,
+            &mut colliders,
+            &mut constraints,
+        );
+
+        assert_eq!(rigid_bodies.len(), links.len());
+        assert_eq!(constraints.iter().count(), 1);
+
+        for (rb, (position, velocity, inertia, com)) in rigid_bodies.iter().zip(links.iter()) {
+            let rb = bodies.rigid_body(*rb).unwrap();
+            let rb_inertia = rb.local_inertia();
+            assert_relative_eq!(*rb.position(), *position, epsilon = 1.0e-6);
+            assert_relative_eq!(
+                rb.velocity().as_vector(),
+                velocity.as_vector(),
+                epsilon = 1.0e-6
+            );
+            assert_relative_eq!(rb_inertia.linear, inertia.linear, epsilon = 1.0e-6);
+            assert_relative_eq!(rb_inertia.angular, inertia.angular, epsilon = 1.0e-6);
+            assert_relative_eq!(rb.local_center_of_mass(), *com, epsilon = 1.0e-6);
+        }
+    }
+}
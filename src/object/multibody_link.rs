@@ -37,6 +37,9 @@ pub struct MultibodyLink<N: RealField> {
 
     pub(crate) local_inertia: Inertia<N>,
     pub(crate) local_com: Point<N>,
+
+    pub(crate) break_force: N,
+    pub(crate) break_torque: N,
     // TODO: User-defined data
     // user_data:       T
 }
@@ -84,6 +87,8 @@ impl<N: RealField> MultibodyLink<N> {
             local_com,
             inertia,
             com,
+            break_force: N::max_value(),
+            break_torque: N::max_value(),
         }
     }
 
@@ -93,6 +98,28 @@ impl<N: RealField> MultibodyLink<N> {
         self.internal_id == 0
     }
 
+    /// The id of the parent of this link, or `None` if this link is the root of the multibody.
+    #[inline]
+    pub fn parent_id(&self) -> Option<usize> {
+        if self.is_root() {
+            None
+        } else {
+            Some(self.parent_internal_id)
+        }
+    }
+
+    /// The number of degrees of freedom of the joint attaching this link to its parent.
+    #[inline]
+    pub fn ndofs(&self) -> usize {
+        self.dof.ndofs()
+    }
+
+    /// The index of the first degree of freedom of this link's joint in the generalized coordinates of the multibody.
+    #[inline]
+    pub fn assembly_id(&self) -> usize {
+        self.assembly_id
+    }
+
     /// Reference to the joint attaching this link to its parent.
     #[inline]
     pub fn joint(&self) -> &dyn Joint<N> {
@@ -134,6 +161,43 @@ impl<N: RealField> MultibodyLink<N> {
     pub fn link_id(&self) -> usize {
         self.internal_id
     }
+
+    /// The maximum force the joint attaching this link to its parent can absorb before breaking.
+    #[inline]
+    pub fn break_force(&self) -> N {
+        self.break_force
+    }
+
+    /// Sets the maximum force the joint attaching this link to its parent can absorb before breaking.
+    ///
+    /// The force absorbed by the joint is its reaction force, i.e., the force the parent exerts on
+    /// the subtree rooted at this link. When it breaks, this subtree is detached into a new
+    /// multibody. The joint of the root link never breaks.
+    #[inline]
+    pub fn set_break_force(&mut self, break_force: N) {
+        self.break_force = break_force
+    }
+
+    /// The maximum torque the joint attaching this link to its parent can absorb before breaking.
+    #[inline]
+    pub fn break_torque(&self) -> N {
+        self.break_torque
+    }
+
+    /// Sets the maximum torque the joint attaching this link to its parent can absorb before breaking.
+    ///
+    /// The torque absorbed by the joint is its reaction torque about the joint anchor. See
+    /// `set_break_force` for the effect of breaking.
+    #[inline]
+    pub fn set_break_torque(&mut self, break_torque: N) {
+        self.break_torque = break_torque
+    }
+
+    // Whether the reaction of this link's joint should be checked against its break thresholds.
+    pub(crate) fn is_breakable(&self) -> bool {
+        !self.is_root()
+            && (self.break_force != N::max_value() || self.break_torque != N::max_value())
+    }
 }
 
 impl<N: RealField> BodyPart<N> for MultibodyLink<N> {
@@ -0,0 +1,195 @@
+use na::{RealField, Unit};
+
+use crate::math::{AngularVector, Isometry, Point, Vector, ANGULAR_DIM, DIM};
+use crate::object::MultibodyLink;
+use crate::solver::ForceDirection;
+
+/// A joint closing a kinematic loop between two links of the same multibody.
+///
+/// The links of a multibody form a tree, so mechanisms like four-bar linkages or parallel robots
+/// cannot be described with multibody joints alone. A loop-closure joint attaches two arbitrary
+/// links of a multibody together. Unlike multibody joints, it is not part of the reduced
+/// coordinates of the multibody: it is enforced by the constraint solver of the multibody.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum LoopClosureJoint<N: RealField> {
+    /// Keeps a point of each link at the same location, leaving their relative rotation free.
+    ///
+    /// In 2D, this is a revolute joint.
+    Ball {
+        /// The point of the first link, in its local-space.
+        anchor1: Point<N>,
+        /// The point of the second link, in its local-space.
+        anchor2: Point<N>,
+    },
+    /// Keeps a point and an axis of each link aligned, only allowing a relative rotation about this axis.
+    #[cfg(feature = "dim3")]
+    Revolute {
+        /// The point of the first link, in its local-space.
+        anchor1: Point<N>,
+        /// The rotation axis of the first link, in its local-space.
+        axis1: Unit<AngularVector<N>>,
+        /// The point of the second link, in its local-space.
+        anchor2: Point<N>,
+        /// The rotation axis of the second link, in its local-space.
+        axis2: Unit<AngularVector<N>>,
+    },
+    /// Keeps a frame of each link at the same position and orientation.
+    Fixed {
+        /// The frame of the first link, in its local-space.
+        frame1: Isometry<N>,
+        /// The frame of the second link, in its local-space.
+        frame2: Isometry<N>,
+    },
+}
+
+// A scalar constraint of a loop-closure joint: the direction of the force it applies to the
+// first link, the application points of this force on both links, and its positional error.
+pub(crate) struct LoopClosureConstraint<N: RealField> {
+    pub(crate) dir: ForceDirection<N>,
+    pub(crate) point1: Point<N>,
+    pub(crate) point2: Point<N>,
+    pub(crate) error: N,
+}
+
+impl<N: RealField> LoopClosureJoint<N> {
+    /// The number of scalar constraints enforced by this joint.
+    pub fn num_constraints(&self) -> usize {
+        match self {
+            LoopClosureJoint::Ball { .. } => DIM,
+            #[cfg(feature = "dim3")]
+            LoopClosureJoint::Revolute { .. } => DIM + 2,
+            LoopClosureJoint::Fixed { .. } => DIM + ANGULAR_DIM,
+        }
+    }
+
+    // The `i`-th scalar constraint of this joint between the given links.
+    //
+    // The velocity of the first link relative to the second along the constraint direction
+    // is the time-derivative of the constraint error.
+    pub(crate) fn constraint(
+        &self,
+        i: usize,
+        link1: &MultibodyLink<N>,
+        link2: &MultibodyLink<N>,
+    ) -> LoopClosureConstraint<N> {
+        let (frame1, frame2) = match self {
+            LoopClosureJoint::Ball { anchor1, anchor2 } => (
+                Isometry::new(anchor1.coords, na::zero()),
+                Isometry::new(anchor2.coords, na::zero()),
+            ),
+            #[cfg(feature = "dim3")]
+            LoopClosureJoint::Revolute {
+                anchor1, anchor2, ..
+            } => (
+                Isometry::new(anchor1.coords, na::zero()),
+                Isometry::new(anchor2.coords, na::zero()),
+            ),
+            LoopClosureJoint::Fixed { frame1, frame2 } => (*frame1, *frame2),
+        };
+
+        let pos1 = link1.local_to_world * frame1;
+        let pos2 = link2.local_to_world * frame2;
+        let point1 = Point::from(pos1.translation.vector);
+        let point2 = Point::from(pos2.translation.vector);
+
+        if i < DIM {
+            let axis = Vector::ith(i, N::one());
+
+            return LoopClosureConstraint {
+                dir: ForceDirection::Linear(Unit::new_unchecked(axis)),
+                point1,
+                point2,
+                error: (point1 - point2).dot(&axis),
+            };
+        }
+
+        let (axis, error) = match self {
+            #[cfg(feature = "dim3")]
+            LoopClosureJoint::Revolute { axis1, axis2, .. } => {
+                let axis1 = pos1 * *axis1;
+                let axis2 = pos2 * *axis2;
+                let mut basis = [AngularVector::zeros(); 2];
+                let mut k = 0;
+                AngularVector::orthonormal_subspace_basis(&[axis1.into_inner()], |dir| {
+                    basis[k] = *dir;
+                    k += 1;
+                    true
+                });
+
+                let axis = basis[i - DIM];
+                (axis, axis2.cross(&*axis1).dot(&axis))
+            }
+            _ => {
+                let axis = AngularVector::ith(i - DIM, N::one());
+                let error = (pos1.rotation * pos2.rotation.inverse()).scaled_axis();
+                (axis, error.dot(&axis))
+            }
+        };
+
+        LoopClosureConstraint {
+            dir: ForceDirection::Angular(Unit::new_unchecked(axis)),
+            point1,
+            point2,
+            error,
+        }
+    }
+}
+
+// A loop-closure joint between two links of a multibody.
+pub(crate) struct MultibodyLoopClosure<N: RealField> {
+    pub(crate) link1: usize,
+    pub(crate) link2: usize,
+    pub(crate) joint: LoopClosureJoint<N>,
+    // The index of the impulse of the first constraint of this joint in the multibody impulses.
+    pub(crate) impulse_id: usize,
+}
+
+#[cfg(test)]
+mod test {
+    use super::LoopClosureJoint;
+    use crate::analytic_tests::Scenario;
+    use crate::joint::{FixedJoint, RevoluteJoint};
+    use crate::math::{Isometry, Point, Vector};
+    use crate::object::{BodyPart, MultibodyDesc};
+
+    #[test]
+    fn loop_closure_holds_a_triangular_linkage_in_place() {
+        let mut scenario = Scenario::new(1.0 / 240.0);
+
+        #[cfg(feature = "dim2")]
+        let hinge = RevoluteJoint::new(0.0);
+        #[cfg(feature = "dim3")]
+        let hinge = RevoluteJoint::new(Vector::z_axis(), 0.0);
+
+        // A horizontal arm hinged at the origin, and a vertical arm hinged at its end, hanging below it.
+        let mut desc = MultibodyDesc::new(FixedJoint::new(Isometry::identity()));
+        let _ = desc
+            .add_child(hinge)
+            .set_body_shift(-Vector::x())
+            .set_mass(1.0)
+            .add_child(hinge)
+            .set_body_shift(Vector::y())
+            .set_mass(1.0);
+
+        // Attaching the end of the second arm to the root turns the linkage into a rigid triangle.
+        let tip = Point::from(Vector::x() - Vector::y());
+        let _ = desc.close_loop(
+            2,
+            0,
+            LoopClosureJoint::Ball {
+                anchor1: Point::origin(),
+                anchor2: tip,
+            },
+        );
+        let handle = scenario.bodies.insert(desc.build());
+
+        while scenario.time() < 2.0 {
+            scenario.step();
+        }
+
+        let link = scenario.bodies.multibody(handle).unwrap().link(2).unwrap();
+        let drift = (Point::from(link.position().translation.vector) - tip).norm();
+        assert!(drift < 0.01, "The loop closure drifted by {}.", drift);
+    }
+}
@@ -1,9 +1,9 @@
-use na::{DVectorSlice, DVectorSliceMut, RealField};
+use na::{DVectorSlice, DVectorSliceMut, RealField, Unit};
 use std::any::Any;
 
 use crate::math::{
-    Dim, Force, ForceType, Inertia, Isometry, Point, Rotation, SpatialVector, Translation, Vector,
-    Velocity, DIM, SPATIAL_DIM,
+    AngularVector, Dim, Force, ForceType, Inertia, Isometry, Point, Rotation, SpatialVector,
+    Translation, Vector, Velocity, DIM, SPATIAL_DIM,
 };
 use crate::object::{
     ActivationStatus, Body, BodyPart, BodyPartMotion, BodyStatus, BodyUpdateStatus,
@@ -16,8 +16,6 @@ use ncollide::interpolation::{
 };
 use ncollide::shape::DeformationsType;
 
-#[cfg(feature = "dim3")]
-use crate::math::AngularVector;
 #[cfg(feature = "dim3")]
 use crate::utils::GeneralizedCross;
 
@@ -40,7 +38,13 @@ pub struct RigidBody<N: RealField> {
     max_linear_velocity: N,
     max_angular_velocity: N,
     status: BodyStatus,
+    // Status and velocity saved while this body is disabled.
+    disabled_state: Option<(BodyStatus, Velocity<N>)>,
+    // Status saved while this body is frozen.
+    frozen_status: Option<BodyStatus>,
+    freeze_threshold: N,
     gravity_enabled: bool,
+    gravity_scale: N,
     linear_motion_interpolation_enabled: bool,
     activation: ActivationStatus<N>,
     jacobian_mask: SpatialVector<N>,
@@ -72,7 +76,11 @@ impl<N: RealField> RigidBody<N> {
             max_linear_velocity: N::max_value(),
             max_angular_velocity: N::max_value(),
             status: BodyStatus::Dynamic,
+            disabled_state: None,
+            frozen_status: None,
+            freeze_threshold: N::max_value(),
             gravity_enabled: true,
+            gravity_scale: N::one(),
             linear_motion_interpolation_enabled: false,
             activation: ActivationStatus::new_active(),
             jacobian_mask: SpatialVector::repeat(N::one()),
@@ -100,6 +108,76 @@ impl<N: RealField> RigidBody<N> {
         self.linear_motion_interpolation_enabled = enabled
     }
 
+    /// Enables or disables this rigid body.
+    ///
+    /// A disabled rigid body behaves like a static obstacle: it is no longer integrated nor
+    /// affected by constraints, but other bodies still collide with it. Contrary to sleeping,
+    /// it won't be woken up by contacts. Its status and velocity are saved when it is disabled
+    /// and restored when it is enabled again.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if enabled {
+            if let Some((status, velocity)) = self.disabled_state.take() {
+                self.set_status(status);
+                self.set_velocity(velocity);
+                self.activate();
+            }
+        } else if self.disabled_state.is_none() {
+            self.disabled_state = Some((self.status, self.velocity));
+            self.set_status(BodyStatus::Static);
+            self.set_velocity(Velocity::zero());
+        }
+    }
+
+    /// Whether or not this rigid body is enabled.
+    ///
+    /// See `set_enabled` for details.
+    pub fn is_enabled(&self) -> bool {
+        self.disabled_state.is_none()
+    }
+
+    /// Snaps this rigid body to rest and keeps it still until it is strongly disturbed.
+    ///
+    /// A frozen rigid body behaves like a static obstacle: other bodies keep colliding with it
+    /// and resting on it, but it won't move nor be woken up by its neighbors. It is automatically
+    /// unfrozen by the mechanical world when a body hits it with an impulse greater than the
+    /// freeze threshold (see `set_freeze_threshold`). This does nothing if the body is disabled.
+    pub fn freeze(&mut self) {
+        if self.frozen_status.is_none() && self.is_enabled() {
+            self.frozen_status = Some(self.status);
+            self.set_status(BodyStatus::Static);
+            self.set_velocity(Velocity::zero());
+        }
+    }
+
+    /// Restores the status this rigid body had before being frozen, and wakes it up.
+    pub fn unfreeze(&mut self) {
+        if let Some(status) = self.frozen_status.take() {
+            self.set_status(status);
+            self.activate();
+        }
+    }
+
+    /// Whether or not this rigid body is frozen.
+    ///
+    /// See `freeze` for details.
+    pub fn is_frozen(&self) -> bool {
+        self.frozen_status.is_some()
+    }
+
+    /// Sets the impulse a colliding body must exceed to unfreeze this rigid body.
+    ///
+    /// This is compared to the normal impulse applied by the constraints solver at each contact
+    /// of this rigid body during the last step. By default, this threshold is infinite so a
+    /// frozen body is only unfrozen by an explicit call to `unfreeze`.
+    pub fn set_freeze_threshold(&mut self, threshold: N) {
+        self.freeze_threshold = threshold
+    }
+
+    /// The impulse a colliding body must exceed to unfreeze this rigid body.
+    pub fn freeze_threshold(&self) -> N {
+        self.freeze_threshold
+    }
+
     /// Mark some translational degrees of freedom as kinematic.
     pub fn set_translations_kinematic(&mut self, is_kinematic: Vector<bool>) {
         self.update_status.set_status_changed(true);
@@ -196,10 +274,26 @@ impl<N: RealField> RigidBody<N> {
         self.set_translations_kinematic(Vector::repeat(false))
     }
 
+    /// Sets the factor multiplying the world gravity applied to this rigid body.
+    ///
+    /// Setting it to zero cancels the gravity while keeping the body able to fall asleep
+    /// when at rest. Negative values make the body fall upward. The gravity scale is `1.0`
+    /// by default.
+    pub fn set_gravity_scale(&mut self, scale: N) {
+        self.gravity_scale = scale
+    }
+
+    /// The factor multiplying the world gravity applied to this rigid body.
+    pub fn gravity_scale(&self) -> N {
+        self.gravity_scale
+    }
+
     /// Sets the linear damping coefficient of this rigid body.
     ///
-    /// Linear damping will make the rigid body loose linear velocity automatically velocity at each timestep.
-    /// There is no damping by default.
+    /// Linear damping will make the rigid body lose linear velocity automatically at each timestep.
+    /// It is applied multiplicatively during integration: the linear velocity is scaled by
+    /// `1 / (1 + dt * damping)`, which is close to the usual `1 - dt * damping` decay for small
+    /// timesteps but remains stable for large damping coefficients. There is no damping by default.
     pub fn set_linear_damping(&mut self, damping: N) {
         self.linear_damping = damping
     }
@@ -211,8 +305,10 @@ impl<N: RealField> RigidBody<N> {
 
     /// Sets the angular damping coefficient of this rigid body.
     ///
-    /// Angular damping will make the rigid body loose angular velocity automatically velocity at each timestep.
-    /// There is no damping by default.
+    /// Angular damping will make the rigid body lose angular velocity automatically at each timestep.
+    /// It is applied multiplicatively during integration: the angular velocity is scaled by
+    /// `1 / (1 + dt * damping)`, which is close to the usual `1 - dt * damping` decay for small
+    /// timesteps but remains stable for large damping coefficients. There is no damping by default.
     pub fn set_angular_damping(&mut self, damping: N) {
         self.angular_damping = damping
     }
@@ -314,6 +410,17 @@ impl<N: RealField> RigidBody<N> {
         self.com = pos * self.local_com;
     }
 
+    /// Moves this rigid body to the given position, as if it had been there since the beginning of the timestep.
+    ///
+    /// Unlike `set_position`, the jump from the previous position is not seen as a motion: it is not
+    /// swept by continuous collision detection, nor used to enlarge the broad-phase AABBs of the
+    /// colliders attached to this body. The velocity of this body is left unchanged.
+    #[inline]
+    pub fn teleport(&mut self, pos: Isometry<N>) {
+        self.set_position(pos);
+        self.position0 = self.position;
+    }
+
     /// Set the velocity of this rigid body.
     #[inline]
     pub fn set_velocity(&mut self, vel: Velocity<N>) {
@@ -356,6 +463,44 @@ impl<N: RealField> RigidBody<N> {
         &self.inv_augmented_mass
     }
 
+    /// The inverse of the effective mass this rigid body presents along `dir` at the world-space `point`.
+    ///
+    /// This is `dir^T M^-1 dir` where `M^-1` is the inverse mass matrix of the body (without the gyroscopic
+    /// terms) at its current orientation, and `dir` is applied with its lever arm wrt. the center of mass.
+    /// Rotations and translations locked with `set_rotations_kinematic` or `set_translations_kinematic`
+    /// do not contribute. Returns zero if this body is not dynamic.
+    pub fn inv_effective_mass_at(&self, point: &Point<N>, dir: &Unit<Vector<N>>) -> N {
+        if self.status != BodyStatus::Dynamic {
+            return N::zero();
+        }
+
+        let com = self.position * self.local_com;
+        let force = ForceDirection::Linear(*dir).at_point(&Point::from(point - com));
+        let mut masked_force = force.clone();
+        masked_force
+            .as_vector_mut()
+            .component_mul_assign(&self.jacobian_mask);
+
+        let inv_mass = self.local_inertia.transformed(&self.position).inverse();
+        let vel = inv_mass * masked_force;
+        masked_force.as_vector().dot(vel.as_vector())
+    }
+
+    /// The effective mass this rigid body presents along `dir` at the world-space `point`.
+    ///
+    /// This is the impulse that must be applied at `point` along `dir` to change the velocity of
+    /// this point along `dir` by one unit. Returns `N::max_value()` if this body cannot be moved
+    /// by such an impulse, e.g., if it is not dynamic.
+    pub fn effective_mass_at(&self, point: &Point<N>, dir: &Unit<Vector<N>>) -> N {
+        let inv_mass = self.inv_effective_mass_at(point, dir);
+
+        if inv_mass > N::zero() {
+            N::one() / inv_mass
+        } else {
+            N::max_value()
+        }
+    }
+
     /// The position of this rigid body.
     #[inline]
     pub fn position(&self) -> &Isometry<N> {
@@ -368,6 +513,75 @@ impl<N: RealField> RigidBody<N> {
         &self.velocity
     }
 
+    /// The kinetic energy of this rigid body, including the energy due to its rotation.
+    #[inline]
+    pub fn kinetic_energy(&self) -> N {
+        let momentum = self.inertia * self.velocity;
+        momentum.as_vector().dot(self.velocity.as_vector()) * na::convert(0.5)
+    }
+
+    /// Applies a world-space torque to this rigid body.
+    ///
+    /// The torque is accumulated with the other external forces until the next timestep.
+    #[inline]
+    pub fn apply_torque(&mut self, torque: &AngularVector<N>, auto_wake_up: bool) {
+        self.apply_torque_to_part(0, torque, ForceType::Force, auto_wake_up)
+    }
+
+    /// Applies a torque expressed in the local-space of this rigid body.
+    #[inline]
+    pub fn apply_local_torque(&mut self, torque: &AngularVector<N>, auto_wake_up: bool) {
+        self.apply_local_torque_to_part(0, torque, ForceType::Force, auto_wake_up)
+    }
+
+    /// Applies a world-space angular impulse to this rigid body.
+    ///
+    /// The angular velocity of this rigid body is modified immediately.
+    #[inline]
+    pub fn apply_angular_impulse(&mut self, impulse: &AngularVector<N>, auto_wake_up: bool) {
+        self.apply_torque_to_part(0, impulse, ForceType::Impulse, auto_wake_up)
+    }
+
+    /// Applies an angular impulse expressed in the local-space of this rigid body.
+    #[inline]
+    pub fn apply_local_angular_impulse(&mut self, impulse: &AngularVector<N>, auto_wake_up: bool) {
+        self.apply_local_torque_to_part(0, impulse, ForceType::Impulse, auto_wake_up)
+    }
+
+    /// Adds `delta` to the linear velocity of this rigid body, and wakes it up.
+    ///
+    /// Unlike an impulse, the velocity change does not depend on the mass of this rigid body.
+    /// This has no effect if this rigid body is not dynamic. See `Multibody::add_link_velocity`
+    /// for the links of a multibody.
+    #[inline]
+    pub fn add_linear_velocity(&mut self, delta: &Vector<N>) {
+        self.apply_force(0, &Force::linear(*delta), ForceType::VelocityChange, true)
+    }
+
+    /// Adds `delta` to the angular velocity of this rigid body, and wakes it up.
+    ///
+    /// Unlike an angular impulse, the velocity change does not depend on the inertia of this
+    /// rigid body. This has no effect if this rigid body is not dynamic.
+    #[inline]
+    pub fn add_angular_velocity(&mut self, delta: &AngularVector<N>) {
+        self.apply_torque_to_part(0, delta, ForceType::VelocityChange, true)
+    }
+
+    /// Changes the linear velocity of this rigid body toward `target`, by at most `max_delta`.
+    ///
+    /// The velocity is set to `target` if it is closer than `max_delta`. Otherwise, its change has
+    /// a magnitude of `max_delta` in the direction of `target`. This wakes up this rigid body.
+    pub fn set_linear_velocity_toward(&mut self, target: &Vector<N>, max_delta: N) {
+        let mut delta = target - self.velocity.linear;
+        let norm = delta.norm();
+
+        if norm > max_delta {
+            delta *= max_delta / norm;
+        }
+
+        self.add_linear_velocity(&delta)
+    }
+
     fn displacement_wrt_com(&self, disp: &Velocity<N>) -> Isometry<N> {
         let shift = Translation::from(self.com.coords);
         shift * disp.to_transform() * shift.inverse()
@@ -379,6 +593,90 @@ impl<N: RealField> RigidBody<N> {
         let new_pos = disp * self.position;
         self.set_position(new_pos);
     }
+
+    fn compute_acceleration(&self, gravity: &Vector<N>) -> Velocity<N> {
+        let mut acceleration = Velocity::zero();
+
+        match self.status {
+            BodyStatus::Dynamic => {
+                // The inverse inertia matrix is constant in 2D.
+                #[cfg(feature = "dim3")]
+                {
+                    /*
+                     * Compute acceleration due to gyroscopic forces.
+                     */
+                    let i = &self.inertia.angular;
+                    let w = &self.velocity.angular;
+                    let iw = i * w;
+                    let gyroscopic = -w.cross(&iw);
+                    acceleration.angular = self.inv_augmented_mass.angular * gyroscopic;
+                }
+
+                if self.inv_augmented_mass.linear != N::zero() && self.gravity_enabled {
+                    acceleration.linear = *gravity * self.gravity_scale;
+                }
+
+                acceleration += self.inv_augmented_mass * self.external_forces;
+                acceleration
+                    .as_vector_mut()
+                    .component_mul_assign(&self.jacobian_mask);
+            }
+            _ => {}
+        }
+
+        acceleration
+    }
+
+    fn damped_and_clamped_velocity(&self, mut velocity: Velocity<N>, dt: N) -> Velocity<N> {
+        velocity.linear *= N::one() / (N::one() + dt * self.linear_damping);
+        velocity.angular *= N::one() / (N::one() + dt * self.angular_damping);
+
+        let linvel_norm = velocity.linear.norm();
+
+        if linvel_norm > self.max_linear_velocity {
+            if self.max_linear_velocity.is_zero() {
+                velocity.linear = na::zero();
+            } else {
+                velocity.linear *= self.max_linear_velocity / linvel_norm;
+            }
+        }
+
+        #[cfg(feature = "dim2")]
+        {
+            if velocity.angular > self.max_angular_velocity {
+                velocity.angular = self.max_angular_velocity;
+            } else if velocity.angular < -self.max_angular_velocity {
+                velocity.angular = -self.max_angular_velocity;
+            }
+        }
+
+        #[cfg(feature = "dim3")]
+        {
+            let angvel_norm = velocity.angular.norm();
+
+            if angvel_norm > self.max_angular_velocity {
+                if self.max_angular_velocity.is_zero() {
+                    velocity.angular = na::zero()
+                } else {
+                    velocity.angular *= self.max_angular_velocity / angvel_norm;
+                }
+            }
+        }
+
+        velocity
+    }
+
+    /// Predicts the position of this rigid body after a timestep of length `dt`, ignoring contacts and joints.
+    ///
+    /// This integrates gravity, the external forces currently applied to this rigid body, and its
+    /// velocity the same way a timestep of the mechanical world would, except that the constraints
+    /// solver is not run. This is cheap, but only approximate if this rigid body is subject to
+    /// contacts or joints.
+    pub fn predict_position(&self, dt: N, gravity: &Vector<N>) -> Isometry<N> {
+        let velocity = self.velocity + self.compute_acceleration(gravity) * dt;
+        let velocity = self.damped_and_clamped_velocity(velocity, dt);
+        self.displacement_wrt_com(&(velocity * dt)) * self.position
+    }
 }
 
 impl<N: RealField> Body<N> for RigidBody<N> {
@@ -465,41 +763,7 @@ impl<N: RealField> Body<N> for RigidBody<N> {
 
     #[inline]
     fn integrate(&mut self, parameters: &IntegrationParameters<N>) {
-        self.velocity.linear *= N::one() / (N::one() + parameters.dt() * self.linear_damping);
-        self.velocity.angular *= N::one() / (N::one() + parameters.dt() * self.angular_damping);
-
-        let linvel_norm = self.velocity.linear.norm();
-
-        if linvel_norm > self.max_linear_velocity {
-            if self.max_linear_velocity.is_zero() {
-                self.velocity.linear = na::zero();
-            } else {
-                self.velocity.linear *= self.max_linear_velocity / linvel_norm;
-            }
-        }
-
-        #[cfg(feature = "dim2")]
-        {
-            if self.velocity.angular > self.max_angular_velocity {
-                self.velocity.angular = self.max_angular_velocity;
-            } else if self.velocity.angular < -self.max_angular_velocity {
-                self.velocity.angular = -self.max_angular_velocity;
-            }
-        }
-
-        #[cfg(feature = "dim3")]
-        {
-            let angvel_norm = self.velocity.angular.norm();
-
-            if angvel_norm > self.max_angular_velocity {
-                if self.max_angular_velocity.is_zero() {
-                    self.velocity.angular = na::zero()
-                } else {
-                    self.velocity.angular *= self.max_angular_velocity / angvel_norm;
-                }
-            }
-        }
-
+        self.velocity = self.damped_and_clamped_velocity(self.velocity, parameters.dt());
         let disp = self.velocity * parameters.dt();
         self.apply_displacement(&disp);
     }
@@ -588,34 +852,7 @@ impl<N: RealField> Body<N> for RigidBody<N> {
     }
 
     fn update_acceleration(&mut self, gravity: &Vector<N>, _: &IntegrationParameters<N>) {
-        self.acceleration = Velocity::zero();
-
-        match self.status {
-            BodyStatus::Dynamic => {
-                // The inverse inertia matrix is constant in 2D.
-                #[cfg(feature = "dim3")]
-                {
-                    /*
-                     * Compute acceleration due to gyroscopic forces.
-                     */
-                    let i = &self.inertia.angular;
-                    let w = &self.velocity.angular;
-                    let iw = i * w;
-                    let gyroscopic = -w.cross(&iw);
-                    self.acceleration.angular = self.inv_augmented_mass.angular * gyroscopic;
-                }
-
-                if self.inv_augmented_mass.linear != N::zero() && self.gravity_enabled {
-                    self.acceleration.linear = *gravity;
-                }
-
-                self.acceleration += self.inv_augmented_mass * self.external_forces;
-                self.acceleration
-                    .as_vector_mut()
-                    .component_mul_assign(&self.jacobian_mask);
-            }
-            _ => {}
-        }
+        self.acceleration = self.compute_acceleration(gravity);
     }
 
     #[inline]
@@ -749,6 +986,7 @@ impl<N: RealField> Body<N> for RigidBody<N> {
         self.update_status.set_local_inertia_changed(true);
 
         let mass_sum = self.inertia.linear + inertia.linear;
+        let old_local_com = self.local_com;
 
         // Update center of mass.
         if !mass_sum.is_zero() {
@@ -760,8 +998,12 @@ impl<N: RealField> Body<N> for RigidBody<N> {
             self.com = self.position.translation.vector.into();
         }
 
-        // Update local inertia.
-        self.local_inertia += inertia;
+        // Update local inertia, expressing both terms wrt. the new center of mass. This keeps
+        // the result exact when a collider is removed, i.e., when `inertia` is negative.
+        self.local_inertia = self
+            .local_inertia
+            .shifted(&(old_local_com - self.local_com))
+            + inertia.shifted(&(com - self.local_com));
         self.update_inertia_from_local_inertia();
     }
 
@@ -949,6 +1191,7 @@ impl<N: RealField> BodyPart<N> for RigidBody<N> {
 pub struct RigidBodyDesc<N: RealField> {
     user_data: Option<UserDataBox>,
     gravity_enabled: bool,
+    gravity_scale: N,
     linear_motion_interpolation_enabled: bool,
     position: Isometry<N>,
     velocity: Velocity<N>,
@@ -973,6 +1216,7 @@ impl<'a, N: RealField> RigidBodyDesc<N> {
         RigidBodyDesc {
             user_data: None,
             gravity_enabled: true,
+            gravity_scale: N::one(),
             linear_motion_interpolation_enabled: false,
             position: Isometry::identity(),
             velocity: Velocity::zero(),
@@ -999,6 +1243,7 @@ impl<'a, N: RealField> RigidBodyDesc<N> {
         self.rotation, set_rotation, axisangle: Vector<N> | { self.position.rotation = Rotation::new(axisangle) }
         self.kinematic_rotations, set_rotations_kinematic, kinematic_rotations: Vector<bool> | { self.kinematic_rotations = kinematic_rotations }
         self.angular_inertia, set_angular_inertia, angular_inertia: na::Matrix3<N> | { self.local_inertia.angular = angular_inertia }
+        self.angular_velocity, set_angular_velocity, angvel: Vector<N> | { self.velocity.angular = angvel }
     );
 
     #[cfg(feature = "dim2")]
@@ -1006,15 +1251,18 @@ impl<'a, N: RealField> RigidBodyDesc<N> {
         self.rotation, set_rotation, angle: N | { self.position.rotation = Rotation::new(angle) }
         self.kinematic_rotations, set_rotations_kinematic, is_kinematic: bool | { self.kinematic_rotations = is_kinematic }
         self.angular_inertia, set_angular_inertia, angular_inertia: N | { self.local_inertia.angular = angular_inertia }
+        self.angular_velocity, set_angular_velocity, angvel: N | { self.velocity.angular = angvel }
     );
 
     desc_custom_setters!(
         self.translation, set_translation, vector: Vector<N> | { self.position.translation.vector = vector }
         self.mass, set_mass, mass: N | { self.local_inertia.linear = mass }
+        self.linear_velocity, set_linear_velocity, linvel: Vector<N> | { self.velocity.linear = linvel }
     );
 
     desc_setters!(
         gravity_enabled, enable_gravity, gravity_enabled: bool
+        gravity_scale, set_gravity_scale, gravity_scale: N
         linear_motion_interpolation_enabled, enable_linear_motion_interpolation, linear_motion_interpolation_enabled: bool
         status, set_status, status: BodyStatus
         position, set_position, position: Isometry<N>
@@ -1034,6 +1282,7 @@ impl<'a, N: RealField> RigidBodyDesc<N> {
         self.get_rotation: Vector<N> | { self.position.rotation.scaled_axis() }
         self.get_kinematic_rotations: Vector<bool> | { self.kinematic_rotations }
         self.get_angular_inertia: &na::Matrix3<N> | { &self.local_inertia.angular }
+        self.get_angular_velocity: &Vector<N> | { &self.velocity.angular }
     );
 
     #[cfg(feature = "dim2")]
@@ -1041,15 +1290,18 @@ impl<'a, N: RealField> RigidBodyDesc<N> {
         self.get_rotation: N | { self.position.rotation.angle() }
         self.get_kinematic_rotations: bool | { self.kinematic_rotations }
         self.get_angular_inertia: N | { self.local_inertia.angular }
+        self.get_angular_velocity: N | { self.velocity.angular }
     );
 
     desc_custom_getters!(
         self.get_translation: &Vector<N> | { &self.position.translation.vector }
         self.get_mass: N | { self.local_inertia.linear }
+        self.get_linear_velocity: &Vector<N> | { &self.velocity.linear }
     );
 
     desc_getters!(
         [val] is_gravity_enabled -> gravity_enabled: bool
+        [val] get_gravity_scale -> gravity_scale: N
         [val] is_linear_motion_interpolation_enabled -> linear_motion_interpolation_enabled: bool
         [val] get_status -> status: BodyStatus
         [val] get_sleep_threshold -> sleep_threshold: Option<N>
@@ -1067,6 +1319,7 @@ impl<'a, N: RealField> RigidBodyDesc<N> {
     pub fn build(&self) -> RigidBody<N> {
         let mut rb = RigidBody::new(self.position);
         rb.enable_linear_motion_interpolation(self.linear_motion_interpolation_enabled);
+        // Rigid bodies are created awake so the initial velocity is integrated from the first timestep.
         rb.set_velocity(self.velocity);
         rb.set_local_inertia(self.local_inertia);
         rb.set_local_center_of_mass(self.local_center_of_mass);
@@ -1074,6 +1327,7 @@ impl<'a, N: RealField> RigidBodyDesc<N> {
         rb.set_deactivation_threshold(self.sleep_threshold);
         rb.set_translations_kinematic(self.kinematic_translations);
         rb.enable_gravity(self.gravity_enabled);
+        rb.set_gravity_scale(self.gravity_scale);
         rb.set_linear_damping(self.linear_damping);
         rb.set_angular_damping(self.angular_damping);
         rb.set_max_linear_velocity(self.max_linear_velocity);
@@ -1084,3 +1338,49 @@ impl<'a, N: RealField> RigidBodyDesc<N> {
         rb
     }
 }
+
+#[cfg(test)]
+mod test {
+    use ncollide::shape::{Ball, ShapeHandle};
+
+    use super::RigidBodyDesc;
+    use crate::analytic_tests::{assert_relative_error, Scenario};
+    use crate::math::{Point, Vector};
+    use crate::object::{BodyPart, BodyPartHandle, ColliderDesc};
+    use crate::volumetric::Volumetric;
+
+    #[test]
+    fn removing_a_collider_updates_the_mass_properties() {
+        let mut scenario = Scenario::new(1.0 / 60.0);
+
+        let desc = RigidBodyDesc::new().gravity_enabled(false);
+        let body = scenario.bodies.insert(desc.build());
+        let ball = Ball::new(0.5);
+        let offset = Vector::x() * 2.0;
+        let left = ColliderDesc::new(ShapeHandle::new(ball))
+            .density(1.0)
+            .translation(-offset)
+            .build(BodyPartHandle(body, 0));
+        let right = ColliderDesc::new(ShapeHandle::new(ball))
+            .density(1.0)
+            .translation(offset)
+            .build(BodyPartHandle(body, 0));
+        let left = scenario.colliders.insert(left);
+        let _ = scenario.colliders.insert(right);
+
+        scenario.step();
+        let com = scenario.rigid_body(body).local_center_of_mass();
+        assert!(com.coords.norm() < 1.0e-6);
+
+        let _ = scenario.colliders.remove(left);
+        scenario.step();
+
+        // Only the right ball remains, so its own mass properties are the ones of the body.
+        let expected = ball.inertia(1.0);
+        let rb = scenario.rigid_body(body);
+        let inertia = rb.local_inertia();
+        assert!((rb.local_center_of_mass() - Point::from(offset)).norm() < 1.0e-6);
+        assert_relative_error(inertia.mass(), expected.mass(), 1.0e-6);
+        assert!((inertia.angular_matrix() - expected.angular_matrix()).norm() < 1.0e-6);
+    }
+}
@@ -0,0 +1,79 @@
+use na::RealField;
+
+use crate::object::{
+    ColliderAnchor, DefaultBodyHandle, DefaultBodySet, DefaultColliderHandle, DefaultColliderSet,
+};
+use crate::volumetric::Volumetric;
+
+/// The new handles of a body and its colliders after they have been moved by `transfer_body`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BodyTransfer {
+    /// The handle of the body in the destination body set.
+    pub body: DefaultBodyHandle,
+    /// The handles of the colliders attached to the body, in the source and destination collider sets.
+    pub colliders: Vec<(DefaultColliderHandle, DefaultColliderHandle)>,
+}
+
+/// Moves a body and all the colliders attached to it from a pair of body and collider sets to another.
+///
+/// The body is moved as-is, preserving its position, velocity, sleeping state, and mass
+/// properties. Its colliders are re-attached to the moved body. The next step of the source
+/// world removes the body from its broad phase and joint constraints attached to it, exactly as
+/// if it had been removed from `src_bodies`, and the next step of the destination world registers
+/// its colliders into its broad phase. Joint constraints are not transferred.
+///
+/// Returns `None` if the body does not exist in `src_bodies`.
+pub fn transfer_body<N: RealField>(
+    handle: DefaultBodyHandle,
+    src_bodies: &mut DefaultBodySet<N>,
+    src_colliders: &mut DefaultColliderSet<N>,
+    dst_bodies: &mut DefaultBodySet<N>,
+    dst_colliders: &mut DefaultColliderSet<N>,
+) -> Option<BodyTransfer> {
+    let mut body = src_bodies.remove(handle)?;
+    let collider_handles: Vec<_> = src_colliders
+        .iter()
+        .filter(|(_, collider)| collider.body() == handle)
+        .map(|(collider_handle, _)| collider_handle)
+        .collect();
+    let mut colliders = Vec::with_capacity(collider_handles.len());
+
+    for collider_handle in collider_handles {
+        let collider = src_colliders
+            .remove(collider_handle)
+            .expect("Invalid collider handle.");
+
+        // The mass of the collider is added back to the body when the destination world
+        // registers the collider.
+        if collider.density() != N::zero() {
+            if let ColliderAnchor::OnBodyPart {
+                body_part,
+                position_wrt_body_part,
+            } = collider.anchor()
+            {
+                let (com, inertia) = collider
+                    .shape()
+                    .transformed_mass_properties(collider.density(), position_wrt_body_part);
+                body.add_local_inertia_and_com(body_part.1, com, -inertia)
+            }
+        }
+
+        colliders.push((collider_handle, collider));
+    }
+
+    let new_handle = dst_bodies.insert_boxed(body);
+    let colliders = colliders
+        .into_iter()
+        .map(|(old_handle, mut collider)| {
+            collider.set_anchor_body(new_handle);
+            collider.set_proxy_handle(None);
+            collider.set_graph_index(None);
+            (old_handle, dst_colliders.insert(collider))
+        })
+        .collect();
+
+    Some(BodyTransfer {
+        body: new_handle,
+        colliders,
+    })
+}
@@ -45,6 +45,8 @@ impl<N: RealField> ConstraintGeometry<N> {
 pub struct UnilateralConstraint<N: RealField, Id> {
     /// The impulse applied by this constraint.
     pub impulse: N,
+    /// The maximum impulse this constraint can apply.
+    pub max_impulse: N,
 
     /// The scaling parameter of the SOR-prox method.
     pub r: N,
@@ -89,6 +91,7 @@ impl<N: RealField, Id> UnilateralConstraint<N, Id> {
         assert!(geom.ndofs1 != 0 && geom.ndofs2 != 0);
         UnilateralConstraint {
             impulse,
+            max_impulse: N::max_value(),
             r: geom.r,
             rhs,
             impulse_id,
@@ -102,12 +105,21 @@ impl<N: RealField, Id> UnilateralConstraint<N, Id> {
             ndofs2: geom.ndofs2,
         }
     }
+
+    /// Limits the impulse this constraint can apply.
+    #[inline]
+    pub fn with_max_impulse(mut self, max_impulse: N) -> Self {
+        self.max_impulse = max_impulse;
+        self
+    }
 }
 
 /// A unilateral (inequality) constraint between a dynamic body and one without any degrees of freedom.
 pub struct UnilateralGroundConstraint<N: RealField, Id> {
     /// The impulse applied by the constraint.
     pub impulse: N,
+    /// The maximum impulse this constraint can apply.
+    pub max_impulse: N,
 
     /// The scaling parameter used by the SOR-prox method.
     pub r: N,
@@ -140,6 +152,7 @@ impl<N: RealField, Id> UnilateralGroundConstraint<N, Id> {
         if geom.ndofs1 == 0 {
             UnilateralGroundConstraint {
                 impulse,
+                max_impulse: N::max_value(),
                 r: geom.r,
                 rhs,
                 impulse_id,
@@ -151,6 +164,7 @@ impl<N: RealField, Id> UnilateralGroundConstraint<N, Id> {
         } else {
             UnilateralGroundConstraint {
                 impulse,
+                max_impulse: N::max_value(),
                 r: geom.r,
                 rhs,
                 impulse_id,
@@ -161,6 +175,13 @@ impl<N: RealField, Id> UnilateralGroundConstraint<N, Id> {
             }
         }
     }
+
+    /// Limits the impulse this constraint can apply.
+    #[inline]
+    pub fn with_max_impulse(mut self, max_impulse: N) -> Self {
+        self.max_impulse = max_impulse;
+        self
+    }
 }
 
 /// Limits of impulse applicable by a bilateral constraint.
@@ -191,6 +212,8 @@ pub struct BilateralConstraint<N: RealField, Id> {
     pub r: N,
     /// The target velocity change this constraint must apply.
     pub rhs: N,
+    /// The constraint force mixing softening this constraint, added to the one of the solver.
+    pub cfm: N,
 
     /// Limits of impulse applicable by this constraint.
     pub limits: ImpulseLimits<N>,
@@ -236,6 +259,7 @@ impl<N: RealField, Id> BilateralConstraint<N, Id> {
             impulse,
             r: geom.r,
             rhs,
+            cfm: N::zero(),
             limits,
             impulse_id,
             assembly_id1,
@@ -248,6 +272,13 @@ impl<N: RealField, Id> BilateralConstraint<N, Id> {
             ndofs2: geom.ndofs2,
         }
     }
+
+    /// Softens this constraint with the given constraint force mixing.
+    #[inline]
+    pub fn with_cfm(mut self, cfm: N) -> Self {
+        self.cfm = cfm;
+        self
+    }
 }
 
 /// A bilateral (equality) constraint between a dynamic body and one without any degrees of freedom.
@@ -259,6 +290,8 @@ pub struct BilateralGroundConstraint<N: RealField, Id> {
     pub r: N,
     /// The target velocity change this constraint must apply.
     pub rhs: N,
+    /// The constraint force mixing softening this constraint, added to the one of the solver.
+    pub cfm: N,
 
     /// Limits of impulse applicable by this constraint.
     pub limits: ImpulseLimits<N>,
@@ -292,6 +325,7 @@ impl<N: RealField, Id> BilateralGroundConstraint<N, Id> {
                 impulse,
                 r: geom.r,
                 rhs,
+                cfm: N::zero(),
                 limits,
                 impulse_id,
                 assembly_id: assembly_id2,
@@ -304,6 +338,7 @@ impl<N: RealField, Id> BilateralGroundConstraint<N, Id> {
                 impulse,
                 r: geom.r,
                 rhs,
+                cfm: N::zero(),
                 limits,
                 impulse_id,
                 assembly_id: assembly_id1,
@@ -313,4 +348,11 @@ impl<N: RealField, Id> BilateralGroundConstraint<N, Id> {
             }
         }
     }
+
+    /// Softens this constraint with the given constraint force mixing.
+    #[inline]
+    pub fn with_cfm(mut self, cfm: N) -> Self {
+        self.cfm = cfm;
+        self
+    }
 }
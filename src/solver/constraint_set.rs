@@ -43,6 +43,25 @@ impl<N: RealField, Id> LinearConstraints<N, Id> {
         self.bilateral_ground.clear();
         self.bilateral.clear();
     }
+
+    /// Multiplies the initial impulse of every constraint on this set by `coeff`.
+    pub fn scale_impulses(&mut self, coeff: N) {
+        for c in &mut self.unilateral_ground {
+            c.impulse *= coeff;
+        }
+
+        for c in &mut self.unilateral {
+            c.impulse *= coeff;
+        }
+
+        for c in &mut self.bilateral_ground {
+            c.impulse *= coeff;
+        }
+
+        for c in &mut self.bilateral {
+            c.impulse *= coeff;
+        }
+    }
 }
 
 /// Set of non-linear position-based constraints.
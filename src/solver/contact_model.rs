@@ -1,14 +1,47 @@
 #![allow(missing_docs)]
 
 use downcast_rs::Downcast;
-use na::{DVector, RealField};
-use ncollide::query::ContactId;
+use na::{self, DVector, RealField};
+use ncollide::query::{ContactId, TrackedContact};
 
 use crate::detection::ColliderContactManifold;
-use crate::material::MaterialsCoefficientsTable;
+use crate::material::{LocalMaterialProperties, MaterialsCoefficientsTable};
 use crate::object::{BodyHandle, BodySet, ColliderHandle};
 use crate::solver::{ConstraintSet, IntegrationParameters};
 
+/// The impulses applied by the constraints solver at a contact during the last step.
+///
+/// All the impulses are expressed in the same unit, i.e., force times time. The tangent impulses
+/// are the friction impulses along the orthonormal basis of the plane orthogonal to the contact
+/// normal.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ContactImpulse<N: RealField> {
+    /// The impulse along the contact normal.
+    pub normal: N,
+    /// The friction impulse along the first tangent direction.
+    pub tangent1: N,
+    /// The friction impulse along the second tangent direction.
+    #[cfg(feature = "dim3")]
+    pub tangent2: N,
+    /// The maximum magnitude of each tangent impulse allowed by the friction coefficient.
+    ///
+    /// This is the product of the friction coefficient and the normal impulse.
+    pub friction_limit: N,
+}
+
+impl<N: RealField> ContactImpulse<N> {
+    /// Whether the friction impulse reached its limit, i.e., the contact is slipping instead of sticking.
+    pub fn is_slipping(&self) -> bool {
+        // Allow for the normal impulse being updated after the friction impulses were clamped.
+        let limit = self.friction_limit * na::convert(0.99f64);
+
+        #[cfg(feature = "dim2")]
+        return self.tangent1.abs() >= limit;
+        #[cfg(feature = "dim3")]
+        return self.tangent1.abs() >= limit || self.tangent2.abs() >= limit;
+    }
+}
+
 /// The modeling of a contact.
 pub trait ContactModel<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>:
     Downcast + Send + Sync
@@ -32,8 +65,87 @@ pub trait ContactModel<N: RealField, Handle: BodyHandle, CollHandle: ColliderHan
         constraints: &mut ConstraintSet<N, Handle, CollHandle, ContactId>,
     );
 
+    /// Generate all constraints for the given contact manifolds, after the material properties of
+    /// each contact are modified by `material_modifier`.
+    ///
+    /// This is the method called by the solver. By default, the modifier is ignored and the
+    /// constraints are generated by `self.constraints`.
+    fn constraints_with_material_modifier(
+        &mut self,
+        parameters: &IntegrationParameters<N>,
+        material_coefficients: &MaterialsCoefficientsTable<N>,
+        _material_modifier: Option<&dyn ContactMaterialModifier<N, Handle, CollHandle>>,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        ext_vels: &DVector<N>,
+        manifolds: &[ColliderContactManifold<N, Handle, CollHandle>],
+        ground_j_id: &mut usize,
+        j_id: &mut usize,
+        jacobians: &mut [N],
+        constraints: &mut ConstraintSet<N, Handle, CollHandle, ContactId>,
+    ) {
+        self.constraints(
+            parameters,
+            material_coefficients,
+            bodies,
+            ext_vels,
+            manifolds,
+            ground_j_id,
+            j_id,
+            jacobians,
+            constraints,
+        )
+    }
+
     /// Stores all the impulses found by the solver into a cache for warmstarting.
     fn cache_impulses(&mut self, constraints: &ConstraintSet<N, Handle, CollHandle, ContactId>);
+
+    /// The impulses applied at the given contact during the last step, if this contact model keeps track of them.
+    fn contact_impulse(&self, _contact: ContactId) -> Option<ContactImpulse<N>> {
+        None
+    }
 }
 
 impl_downcast!(ContactModel<N, Handle, CollHandle> where N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle);
+
+/// A hook for overriding the material properties of each contact right before they are used by the solver.
+///
+/// This is useful when the friction or restitution coefficients depend on a dynamic state that
+/// is not captured by the colliders materials, e.g., a surface becoming slippery when wet.
+/// Setting the `target_normal_velocity` of the material properties of a contact only affects
+/// that contact, so it can be used to implement bumpers or boost pads without altering the
+/// other contacts of the same bodies.
+pub trait ContactMaterialModifier<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>:
+    Send + Sync
+{
+    /// Modifies the material properties resulting from the combination of the materials of both colliders of a contact.
+    ///
+    /// The colliders involved in the contact, and their handles, are given by `manifold`.
+    fn modify_material(
+        &self,
+        manifold: &ColliderContactManifold<N, Handle, CollHandle>,
+        contact: &TrackedContact<N>,
+        material: &mut LocalMaterialProperties<N>,
+    );
+}
+
+impl<N, Handle, CollHandle, F> ContactMaterialModifier<N, Handle, CollHandle> for F
+where
+    N: RealField,
+    Handle: BodyHandle,
+    CollHandle: ColliderHandle,
+    F: Fn(
+            &ColliderContactManifold<N, Handle, CollHandle>,
+            &TrackedContact<N>,
+            &mut LocalMaterialProperties<N>,
+        ) + Send
+        + Sync,
+{
+    fn modify_material(
+        &self,
+        manifold: &ColliderContactManifold<N, Handle, CollHandle>,
+        contact: &TrackedContact<N>,
+        material: &mut LocalMaterialProperties<N>,
+    ) {
+        self(manifold, contact, material)
+    }
+}
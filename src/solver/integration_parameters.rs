@@ -1,5 +1,7 @@
 use na::{self, RealField};
 
+use crate::detection::ContactReduction;
+
 /// Parameters for a time-step of the physics engine.
 #[derive(Clone)]
 pub struct IntegrationParameters<N: RealField> {
@@ -17,27 +19,96 @@ pub struct IntegrationParameters<N: RealField> {
     /// The Error Reduction Parameter in `[0, 1]` is the proportion of
     /// the positional error to be corrected at each time step (default: `0.2`).
     pub erp: N,
+    /// The Error Reduction Parameter used to correct the penetration of contacts (default: `None`).
+    ///
+    /// If `None`, contacts use `self.erp`. Lower values yield softer contacts that take more steps
+    /// to push penetrating bodies apart, higher values correct penetrations faster at the cost of
+    /// some jitter.
+    pub contact_erp: Option<N>,
+    /// The Error Reduction Parameter used to correct the positional drift of joints (default: `None`).
+    ///
+    /// If `None`, joints (including the joint limits of multibodies) use `self.erp`.
+    pub joint_erp: Option<N>,
+    /// The Constraint Force Mixing coefficient of contacts (default: `0.0`).
+    ///
+    /// A non-negative value that softens the velocity constraints of contacts: the larger it is,
+    /// the more the contacts behave like springs instead of rigid constraints. Zero means the
+    /// contacts are perfectly stiff.
+    pub contact_cfm: N,
+    /// The Constraint Force Mixing coefficient of joints (default: `0.0`).
+    ///
+    /// A non-negative value that softens the velocity constraints of constraint-based joints.
+    /// Zero means the joints are perfectly stiff.
+    pub joint_cfm: N,
     /// Each cached impulse are multiplied by this coefficient in `[0, 1]`
     /// when they are re-used to initialize the solver (default `1.0`).
+    ///
+    /// This applies to the impulses of both contacts and joints. Setting it to `0.0` disables
+    /// warmstarting, i.e., each step is solved starting from zero impulses.
     pub warmstart_coeff: N,
     /// Contacts at points where the involved bodies have a relative
     /// velocity smaller than this threshold wont be affected by the restitution force (default: `1.0`).
     pub restitution_velocity_threshold: N,
+    /// Distance at which contacts are generated before the colliders actually touch (default: `0.0`).
+    ///
+    /// Contacts between colliders separated by less than this distance (in addition to their margins)
+    /// are handled speculatively by the default contact model: they are solved with a target
+    /// separation of zero so fast approaching bodies are stopped right at the contact instead of
+    /// sinking into a penetration. The narrow phase uses this distance for the colliders with a
+    /// smaller prediction distance, without modifying them, so the broad-phase margin should be at
+    /// least half this distance (see `GeometricalWorld::set_contact_prediction_distance`). Keep this
+    /// to zero to use the prediction distance of each collider as-is.
+    pub contact_prediction_distance: N,
     /// Ammount of penetration the engine wont attempt to correct (default: `0.001m`).
     pub allowed_linear_error: N,
     /// Ammount of angular drift of joint limits the engine wont
     /// attempt to correct (default: `0.001rad`).
     pub allowed_angular_error: N,
     /// Maximum linear correction during one step of the non-linear position solver (default: `0.2`).
+    ///
+    /// This caps the distance a constraint is de-penetrated by each iteration of the position
+    /// solver, so the total correction during one timestep is at most this value multiplied by
+    /// `self.max_position_iterations`. To cap the distance penetrating colliders are moved apart
+    /// during a whole timestep instead, e.g., so deeply overlapping bodies separate gradually and
+    /// predictably, set `self.max_penetration_correction_velocity`.
     pub max_linear_correction: N,
     /// Maximum angular correction during one step of the non-linear position solver (default: `0.2`).
+    ///
+    /// Like `self.max_linear_correction`, this applies to each iteration of the position solver.
+    /// Only joints are corrected angularly: the position correction of contacts is linear, and
+    /// is capped per timestep by `self.max_penetration_correction_velocity`.
     pub max_angular_correction: N,
+    /// Maximum velocity at which penetrations between colliders are corrected (default: `None`).
+    ///
+    /// If set, the non-linear position solver moves penetrating colliders apart by at most
+    /// `max_penetration_correction_velocity * dt` during one step, so bodies spawned inside
+    /// each other are gently pushed apart over several steps. This is the per-step cap of the
+    /// position-level de-penetration: the correction is split evenly between the iterations of
+    /// the position solver. Use `None` to only limit the correction with
+    /// `self.max_linear_correction`.
+    pub max_penetration_correction_velocity: Option<N>,
     /// Maximum nonlinear SOR-prox scaling parameter when the constraint
     /// correction direction is close to the kernel of the involved multibody's
     /// jacobian (default: `0.2`).
     pub max_stabilization_multiplier: N,
+    /// Maximum number of contacts of each contact manifold given to the constraints solver (default: `None`).
+    ///
+    /// If a contact manifold has more contacts, only `max_contacts_per_manifold` of them, selected
+    /// with `self.contact_reduction`, are taken into account by the solver and exposed to the
+    /// `pre_solve` hook. Use `None` to keep every contact generated by the narrow phase.
+    pub max_contacts_per_manifold: Option<usize>,
+    /// The strategy used to select the contacts kept when a contact manifold has more than
+    /// `self.max_contacts_per_manifold` contacts (default: `ContactReduction::MaximumArea`).
+    pub contact_reduction: ContactReduction,
     /// Maximum number of iterations performed by the velocity constraints solver (default: `8`).
     pub max_velocity_iterations: usize,
+    /// Whether the velocity constraints solver alternates the order it goes through the constraints (default: `false`).
+    ///
+    /// If `true`, every other iteration of the velocity constraints solver processes the
+    /// constraints in reverse order (symmetric Gauss-Seidel). This removes the bias due to the
+    /// order of the constraints, e.g., a symmetric stack of boxes slowly drifting sideways, at the
+    /// cost of a slightly slower convergence for an even number of iterations.
+    pub symmetric_velocity_solver: bool,
     /// Maximum number of iterations performed by the position-based constraints solver (default: `3`).
     pub max_position_iterations: usize,
     /// Maximum number of iterations performed by the position-based constraints solver for CCD steps (default: `10`).
@@ -104,14 +175,23 @@ impl<N: RealField> IntegrationParameters<N> {
                 N::one() / dt
             },
             erp,
+            contact_erp: None,
+            joint_erp: None,
+            contact_cfm: N::zero(),
+            joint_cfm: N::zero(),
             warmstart_coeff,
             restitution_velocity_threshold,
+            contact_prediction_distance: N::zero(),
             allowed_linear_error,
             allowed_angular_error,
             max_linear_correction,
             max_angular_correction,
+            max_penetration_correction_velocity: None,
             max_stabilization_multiplier,
             max_velocity_iterations,
+            symmetric_velocity_solver: false,
+            max_contacts_per_manifold: None,
+            contact_reduction: ContactReduction::MaximumArea,
             max_position_iterations,
             max_ccd_position_iterations,
             max_ccd_substeps,
@@ -137,7 +217,9 @@ impl<N: RealField> IntegrationParameters<N> {
 
     /// Sets the time-stepping length.
     ///
-    /// This automatically recompute `self.inv_dt`.
+    /// This automatically recompute `self.inv_dt`. Use `MechanicalWorld::set_timestep` instead to
+    /// change the timestep of a running simulation, so the impulses used by the next step for
+    /// warmstarting are rescaled consistently.
     #[inline]
     pub fn set_dt(&mut self, dt: N) {
         assert!(
@@ -5,7 +5,7 @@ pub use self::constraint::{
     UnilateralConstraint, UnilateralGroundConstraint,
 };
 pub use self::constraint_set::{ConstraintSet, LinearConstraints};
-pub use self::contact_model::ContactModel;
+pub use self::contact_model::{ContactImpulse, ContactMaterialModifier, ContactModel};
 pub use self::helper::ForceDirection;
 pub use self::impulse_cache::ImpulseCache;
 pub use self::integration_parameters::IntegrationParameters;
@@ -7,7 +7,8 @@ use crate::joint::{JointConstraint, JointConstraintSet};
 use crate::material::MaterialsCoefficientsTable;
 use crate::object::{BodyHandle, BodySet, ColliderHandle, ColliderSet};
 use crate::solver::{
-    ConstraintSet, ContactModel, IntegrationParameters, NonlinearSORProx, SORProx,
+    ConstraintSet, ContactImpulse, ContactMaterialModifier, ContactModel, IntegrationParameters,
+    NonlinearSORProx, SORProx,
 };
 
 /// Moreau-Jean time-stepping scheme.
@@ -17,9 +18,11 @@ pub struct MoreauJeanSolver<N: RealField, Handle: BodyHandle, CollHandle: Collid
     mj_lambda_vel: DVector<N>,
     ext_vels: DVector<N>,
     contact_model: Box<dyn ContactModel<N, Handle, CollHandle>>,
+    material_modifier: Option<Box<dyn ContactMaterialModifier<N, Handle, CollHandle>>>,
     contact_constraints: ConstraintSet<N, Handle, CollHandle, ContactId>,
     joint_constraints: ConstraintSet<N, Handle, CollHandle, usize>,
     internal_constraints: Vec<Handle>,
+    velocity_residual: N,
 }
 
 impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
@@ -32,9 +35,11 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
             mj_lambda_vel: DVector::zeros(0),
             ext_vels: DVector::zeros(0),
             contact_model,
+            material_modifier: None,
             contact_constraints: ConstraintSet::new(),
             joint_constraints: ConstraintSet::new(),
             internal_constraints: Vec::new(),
+            velocity_residual: N::zero(),
         }
     }
 
@@ -43,6 +48,34 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         self.contact_model = model
     }
 
+    /// The impulses applied at the given contact during the last step.
+    ///
+    /// Returns `None` if the contact was not processed by the solver, or if the contact model
+    /// does not keep track of its impulses.
+    pub fn contact_impulse(&self, contact: ContactId) -> Option<ContactImpulse<N>> {
+        self.contact_model.contact_impulse(contact)
+    }
+
+    /// The largest change of a generalized velocity during the last iteration of the velocity solver.
+    ///
+    /// This is measured during the last step, and is close to zero if the velocity constraints
+    /// converged. Larger values indicate that more velocity iterations would change the result.
+    pub fn last_velocity_residual(&self) -> N {
+        self.velocity_residual
+    }
+
+    /// Sets the hook called to override the material properties of each contact before they are used by the contact model.
+    ///
+    /// Set to `None` to use the combined collider materials unmodified. The built-in contact
+    /// models apply the modifier, but custom ones ignore it unless they implement
+    /// `ContactModel::constraints_with_material_modifier`.
+    pub fn set_material_modifier(
+        &mut self,
+        modifier: Option<Box<dyn ContactMaterialModifier<N, Handle, CollHandle>>>,
+    ) {
+        self.material_modifier = modifier
+    }
+
     /// Perform one step of the time-stepping scheme.
     pub fn step<
         Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
@@ -76,7 +109,7 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         );
 
         counters.velocity_resolution_started();
-        self.solve_velocity_constraints(parameters, bodies);
+        self.velocity_residual = self.solve_velocity_constraints(parameters, bodies);
         self.cache_impulses(parameters, bodies, joints, island_joints);
         counters.velocity_resolution_completed();
 
@@ -122,7 +155,7 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
             bodies.get_mut(*ccd_body).unwrap().validate_advancement();
         }
 
-        self.solve_velocity_constraints(parameters, bodies);
+        let _ = self.solve_velocity_constraints(parameters, bodies);
         self.update_velocities_and_integrate(parameters, bodies, island);
     }
 
@@ -237,10 +270,16 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
             }
         }
 
+        // The joints initialize their constraints with the impulses from the last step.
+        self.joint_constraints
+            .velocity
+            .scale_impulses(parameters.warmstart_coeff);
+
         counters.custom_started();
-        self.contact_model.constraints(
+        self.contact_model.constraints_with_material_modifier(
             parameters,
             coefficients,
+            self.material_modifier.as_ref().map(|m| &**m),
             bodies,
             &self.ext_vels,
             manifolds,
@@ -263,7 +302,7 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         &mut self,
         parameters: &IntegrationParameters<N>,
         bodies: &mut dyn BodySet<N, Handle = Handle>,
-    ) {
+    ) -> N {
         SORProx::solve(
             bodies,
             &mut self.contact_constraints.velocity,
@@ -272,7 +311,10 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
             &mut self.mj_lambda_vel,
             &self.jacobians,
             parameters.max_velocity_iterations,
-        );
+            parameters.symmetric_velocity_solver,
+            parameters.contact_cfm,
+            parameters.joint_cfm,
+        )
     }
 
     fn solve_position_constraints<
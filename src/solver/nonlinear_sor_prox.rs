@@ -84,7 +84,12 @@ impl NonlinearSORProx {
         let dim1 = Dynamic::new(constraint.dim1);
         let dim2 = Dynamic::new(constraint.dim2);
 
-        let rhs = Self::clamp_rhs(constraint.rhs, constraint.is_angular, parameters);
+        let rhs = Self::clamp_rhs(
+            constraint.rhs,
+            constraint.is_angular,
+            parameters.joint_erp.unwrap_or(parameters.erp),
+            parameters,
+        );
 
         if rhs < N::zero() {
             let impulse = -rhs * constraint.r;
@@ -227,7 +232,18 @@ impl NonlinearSORProx {
             coords2,
             &constraint.normal1,
         ) {
-            constraint.rhs = Self::clamp_rhs(-contact.depth, false, parameters);
+            constraint.rhs = Self::clamp_rhs(
+                -contact.depth,
+                false,
+                parameters.contact_erp.unwrap_or(parameters.erp),
+                parameters,
+            );
+
+            if let Some(max_velocity) = parameters.max_penetration_correction_velocity {
+                let iterations: N = na::convert(parameters.max_position_iterations.max(1) as f64);
+                let max_correction = max_velocity * parameters.dt() / iterations;
+                constraint.rhs = constraint.rhs.max(-max_correction);
+            }
 
             if constraint.rhs >= N::zero() {
                 return false;
@@ -297,14 +313,13 @@ impl NonlinearSORProx {
     pub fn clamp_rhs<N: RealField>(
         rhs: N,
         is_angular: bool,
+        erp: N,
         parameters: &IntegrationParameters<N>,
     ) -> N {
         if is_angular {
-            ((rhs + parameters.allowed_angular_error) * parameters.erp)
-                .max(-parameters.max_angular_correction)
+            ((rhs + parameters.allowed_angular_error) * erp).max(-parameters.max_angular_correction)
         } else {
-            ((rhs + parameters.allowed_linear_error) * parameters.erp)
-                .max(-parameters.max_linear_correction)
+            ((rhs + parameters.allowed_linear_error) * erp).max(-parameters.max_linear_correction)
         }
     }
 }
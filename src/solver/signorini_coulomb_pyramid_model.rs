@@ -9,8 +9,9 @@ use crate::math::{Vector, DIM};
 use crate::object::{BodyHandle, BodySet, ColliderHandle};
 use crate::solver::helper;
 use crate::solver::{
-    BilateralConstraint, BilateralGroundConstraint, ConstraintSet, ContactModel, ForceDirection,
-    ImpulseCache, ImpulseLimits, IntegrationParameters, SignoriniModel,
+    BilateralConstraint, BilateralGroundConstraint, ConstraintSet, ContactImpulse,
+    ContactMaterialModifier, ContactModel, ForceDirection, ImpulseCache, ImpulseLimits,
+    IntegrationParameters, SignoriniModel,
 };
 
 /// A contact model generating one non-penetration constraint and two friction constraints per contact.
@@ -18,6 +19,7 @@ use crate::solver::{
 /// This contact model approximates the friction cone at a contact with pyramid.
 pub struct SignoriniCoulombPyramidModel<N: RealField> {
     impulses: ImpulseCache<Vector<N>>,
+    friction_coefficients: ImpulseCache<N>,
     vel_ground_rng: Range<usize>,
     vel_rng: Range<usize>,
     friction_ground_rng: Range<usize>,
@@ -29,6 +31,7 @@ impl<N: RealField> SignoriniCoulombPyramidModel<N> {
     pub fn new() -> Self {
         SignoriniCoulombPyramidModel {
             impulses: ImpulseCache::new(),
+            friction_coefficients: ImpulseCache::new(),
             vel_ground_rng: 0..0,
             vel_rng: 0..0,
             friction_ground_rng: 0..0,
@@ -64,6 +67,33 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         j_id: &mut usize,
         jacobians: &mut [N],
         constraints: &mut ConstraintSet<N, Handle, CollHandle, ContactId>,
+    ) {
+        self.constraints_with_material_modifier(
+            parameters,
+            coefficients,
+            None,
+            bodies,
+            ext_vels,
+            manifolds,
+            ground_j_id,
+            j_id,
+            jacobians,
+            constraints,
+        )
+    }
+
+    fn constraints_with_material_modifier(
+        &mut self,
+        parameters: &IntegrationParameters<N>,
+        coefficients: &MaterialsCoefficientsTable<N>,
+        material_modifier: Option<&dyn ContactMaterialModifier<N, Handle, CollHandle>>,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        ext_vels: &DVector<N>,
+        manifolds: &[ColliderContactManifold<N, Handle, CollHandle>],
+        ground_j_id: &mut usize,
+        j_id: &mut usize,
+        jacobians: &mut [N],
+        constraints: &mut ConstraintSet<N, Handle, CollHandle, ContactId>,
     ) {
         let id_vel_ground = constraints.velocity.unilateral_ground.len();
         let id_vel = constraints.velocity.unilateral.len();
@@ -94,8 +124,13 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                     c,
                     false,
                 );
-                let props =
+                let mut props =
                     Material::combine(coefficients, material1, context1, material2, context2);
+                props.max_normal_impulse = manifold.max_contact_impulse();
+
+                if let Some(modifier) = material_modifier {
+                    modifier.modify_material(manifold, c, &mut props);
+                }
 
                 // if !SignoriniModel::is_constraint_active(c, manifold) {
                 //     continue;
@@ -122,7 +157,9 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                     constraints,
                 );
 
-                SignoriniModel::build_position_constraint(bodies, manifold, c, constraints);
+                if props.max_normal_impulse.is_none() {
+                    SignoriniModel::build_position_constraint(bodies, manifold, c, constraints);
+                }
 
                 let dependency;
 
@@ -138,6 +175,7 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                 let assembly_id2 = body2.companion_id();
 
                 // Generate friction constraints.
+                let _ = self.friction_coefficients.insert(c.id, props.friction.0);
                 let limits = ImpulseLimits::Dependent {
                     dependency,
                     coeff: props.friction.0,
@@ -255,4 +293,104 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
             }
         }
     }
+
+    fn contact_impulse(&self, contact: ContactId) -> Option<ContactImpulse<N>> {
+        let impulse = self.impulses.get(contact)?;
+        let friction = self
+            .friction_coefficients
+            .get(contact)
+            .cloned()
+            .unwrap_or(N::zero());
+
+        Some(ContactImpulse {
+            normal: impulse[0],
+            tangent1: impulse[1],
+            #[cfg(feature = "dim3")]
+            tangent2: impulse[2],
+            friction_limit: friction * impulse[0],
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ncollide::shape::{Cuboid, ShapeHandle};
+
+    use crate::analytic_tests::{assert_relative_error, Scenario, GRAVITY};
+    use crate::math::Vector;
+    use crate::object::RigidBodyDesc;
+
+    /// The body carrying the floor of a scenario.
+    #[derive(Copy, Clone)]
+    enum FloorBody {
+        Ground,
+        Static,
+        Dynamic,
+    }
+
+    /// The distance traveled by a box sliding from rest during `duration` seconds down a ramp tilted by `angle`.
+    ///
+    /// The ramp is a flat floor and the gravity is tilted instead. The distance is measured relative to the floor.
+    fn ramp_sliding_distance(floor: FloorBody, angle: f64, friction: f64, duration: f64) -> f64 {
+        let mut scenario = Scenario::new(1.0 / 240.0);
+        scenario.mechanical_world.gravity =
+            (Vector::x() * angle.sin() - Vector::y() * angle.cos()) * GRAVITY;
+
+        let floor = match floor {
+            FloorBody::Ground => {
+                scenario.add_floor(0.0, friction);
+                None
+            }
+            FloorBody::Static => {
+                scenario.add_static_floor(0.0, friction);
+                None
+            }
+            FloorBody::Dynamic => Some(scenario.add_dynamic_floor(0.0, friction)),
+        };
+
+        let desc = RigidBodyDesc::new().translation(Vector::y() * 0.5);
+        let cuboid = ShapeHandle::new(Cuboid::new(Vector::repeat(0.5)));
+        let block = scenario.add_body(&desc, cuboid, 0.0, friction);
+
+        while scenario.time() < duration {
+            scenario.step();
+        }
+
+        let floor_x = floor.map_or(0.0, |floor| {
+            scenario.rigid_body(floor).position().translation.vector.x
+        });
+        scenario.rigid_body(block).position().translation.vector.x - floor_x
+    }
+
+    #[test]
+    fn box_sliding_down_ramp_on_ground_and_static_body() {
+        let angle = std::f64::consts::PI / 6.0;
+        let friction = 0.3;
+        let duration = 1.0;
+        let acceleration = GRAVITY * (angle.sin() - friction * angle.cos());
+        let expected_distance = 0.5 * acceleration * duration * duration;
+
+        let on_ground = ramp_sliding_distance(FloorBody::Ground, angle, friction, duration);
+        let on_static_body = ramp_sliding_distance(FloorBody::Static, angle, friction, duration);
+
+        assert_relative_error(on_ground, expected_distance, 0.05);
+        assert_relative_error(on_static_body, expected_distance, 0.05);
+        assert_relative_error(on_static_body, on_ground, 1.0e-6);
+    }
+
+    #[test]
+    fn box_sliding_down_ramp_on_dynamic_body() {
+        let angle = std::f64::consts::PI / 6.0;
+        let friction = 0.3;
+        let duration = 1.0;
+        let acceleration = GRAVITY * (angle.sin() - friction * angle.cos());
+        let expected_distance = 0.5 * acceleration * duration * duration;
+
+        // The floor is a million times heavier than the box, so it barely moves.
+        let on_dynamic_body = ramp_sliding_distance(FloorBody::Dynamic, angle, friction, duration);
+        let on_ground = ramp_sliding_distance(FloorBody::Ground, angle, friction, duration);
+
+        assert_relative_error(on_dynamic_body, expected_distance, 0.05);
+        assert_relative_error(on_dynamic_body, on_ground, 1.0e-3);
+    }
 }
@@ -8,8 +8,9 @@ use crate::material::{
 use crate::object::{Body, BodyHandle, BodyPart, BodyPartHandle, BodySet, ColliderHandle};
 use crate::solver::helper;
 use crate::solver::{
-    ConstraintSet, ContactModel, ForceDirection, ImpulseCache, IntegrationParameters,
-    NonlinearUnilateralConstraint, UnilateralConstraint, UnilateralGroundConstraint,
+    ConstraintSet, ContactImpulse, ContactMaterialModifier, ContactModel, ForceDirection,
+    ImpulseCache, IntegrationParameters, NonlinearUnilateralConstraint, UnilateralConstraint,
+    UnilateralGroundConstraint,
 };
 use ncollide::query::{ContactId, TrackedContact};
 use ncollide::utils::IsometryOps;
@@ -89,9 +90,15 @@ impl<N: RealField> SignoriniModel<N> {
 
         //        println!("rhs before: {}", rhs);
 
-        // Handle restitution.
-        if rhs <= -parameters.restitution_velocity_threshold {
-            rhs += props.restitution.0 * rhs;
+        // Handle restitution, unless it is overridden by a target velocity.
+        if let Some(target_normal_velocity) = props.target_normal_velocity {
+            rhs -= target_normal_velocity;
+        } else if rhs <= -parameters.restitution_velocity_threshold {
+            let restitution = props
+                .restitution_curve
+                .map(|curve| curve.restitution(-rhs))
+                .unwrap_or(props.restitution.0);
+            rhs += restitution * rhs;
         }
 
         // Handle predictive contact if no penetration.
@@ -105,33 +112,26 @@ impl<N: RealField> SignoriniModel<N> {
         // FIXME: would it be more efficient to consider the contact active iff. the rhs
         // is still negative at this point?
 
-        let warmstart = impulse * parameters.warmstart_coeff;
+        let max_impulse = props.max_normal_impulse.unwrap_or(N::max_value());
+        let warmstart = (impulse * parameters.warmstart_coeff).min(max_impulse);
         if geom.is_ground_constraint() {
-            constraints
-                .velocity
-                .unilateral_ground
-                .push(UnilateralGroundConstraint::new(
-                    geom,
-                    assembly_id1,
-                    assembly_id2,
-                    rhs,
-                    warmstart,
-                    c.id,
-                ));
+            let constraint = UnilateralGroundConstraint::new(
+                geom,
+                assembly_id1,
+                assembly_id2,
+                rhs,
+                warmstart,
+                c.id,
+            )
+            .with_max_impulse(max_impulse);
+            constraints.velocity.unilateral_ground.push(constraint);
 
             return true;
         } else {
-            constraints
-                .velocity
-                .unilateral
-                .push(UnilateralConstraint::new(
-                    geom,
-                    assembly_id1,
-                    assembly_id2,
-                    rhs,
-                    warmstart,
-                    c.id,
-                ));
+            let constraint =
+                UnilateralConstraint::new(geom, assembly_id1, assembly_id2, rhs, warmstart, c.id)
+                    .with_max_impulse(max_impulse);
+            constraints.velocity.unilateral.push(constraint);
 
             return false;
         }
@@ -204,7 +204,7 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         &self,
         c: &ColliderContactManifold<N, Handle, CollHandle>,
     ) -> usize {
-        c.manifold.len()
+        c.len()
     }
 
     fn constraints(
@@ -218,6 +218,33 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         j_id: &mut usize,
         jacobians: &mut [N],
         constraints: &mut ConstraintSet<N, Handle, CollHandle, ContactId>,
+    ) {
+        self.constraints_with_material_modifier(
+            parameters,
+            coefficients,
+            None,
+            bodies,
+            ext_vels,
+            manifolds,
+            ground_j_id,
+            j_id,
+            jacobians,
+            constraints,
+        )
+    }
+
+    fn constraints_with_material_modifier(
+        &mut self,
+        parameters: &IntegrationParameters<N>,
+        coefficients: &MaterialsCoefficientsTable<N>,
+        material_modifier: Option<&dyn ContactMaterialModifier<N, Handle, CollHandle>>,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        ext_vels: &DVector<N>,
+        manifolds: &[ColliderContactManifold<N, Handle, CollHandle>],
+        ground_j_id: &mut usize,
+        j_id: &mut usize,
+        jacobians: &mut [N],
+        constraints: &mut ConstraintSet<N, Handle, CollHandle, ContactId>,
     ) {
         let id_vel_ground = constraints.velocity.unilateral_ground.len();
         let id_vel = constraints.velocity.unilateral.len();
@@ -250,8 +277,13 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                     c,
                     false,
                 );
-                let props =
+                let mut props =
                     Material::combine(coefficients, material1, context1, material2, context2);
+                props.max_normal_impulse = manifold.max_contact_impulse();
+
+                if let Some(modifier) = material_modifier {
+                    modifier.modify_material(manifold, c, &mut props);
+                }
 
                 let _ = Self::build_velocity_constraint(
                     parameters,
@@ -272,7 +304,9 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                     constraints,
                 );
 
-                Self::build_position_constraint(bodies, manifold, c, constraints);
+                if props.max_normal_impulse.is_none() {
+                    Self::build_position_constraint(bodies, manifold, c, constraints);
+                }
             }
         }
 
@@ -292,4 +326,15 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
             let _ = self.impulses.insert(c.impulse_id, c.impulse);
         }
     }
+
+    fn contact_impulse(&self, contact: ContactId) -> Option<ContactImpulse<N>> {
+        // This model is frictionless.
+        Some(ContactImpulse {
+            normal: *self.impulses.get(contact)?,
+            tangent1: N::zero(),
+            #[cfg(feature = "dim3")]
+            tangent2: N::zero(),
+            friction_limit: N::zero(),
+        })
+    }
 }
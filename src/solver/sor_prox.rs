@@ -45,6 +45,8 @@ impl SORProx {
     }
 
     /// Solve the given set of constraints.
+    ///
+    /// Returns the largest change of a generalized velocity during the last iteration.
     pub fn solve<N: RealField, Handle: BodyHandle>(
         bodies: &mut dyn BodySet<N, Handle = Handle>,
         contact_constraints: &mut LinearConstraints<N, ContactId>,
@@ -53,7 +55,10 @@ impl SORProx {
         mj_lambda: &mut DVector<N>,
         jacobians: &[N],
         max_iter: usize,
-    ) {
+        symmetric: bool,
+        contact_cfm: N,
+        joint_cfm: N,
+    ) -> N {
         Self::warmstart_set(bodies, contact_constraints, jacobians, mj_lambda);
         Self::warmstart_set(bodies, joint_constraints, jacobians, mj_lambda);
 
@@ -67,7 +72,17 @@ impl SORProx {
         /*
          * Solve.
          */
-        for _ in 0..max_iter {
+        let mut last_mj_lambda = None;
+
+        for i in 0..max_iter {
+            // With the symmetric solver, every other iteration goes through the constraints in
+            // reverse order so the result does not depend on the order of the constraints.
+            let reverse = symmetric && i % 2 == 1;
+
+            if i == max_iter - 1 {
+                last_mj_lambda = Some(mj_lambda.clone());
+            }
+
             Self::step(
                 bodies,
                 contact_constraints,
@@ -75,8 +90,16 @@ impl SORProx {
                 internal,
                 jacobians,
                 mj_lambda,
+                reverse,
+                contact_cfm,
+                joint_cfm,
             )
         }
+
+        match last_mj_lambda {
+            Some(last_mj_lambda) if mj_lambda.len() != 0 => (&*mj_lambda - last_mj_lambda).amax(),
+            _ => N::zero(),
+        }
     }
 
     fn step_unilateral<N: RealField, Handle: BodyHandle, Id>(
@@ -84,27 +107,33 @@ impl SORProx {
         constraints: &mut LinearConstraints<N, Id>,
         jacobians: &[N],
         mj_lambda: &mut DVector<N>,
+        reverse: bool,
+        cfm: N,
     ) {
-        for c in constraints.unilateral.iter_mut() {
+        for i in indices(constraints.unilateral.len(), reverse) {
+            let c = &mut constraints.unilateral[i];
+
             if c.ndofs1 == SPATIAL_DIM && c.ndofs2 == SPATIAL_DIM {
                 // Most common case (between two free rigid bodies).
-                Self::solve_unilateral(c, jacobians, mj_lambda, SpatialDim {}, SpatialDim {})
+                Self::solve_unilateral(c, jacobians, mj_lambda, SpatialDim {}, SpatialDim {}, cfm)
             } else {
                 let dim1 = Dynamic::new(c.ndofs1);
                 let dim2 = Dynamic::new(c.ndofs2);
-                Self::solve_unilateral(c, jacobians, mj_lambda, dim1, dim2)
+                Self::solve_unilateral(c, jacobians, mj_lambda, dim1, dim2, cfm)
             }
         }
 
-        for c in constraints.unilateral_ground.iter_mut() {
+        for i in indices(constraints.unilateral_ground.len(), reverse) {
+            let c = &mut constraints.unilateral_ground[i];
+
             if c.ndofs == SPATIAL_DIM {
                 // Most common case (with one free rigid body).
                 // NOTE: it's weird that the compiler requires the { } even though SpatialDim is the
                 // alias of a marker type.
-                Self::solve_unilateral_ground(c, jacobians, mj_lambda, SpatialDim {})
+                Self::solve_unilateral_ground(c, jacobians, mj_lambda, SpatialDim {}, cfm)
             } else {
                 let dim = Dynamic::new(c.ndofs);
-                Self::solve_unilateral_ground(c, jacobians, mj_lambda, dim)
+                Self::solve_unilateral_ground(c, jacobians, mj_lambda, dim, cfm)
             }
         }
     }
@@ -114,8 +143,12 @@ impl SORProx {
         constraints: &mut LinearConstraints<N, Id>,
         jacobians: &[N],
         mj_lambda: &mut DVector<N>,
+        reverse: bool,
+        cfm: N,
     ) {
-        for c in constraints.bilateral.iter_mut() {
+        for i in indices(constraints.bilateral.len(), reverse) {
+            let c = &mut constraints.bilateral[i];
+
             if c.ndofs1 == SPATIAL_DIM && c.ndofs2 == SPATIAL_DIM {
                 // Most common case (between two free rigid bodies).
                 Self::solve_bilateral(
@@ -125,15 +158,26 @@ impl SORProx {
                     mj_lambda,
                     SpatialDim {},
                     SpatialDim {},
+                    cfm,
                 )
             } else {
                 let dim1 = Dynamic::new(c.ndofs1);
                 let dim2 = Dynamic::new(c.ndofs2);
-                Self::solve_bilateral(c, &constraints.unilateral, jacobians, mj_lambda, dim1, dim2)
+                Self::solve_bilateral(
+                    c,
+                    &constraints.unilateral,
+                    jacobians,
+                    mj_lambda,
+                    dim1,
+                    dim2,
+                    cfm,
+                )
             }
         }
 
-        for c in constraints.bilateral_ground.iter_mut() {
+        for i in indices(constraints.bilateral_ground.len(), reverse) {
+            let c = &mut constraints.bilateral_ground[i];
+
             if c.ndofs == SPATIAL_DIM {
                 // Most common case (with one free rigid body).
                 Self::solve_bilateral_ground(
@@ -142,6 +186,7 @@ impl SORProx {
                     jacobians,
                     mj_lambda,
                     SpatialDim {},
+                    cfm,
                 )
             } else {
                 let dim = Dynamic::new(c.ndofs);
@@ -151,6 +196,7 @@ impl SORProx {
                     jacobians,
                     mj_lambda,
                     dim,
+                    cfm,
                 )
             }
         }
@@ -163,19 +209,92 @@ impl SORProx {
         internal: &[Handle],
         jacobians: &[N],
         mj_lambda: &mut DVector<N>,
+        reverse: bool,
+        contact_cfm: N,
+        joint_cfm: N,
     ) {
-        Self::step_bilateral(bodies, joint_constraints, jacobians, mj_lambda);
-        Self::step_bilateral(bodies, contact_constraints, jacobians, mj_lambda);
+        if reverse {
+            Self::step_unilateral(
+                bodies,
+                contact_constraints,
+                jacobians,
+                mj_lambda,
+                true,
+                contact_cfm,
+            );
+            Self::step_unilateral(
+                bodies,
+                joint_constraints,
+                jacobians,
+                mj_lambda,
+                true,
+                joint_cfm,
+            );
+            Self::step_internal(bodies, internal, mj_lambda);
+            Self::step_bilateral(
+                bodies,
+                contact_constraints,
+                jacobians,
+                mj_lambda,
+                true,
+                contact_cfm,
+            );
+            Self::step_bilateral(
+                bodies,
+                joint_constraints,
+                jacobians,
+                mj_lambda,
+                true,
+                joint_cfm,
+            );
+        } else {
+            Self::step_bilateral(
+                bodies,
+                joint_constraints,
+                jacobians,
+                mj_lambda,
+                false,
+                joint_cfm,
+            );
+            Self::step_bilateral(
+                bodies,
+                contact_constraints,
+                jacobians,
+                mj_lambda,
+                false,
+                contact_cfm,
+            );
+            Self::step_internal(bodies, internal, mj_lambda);
+            Self::step_unilateral(
+                bodies,
+                joint_constraints,
+                jacobians,
+                mj_lambda,
+                false,
+                joint_cfm,
+            );
+            Self::step_unilateral(
+                bodies,
+                contact_constraints,
+                jacobians,
+                mj_lambda,
+                false,
+                contact_cfm,
+            );
+        }
+    }
 
+    fn step_internal<N: RealField, Handle: BodyHandle>(
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+        internal: &[Handle],
+        mj_lambda: &mut DVector<N>,
+    ) {
         for handle in internal {
             if let Some(body) = bodies.get_mut(*handle) {
                 let mut dvels = mj_lambda.rows_mut(body.companion_id(), body.ndofs());
                 body.step_solve_internal_velocity_constraints(&mut dvels);
             }
         }
-
-        Self::step_unilateral(bodies, joint_constraints, jacobians, mj_lambda);
-        Self::step_unilateral(bodies, contact_constraints, jacobians, mj_lambda);
     }
 
     fn solve_unilateral<N: RealField, D1: Dim, D2: Dim, Id>(
@@ -184,6 +303,7 @@ impl SORProx {
         mj_lambda: &mut DVector<N>,
         dim1: D1,
         dim2: D2,
+        cfm: N,
     ) {
         let id1 = c.assembly_id1;
         let id2 = c.assembly_id2;
@@ -197,7 +317,8 @@ impl SORProx {
             + jacobian2.dot(&mj_lambda.rows_generic(id2, dim2))
             + c.rhs;
 
-        let new_impulse = (c.impulse - c.r * dimpulse).max(N::zero());
+        let (r, dimpulse) = soften(c.r, dimpulse, c.impulse, cfm);
+        let new_impulse = (c.impulse - r * dimpulse).max(N::zero()).min(c.max_impulse);
         let dlambda = new_impulse - c.impulse;
 
         c.impulse = new_impulse;
@@ -214,13 +335,15 @@ impl SORProx {
         jacobians: &[N],
         mj_lambda: &mut Vector<N, DMJ, S>,
         dim: D,
+        cfm: N,
     ) {
         let jacobian = VectorSliceN::from_slice_generic(&jacobians[c.j_id..], dim, U1);
         let weighted_jacobian = VectorSliceN::from_slice_generic(&jacobians[c.wj_id..], dim, U1);
 
         let dimpulse = jacobian.dot(&mj_lambda.rows_generic_mut(c.assembly_id, dim)) + c.rhs;
 
-        let new_impulse = (c.impulse - c.r * dimpulse).max(N::zero());
+        let (r, dimpulse) = soften(c.r, dimpulse, c.impulse, cfm);
+        let new_impulse = (c.impulse - r * dimpulse).max(N::zero()).min(c.max_impulse);
         let dlambda = new_impulse - c.impulse;
 
         c.impulse = new_impulse;
@@ -236,6 +359,7 @@ impl SORProx {
         mj_lambda: &mut DVector<N>,
         dim1: D1,
         dim2: D2,
+        cfm: N,
     ) {
         let id1 = c.assembly_id1;
         let id2 = c.assembly_id2;
@@ -281,7 +405,8 @@ impl SORProx {
             + jacobian2.dot(&mj_lambda.rows_generic(id2, dim2))
             + c.rhs;
 
-        let new_impulse = na::clamp(c.impulse - c.r * dimpulse, min_impulse, max_impulse);
+        let (r, dimpulse) = soften(c.r, dimpulse, c.impulse, cfm + c.cfm);
+        let new_impulse = na::clamp(c.impulse - r * dimpulse, min_impulse, max_impulse);
         let dlambda = new_impulse - c.impulse;
 
         c.impulse = new_impulse;
@@ -299,6 +424,7 @@ impl SORProx {
         jacobians: &[N],
         mj_lambda: &mut Vector<N, DMJ, S>,
         dim: D,
+        cfm: N,
     ) {
         let min_impulse;
         let max_impulse;
@@ -333,7 +459,8 @@ impl SORProx {
 
         let dimpulse = jacobian.dot(&mj_lambda.rows_generic(c.assembly_id, dim)) + c.rhs;
 
-        let new_impulse = na::clamp(c.impulse - c.r * dimpulse, min_impulse, max_impulse);
+        let (r, dimpulse) = soften(c.r, dimpulse, c.impulse, cfm + c.cfm);
+        let new_impulse = na::clamp(c.impulse - r * dimpulse, min_impulse, max_impulse);
         let dlambda = new_impulse - c.impulse;
 
         c.impulse = new_impulse;
@@ -434,3 +561,59 @@ impl SORProx {
         }
     }
 }
+
+// The inverse effective mass and the impulse correction of a constraint softened by the
+// constraint force mixing `cfm`. This leaves them unchanged if `cfm` is zero.
+fn soften<N: RealField>(r: N, dimpulse: N, impulse: N, cfm: N) -> (N, N) {
+    if cfm.is_zero() {
+        (r, dimpulse)
+    } else {
+        (r / (N::one() + r * cfm), dimpulse + cfm * impulse)
+    }
+}
+
+// Iterates through `0..len`, in reverse order if `reverse` is `true`.
+fn indices(len: usize, reverse: bool) -> impl Iterator<Item = usize> {
+    (0..len).map(move |i| if reverse { len - 1 - i } else { i })
+}
+
+#[cfg(test)]
+mod test {
+    use ncollide::shape::{Cuboid, ShapeHandle};
+
+    use crate::analytic_tests::Scenario;
+    use crate::math::Vector;
+    use crate::object::RigidBodyDesc;
+
+    #[test]
+    fn symmetric_solver_keeps_a_box_tower_centered() {
+        let mut scenario = Scenario::new(1.0 / 60.0);
+        scenario
+            .mechanical_world
+            .integration_parameters
+            .symmetric_velocity_solver = true;
+        scenario.add_floor(0.0, 0.5);
+
+        let half_extent = 0.5;
+        let cuboid = ShapeHandle::new(Cuboid::new(Vector::repeat(half_extent)));
+        let boxes: Vec<_> = (0..5)
+            .map(|i| {
+                let height = half_extent + 2.0 * half_extent * i as f64;
+                let desc = RigidBodyDesc::new().translation(Vector::y() * height);
+                scenario.add_body(&desc, cuboid.clone(), 0.0, 0.5)
+            })
+            .collect();
+
+        for _ in 0..2000 {
+            scenario.step();
+
+            // All the boxes have the same mass.
+            let com_x = boxes
+                .iter()
+                .map(|b| scenario.rigid_body(*b).position().translation.vector.x)
+                .sum::<f64>()
+                / boxes.len() as f64;
+            assert!(com_x.abs() < 1.0e-3, "The tower drifted by {}.", com_x);
+        }
+    }
+}
@@ -28,6 +28,11 @@ pub use self::volumetric_convex3::{
     convex_hull_volume, convex_mesh_area_unchecked, convex_mesh_mass_properties_unchecked,
     convex_mesh_volume_and_center_of_mass_unchecked,
 };
+#[cfg(all(feature = "dim3", feature = "trimesh-mass-properties"))]
+pub use self::volumetric_trimesh3::{
+    convex_decomposition, trimesh_area, trimesh_mass_properties,
+    trimesh_volume_and_center_of_mass,
+};
 
 #[doc(hidden)]
 pub mod volumetric;
@@ -43,3 +48,5 @@ mod volumetric_convex3;
 mod volumetric_cuboid;
 mod volumetric_cylinder;
 mod volumetric_shape;
+#[cfg(all(feature = "dim3", feature = "trimesh-mass-properties"))]
+mod volumetric_trimesh3;
@@ -59,18 +59,21 @@ where
 
     match dimension {
         2 => {
-            // FIXME: not sure about that…
+            // Isosceles triangle with a base of width `2 * radius`, around its centroid.
+            let sq_radius = radius * radius;
+            let sq_height = half_height * half_height * na::convert(4.0f64);
             let mut res = I::zero();
 
-            res[(0, 0)] = radius * half_height * half_height * half_height / na::convert(3.0f64);
+            res[(0, 0)] = sq_radius / na::convert(6.0f64) + sq_height / na::convert(18.0f64);
 
             res
         }
         3 => {
             let sq_radius = radius * radius;
             let sq_height = half_height * half_height * na::convert(4.0f64);
+            // Around the center of mass, not the apex.
             let off_principal =
-                sq_radius * na::convert(3.0f64 / 20.0) + sq_height * na::convert(3.0f64 / 5.0);
+                sq_radius * na::convert(3.0f64 / 20.0) + sq_height * na::convert(3.0f64 / 80.0);
 
             let principal = sq_radius * na::convert(3.0f64 / 10.0);
 
@@ -110,3 +113,51 @@ where
 //
 //impl_volumetric_cone!(Cone2, 2, Point2, Matrix1);
 //impl_volumetric_cone!(Cone3, 3, Point3, Matrix3);
+
+#[cfg(test)]
+mod test {
+    use na::{Matrix1, Matrix3, Vector3};
+
+    use super::cone_unit_angular_inertia;
+
+    #[test]
+    fn test_cone_unit_angular_inertia() {
+        let radius = 1.5f64;
+        let half_height = 2.0;
+        let height = half_height * 2.0;
+
+        // Isosceles triangle around its centroid: r² / 6 + h² / 18.
+        let actual: Matrix1<f64> = cone_unit_angular_inertia(2, half_height, radius);
+        let expected = Matrix1::new(radius * radius / 6.0 + height * height / 18.0);
+        assert!(relative_eq!(actual, expected, epsilon = 1.0e-8));
+
+        // Solid cone around its center of mass: 3/20 r² + 3/80 h² about the axes orthogonal to
+        // its axis, and 3/10 r² about its axis.
+        let actual: Matrix3<f64> = cone_unit_angular_inertia(3, half_height, radius);
+        let off = radius * radius * 3.0 / 20.0 + height * height * 3.0 / 80.0;
+        let principal = radius * radius * 3.0 / 10.0;
+        let expected = Matrix3::from_diagonal(&Vector3::new(off, principal, off));
+        assert!(relative_eq!(actual, expected, epsilon = 1.0e-8));
+    }
+
+    #[cfg(feature = "dim2")]
+    #[test]
+    fn test_cone_unit_angular_inertia_matches_triangle() {
+        use crate::volumetric::Volumetric;
+        use na::Point2;
+        use ncollide::shape::ConvexPolygon;
+
+        let radius = 1.5f64;
+        let half_height = 2.0;
+        let triangle = ConvexPolygon::try_new(vec![
+            Point2::new(-radius, -half_height),
+            Point2::new(radius, -half_height),
+            Point2::new(0.0, half_height),
+        ])
+        .unwrap();
+
+        let actual: Matrix1<f64> = cone_unit_angular_inertia(2, half_height, radius);
+        let expected = triangle.unit_angular_inertia();
+        assert!(relative_eq!(actual, expected, epsilon = 1.0e-8));
+    }
+}
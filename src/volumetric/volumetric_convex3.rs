@@ -10,7 +10,7 @@ use ncollide::shape::ConvexHull;
 use ncollide::transformation;
 use ncollide::utils;
 
-fn tetrahedron_unit_inertia_tensor_wrt_point<N: RealField>(
+pub(crate) fn tetrahedron_unit_inertia_tensor_wrt_point<N: RealField>(
     point: &Point<N>,
     p1: &Point<N>,
     p2: &Point<N>,
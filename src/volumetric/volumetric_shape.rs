@@ -5,6 +5,8 @@ use na::RealField;
 use ncollide::shape::ConvexHull;
 #[cfg(feature = "dim2")]
 use ncollide::shape::ConvexPolygon;
+#[cfg(all(feature = "dim3", feature = "trimesh-mass-properties"))]
+use ncollide::shape::TriMesh;
 use ncollide::shape::{Ball, Capsule, Compound, Cuboid, Shape};
 
 macro_rules! dispatch(
@@ -25,6 +27,12 @@ macro_rules! dispatch(
                     return c.$name($($argN,)*)
                 }
             }
+            #[cfg(all(feature = "dim3", feature = "trimesh-mass-properties"))]
+            {
+                if let Some(c) = $sself.as_shape::<TriMesh<N>>() {
+                    return c.$name($($argN,)*)
+                }
+            }
             #[cfg(feature = "dim2")]
             {
                 if let Some(c) = $sself.as_shape::<ConvexPolygon<N>>() {
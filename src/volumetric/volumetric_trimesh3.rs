@@ -0,0 +1,182 @@
+use num::Zero;
+
+use super::volumetric_convex3::tetrahedron_unit_inertia_tensor_wrt_point;
+use crate::math::{AngularInertia, Isometry, Point};
+use crate::volumetric::Volumetric;
+use na::{self, Point3, RealField};
+use ncollide::procedural::{self, IndexBuffer};
+use ncollide::shape::{Compound, ConvexHull, ShapeHandle, TriMesh};
+use ncollide::transformation;
+use ncollide::utils;
+
+/// The volume and center of mass of a closed 3D triangle mesh.
+///
+/// The mesh does not have to be convex but it must be closed and its triangles must be
+/// consistently oriented counter-clockwise when seen from the outside.
+pub fn trimesh_volume_and_center_of_mass<N: RealField>(mesh: &TriMesh<N>) -> (N, Point<N>) {
+    let geometric_center = utils::center(mesh.points());
+
+    let mut res = Point::origin();
+    let mut vol = N::zero();
+
+    for face in mesh.faces() {
+        let p2 = &mesh.points()[face.indices.x];
+        let p3 = &mesh.points()[face.indices.y];
+        let p4 = &mesh.points()[face.indices.z];
+
+        let volume = utils::tetrahedron_signed_volume(&geometric_center, p2, p3, p4);
+        let center = utils::tetrahedron_center(&geometric_center, p2, p3, p4);
+
+        res += center.coords * volume;
+        vol += volume;
+    }
+
+    if vol.is_zero() {
+        (vol, geometric_center)
+    } else {
+        (vol, res / vol)
+    }
+}
+
+/// The mass properties of a closed 3D triangle mesh.
+///
+/// The mesh does not have to be convex but it must be closed and its triangles must be
+/// consistently oriented counter-clockwise when seen from the outside.
+pub fn trimesh_mass_properties<N: RealField>(
+    mesh: &TriMesh<N>,
+    density: N,
+) -> (N, Point<N>, AngularInertia<N>) {
+    let (volume, com) = trimesh_volume_and_center_of_mass(mesh);
+
+    if volume.is_zero() {
+        return (na::zero(), com, na::zero());
+    }
+
+    let geometric_center = utils::center(mesh.points());
+    let mut itot = AngularInertia::zero();
+
+    for face in mesh.faces() {
+        let p2 = &mesh.points()[face.indices.x];
+        let p3 = &mesh.points()[face.indices.y];
+        let p4 = &mesh.points()[face.indices.z];
+
+        // Tetrahedra with a negative volume cancel out the parts lying outside of the mesh.
+        let vol = utils::tetrahedron_signed_volume(&geometric_center, p2, p3, p4);
+        let ipart = tetrahedron_unit_inertia_tensor_wrt_point(&com, &geometric_center, p2, p3, p4);
+
+        itot += ipart * vol;
+    }
+
+    (volume * density, com, itot * density)
+}
+
+/// The area of a 3D triangle mesh.
+pub fn trimesh_area<N: RealField>(mesh: &TriMesh<N>) -> N {
+    let mut area = N::zero();
+
+    for face in mesh.faces() {
+        let p1 = &mesh.points()[face.indices.x];
+        let p2 = &mesh.points()[face.indices.y];
+        let p3 = &mesh.points()[face.indices.z];
+
+        area += utils::triangle_area(p1, p2, p3);
+    }
+
+    area
+}
+
+/// Approximates a concave triangle mesh by a compound of convex hulls.
+///
+/// This relies on the HACD algorithm, `error` being the maximum concavity allowed for each
+/// convex part (relative to the size of the mesh). The resulting compound has proper
+/// mass properties and can thus be attached to a dynamic body, which is not the case of
+/// triangle meshes that are not closed.
+pub fn convex_decomposition<N: RealField>(mesh: &TriMesh<N>, error: N) -> Compound<N> {
+    let indices = mesh
+        .faces()
+        .iter()
+        .map(|f| Point3::new(f.indices.x as u32, f.indices.y as u32, f.indices.z as u32))
+        .collect();
+    let mut trimesh = procedural::TriMesh::new(
+        mesh.points().to_vec(),
+        None,
+        None,
+        Some(IndexBuffer::Unified(indices)),
+    );
+    // HACD requires vertex normals.
+    trimesh.recompute_normals();
+    trimesh.split_index_buffer(true);
+
+    let (decomp, _) = transformation::hacd(trimesh, error, 1);
+    let mut parts = Vec::new();
+
+    for hull in decomp.into_iter() {
+        let indices: Vec<usize> = hull
+            .flat_indices()
+            .into_iter()
+            .map(|i| i as usize)
+            .collect();
+
+        if let Some(chull) = ConvexHull::try_new(hull.coords, &indices) {
+            parts.push((Isometry::identity(), ShapeHandle::new(chull)));
+        }
+    }
+
+    Compound::new(parts)
+}
+
+impl<N: RealField> Volumetric<N> for TriMesh<N> {
+    fn area(&self) -> N {
+        trimesh_area(self)
+    }
+
+    fn volume(&self) -> N {
+        trimesh_volume_and_center_of_mass(self).0
+    }
+
+    fn center_of_mass(&self) -> Point<N> {
+        trimesh_volume_and_center_of_mass(self).1
+    }
+
+    fn unit_angular_inertia(&self) -> AngularInertia<N> {
+        let (vol, _, i) = trimesh_mass_properties(self, na::one());
+
+        if vol.is_zero() {
+            i
+        } else {
+            i * (N::one() / vol)
+        }
+    }
+
+    fn mass_properties(&self, density: N) -> (N, Point<N>, AngularInertia<N>) {
+        trimesh_mass_properties(self, density)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::volumetric::Volumetric;
+    use na::{Point3, Vector3};
+    use ncollide::procedural;
+    use ncollide::shape::{Cuboid, TriMesh};
+
+    #[test]
+    fn test_trimesh_mass_properties() {
+        let mut shape = procedural::cuboid(&Vector3::new(2.0f64, 4.0, 6.0));
+        shape.unify_index_buffer();
+
+        for c in shape.coords.iter_mut() {
+            c.x = c.x + 3.0;
+        }
+
+        let trimesh = TriMesh::from(shape);
+        let cuboid = Cuboid::new(Vector3::new(1.0f64, 2.0, 3.0));
+
+        let (actual_m, actual_c, actual_i) = trimesh.mass_properties(1.5);
+        let (expected_m, _, expected_i) = cuboid.mass_properties(1.5);
+
+        assert!(relative_eq!(actual_m, expected_m, epsilon = 1.0e-8));
+        assert!(relative_eq!(actual_c, Point3::new(3.0, 0.0, 0.0), epsilon = 1.0e-8));
+        assert!(relative_eq!(actual_i, expected_i, epsilon = 1.0e-8));
+    }
+}
@@ -0,0 +1,253 @@
+use na::RealField;
+
+use ncollide::bounding_volume::BoundingVolume;
+#[cfg(feature = "dim2")]
+use ncollide::shape::ConvexPolygon;
+#[cfg(feature = "dim3")]
+use ncollide::shape::TriMesh;
+use ncollide::shape::{Ball, Compound, Cuboid, Shape};
+
+use crate::joint::{JointConstraint, JointConstraintSet};
+use crate::math::{Isometry, Point, Vector, DIM};
+use crate::object::{BodyHandle, BodySet, ColliderSet};
+use crate::world::GeometricalWorld;
+
+bitflags! {
+    /// Flags selecting the elements drawn by the debug renderer.
+    pub struct DebugRenderFlags: u8 {
+        /// Draw the outline of every collider shape.
+        const COLLIDER_SHAPES = 0b00001;
+        /// Draw the AABB of every collider.
+        const COLLIDER_AABBS = 0b00010;
+        /// Draw the contact points.
+        const CONTACT_POINTS = 0b00100;
+        /// Draw the contact normals.
+        const CONTACT_NORMALS = 0b01000;
+        /// Draw a segment between the two body parts attached by each joint.
+        const JOINTS = 0b10000;
+    }
+}
+
+/// The kind of element a debug line belongs to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DebugRenderObject {
+    /// A line from the outline of a collider shape.
+    ColliderShape,
+    /// A line from the AABB of a collider.
+    ColliderAABB,
+    /// A segment joining the two points of a contact.
+    ContactPoint,
+    /// A segment representing a contact normal.
+    ContactNormal,
+    /// A segment joining the two body parts attached by a joint.
+    Joint,
+}
+
+/// Trait implemented by renderers able to display the debug data generated by nphysics.
+pub trait DebugRenderBackend<N: RealField> {
+    /// Draws the line segment `[a, b]` belonging to the given kind of element.
+    fn draw_line(&mut self, object: DebugRenderObject, a: &Point<N>, b: &Point<N>);
+
+    /// The length of the segments used to represent contact normals.
+    fn contact_normal_length(&self) -> N {
+        na::convert(0.2)
+    }
+}
+
+const NUM_CIRCLE_SUBDIVISIONS: usize = 16;
+
+pub(crate) fn debug_render<N, Handle, Colliders, Constraints>(
+    gworld: &GeometricalWorld<N, Handle, Colliders::Handle>,
+    bodies: &dyn BodySet<N, Handle = Handle>,
+    colliders: &Colliders,
+    constraints: &Constraints,
+    flags: DebugRenderFlags,
+    backend: &mut dyn DebugRenderBackend<N>,
+) where
+    N: RealField,
+    Handle: BodyHandle,
+    Colliders: ColliderSet<N, Handle>,
+    Constraints: JointConstraintSet<N, Handle>,
+{
+    if flags.intersects(DebugRenderFlags::COLLIDER_SHAPES | DebugRenderFlags::COLLIDER_AABBS) {
+        colliders.foreach(|_, collider| {
+            if flags.contains(DebugRenderFlags::COLLIDER_SHAPES) {
+                render_shape(collider.shape(), collider.position(), backend);
+            }
+
+            if flags.contains(DebugRenderFlags::COLLIDER_AABBS) {
+                let aabb = collider
+                    .shape()
+                    .aabb(collider.position())
+                    .loosened(collider.margin());
+                let pos = Isometry::new(aabb.center().coords, na::zero());
+                render_box(
+                    DebugRenderObject::ColliderAABB,
+                    &aabb.half_extents(),
+                    &pos,
+                    backend,
+                );
+            }
+        });
+    }
+
+    if flags.intersects(DebugRenderFlags::CONTACT_POINTS | DebugRenderFlags::CONTACT_NORMALS) {
+        let normal_length = backend.contact_normal_length();
+
+        for (_, _, _, _, _, manifold) in gworld.contact_pairs(colliders, true) {
+            for c in manifold.contacts() {
+                if flags.contains(DebugRenderFlags::CONTACT_POINTS) {
+                    backend.draw_line(
+                        DebugRenderObject::ContactPoint,
+                        &c.contact.world1,
+                        &c.contact.world2,
+                    );
+                }
+
+                if flags.contains(DebugRenderFlags::CONTACT_NORMALS) {
+                    let end = c.contact.world1 + c.contact.normal.into_inner() * normal_length;
+                    backend.draw_line(DebugRenderObject::ContactNormal, &c.contact.world1, &end);
+                }
+            }
+        }
+    }
+
+    if flags.contains(DebugRenderFlags::JOINTS) {
+        constraints.foreach(|_, joint| {
+            let (part1, part2) = joint.anchors();
+            let body1 = try_ret!(bodies.get(part1.0));
+            let body2 = try_ret!(bodies.get(part2.0));
+            let part1 = try_ret!(body1.part(part1.1));
+            let part2 = try_ret!(body2.part(part2.1));
+
+            let a = Point::from(part1.position().translation.vector);
+            let b = Point::from(part2.position().translation.vector);
+            backend.draw_line(DebugRenderObject::Joint, &a, &b);
+        });
+    }
+}
+
+fn render_shape<N: RealField>(
+    shape: &dyn Shape<N>,
+    pos: &Isometry<N>,
+    backend: &mut dyn DebugRenderBackend<N>,
+) {
+    let object = DebugRenderObject::ColliderShape;
+
+    if let Some(s) = shape.as_shape::<Cuboid<N>>() {
+        render_box(object, s.half_extents(), pos, backend)
+    } else if let Some(s) = shape.as_shape::<Ball<N>>() {
+        render_ball(object, s.radius(), pos, backend)
+    } else if let Some(s) = shape.as_shape::<Compound<N>>() {
+        for (sub_pos, sub_shape) in s.shapes() {
+            render_shape(&**sub_shape, &(pos * sub_pos), backend)
+        }
+    } else if !render_polyhedral_shape(shape, pos, backend) {
+        // Fallback to the AABB for the shapes we don't know how to draw.
+        let aabb = shape.local_aabb();
+        let center = pos * Isometry::new(aabb.center().coords, na::zero());
+        render_box(object, &aabb.half_extents(), &center, backend)
+    }
+}
+
+#[cfg(feature = "dim2")]
+fn render_polyhedral_shape<N: RealField>(
+    shape: &dyn Shape<N>,
+    pos: &Isometry<N>,
+    backend: &mut dyn DebugRenderBackend<N>,
+) -> bool {
+    let object = DebugRenderObject::ColliderShape;
+
+    if let Some(s) = shape.as_shape::<ConvexPolygon<N>>() {
+        let pts = s.points();
+
+        for i in 0..pts.len() {
+            let j = (i + 1) % pts.len();
+            backend.draw_line(object, &(pos * pts[i]), &(pos * pts[j]));
+        }
+
+        true
+    } else {
+        false
+    }
+}
+
+#[cfg(feature = "dim3")]
+fn render_polyhedral_shape<N: RealField>(
+    shape: &dyn Shape<N>,
+    pos: &Isometry<N>,
+    backend: &mut dyn DebugRenderBackend<N>,
+) -> bool {
+    let object = DebugRenderObject::ColliderShape;
+
+    if let Some(s) = shape.as_shape::<TriMesh<N>>() {
+        for face in s.faces() {
+            let a = pos * s.points()[face.indices.x];
+            let b = pos * s.points()[face.indices.y];
+            let c = pos * s.points()[face.indices.z];
+            backend.draw_line(object, &a, &b);
+            backend.draw_line(object, &b, &c);
+            backend.draw_line(object, &c, &a);
+        }
+
+        true
+    } else {
+        false
+    }
+}
+
+fn render_box<N: RealField>(
+    object: DebugRenderObject,
+    half_extents: &Vector<N>,
+    pos: &Isometry<N>,
+    backend: &mut dyn DebugRenderBackend<N>,
+) {
+    let corner = |i: usize| {
+        let mut pt = Point::from(*half_extents);
+
+        for k in 0..DIM {
+            if (i >> k) & 1 == 0 {
+                pt[k] = -pt[k];
+            }
+        }
+
+        pos * pt
+    };
+
+    // Each edge joins two corners differing by only one coordinate.
+    for i in 0..1 << DIM {
+        for k in 0..DIM {
+            if (i >> k) & 1 == 0 {
+                backend.draw_line(object, &corner(i), &corner(i | (1 << k)));
+            }
+        }
+    }
+}
+
+fn render_ball<N: RealField>(
+    object: DebugRenderObject,
+    radius: N,
+    pos: &Isometry<N>,
+    backend: &mut dyn DebugRenderBackend<N>,
+) {
+    #[cfg(feature = "dim2")]
+    let planes = [(0, 1)];
+    #[cfg(feature = "dim3")]
+    let planes = [(0, 1), (1, 2), (2, 0)];
+
+    let step = N::two_pi() / na::convert(NUM_CIRCLE_SUBDIVISIONS as f64);
+
+    for (a, b) in planes.iter() {
+        let circle_point = |i: usize| {
+            let angle = step * na::convert(i as f64);
+            let mut pt = Point::origin();
+            pt[*a] = angle.cos() * radius;
+            pt[*b] = angle.sin() * radius;
+            pos * pt
+        };
+
+        for i in 0..NUM_CIRCLE_SUBDIVISIONS {
+            backend.draw_line(object, &circle_point(i), &circle_point(i + 1));
+        }
+    }
+}
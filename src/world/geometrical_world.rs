@@ -1,26 +1,70 @@
-use std::collections::{hash_map, HashMap};
+use std::collections::{hash_map, HashMap, HashSet};
 
-use na::RealField;
+use na::{RealField, Unit};
 
-use ncollide::bounding_volume::AABB;
+use ncollide::bounding_volume::{BoundingVolume, AABB};
+use ncollide::interpolation::{ConstantVelocityRigidMotion, RigidMotion};
 use ncollide::pipeline::{
-    self, BroadPhase, BroadPhasePairFilter, CollisionGroups, ContactAlgorithm, ContactEvents,
-    DBVTBroadPhase, DefaultContactDispatcher, DefaultProximityDispatcher, Interaction,
-    InteractionGraph, NarrowPhase, ProximityDetector, ProximityEvents,
+    self, BroadPhase, BroadPhasePairFilter, BroadPhaseProxyHandle, CollisionGroups,
+    CollisionObjectGraphIndex, CollisionObjectRef, CollisionObjectUpdateFlags, ContactAlgorithm,
+    ContactEvents, DBVTBroadPhase, DefaultContactDispatcher, DefaultProximityDispatcher,
+    GeometricQueryType, Interaction, InteractionGraph, NarrowPhase, ProximityDetector,
+    ProximityEvents,
 };
-use ncollide::query::{ContactManifold, Proximity, Ray};
+use ncollide::query::{self, ClosestPoints, ContactManifold, Proximity, Ray, RayIntersection, TOI};
+use ncollide::shape::Shape;
 
 use crate::object::{
-    BodyHandle, BodySet, Collider, ColliderAnchor, ColliderHandle, ColliderSet, DefaultBodyHandle,
-    DefaultColliderHandle,
+    Body, BodyHandle, BodyPartHandle, BodySet, Collider, ColliderAnchor, ColliderHandle,
+    ColliderSet, DefaultBodyHandle, DefaultColliderHandle, Multibody,
 };
 use crate::volumetric::Volumetric;
 
-use crate::math::Point;
+use crate::math::{Isometry, Point, Vector};
+
+/// The first impact between two bodies predicted by `GeometricalWorld::predict_impact`.
+#[derive(Copy, Clone, Debug)]
+pub struct PredictedImpact<N: RealField, CollHandle: ColliderHandle> {
+    /// The time from now after which the two bodies touch.
+    pub toi: N,
+    /// The collider of the first body involved in the impact.
+    pub collider1: CollHandle,
+    /// The collider of the second body involved in the impact.
+    pub collider2: CollHandle,
+    /// The world-space point of the first collider touching the second one, at the time of impact.
+    pub point1: Point<N>,
+    /// The world-space point of the second collider touching the first one, at the time of impact.
+    pub point2: Point<N>,
+    /// The world-space contact normal at the time of impact, pointing toward the second collider.
+    pub normal: Unit<Vector<N>>,
+}
 
 /// The default geometrical world, that can be used with a `DefaultBodyHandle` and `DefaultColliderHandle`.
 pub type DefaultGeometricalWorld<N> = GeometricalWorld<N, DefaultBodyHandle, DefaultColliderHandle>;
 
+/// A filter deciding, at each timestep, whether the narrow-phase should update a pair of colliders.
+///
+/// Unlike the broad-phase pair filter which is evaluated only when a new pair of colliders starts
+/// overlapping, this filter is evaluated each time the narrow-phase would update a pair with
+/// overlapping AABBs. This makes it possible to skip expensive contact computations depending on
+/// the current state of the simulation.
+pub trait NarrowPhasePairFilter<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>:
+    Send + Sync
+{
+    /// Returns `false` if the contacts or proximity between the two given colliders should not be
+    /// updated during this timestep.
+    ///
+    /// A pair which is not updated keeps the contacts or proximity computed the last time it was.
+    /// Therefore, pairs of colliders in contact should generally not be skipped.
+    fn should_update_pair(
+        &self,
+        c1: &Collider<N, Handle>,
+        c2: &Collider<N, Handle>,
+        h1: CollHandle,
+        h2: CollHandle,
+    ) -> bool;
+}
+
 /// The world managing all geometric queries.
 ///
 /// This is a wrapper over the `CollisionWorld` structure from `ncollide` to simplify
@@ -33,7 +77,10 @@ pub struct GeometricalWorld<N: RealField, Handle: BodyHandle, CollHandle: Collid
     /// The graph of interactions detected so far.
     pub(crate) interactions: InteractionGraph<N, CollHandle>,
     pair_filter: DefaultCollisionFilter<N, Handle, CollHandle>,
+    narrow_phase_pair_filter: Option<Box<dyn NarrowPhasePairFilter<N, Handle, CollHandle>>>,
     pub(crate) body_colliders: HashMap<Handle, Vec<CollHandle>>,
+    contact_ages: HashMap<(CollHandle, CollHandle), usize>,
+    contact_prediction_distance: N,
 }
 
 impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
@@ -51,20 +98,61 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
             narrow_phase,
             interactions: InteractionGraph::new(),
             pair_filter,
+            narrow_phase_pair_filter: None,
             body_colliders: HashMap::new(),
+            contact_ages: HashMap::new(),
+            contact_prediction_distance: N::zero(),
         }
     }
 
     /// Creates a new collision world.
-    // FIXME: use default values for `margin` and allow its modification by the user ?
     pub fn new() -> Self {
+        Self::new_with_broad_phase_margin(Self::default_broad_phase_margin())
+    }
+
+    /// Creates a new collision world with the given broad-phase margin.
+    ///
+    /// The bounding volumes stored by the broad phase are enlarged by this margin so that they
+    /// don't have to be updated each time their collider moves slightly. Larger margins reduce the
+    /// cost of updating the broad phase for objects moving a lot, but produce more pairs of objects
+    /// to be checked by the narrow phase.
+    pub fn new_with_broad_phase_margin(margin: N) -> Self {
         let coll_dispatcher = Box::new(DefaultContactDispatcher::new());
         let prox_dispatcher = Box::new(DefaultProximityDispatcher::new());
-        let broad_phase = DBVTBroadPhase::new(na::convert(0.01));
+        let broad_phase = DBVTBroadPhase::new(margin);
         let narrow_phase = NarrowPhase::new(coll_dispatcher, prox_dispatcher);
         Self::from_parts(broad_phase, narrow_phase)
     }
 
+    /// The broad-phase margin used by `GeometricalWorld::new`.
+    pub fn default_broad_phase_margin() -> N {
+        na::convert(0.01)
+    }
+
+    /// Replaces the broad phase of this geometrical world.
+    ///
+    /// All the colliders already registered are inserted into `broad_phase` at once. This can also
+    /// be used to rebuild the broad phase from scratch, e.g., after inserting a large number of
+    /// static colliders, or to switch to a broad phase better suited to the current scene.
+    pub fn set_broad_phase<BF, Colliders>(&mut self, broad_phase: BF, colliders: &mut Colliders)
+    where
+        BF: BroadPhase<N, AABB<N>, CollHandle>,
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        let mut broad_phase = Box::new(broad_phase);
+
+        colliders.foreach_mut(|handle, collider| {
+            if collider.proxy_handle().is_some() {
+                let mut aabb = collider.shape().aabb(collider.position());
+                aabb.loosen(collider.query_type().query_limit());
+                let proxy = broad_phase.create_proxy(aabb, handle);
+                collider.set_proxy_handle(Some(proxy));
+            }
+        });
+
+        self.broad_phase = broad_phase;
+    }
+
     fn register_collider(&mut self, handle: CollHandle, collider: &mut Collider<N, Handle>) {
         assert!(
             collider.proxy_handle().is_none(),
@@ -190,13 +278,17 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                         let (com, inertia) = removed
                             .shape
                             .transformed_mass_properties(removed.density, position_wrt_body_part);
-                        body.add_local_inertia_and_com(body_part.1, -com, -inertia)
+                        body.add_local_inertia_and_com(body_part.1, com, -inertia)
                     }
                 }
 
                 body.activate()
             }
 
+            // Forget the age of the contacts involving the deleted collider.
+            self.contact_ages
+                .retain(|pair, _| pair.0 != removed_handle && pair.1 != removed_handle);
+
             // Remove the collider from the list of colliders for this body.
             match self.body_colliders.entry(removed.anchor.body()) {
                 hash_map::Entry::Occupied(mut e) => {
@@ -235,11 +327,22 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         bodies: &dyn BodySet<N, Handle = Handle>,
         colliders: &mut Colliders,
     ) {
+        let broad_phase = &mut self.broad_phase;
+
         colliders.foreach_mut(|_collider_id, collider| {
             let body = try_ret!(bodies.get(collider.body()));
 
             collider.set_body_status_dependent_ndofs(body.status_dependent_ndofs());
 
+            if let Some(multibody) = body.downcast_ref::<Multibody<N>>() {
+                if collider.set_self_collision_filter(multibody.self_collision_filter()) {
+                    // The pairs involving this collider must go through the new filter.
+                    if let Some(proxy) = collider.proxy_handle() {
+                        broad_phase.deferred_recompute_all_proximities_with(proxy);
+                    }
+                }
+            }
+
             if !body.update_status().colliders_need_update() {
                 return;
             }
@@ -274,6 +377,125 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         self.body_colliders.get(&body).map(|c| &c[..])
     }
 
+    // Records that the given collider has been attached to another body.
+    pub(crate) fn collider_reattached(
+        &mut self,
+        handle: CollHandle,
+        old_body: Handle,
+        new_body: Handle,
+    ) {
+        if let hash_map::Entry::Occupied(mut e) = self.body_colliders.entry(old_body) {
+            e.get_mut().retain(|h| *h != handle);
+
+            if e.get().is_empty() {
+                let _ = e.remove_entry();
+            }
+        }
+
+        self.body_colliders
+            .entry(new_body)
+            .or_insert(Vec::new())
+            .push(handle);
+    }
+
+    /// The world-space AABB enclosing all the colliders attached to the specified body.
+    ///
+    /// For a multibody, this encloses the colliders of all its links. Returns `None` if the body
+    /// has no collider attached to it, or if the body does not exist.
+    pub fn body_aabb<Colliders: ColliderSet<N, Handle, Handle = CollHandle>>(
+        &self,
+        colliders: &Colliders,
+        body: Handle,
+    ) -> Option<AABB<N>> {
+        self.merged_collider_aabbs(colliders, body, |_| true)
+    }
+
+    /// The world-space AABB enclosing all the colliders attached to the specified body part.
+    ///
+    /// Returns `None` if the body part has no collider attached to it, or if it does not exist.
+    pub fn body_part_aabb<Colliders: ColliderSet<N, Handle, Handle = CollHandle>>(
+        &self,
+        colliders: &Colliders,
+        part: BodyPartHandle<Handle>,
+    ) -> Option<AABB<N>> {
+        self.merged_collider_aabbs(colliders, part.0, |collider| match collider.anchor() {
+            ColliderAnchor::OnBodyPart { body_part, .. } => *body_part == part,
+            ColliderAnchor::OnDeformableBody { .. } => false,
+        })
+    }
+
+    fn merged_collider_aabbs<Colliders: ColliderSet<N, Handle, Handle = CollHandle>>(
+        &self,
+        colliders: &Colliders,
+        body: Handle,
+        filter: impl Fn(&Collider<N, Handle>) -> bool,
+    ) -> Option<AABB<N>> {
+        self.body_colliders(body)?
+            .iter()
+            .filter_map(|handle| colliders.get(*handle))
+            .filter(|collider| filter(collider))
+            .map(|collider| collider.compute_aabb())
+            .fold(None, |result: Option<AABB<N>>, aabb| match result {
+                Some(result) => Some(result.merged(&aabb)),
+                None => Some(aabb),
+            })
+    }
+
+    /// Predicts the first impact between two bodies, assuming they keep their current velocities.
+    ///
+    /// Each body part moves with a constant linear and angular velocity, so rotating bodies
+    /// follow a nonlinear trajectory. Returns an impact at time zero if the bodies are already
+    /// touching, and `None` if they do not touch within `max_time`, or if one of the bodies
+    /// does not exist or has no collider. Colliders attached to deformable bodies are ignored.
+    pub fn predict_impact<Colliders: ColliderSet<N, Handle, Handle = CollHandle>>(
+        &self,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        colliders: &Colliders,
+        body1: Handle,
+        body2: Handle,
+        max_time: N,
+    ) -> Option<PredictedImpact<N, CollHandle>> {
+        let b1 = bodies.get(body1)?;
+        let b2 = bodies.get(body2)?;
+        let mut best: Option<PredictedImpact<N, CollHandle>> = None;
+
+        for ch1 in self.body_colliders(body1)? {
+            let c1 = try_continue!(colliders.get(*ch1));
+            let motion1 = try_continue!(collider_motion(b1, c1));
+
+            for ch2 in self.body_colliders(body2)? {
+                let c2 = try_continue!(colliders.get(*ch2));
+                let motion2 = try_continue!(collider_motion(b2, c2));
+                let max_toi = best.map(|impact| impact.toi).unwrap_or(max_time);
+
+                if let Some(toi) = query::nonlinear_time_of_impact(
+                    &motion1,
+                    c1.shape(),
+                    &motion2,
+                    c2.shape(),
+                    max_toi,
+                    N::zero(),
+                ) {
+                    if best.is_none() || toi.toi < max_toi {
+                        let pos1 = motion1.position_at_time(toi.toi);
+                        let pos2 = motion2.position_at_time(toi.toi);
+
+                        best = Some(PredictedImpact {
+                            toi: toi.toi,
+                            collider1: *ch1,
+                            collider2: *ch2,
+                            point1: pos1 * toi.witness1,
+                            point2: pos2 * toi.witness2,
+                            normal: pos1 * toi.normal1,
+                        });
+                    }
+                }
+            }
+        }
+
+        best
+    }
+
     /*
         /// Customize the selection of narrow-phase collision detection algorithms
         pub fn set_narrow_phase(&mut self, narrow_phase: NarrowPhase<N, CollHandle>) {
@@ -304,6 +526,19 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         self.pair_filter.user_filter = None;
     }
 
+    /// Sets the filter used to skip the update of some pairs of colliders by the narrow-phase.
+    pub fn set_narrow_phase_pair_filter<F>(&mut self, filter: F)
+    where
+        F: NarrowPhasePairFilter<N, Handle, CollHandle> + 'static,
+    {
+        self.narrow_phase_pair_filter = Some(Box::new(filter));
+    }
+
+    /// Removes the filter used to skip the update of some pairs of colliders by the narrow-phase.
+    pub fn remove_narrow_phase_pair_filter(&mut self) {
+        self.narrow_phase_pair_filter = None;
+    }
+
     /// Executes the broad phase of the collision detection pipeline.
     pub fn perform_broad_phase<Colliders>(&mut self, colliders: &Colliders)
     where
@@ -318,12 +553,123 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         )
     }
 
+    /// The minimum distance, in addition to their margins, at which contacts are generated between two colliders.
+    pub fn contact_prediction_distance(&self) -> N {
+        self.contact_prediction_distance
+    }
+
+    /// Sets the minimum distance, in addition to their margins, at which contacts are generated between two colliders.
+    ///
+    /// This is applied by the narrow phase to the contact queries of the colliders with a smaller
+    /// linear prediction, without modifying them. The mechanical world sets it to
+    /// `IntegrationParameters::contact_prediction_distance` at the beginning of each timestep.
+    /// Contacts are only predicted between colliders paired by the broad phase, so its margin
+    /// should be at least half this distance for the prediction to be fully effective.
+    pub fn set_contact_prediction_distance(&mut self, distance: N) {
+        self.contact_prediction_distance = distance
+    }
+
     /// Executes the narrow phase of the collision detection pipeline.
     pub fn perform_narrow_phase<Colliders>(&mut self, colliders: &Colliders)
     where
         Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
     {
-        pipeline::perform_narrow_phase(colliders, &mut self.narrow_phase, &mut self.interactions)
+        let prediction = self.contact_prediction_distance;
+        let filter = self.narrow_phase_pair_filter.as_ref();
+
+        if filter.is_none() && prediction <= N::zero() {
+            return pipeline::perform_narrow_phase(
+                colliders,
+                &mut self.narrow_phase,
+                &mut self.interactions,
+            );
+        }
+
+        let narrow_phase = &mut self.narrow_phase;
+        let interactions = &mut self.interactions;
+        let mut visited = HashSet::new();
+
+        ColliderSet::foreach(colliders, |handle, collider| {
+            let graph_id = try_ret!(collider.graph_index());
+
+            for (ch1, ch2, _, inter) in interactions.interactions_with_mut(graph_id) {
+                // Each interaction is reachable from both its colliders.
+                let other = if ch1 == handle { ch2 } else { ch1 };
+                if visited.contains(&other) {
+                    continue;
+                }
+
+                let c1 = try_continue!(colliders.get(ch1));
+                let c2 = try_continue!(colliders.get(ch2));
+
+                if !c1.update_flags().needs_narrow_phase_update()
+                    && !c2.update_flags().needs_narrow_phase_update()
+                {
+                    continue;
+                }
+
+                if let Some(filter) = filter {
+                    if !filter.should_update_pair(c1, c2, ch1, ch2) {
+                        continue;
+                    }
+                }
+
+                match inter {
+                    Interaction::Contact(alg, manifold) => {
+                        let co1 = PredictiveCollider::new(c1, prediction);
+                        let co2 = PredictiveCollider::new(c2, prediction);
+                        narrow_phase.update_contact(&co1, &co2, ch1, ch2, &mut **alg, manifold)
+                    }
+                    Interaction::Proximity(detector, prox) => {
+                        narrow_phase.update_proximity(c1, c2, ch1, ch2, detector, prox)
+                    }
+                }
+            }
+
+            let _ = visited.insert(handle);
+        })
+    }
+
+    /// Runs the collision detection pipeline without stepping the mechanical world.
+    ///
+    /// This handles collider insertions and removals, synchronizes the colliders with the
+    /// current positions of their bodies, then executes the broad phase and narrow phase so that
+    /// contacts and proximities can be queried before the first timestep, or after bodies have been
+    /// moved manually. The contact and proximity events generated by this update are cleared at the
+    /// beginning of the next timestep, and contact ages are left unchanged.
+    pub fn update<Colliders: ColliderSet<N, Handle, Handle = CollHandle>>(
+        &mut self,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+        colliders: &mut Colliders,
+    ) {
+        self.maintain(bodies, colliders);
+        self.sync_colliders(bodies, colliders);
+        self.perform_broad_phase(colliders);
+        self.perform_narrow_phase(colliders);
+    }
+
+    /// The number of consecutive timesteps the two given colliders have been in contact.
+    ///
+    /// This is zero if the colliders are not in contact. The age of a contact is reset each time
+    /// the colliders stop touching, even if only for one timestep.
+    pub fn contact_age(&self, handle1: CollHandle, handle2: CollHandle) -> usize {
+        self.contact_ages
+            .get(&(handle1, handle2))
+            .or_else(|| self.contact_ages.get(&(handle2, handle1)))
+            .cloned()
+            .unwrap_or(0)
+    }
+
+    // Increments the age of the contacts still active after the last narrow-phase, and forgets the others.
+    pub(crate) fn update_contact_ages<Colliders>(&mut self, colliders: &Colliders)
+    where
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        let ages: HashMap<_, _> = self
+            .contact_pairs(colliders, true)
+            .map(|(h1, _, h2, _, _, _)| ((h1, h2), self.contact_age(h1, h2) + 1))
+            .collect();
+        self.contact_ages = ages;
     }
 
     /// The broad-phase used by this geometrical world.
@@ -331,6 +677,17 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         &*self.broad_phase
     }
 
+    /// The world-space AABB of the given collider, as tracked by the broad phase.
+    ///
+    /// This AABB is enlarged by the collider margin, its prediction distances, and the
+    /// broad-phase own margin. It is updated by `maintain` and at each step, and is `None` if
+    /// the collider has not been registered to this geometrical world yet.
+    pub fn broad_phase_aabb(&self, collider: &Collider<N, Handle>) -> Option<&AABB<N>> {
+        self.broad_phase
+            .proxy(collider.proxy_handle()?)
+            .map(|(aabb, _)| aabb)
+    }
+
     /// Computes the interferences between every rigid bodies on this world and a ray.
     #[inline]
     pub fn interferences_with_ray<
@@ -347,6 +704,163 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         pipeline::interferences_with_ray(&colliders, &*self.broad_phase, ray, max_toi, groups)
     }
 
+    /// Computes the closest intersection between a ray and the colliders accepted by `filter`.
+    ///
+    /// The `filter` predicate is evaluated lazily on each collider selected by the broad-phase,
+    /// before the actual ray-cast. Colliders for which it returns `false` are ignored. This is
+    /// typically used to exclude the collider the ray is cast from.
+    pub fn cast_ray_filtered<'a, Colliders: ColliderSet<N, Handle, Handle = CollHandle>>(
+        &self,
+        colliders: &'a Colliders,
+        ray: &Ray<N>,
+        max_toi: N,
+        groups: &CollisionGroups,
+        mut filter: impl FnMut(CollHandle) -> bool,
+    ) -> Option<(CollHandle, &'a Collider<N, Handle>, RayIntersection<N>)> {
+        let mut candidates = Vec::new();
+        self.broad_phase
+            .interferences_with_ray(ray, max_toi, &mut candidates);
+
+        let mut best = None;
+        let mut best_toi = max_toi;
+
+        for handle in candidates {
+            if !filter(*handle) {
+                continue;
+            }
+
+            let collider = try_continue!(colliders.get(*handle));
+
+            if !collider.collision_groups().can_interact_with(groups) {
+                continue;
+            }
+
+            let ray_cast = try_continue!(collider.shape().as_ray_cast());
+
+            if let Some(inter) =
+                ray_cast.toi_and_normal_with_ray(collider.position(), ray, best_toi, true)
+            {
+                best_toi = inter.toi;
+                best = Some((*handle, collider, inter));
+            }
+        }
+
+        best
+    }
+
+    /// Computes the first collider hit by `shape` moving along `translation` from `position`.
+    ///
+    /// The returned time of impact is the fraction of `translation` traveled by the shape
+    /// until its distance to the hit collider is equal to `target_distance`. Sensors and the
+    /// colliders for which the `filter` predicate returns `false` are ignored.
+    pub fn cast_shape_filtered<'a, Colliders: ColliderSet<N, Handle, Handle = CollHandle>>(
+        &self,
+        colliders: &'a Colliders,
+        shape: &dyn Shape<N>,
+        position: &Isometry<N>,
+        translation: &Vector<N>,
+        target_distance: N,
+        groups: &CollisionGroups,
+        mut filter: impl FnMut(CollHandle) -> bool,
+    ) -> Option<(CollHandle, &'a Collider<N, Handle>, TOI<N>)> {
+        let start = shape.aabb(position);
+        let end = AABB::new(*start.mins() + translation, *start.maxs() + translation);
+        let swept = start.merged(&end).loosened(target_distance);
+
+        let mut candidates = Vec::new();
+        self.broad_phase
+            .interferences_with_bounding_volume(&swept, &mut candidates);
+
+        let mut best = None;
+        let mut best_toi = N::one();
+
+        for handle in candidates {
+            if !filter(*handle) {
+                continue;
+            }
+
+            let collider = try_continue!(colliders.get(*handle));
+
+            if collider.is_sensor() || !collider.collision_groups().can_interact_with(groups) {
+                continue;
+            }
+
+            if let Some(toi) = query::time_of_impact(
+                position,
+                translation,
+                shape,
+                collider.position(),
+                &Vector::zeros(),
+                collider.shape(),
+                best_toi,
+                target_distance,
+            ) {
+                if best.is_none() || toi.toi < best_toi {
+                    best_toi = toi.toi;
+                    best = Some((*handle, collider, toi));
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Computes the collider closest to `shape` at `position`, among those at a distance smaller than `max_dist`.
+    ///
+    /// Returns this collider with its closest points to `shape`, or `ClosestPoints::Intersecting`
+    /// if they overlap. Sensors and the colliders for which the `filter` predicate returns `false`
+    /// are ignored.
+    pub fn closest_points_filtered<'a, Colliders: ColliderSet<N, Handle, Handle = CollHandle>>(
+        &self,
+        colliders: &'a Colliders,
+        shape: &dyn Shape<N>,
+        position: &Isometry<N>,
+        max_dist: N,
+        groups: &CollisionGroups,
+        mut filter: impl FnMut(CollHandle) -> bool,
+    ) -> Option<(CollHandle, &'a Collider<N, Handle>, ClosestPoints<N>)> {
+        let aabb = shape.aabb(position).loosened(max_dist);
+
+        let mut candidates = Vec::new();
+        self.broad_phase
+            .interferences_with_bounding_volume(&aabb, &mut candidates);
+
+        let mut best = None;
+        let mut best_dist = max_dist;
+
+        for handle in candidates {
+            if !filter(*handle) {
+                continue;
+            }
+
+            let collider = try_continue!(colliders.get(*handle));
+
+            if collider.is_sensor() || !collider.collision_groups().can_interact_with(groups) {
+                continue;
+            }
+
+            let points = query::closest_points(
+                position,
+                shape,
+                collider.position(),
+                collider.shape(),
+                best_dist,
+            );
+            let dist = match &points {
+                ClosestPoints::Intersecting => N::zero(),
+                ClosestPoints::WithinMargin(pt1, pt2) => na::distance(pt1, pt2),
+                ClosestPoints::Disjoint => continue,
+            };
+
+            if best.is_none() || dist < best_dist {
+                best_dist = dist;
+                best = Some((*handle, collider, points));
+            }
+        }
+
+        best
+    }
+
     /// Computes the interferences between every rigid bodies of a given broad phase, and a point.
     #[inline]
     pub fn interferences_with_point<
@@ -868,6 +1382,170 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                 ),
         )
     }
+
+    /// All the contact pairs involving at least one of the colliders attached to the specified body.
+    ///
+    /// Each pair is yielded in the same order as with `contacts_with`, so the collider attached to
+    /// `body` may be either the first or the second collider of the pair: contact normals point
+    /// from the first collider toward the second one. Pairs between two colliders attached to
+    /// `body` are yielded only once. Sensors never generate contacts so they are not part of the
+    /// result; use `proximities_with` on each collider of `body` instead.
+    pub fn contacts_with_body<'a, Colliders: ColliderSet<N, Handle, Handle = CollHandle>>(
+        &'a self,
+        colliders: &'a Colliders,
+        body: Handle,
+        effective_only: bool,
+    ) -> impl Iterator<
+        Item = (
+            CollHandle,
+            &'a Collider<N, Handle>,
+            CollHandle,
+            &'a Collider<N, Handle>,
+            &'a ContactAlgorithm<N>,
+            &'a ContactManifold<N>,
+        ),
+    > {
+        self.body_colliders(body)
+            .unwrap_or(&[])
+            .iter()
+            .flat_map(move |handle| {
+                let handle = *handle;
+                self.contacts_with(colliders, handle, effective_only)
+                    .into_iter()
+                    .flatten()
+                    .filter(move |(h1, c1, _, c2, _, _)| c1.body() != c2.body() || *h1 == handle)
+            })
+    }
+
+    /// All the contact pairs between a collider member of `group1` and a collider member of `group2`.
+    ///
+    /// Only the interactions found by the broad phase for the members of `group1` are traversed,
+    /// so this is cheaper when `group1` is the smallest of the two groups. The colliders of each pair
+    /// are not reordered, so the first collider of a pair may be the member of `group2`.
+    /// If `effective_only` is `true` then only contact pairs with at least one contact point are returned.
+    pub fn contacts_between_groups<'a, Colliders: ColliderSet<N, Handle, Handle = CollHandle>>(
+        &'a self,
+        colliders: &'a Colliders,
+        group1: usize,
+        group2: usize,
+        effective_only: bool,
+    ) -> impl Iterator<
+        Item = (
+            CollHandle,
+            &'a Collider<N, Handle>,
+            CollHandle,
+            &'a Collider<N, Handle>,
+            &'a ContactAlgorithm<N>,
+            &'a ContactManifold<N>,
+        ),
+    > {
+        let mut members1 = Vec::new();
+        ColliderSet::foreach(colliders, |handle, collider| {
+            if collider.collision_groups().is_member_of(group1) {
+                members1.push(handle)
+            }
+        });
+
+        // A pair is reached twice if both its colliders are members of both groups.
+        let mut visited = HashSet::new();
+
+        members1
+            .into_iter()
+            .flat_map(move |handle| {
+                self.contacts_with(colliders, handle, effective_only)
+                    .into_iter()
+                    .flatten()
+                    .filter(move |(h1, c1, _, c2, _, _)| {
+                        let other = if *h1 == handle { c2 } else { c1 };
+                        other.collision_groups().is_member_of(group2)
+                    })
+            })
+            .filter(move |(h1, _, h2, _, _, _)| visited.insert((*h1, *h2)))
+    }
+}
+
+// The motion of a collider attached to a body part moving with its current velocity.
+fn collider_motion<N: RealField, Handle: BodyHandle>(
+    body: &dyn Body<N>,
+    collider: &Collider<N, Handle>,
+) -> Option<impl RigidMotion<N>> {
+    match collider.anchor() {
+        ColliderAnchor::OnBodyPart {
+            body_part,
+            position_wrt_body_part,
+        } => {
+            let part = body.part(body_part.1)?;
+            let velocity = part.velocity();
+            let motion = ConstantVelocityRigidMotion::new(
+                N::zero(),
+                part.position(),
+                part.local_center_of_mass(),
+                velocity.linear,
+                velocity.angular,
+            );
+            Some(motion.prepend_transformation(*position_wrt_body_part))
+        }
+        ColliderAnchor::OnDeformableBody { .. } => None,
+    }
+}
+
+// A collider whose contact queries are enlarged up to the contact prediction distance of the geometrical world.
+struct PredictiveCollider<'a, N: RealField, Handle: BodyHandle> {
+    collider: &'a Collider<N, Handle>,
+    query_type: GeometricQueryType<N>,
+}
+
+impl<'a, N: RealField, Handle: BodyHandle> PredictiveCollider<'a, N, Handle> {
+    fn new(collider: &'a Collider<N, Handle>, prediction: N) -> Self {
+        let query_type = match collider.query_type() {
+            GeometricQueryType::Contacts(linear, angular) => {
+                let limit = collider.margin() + prediction;
+                GeometricQueryType::Contacts(linear.max(limit), angular)
+            }
+            query_type => query_type,
+        };
+
+        PredictiveCollider {
+            collider,
+            query_type,
+        }
+    }
+}
+
+impl<'a, N: RealField, Handle: BodyHandle> CollisionObjectRef<N>
+    for PredictiveCollider<'a, N, Handle>
+{
+    fn graph_index(&self) -> Option<CollisionObjectGraphIndex> {
+        self.collider.graph_index()
+    }
+
+    fn proxy_handle(&self) -> Option<BroadPhaseProxyHandle> {
+        self.collider.proxy_handle()
+    }
+
+    fn position(&self) -> &Isometry<N> {
+        CollisionObjectRef::position(self.collider)
+    }
+
+    fn predicted_position(&self) -> Option<&Isometry<N>> {
+        self.collider.predicted_position()
+    }
+
+    fn shape(&self) -> &dyn Shape<N> {
+        CollisionObjectRef::shape(self.collider)
+    }
+
+    fn collision_groups(&self) -> &CollisionGroups {
+        CollisionObjectRef::collision_groups(self.collider)
+    }
+
+    fn query_type(&self) -> GeometricQueryType<N> {
+        self.query_type
+    }
+
+    fn update_flags(&self) -> CollisionObjectUpdateFlags {
+        self.collider.update_flags()
+    }
 }
 
 struct DefaultCollisionFilter<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle> {
@@ -897,6 +1575,14 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                 if part1 == part2 {
                     return false;
                 }
+
+                if part1.0 == part2.0 {
+                    if let Some(filter) = c1.self_collision_filter() {
+                        if !filter.can_collide(part1.1, part2.1) {
+                            return false;
+                        }
+                    }
+                }
             }
             _ => {}
         }
@@ -909,3 +1595,284 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                 .unwrap_or(true)
     }
 }
+
+#[cfg(all(test, feature = "dim2"))]
+mod test {
+    use na::{Isometry2, Point2, Vector2};
+    use ncollide::bounding_volume::AABB;
+    use ncollide::pipeline::CollisionGroups;
+    use ncollide::query::{ClosestPoints, Ray};
+    use ncollide::shape::{Ball, Cuboid, ShapeHandle};
+
+    use crate::force_generator::DefaultForceGeneratorSet;
+    use crate::joint::DefaultJointConstraintSet;
+    use crate::object::{
+        BodyPartHandle, ColliderDesc, DefaultBodySet, DefaultColliderHandle, DefaultColliderSet,
+        Ground,
+    };
+    use crate::world::{DefaultGeometricalWorld, DefaultMechanicalWorld};
+
+    // A world containing a 2x2 square centered at (5, 0), registered to the broad phase.
+    fn square_world() -> (
+        DefaultGeometricalWorld<f64>,
+        DefaultColliderSet<f64>,
+        DefaultColliderHandle,
+    ) {
+        let mut mechanical_world = DefaultMechanicalWorld::new(Vector2::zeros());
+        let mut geometrical_world = DefaultGeometricalWorld::new();
+        let mut bodies = DefaultBodySet::new();
+        let mut colliders = DefaultColliderSet::new();
+        let ground = bodies.insert(Ground::new());
+        let co = ColliderDesc::new(ShapeHandle::new(Cuboid::new(Vector2::repeat(1.0))))
+            .translation(Vector2::x() * 5.0)
+            .build(BodyPartHandle(ground, 0));
+        let square = colliders.insert(co);
+
+        mechanical_world.step(
+            &mut geometrical_world,
+            &mut bodies,
+            &mut colliders,
+            &mut DefaultJointConstraintSet::new(),
+            &mut DefaultForceGeneratorSet::new(),
+        );
+
+        (geometrical_world, colliders, square)
+    }
+
+    #[test]
+    fn cast_ray_filtered2() {
+        let (world, colliders, square) = square_world();
+        let groups = CollisionGroups::new();
+        let ray = Ray::new(Point2::origin(), Vector2::x());
+
+        let (handle, _, inter) = world
+            .cast_ray_filtered(&colliders, &ray, 10.0, &groups, |_| true)
+            .expect("The ray missed the square.");
+        assert_eq!(handle, square);
+        assert_relative_eq!(inter.toi, 4.0, epsilon = 1.0e-6);
+        assert_relative_eq!(inter.normal, -Vector2::x(), epsilon = 1.0e-6);
+
+        assert!(world
+            .cast_ray_filtered(&colliders, &ray, 3.0, &groups, |_| true)
+            .is_none());
+        assert!(world
+            .cast_ray_filtered(&colliders, &ray, 10.0, &groups, |h| h != square)
+            .is_none());
+    }
+
+    #[test]
+    fn cast_shape_filtered2() {
+        let (world, colliders, square) = square_world();
+        let groups = CollisionGroups::new();
+        let ball = Ball::new(0.5);
+        let position = Isometry2::identity();
+
+        let (handle, _, toi) = world
+            .cast_shape_filtered(
+                &colliders,
+                &ball,
+                &position,
+                &(Vector2::x() * 10.0),
+                0.0,
+                &groups,
+                |_| true,
+            )
+            .expect("The ball missed the square.");
+        assert_eq!(handle, square);
+        assert_relative_eq!(toi.toi, 0.35, epsilon = 1.0e-3);
+
+        assert!(world
+            .cast_shape_filtered(
+                &colliders,
+                &ball,
+                &position,
+                &(Vector2::y() * 10.0),
+                0.0,
+                &groups,
+                |_| true,
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn interferences_with_point2() {
+        let (world, colliders, square) = square_world();
+        let groups = CollisionGroups::new();
+
+        let inside: Vec<_> = world
+            .interferences_with_point(&colliders, &Point2::new(5.5, 0.5), &groups)
+            .map(|(h, _)| h)
+            .collect();
+        assert_eq!(inside, vec![square]);
+
+        let outside = world.interferences_with_point(&colliders, &Point2::origin(), &groups);
+        assert_eq!(outside.count(), 0);
+    }
+
+    #[test]
+    fn interferences_with_aabb2() {
+        let (world, colliders, square) = square_world();
+        let groups = CollisionGroups::new();
+
+        let region = AABB::new(Point2::new(3.5, -0.5), Point2::new(4.5, 0.5));
+        let overlapping: Vec<_> = world
+            .interferences_with_aabb(&colliders, &region, &groups)
+            .map(|(h, _)| h)
+            .collect();
+        assert_eq!(overlapping, vec![square]);
+
+        let region = AABB::new(Point2::new(-1.0, -1.0), Point2::new(1.0, 1.0));
+        let disjoint = world.interferences_with_aabb(&colliders, &region, &groups);
+        assert_eq!(disjoint.count(), 0);
+    }
+
+    #[test]
+    fn closest_points_filtered2() {
+        let (world, colliders, square) = square_world();
+        let groups = CollisionGroups::new();
+        let ball = Ball::new(0.5);
+        let origin = Isometry2::identity();
+
+        let (handle, _, points) = world
+            .closest_points_filtered(&colliders, &ball, &origin, 10.0, &groups, |_| true)
+            .expect("The square is not within the maximum distance.");
+        assert_eq!(handle, square);
+
+        match points {
+            ClosestPoints::WithinMargin(pt1, pt2) => {
+                assert_relative_eq!(pt1, Point2::new(0.5, 0.0), epsilon = 1.0e-6);
+                assert_relative_eq!(pt2, Point2::new(4.0, 0.0), epsilon = 1.0e-6);
+            }
+            _ => panic!("The ball and the square should be disjoint."),
+        }
+
+        assert!(world
+            .closest_points_filtered(&colliders, &ball, &origin, 3.0, &groups, |_| true)
+            .is_none());
+        assert!(world
+            .closest_points_filtered(&colliders, &ball, &origin, 10.0, &groups, |h| h != square)
+            .is_none());
+
+        let overlapping = Isometry2::translation(4.0, 0.0);
+        let (_, _, points) = world
+            .closest_points_filtered(&colliders, &ball, &overlapping, 10.0, &groups, |_| true)
+            .expect("The ball does not overlap the square.");
+        assert_eq!(points, ClosestPoints::Intersecting);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ncollide::pipeline::CollisionGroups;
+    use ncollide::shape::{Ball, Cuboid, ShapeHandle};
+
+    use crate::analytic_tests::Scenario;
+    use crate::math::Vector;
+    use crate::object::{BodyPartHandle, ColliderDesc, RigidBodyDesc};
+    use crate::world::DefaultGeometricalWorld;
+
+    #[test]
+    fn predicted_contacts_hold_a_falling_box_stack() {
+        let mut scenario = Scenario::new(1.0 / 60.0);
+        let prediction = 0.1;
+        scenario.geometrical_world =
+            DefaultGeometricalWorld::new_with_broad_phase_margin(prediction / 2.0);
+        scenario
+            .mechanical_world
+            .integration_parameters
+            .contact_prediction_distance = prediction;
+        scenario.add_floor(0.0, 0.5);
+
+        // The boxes start apart from each other, so they hit each other while falling.
+        let half_extent = 0.5;
+        let gap = 0.2;
+        let cuboid = ShapeHandle::new(Cuboid::new(Vector::repeat(half_extent)));
+        let boxes: Vec<_> = (0..5)
+            .map(|i| {
+                let height = half_extent + (2.0 * half_extent + gap) * i as f64 + gap;
+                let desc = RigidBodyDesc::new().translation(Vector::y() * height);
+                scenario.add_body(&desc, cuboid.clone(), 0.0, 0.5)
+            })
+            .collect();
+
+        let query_limits = |scenario: &Scenario| -> Vec<f64> {
+            scenario
+                .colliders
+                .iter()
+                .map(|(_, co)| co.query_type().query_limit())
+                .collect()
+        };
+        let initial_query_limits = query_limits(&scenario);
+
+        while scenario.time() < 3.0 {
+            scenario.step();
+        }
+
+        for (i, handle) in boxes.iter().enumerate() {
+            let rb = scenario.rigid_body(*handle);
+            let expected = half_extent + 2.0 * half_extent * i as f64;
+            let position = rb.position().translation.vector;
+            assert!(
+                (position.y - expected).abs() < 0.02,
+                "Box {} rests at {} instead of {}.",
+                i,
+                position.y,
+                expected
+            );
+            assert!(position.x.abs() < 0.02, "Box {} slid to {}.", i, position.x);
+            assert!(rb.velocity().linear.norm() < 1.0e-2);
+        }
+
+        // The prediction distance is applied without modifying the colliders.
+        assert_eq!(query_limits(&scenario), initial_query_limits);
+    }
+
+    #[test]
+    fn contacts_between_groups_are_reported_once() {
+        let mut scenario = Scenario::new(1.0 / 60.0);
+
+        let mut half_extents = Vector::repeat(50.0);
+        half_extents.y = 1.0;
+        let floor = ColliderDesc::new(ShapeHandle::new(Cuboid::new(half_extents)))
+            .translation(-Vector::y())
+            .collision_groups(CollisionGroups::new().with_membership(&[2]))
+            .build(BodyPartHandle(scenario.ground, 0));
+        let floor = scenario.colliders.insert(floor);
+
+        // Two touching balls are members of both groups, so their pair is reached from each of them.
+        let radius = 0.5;
+        let mut add_ball = |x: f64, groups: &[usize]| {
+            let desc = RigidBodyDesc::new().translation(Vector::x() * x + Vector::y() * radius);
+            let handle = scenario.bodies.insert(desc.build());
+            let co = ColliderDesc::new(ShapeHandle::new(Ball::new(radius)))
+                .density(1.0)
+                .collision_groups(CollisionGroups::new().with_membership(groups))
+                .build(BodyPartHandle(handle, 0));
+            scenario.colliders.insert(co)
+        };
+        let player = add_ball(-3.0, &[1]);
+        let both1 = add_ball(0.0, &[1, 2]);
+        let both2 = add_ball(2.0 * radius, &[1, 2]);
+        let other = add_ball(3.0, &[3]);
+
+        scenario.step();
+
+        let pairs: Vec<_> = scenario
+            .geometrical_world
+            .contacts_between_groups(&scenario.colliders, 1, 2, true)
+            .map(|(h1, _, h2, _, _, _)| (h1, h2))
+            .collect();
+        let touching = |a, b| {
+            pairs
+                .iter()
+                .any(|(h1, h2)| (*h1 == a && *h2 == b) || (*h1 == b && *h2 == a))
+        };
+
+        assert_eq!(pairs.len(), 4);
+        assert!(touching(player, floor));
+        assert!(touching(both1, floor));
+        assert!(touching(both2, floor));
+        assert!(touching(both1, both2));
+        assert!(!touching(other, floor));
+    }
+}
@@ -1,23 +1,31 @@
 use std::collections::{HashMap, HashSet};
 
-use na::{self, RealField};
+use na::{self, RealField, Unit};
 use ncollide;
+use ncollide::bounding_volume::AABB;
 use ncollide::interpolation::{RigidMotion, RigidMotionComposition};
 use ncollide::narrow_phase::Interaction;
-use ncollide::query::{self, Proximity, TOIStatus};
+use ncollide::pipeline::{ContactEvent, ProximityEvent};
+use ncollide::query::{self, Proximity, TOIStatus, TrackedContact};
 
 use crate::counters::Counters;
 use crate::detection::{ActivationManager, ColliderContactManifold};
-use crate::force_generator::{ForceGenerator, ForceGeneratorSet};
+use crate::force_generator::{BodyContact, ForceGenerator, ForceGeneratorSet};
 use crate::joint::{JointConstraint, JointConstraintSet};
 use crate::material::MaterialsCoefficientsTable;
-use crate::math::Vector;
+use crate::math::{Force, ForceType, Point, Vector, DIM};
 use crate::object::{
-    Body, BodyHandle, BodyPartMotion, BodySet, BodyStatus, Collider, ColliderHandle, ColliderSet,
-    DefaultBodyHandle, DefaultColliderHandle,
+    Body, BodyHandle, BodyPart, BodyPartHandle, BodyPartMotion, BodySet, BodyStatus, Collider,
+    ColliderAnchor, ColliderHandle, ColliderSet, DefaultBodyHandle, DefaultBodySet,
+    DefaultColliderHandle, LinkLocation, Multibody, RigidBody,
 };
 use crate::solver::{IntegrationParameters, MoreauJeanSolver, SignoriniCoulombPyramidModel};
-use crate::world::GeometricalWorld;
+use crate::volumetric::Volumetric;
+use crate::world::space_scaling;
+use crate::world::{
+    debug_render, DebugRenderBackend, DebugRenderFlags, GeometricalWorld, ScaleSpaceError,
+    StepHooks,
+};
 
 /// The default mechanical world, that can be used with a `DefaultBodyHandle` and `DefaultColliderHandle`.
 pub type DefaultMechanicalWorld<N> = MechanicalWorld<N, DefaultBodyHandle, DefaultColliderHandle>;
@@ -40,6 +48,12 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
     }
 }
 
+struct GravityRamp<N: RealField> {
+    target: Vector<N>,
+    remaining_steps: usize,
+    wake_up: bool,
+}
+
 struct SubstepState<N: RealField, Handle: BodyHandle> {
     active: bool,
     dt: N,
@@ -50,6 +64,78 @@ struct SubstepState<N: RealField, Handle: BodyHandle> {
     body_times: HashMap<Handle, N>,
 }
 
+/// An impact between two colliders resolved by the continuous collision detection.
+#[derive(Copy, Clone, Debug)]
+pub struct CCDEvent<N: RealField, CollHandle: ColliderHandle> {
+    /// The first collider involved in the impact.
+    pub collider1: CollHandle,
+    /// The second collider involved in the impact.
+    pub collider2: CollHandle,
+    /// The time of impact, relative to the beginning of the timestep.
+    pub toi: N,
+    /// The world-space contact point on the first collider at the time of impact.
+    pub point: Point<N>,
+    /// The world-space contact normal at the time of impact, pointing toward the second collider.
+    pub normal: Unit<Vector<N>>,
+}
+
+/// Statistics about the objects processed during a step, as reported by `MechanicalWorld::last_step_stats`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct StepStats {
+    /// The number of contact points given to the solver.
+    pub num_contacts: usize,
+    /// The number of pairs of colliders with at least one contact point given to the solver.
+    pub num_contact_pairs: usize,
+    /// The number of active joint constraints given to the solver.
+    pub num_joint_constraints: usize,
+    /// The number of active bodies simulated.
+    pub num_active_bodies: usize,
+}
+
+/// The reason why a sleeping body has been woken up.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WakeReason {
+    /// The body has been woken up by the user between two steps, e.g., by calling `activate` or
+    /// by attaching a joint to it.
+    Manual,
+    /// The body has been woken up by a force generator.
+    ForceApplied,
+    /// The body has been woken up by a contact with an awake body.
+    Contact,
+    /// The body has been woken up because an awake body interacts with it indirectly, e.g.,
+    /// through a joint or a chain of contacts with other sleeping bodies.
+    NeighborActivity,
+}
+
+/// A change of the activation status of a body during a step.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ActivationEvent<Handle: BodyHandle> {
+    /// The body has been put to sleep.
+    Sleep(Handle),
+    /// The body has been woken up.
+    WakeUp(Handle, WakeReason),
+}
+
+/// Any event generated during a step, as reported by `MechanicalWorld::drain_events`.
+#[derive(Copy, Clone, Debug)]
+pub enum PhysicsEvent<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle> {
+    /// Two colliders started or stopped being in contact.
+    Contact(ContactEvent<CollHandle>),
+    /// The proximity status of two colliders changed.
+    Proximity(ProximityEvent<CollHandle>),
+    /// An impact was resolved by the continuous collision detection.
+    CCD(CCDEvent<N, CollHandle>),
+    /// A body was woken up or put to sleep.
+    Activation(ActivationEvent<Handle>),
+    /// A joint attached to the two given body parts broke.
+    ///
+    /// If it was a joint constraint, it has been removed from the joint constraint set, which
+    /// reports its handle with `JointConstraintSet::pop_break_event`. If it was the joint of a
+    /// multibody link, the subtree rooted at this link has been detached into a new multibody
+    /// and the second body part is the root of this new multibody.
+    JointBroken(BodyPartHandle<Handle>, BodyPartHandle<Handle>),
+}
+
 /// The physics world.
 pub struct MechanicalWorld<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle> {
     /// Performance counters used for debugging and benchmarking nphysics.
@@ -64,6 +150,22 @@ pub struct MechanicalWorld<N: RealField, Handle: BodyHandle, CollHandle: Collide
     pub gravity: Vector<N>,
     activation_manager: ActivationManager<N, Handle>,
     substep: SubstepState<N, Handle>,
+    ccd_events: Vec<CCDEvent<N, CollHandle>>,
+    activation_events: Vec<ActivationEvent<Handle>>,
+    // The bodies found sleeping at the end of the last step.
+    sleeping_bodies: Vec<Handle>,
+    // The frozen rigid bodies found at the start of the current step.
+    frozen_bodies: Vec<Handle>,
+    events: Vec<PhysicsEvent<N, Handle, CollHandle>>,
+    // The number of contact and proximity events of the geometrical world already added to `events`.
+    num_recorded_collision_events: (usize, usize),
+    last_step_stats: StepStats,
+    world_bounds: Option<AABB<N>>,
+    escaped_bodies: Vec<Handle>,
+    auto_remove_escaped_bodies: bool,
+    gravity_ramp: Option<GravityRamp<N>>,
+    // The factor to apply to the impulses cached for warmstarting, since the timestep changed.
+    warmstart_rescale: N,
 }
 
 impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
@@ -95,7 +197,140 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
             gravity,
             integration_parameters,
             substep,
+            ccd_events: Vec::new(),
+            activation_events: Vec::new(),
+            sleeping_bodies: Vec::new(),
+            frozen_bodies: Vec::new(),
+            events: Vec::new(),
+            num_recorded_collision_events: (0, 0),
+            last_step_stats: StepStats::default(),
+            world_bounds: None,
+            escaped_bodies: Vec::new(),
+            auto_remove_escaped_bodies: false,
+            gravity_ramp: None,
+            warmstart_rescale: N::one(),
+        }
+    }
+
+    /// Sets the gravity of this mechanical world.
+    ///
+    /// The new gravity is applied at the next step. Sleeping bodies are not affected by gravity
+    /// until they are woken up, so set `wake_up` to `true` to wake up all the dynamic bodies and
+    /// let, e.g., resting bodies that lost their support start falling. This cancels any gravity
+    /// ramp started with `ramp_gravity`.
+    pub fn set_gravity(
+        &mut self,
+        gravity: Vector<N>,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+        wake_up: bool,
+    ) {
+        self.gravity = gravity;
+        self.gravity_ramp = None;
+
+        if wake_up {
+            Self::wake_up_dynamic_bodies(bodies)
+        }
+    }
+
+    /// Smoothly changes the gravity to `target` over the next `nsteps` steps.
+    ///
+    /// At the beginning of each of those steps, the gravity is linearly interpolated toward
+    /// `target`, which is reached exactly at the last of them. If `wake_up` is `true`, all the
+    /// dynamic bodies are woken up at each of those steps, as with `set_gravity`.
+    pub fn ramp_gravity(&mut self, target: Vector<N>, nsteps: usize, wake_up: bool) {
+        if nsteps == 0 {
+            self.gravity = target;
+            self.gravity_ramp = None;
+        } else {
+            self.gravity_ramp = Some(GravityRamp {
+                target,
+                remaining_steps: nsteps,
+                wake_up,
+            });
+        }
+    }
+
+    /// Whether the gravity is being changed by a ramp started with `ramp_gravity`.
+    pub fn is_gravity_ramping(&self) -> bool {
+        self.gravity_ramp.is_some()
+    }
+
+    fn update_gravity_ramp(&mut self, bodies: &mut dyn BodySet<N, Handle = Handle>) {
+        let ramp = try_ret!(self.gravity_ramp.as_mut());
+        let nsteps: N = na::convert(ramp.remaining_steps as f64);
+        self.gravity += (ramp.target - self.gravity) / nsteps;
+        ramp.remaining_steps -= 1;
+
+        if ramp.wake_up {
+            Self::wake_up_dynamic_bodies(bodies)
         }
+
+        if ramp.remaining_steps == 0 {
+            self.gravity = ramp.target;
+            self.gravity_ramp = None;
+        }
+    }
+
+    fn wake_up_dynamic_bodies(bodies: &mut dyn BodySet<N, Handle = Handle>) {
+        bodies.foreach_mut(&mut |_, b: &mut dyn Body<N>| {
+            if b.is_dynamic() && !b.is_active() {
+                b.activate()
+            }
+        });
+    }
+
+    /// Statistics about the objects processed by the solver during the last step.
+    ///
+    /// Unlike the performance `counters`, these are always maintained.
+    pub fn last_step_stats(&self) -> &StepStats {
+        &self.last_step_stats
+    }
+
+    /// The largest change of a generalized velocity during the last iteration of the velocity solver, at the last step.
+    ///
+    /// This measures the convergence of the constraints solver: it is close to zero if more velocity
+    /// iterations would not change the result, so `self.integration_parameters.max_velocity_iterations`
+    /// can be adapted accordingly. Since no island is built, this covers the whole world.
+    pub fn last_step_residual(&self) -> N {
+        self.solver.last_velocity_residual()
+    }
+
+    /// The bounds outside of which bodies are reported by `escaped_bodies`, if any.
+    pub fn world_bounds(&self) -> Option<&AABB<N>> {
+        self.world_bounds.as_ref()
+    }
+
+    /// Sets the bounds outside of which bodies are reported by `escaped_bodies`.
+    ///
+    /// Set this to `None` to disable the detection of escaped bodies.
+    pub fn set_world_bounds(&mut self, bounds: Option<AABB<N>>) {
+        self.world_bounds = bounds;
+        self.escaped_bodies.clear();
+    }
+
+    /// The non-static bodies found outside of the world bounds at the end of the last step.
+    ///
+    /// A body is outside of the world bounds if the center of mass of its first part (i.e. the
+    /// root link of a multibody) is. Unless `auto_remove_escaped_bodies` is enabled, these bodies
+    /// are only reported: removing them from the body set is up to the user, e.g., with
+    /// `remove_escaped_bodies`. Their colliders and joint constraints will then be removed
+    /// automatically by the next step.
+    pub fn escaped_bodies(&self) -> &[Handle] {
+        &self.escaped_bodies[..]
+    }
+
+    /// Whether the bodies that escaped the world bounds are removed automatically at the end of each step.
+    pub fn auto_remove_escaped_bodies(&self) -> bool {
+        self.auto_remove_escaped_bodies
+    }
+
+    /// Enables or disables the automatic removal of the bodies that escaped the world bounds.
+    ///
+    /// If enabled, the bodies reported by `escaped_bodies` are removed from the body set at the
+    /// end of the step that found them, together with their colliders and joint constraints.
+    /// This requires the body set to implement `BodySet::remove`. This is disabled by default.
+    pub fn set_auto_remove_escaped_bodies(&mut self, enabled: bool) {
+        self.auto_remove_escaped_bodies = enabled
     }
 
     /// Retrieve the timestep used for the integration.
@@ -104,19 +339,189 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
     }
 
     /// Sets the timestep used for the integration.
+    ///
+    /// This can be changed between two steps, e.g., for slow-motion effects. The next step then
+    /// multiplies `self.integration_parameters.warmstart_coeff` by the ratio of the new and old
+    /// timesteps, so the impulses used to warmstart the contacts, the joint constraints and the
+    /// multibody joints match the new timestep. The impulses cached by the joint constraints and
+    /// bodies themselves are not modified, and bodies warmstarting their internal constraints with
+    /// their own coefficient, e.g., `MassConstraintSystem`, are not rescaled. Prefer this over
+    /// changing the timestep with `self.integration_parameters.set_dt` directly, which does not
+    /// rescale any warmstart impulse.
     pub fn set_timestep(&mut self, dt: N) {
+        let old_dt = self.integration_parameters.dt();
+
+        if old_dt != N::zero() {
+            self.warmstart_rescale *= dt / old_dt;
+        }
+
         self.integration_parameters.set_dt(dt);
     }
 
+    /// Scales the whole space of the simulation by `factor`, e.g., to convert its length unit.
+    ///
+    /// This scales the positions and linear velocities of the bodies, the shapes and positions of
+    /// the colliders, the gravity, and the distance-based integration parameters. The mass of each
+    /// body is left unchanged, so its angular inertia is scaled by `factor * factor`.
+    ///
+    /// Only rigid bodies and the ground are supported. The supported shapes are balls, cuboids,
+    /// capsules, planes, segments, triangles, polylines, convex polygons or hulls, triangle meshes,
+    /// and compound shapes made of those. The joint constraints are scaled with their
+    /// `JointConstraint::scale_space` method, which is supported by ball, fixed, and revolute
+    /// constraints. If anything else is found, an error is returned and nothing is modified. Note
+    /// that the collider margins are not scaled.
+    pub fn scale_space<Colliders, Constraints>(
+        &mut self,
+        factor: N,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+        colliders: &mut Colliders,
+        constraints: &mut Constraints,
+    ) -> Result<(), ScaleSpaceError<Handle, CollHandle, Constraints::Handle>>
+    where
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+        Constraints: JointConstraintSet<N, Handle>,
+    {
+        let mut error = None;
+
+        bodies.foreach(&mut |handle, body: &dyn Body<N>| {
+            if error.is_none() && !body.is_ground() && body.downcast_ref::<RigidBody<N>>().is_none()
+            {
+                error = Some(ScaleSpaceError::UnsupportedBody(handle))
+            }
+        });
+
+        let mut shapes = Vec::new();
+
+        colliders.foreach(|handle, collider| {
+            if error.is_none() {
+                match space_scaling::scaled_shape(collider.shape_handle(), factor) {
+                    Some(shape) => shapes.push((handle, shape)),
+                    None => error = Some(ScaleSpaceError::UnsupportedShape(handle)),
+                }
+            }
+        });
+
+        constraints.foreach(|handle, constraint| {
+            if error.is_none() && !constraint.can_scale_space() {
+                error = Some(ScaleSpaceError::UnsupportedJoint(handle))
+            }
+        });
+
+        if let Some(error) = error {
+            return Err(error);
+        }
+
+        bodies.foreach_mut(&mut |_, body: &mut dyn Body<N>| {
+            if let Some(rb) = body.downcast_mut::<RigidBody<N>>() {
+                let mut local_inertia = rb.local_inertia();
+                local_inertia.angular *= factor * factor;
+                let local_com = rb.local_center_of_mass() * factor;
+                let position = space_scaling::scaled_isometry(rb.position(), factor);
+                let linvel = rb.velocity().linear * factor;
+
+                rb.set_local_inertia(local_inertia);
+                rb.set_local_center_of_mass(local_com);
+                rb.teleport(position);
+                rb.set_linear_velocity(linvel);
+            }
+        });
+
+        for (handle, shape) in shapes {
+            if let Some(collider) = colliders.get_mut(handle) {
+                collider.scale(factor, shape);
+            }
+        }
+
+        constraints.foreach_mut(|_, constraint| constraint.scale_space(factor));
+
+        let params = &mut self.integration_parameters;
+        params.restitution_velocity_threshold *= factor;
+        params.contact_prediction_distance *= factor;
+        params.allowed_linear_error *= factor;
+        params.max_linear_correction *= factor;
+        params.max_penetration_correction_velocity = params
+            .max_penetration_correction_velocity
+            .map(|max| max * factor);
+        self.gravity *= factor;
+
+        Ok(())
+    }
+
+    /// The islands of active bodies computed during the last step.
+    ///
+    /// Each island contains the handles of the non-kinematic bodies interacting with each other,
+    /// directly or indirectly, through contacts or joints. Sleeping islands are not included.
+    pub fn islands(&self) -> &[Vec<Handle>] {
+        self.activation_manager.islands()
+    }
+
+    /// The persistent identifier of the active island containing the given body.
+    ///
+    /// This identifier remains the same from one step to the next as long as the set of bodies
+    /// of the island does not change, making it suitable for, e.g., coloring islands
+    /// consistently during debug visualization. Returns `None` if the body is not part of any
+    /// active island, i.e., if it is sleeping, kinematic, static, or does not exist.
+    pub fn island_id(&self, handle: Handle) -> Option<usize> {
+        self.activation_manager.island_id(handle)
+    }
+
+    /// The impacts resolved by the continuous collision detection during the last step.
+    ///
+    /// This is cleared at the beginning of each step.
+    pub fn ccd_events(&self) -> &[CCDEvent<N, CollHandle>] {
+        &self.ccd_events
+    }
+
+    /// The bodies woken up or put to sleep during the last step.
+    ///
+    /// This is cleared at the beginning of each step.
+    pub fn activation_events(&self) -> &[ActivationEvent<Handle>] {
+        &self.activation_events
+    }
+
+    /// Takes all the events generated during the last step, in the order they occurred.
+    ///
+    /// This merges the contact and proximity events of the geometrical world with the CCD,
+    /// activation, and joint events of this mechanical world. The events that are not drained
+    /// are discarded at the beginning of the next step. The separate event streams are not
+    /// affected by this method.
+    pub fn drain_events(&mut self) -> Vec<PhysicsEvent<N, Handle, CollHandle>> {
+        std::mem::replace(&mut self.events, Vec::new())
+    }
+
+    /// Generates debug line segments for the elements selected by `flags`, and sends them to `backend`.
+    ///
+    /// This allows the display of collider outlines, AABBs, contacts and joints with any renderer.
+    pub fn debug_render<Colliders, Constraints>(
+        &self,
+        gworld: &GeometricalWorld<N, Handle, CollHandle>,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        colliders: &Colliders,
+        constraints: &Constraints,
+        flags: DebugRenderFlags,
+        backend: &mut dyn DebugRenderBackend<N>,
+    ) where
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+        Constraints: JointConstraintSet<N, Handle>,
+    {
+        debug_render::debug_render(gworld, bodies, colliders, constraints, flags, backend)
+    }
+
     /// Maintain the internal structures of the mechanical world by handling insersion and removal
     /// events from every sets this mechanical world interacts with.
+    ///
+    /// The colliders and joint constraints attached to the removed bodies are removed too, so their
+    /// contacts are cleared from the geometrical world. Returns the handles of the joint constraints
+    /// removed because one of their bodies was removed. This is called at the beginning of each
+    /// timestep, but can be called right after removing bodies to find out these joints.
     pub fn maintain<Colliders, Constraints>(
         &mut self,
         gworld: &mut GeometricalWorld<N, Handle, CollHandle>,
         bodies: &mut dyn BodySet<N, Handle = Handle>,
         colliders: &mut Colliders,
         constraints: &mut Constraints,
-    ) where
+    ) -> Vec<Constraints::Handle>
+    where
         Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
         Constraints: JointConstraintSet<N, Handle>,
     {
@@ -138,8 +543,9 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         }
 
         // Remove constraints with a missing body.
+        let mut constraints_to_remove = Vec::new();
+
         if at_least_one_body_removed {
-            let mut constraints_to_remove = Vec::new();
             constraints.foreach(|h, c| {
                 let (b1, b2) = c.anchors();
                 if !bodies.contains(b1.0) || !bodies.contains(b2.0) {
@@ -147,8 +553,8 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                 }
             });
 
-            for to_remove in constraints_to_remove {
-                constraints.remove(to_remove);
+            for to_remove in &constraints_to_remove {
+                constraints.remove(*to_remove);
             }
         }
 
@@ -175,6 +581,7 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         }
 
         gworld.maintain(bodies, colliders);
+        constraints_to_remove
     }
 
     /// Execute one time step of the physics simulation.
@@ -190,48 +597,110 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
         Constraints: JointConstraintSet<N, Handle>,
         Forces: ForceGeneratorSet<N, Handle>,
     {
+        self.step_with_hooks(gworld, bodies, colliders, constraints, forces, &mut ())
+    }
+
+    /// Execute one time step of the physics simulation, calling the given hooks along the way.
+    ///
+    /// See the documentation of `StepHooks` for the points of the step at which each hook is called.
+    pub fn step_with_hooks<Colliders, Constraints, Forces, Hooks>(
+        &mut self,
+        gworld: &mut GeometricalWorld<N, Handle, CollHandle>,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+        colliders: &mut Colliders,
+        constraints: &mut Constraints,
+        forces: &mut Forces,
+        hooks: &mut Hooks,
+    ) where
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+        Constraints: JointConstraintSet<N, Handle>,
+        Forces: ForceGeneratorSet<N, Handle>,
+        Hooks: StepHooks<N, Handle, CollHandle, Colliders>,
+    {
+        self.ccd_events.clear();
+
         if !self.substep.active {
             self.counters.step_started();
+            self.activation_events.clear();
+            self.events.clear();
+
+            // Like the events, the joints broken during the last step are only reported until
+            // the next one.
+            while constraints.pop_break_event().is_some() {}
+
+            hooks.pre_step(gworld, bodies, colliders);
+            self.update_gravity_ramp(bodies);
 
             /*
              *
              * Handle insertions/removals.
              *
              */
-            self.maintain(gworld, bodies, colliders, constraints);
+            let _ = self.maintain(gworld, bodies, colliders, constraints);
+            self.collect_woken_bodies(bodies, |_| WakeReason::Manual);
 
             /*
              *
              * Update body dynamics and accelerations.
              *
              */
-            bodies.foreach_mut(&mut |_, b: &mut dyn Body<N>| {
+            self.counters.update_started();
+            let dt = self.integration_parameters.dt();
+            let frozen_bodies = &mut self.frozen_bodies;
+            frozen_bodies.clear();
+
+            bodies.foreach_mut(&mut |handle, b: &mut dyn Body<N>| {
                 b.step_started();
                 b.update_kinematics();
-                b.update_dynamics(self.integration_parameters.dt());
+                b.update_dynamics(dt);
+
+                if b.downcast_ref::<RigidBody<N>>()
+                    .map_or(false, |rb| rb.is_frozen())
+                {
+                    frozen_bodies.push(handle);
+                }
             });
 
             // FIXME: how to make force generators work
             // with the external body set?
+            let mut needs_contacts = false;
+            forces.foreach(|_, f| needs_contacts = needs_contacts || f.needs_contacts());
+            let contacts = if needs_contacts {
+                Self::collect_body_contacts(gworld, colliders)
+            } else {
+                Vec::new()
+            };
+
             let parameters = &self.integration_parameters;
-            forces.foreach_mut(|_, f| f.apply(parameters, bodies));
+            forces.foreach_mut(|_, f| f.apply_with_contacts(parameters, bodies, &contacts));
+            self.apply_fluid_forces(gworld, bodies, colliders);
 
             bodies.foreach_mut(&mut |_, b: &mut dyn Body<N>| {
                 b.update_acceleration(&self.gravity, parameters);
             });
+            self.collect_woken_bodies(bodies, |_| WakeReason::ForceApplied);
+            self.counters.update_completed();
 
             /*
              *
              * Sync colliders and perform CD if the user moved
              * manually some bodies.
              */
+            gworld.set_contact_prediction_distance(
+                self.integration_parameters.contact_prediction_distance,
+            );
             gworld.clear_events();
+            self.num_recorded_collision_events = (0, 0);
             gworld.sync_colliders(bodies, colliders);
             gworld.perform_broad_phase(colliders);
             gworld.perform_narrow_phase(colliders);
+            gworld.update_contact_ages(colliders);
+            self.record_collision_events(gworld);
 
             colliders.foreach_mut(|_, c| c.clear_update_flags());
 
+            self.unfreeze_disturbed_bodies(gworld, bodies, colliders);
+
             /*
              *
              * Handle sleeping and collision
@@ -248,6 +717,7 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                 constraints,
                 &mut active_bodies,
             );
+            self.update_activation_events(gworld, bodies, colliders);
 
             let mut active_joints = Vec::new();
             constraints.foreach(|h, j| {
@@ -268,15 +738,39 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                 let b2 = try_continue!(bodies.get(c2.body()));
 
                 if manifold.len() > 0
+                    && c1.is_contact_solving_enabled()
+                    && c2.is_contact_solving_enabled()
                     && b1.status() != BodyStatus::Disabled
                     && b2.status() != BodyStatus::Disabled
                     && ((b1.status_dependent_ndofs() != 0 && b1.is_active())
                         || (b2.status_dependent_ndofs() != 0 && b2.is_active()))
                 {
-                    contact_manifolds.push(ColliderContactManifold::new(h1, c1, h2, c2, manifold));
+                    let mut manifold = ColliderContactManifold::new(h1, c1, h2, c2, manifold);
+
+                    if let Some(max) = self.integration_parameters.max_contacts_per_manifold {
+                        manifold.reduce(max, self.integration_parameters.contact_reduction);
+                    }
+
+                    contact_manifolds.push(manifold);
                 }
             }
 
+            hooks.pre_solve(gworld, bodies, colliders, &mut contact_manifolds);
+
+            self.last_step_stats = StepStats {
+                num_contacts: contact_manifolds.iter().map(|m| m.len()).sum(),
+                num_contact_pairs: contact_manifolds.len(),
+                num_joint_constraints: active_joints.len(),
+                num_active_bodies: active_bodies.len(),
+            };
+
+            if self.counters.enabled() {
+                self.counters
+                    .set_ncontacts(self.last_step_stats.num_contacts);
+                self.counters.set_ncontact_pairs(contact_manifolds.len());
+                self.counters.set_nactive_bodies(active_bodies.len());
+            }
+
             /*
              *
              * Solve the system and integrate.
@@ -290,6 +784,24 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                 b.set_companion_id(0);
             });
 
+            // The impulses cached during the last step are proportional to its timestep.
+            let warmstart_coeff = self.integration_parameters.warmstart_coeff;
+            self.integration_parameters.warmstart_coeff *= self.warmstart_rescale;
+            self.warmstart_rescale = N::one();
+
+            // The reactions of breakable multibody joints depend on the contact impulses.
+            let multibodies: Vec<_> = active_bodies
+                .iter()
+                .cloned()
+                .filter(|h| {
+                    bodies
+                        .get(*h)
+                        .and_then(|b| b.downcast_ref::<Multibody<N>>())
+                        .map_or(false, |m| m.has_breakable_joints())
+                })
+                .collect();
+
+            let parameters = &self.integration_parameters;
             self.counters.solver_started();
             self.solver.step(
                 &mut self.counters,
@@ -303,6 +815,23 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                 &self.material_coefficients,
             );
 
+            for handle in &active_joints {
+                let joint = try_continue!(constraints.get(*handle));
+
+                if joint.is_broken() {
+                    let (part1, part2) = joint.anchors();
+                    self.events.push(PhysicsEvent::JointBroken(part1, part2));
+                    constraints.remove_broken(*handle);
+                }
+            }
+
+            if !multibodies.is_empty() {
+                let impulses =
+                    self.multibody_contact_impulses(bodies, &multibodies, &contact_manifolds);
+                self.break_multibody_joints(gworld, bodies, colliders, &multibodies, &impulses);
+            }
+
+            let parameters = &self.integration_parameters;
             bodies.foreach_mut(&mut |_, b: &mut dyn Body<N>| {
                 if b.status() == BodyStatus::Kinematic {
                     b.integrate(parameters)
@@ -322,6 +851,8 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                 b.update_kinematics();
                 b.update_dynamics(parameters.dt());
             });
+
+            self.integration_parameters.warmstart_coeff = warmstart_coeff;
         }
 
         /*
@@ -359,6 +890,7 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
 
             self.counters.narrow_phase_started();
             gworld.perform_narrow_phase(colliders);
+            self.record_collision_events(gworld);
             self.counters.narrow_phase_completed();
             self.counters.collision_detection_completed();
 
@@ -369,7 +901,413 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                 b.clear_update_flags();
             });
 
-            colliders.foreach_mut(|_, c| c.clear_update_flags())
+            colliders.foreach_mut(|_, c| c.clear_update_flags());
+            self.find_escaped_bodies(bodies);
+
+            if self.auto_remove_escaped_bodies && !self.escaped_bodies.is_empty() {
+                for handle in &self.escaped_bodies {
+                    bodies.remove(*handle);
+                }
+
+                let _ = self.maintain(gworld, bodies, colliders, constraints);
+            }
+
+            hooks.post_step(gworld, bodies, colliders);
+        }
+    }
+
+    fn find_escaped_bodies(&mut self, bodies: &dyn BodySet<N, Handle = Handle>) {
+        self.escaped_bodies.clear();
+        let bounds = try_ret!(self.world_bounds.as_ref());
+        let escaped_bodies = &mut self.escaped_bodies;
+
+        bodies.foreach(&mut |handle, body: &dyn Body<N>| {
+            if body.is_ground() || body.is_static() {
+                return;
+            }
+
+            let com = try_ret!(body.part(0)).center_of_mass();
+            let inside = (0..DIM).all(|i| com[i] >= bounds.mins()[i] && com[i] <= bounds.maxs()[i]);
+
+            if !inside {
+                escaped_bodies.push(handle)
+            }
+        });
+    }
+
+    // The contact points found by the last narrow-phase, for the force generators that need them.
+    fn collect_body_contacts<Colliders>(
+        gworld: &GeometricalWorld<N, Handle, CollHandle>,
+        colliders: &Colliders,
+    ) -> Vec<BodyContact<N, Handle>>
+    where
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        let mut contacts = Vec::new();
+
+        for (h1, c1, h2, c2, _, manifold) in gworld.contact_pairs(colliders, true) {
+            let manifold = ColliderContactManifold::new(h1, c1, h2, c2, manifold);
+
+            for c in manifold.contacts() {
+                contacts.push(BodyContact {
+                    body_part1: manifold.body_part1(c.kinematic.feature1()),
+                    body_part2: manifold.body_part2(c.kinematic.feature2()),
+                    point1: c.contact.world1,
+                    point2: c.contact.world2,
+                    normal: c.contact.normal,
+                    depth: c.contact.depth,
+                });
+            }
+        }
+
+        contacts
+    }
+
+    // Applies buoyancy and drag forces to the dynamic bodies overlapping a collider filled with a fluid.
+    //
+    // The immersed volume of a collider is approximated by the fraction of its AABB contained by the
+    // AABB of the fluid collider, and the overlaps are those detected by the last narrow-phase.
+    fn apply_fluid_forces<Colliders>(
+        &self,
+        gworld: &GeometricalWorld<N, Handle, CollHandle>,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+        colliders: &Colliders,
+    ) where
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        for (_, c1, _, c2, _, prox) in gworld.proximity_pairs(colliders, true) {
+            if prox != Proximity::Intersecting {
+                continue;
+            }
+
+            let (fluid, fluid_collider, immersed) = if let Some(fluid) = c1.fluid() {
+                (fluid, c1, c2)
+            } else if let Some(fluid) = c2.fluid() {
+                (fluid, c2, c1)
+            } else {
+                continue;
+            };
+
+            // Colliders without density may have a shape with an unknown volume.
+            if immersed.is_sensor() || immersed.density().is_zero() {
+                continue;
+            }
+
+            let part = match immersed.anchor() {
+                ColliderAnchor::OnBodyPart { body_part, .. } => *body_part,
+                ColliderAnchor::OnDeformableBody { .. } => continue,
+            };
+            let body = try_continue!(bodies.get_mut(part.0));
+
+            if !body.is_dynamic() || !body.is_active() {
+                continue;
+            }
+
+            let aabb = immersed.shape().aabb(immersed.position());
+            let fluid_aabb = fluid_collider.shape().aabb(fluid_collider.position());
+            let mins = aabb.mins().sup(fluid_aabb.mins());
+            let maxs = aabb.maxs().inf(fluid_aabb.maxs());
+            let extents = aabb.extents();
+            let immersed_extents = maxs - mins;
+
+            if immersed_extents.iter().any(|e| *e <= N::zero()) {
+                continue;
+            }
+
+            let volume = extents.iter().fold(N::one(), |v, e| v * *e);
+            let immersed_volume = immersed_extents.iter().fold(N::one(), |v, e| v * *e);
+
+            if volume.is_zero() {
+                continue;
+            }
+
+            let fraction = immersed_volume / volume;
+            let center = na::center(&mins, &maxs);
+            let velocity = body.velocity_at_point(part.1, &center);
+            let buoyancy = -self.gravity * (fluid.density * immersed.shape().volume() * fraction);
+            let linear_drag = velocity.linear * (-fluid.linear_drag * fraction);
+            let angular_drag = velocity.angular * (-fluid.angular_drag * fraction);
+
+            body.apply_force_at_point(
+                part.1,
+                &(buoyancy + linear_drag),
+                &center,
+                ForceType::Force,
+                false,
+            );
+            body.apply_force(
+                part.1,
+                &Force::new(Vector::zeros(), angular_drag),
+                ForceType::Force,
+                false,
+            );
+        }
+    }
+
+    // Adds to the merged event queue the contact and proximity events generated since the last call.
+    fn record_collision_events(&mut self, gworld: &GeometricalWorld<N, Handle, CollHandle>) {
+        let (num_contacts, num_proximities) = self.num_recorded_collision_events;
+        let contact_events = gworld.contact_events();
+        let proximity_events = gworld.proximity_events();
+
+        for event in contact_events.iter().skip(num_contacts) {
+            self.events.push(PhysicsEvent::Contact(*event));
+        }
+
+        for event in proximity_events.iter().skip(num_proximities) {
+            self.events.push(PhysicsEvent::Proximity(*event));
+        }
+
+        self.num_recorded_collision_events = (contact_events.len(), proximity_events.len());
+    }
+
+    // Generates the wake-up events of the bodies that were sleeping at the end of the last step
+    // and are now awake.
+    fn collect_woken_bodies(
+        &mut self,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        mut reason: impl FnMut(Handle) -> WakeReason,
+    ) {
+        let events = &mut self.activation_events;
+        let all_events = &mut self.events;
+
+        self.sleeping_bodies
+            .retain(|handle| match bodies.get(*handle) {
+                Some(body) if body.is_sleeping() => true,
+                Some(body) if body.is_active() => {
+                    let event = ActivationEvent::WakeUp(*handle, reason(*handle));
+                    events.push(event);
+                    all_events.push(PhysicsEvent::Activation(event));
+                    false
+                }
+                _ => false,
+            });
+    }
+
+    // Generates the activation events due to the activation manager, and records the sleeping bodies for the next step.
+    fn update_activation_events<Colliders>(
+        &mut self,
+        gworld: &GeometricalWorld<N, Handle, CollHandle>,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        colliders: &Colliders,
+    ) where
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        let was_sleeping: HashSet<Handle> = self.sleeping_bodies.iter().cloned().collect();
+
+        if !was_sleeping.is_empty() {
+            // The sleeping bodies directly touching a body that was already awake.
+            let mut touching_awake_body = HashSet::new();
+
+            for (_, c1, _, c2, _, manifold) in gworld.contact_pairs(colliders, false) {
+                if manifold.len() == 0 {
+                    continue;
+                }
+
+                for &(body, other) in [(c1.body(), c2.body()), (c2.body(), c1.body())].iter() {
+                    if was_sleeping.contains(&body)
+                        && !was_sleeping.contains(&other)
+                        && bodies.get(other).map(|b| b.is_active()).unwrap_or(false)
+                    {
+                        let _ = touching_awake_body.insert(body);
+                    }
+                }
+            }
+
+            self.collect_woken_bodies(bodies, |handle| {
+                if touching_awake_body.contains(&handle) {
+                    WakeReason::Contact
+                } else {
+                    WakeReason::NeighborActivity
+                }
+            });
+        }
+
+        let mut sleeping_bodies = Vec::new();
+        let events = &mut self.activation_events;
+        let all_events = &mut self.events;
+
+        bodies.foreach(&mut |handle, body: &dyn Body<N>| {
+            if body.is_sleeping() {
+                if !was_sleeping.contains(&handle) {
+                    let event = ActivationEvent::Sleep(handle);
+                    events.push(event);
+                    all_events.push(PhysicsEvent::Activation(event));
+                }
+
+                sleeping_bodies.push(handle);
+            }
+        });
+
+        self.sleeping_bodies = sleeping_bodies;
+    }
+
+    // Unfreezes the frozen rigid bodies to which the solver applied, during the last step, a
+    // contact impulse above their freeze threshold.
+    fn unfreeze_disturbed_bodies<Colliders>(
+        &self,
+        gworld: &GeometricalWorld<N, Handle, CollHandle>,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+        colliders: &Colliders,
+    ) where
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        let mut to_unfreeze = Vec::new();
+
+        'frozen: for &handle in &self.frozen_bodies {
+            let rb = try_continue!(bodies
+                .get(handle)
+                .and_then(|b| b.downcast_ref::<RigidBody<N>>()));
+
+            for (.., manifold) in gworld.contacts_with_body(colliders, handle, true) {
+                for c in manifold.contacts() {
+                    let impulse = try_continue!(self.solver.contact_impulse(c.id));
+
+                    if impulse.normal > rb.freeze_threshold() {
+                        to_unfreeze.push(handle);
+                        continue 'frozen;
+                    }
+                }
+            }
+        }
+
+        for handle in to_unfreeze {
+            if let Some(rb) = bodies
+                .get_mut(handle)
+                .and_then(|b| b.downcast_mut::<RigidBody<N>>())
+            {
+                rb.unfreeze()
+            }
+        }
+    }
+
+    // The impulse applied by the solver at the given contact to the first body in the last step.
+    fn contact_impulse_on_body1(&self, c: &TrackedContact<N>) -> Option<Vector<N>> {
+        let impulse = self.solver.contact_impulse(c.id)?;
+        let normal = c.contact.normal.into_inner();
+        #[cfg(feature = "dim2")]
+        let tangent_impulses = [impulse.tangent1];
+        #[cfg(feature = "dim3")]
+        let tangent_impulses = [impulse.tangent1, impulse.tangent2];
+        let mut result = normal * -impulse.normal;
+        let mut i = 0;
+
+        // Same tangent basis as the one of the friction constraints.
+        Vector::orthonormal_subspace_basis(&[normal], |tangent| {
+            result += tangent * tangent_impulses[i];
+            i += 1;
+            true
+        });
+
+        Some(result)
+    }
+
+    // The impulses applied by the contacts during the last step to each link of the given
+    // multibodies, expressed at the center of mass of each link.
+    fn multibody_contact_impulses(
+        &self,
+        bodies: &dyn BodySet<N, Handle = Handle>,
+        multibodies: &[Handle],
+        manifolds: &[ColliderContactManifold<N, Handle, CollHandle>],
+    ) -> Vec<Vec<Force<N>>> {
+        let mut result: Vec<_> = multibodies
+            .iter()
+            .map(|h| vec![Force::zero(); bodies.get(*h).map_or(0, |b| b.num_parts())])
+            .collect();
+
+        for manifold in manifolds {
+            for c in manifold.contacts() {
+                let impulse = try_continue!(self.contact_impulse_on_body1(c));
+                let normal = c.contact.normal.into_inner();
+                let part1 = manifold.body_part1(c.kinematic.feature1());
+                let part2 = manifold.body_part2(c.kinematic.feature2());
+                let center1 = c.contact.world1 + normal * manifold.collider1.margin();
+                let center2 = c.contact.world2 - normal * manifold.collider2.margin();
+
+                for (part, point, impulse) in
+                    vec![(part1, center1, impulse), (part2, center2, -impulse)]
+                {
+                    let i = try_continue!(multibodies.iter().position(|h| *h == part.0));
+                    let body = try_continue!(bodies.get(part.0));
+                    let com = try_continue!(body.part(part.1)).center_of_mass();
+                    result[i][part.1] += Force::linear_at_point(impulse, &(point - com.coords));
+                }
+            }
+        }
+
+        result
+    }
+
+    // Detaches the subtree rooted at each multibody link whose joint absorbed, during the last
+    // step, more than its break force or torque. Each subtree becomes a new multibody, and the
+    // colliders of its links are attached to it.
+    fn break_multibody_joints<Colliders>(
+        &mut self,
+        gworld: &mut GeometricalWorld<N, Handle, CollHandle>,
+        bodies: &mut dyn BodySet<N, Handle = Handle>,
+        colliders: &mut Colliders,
+        multibodies: &[Handle],
+        contact_impulses: &[Vec<Force<N>>],
+    ) where
+        Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+    {
+        let inv_dt = self.integration_parameters.inv_dt();
+
+        for (handle, impulses) in multibodies.iter().zip(contact_impulses.iter()) {
+            let overloaded = {
+                let body = try_continue!(bodies.get(*handle));
+                let multibody = try_continue!(body.downcast_ref::<Multibody<N>>());
+                multibody.overloaded_joints(&self.gravity, inv_dt, impulses)
+            };
+            let mut broken: Vec<(usize, Handle)> = Vec::new();
+
+            // Removing a subtree only changes the ids of the links after its root.
+            for link_id in overloaded.into_iter().rev() {
+                let body = try_continue!(bodies.get_mut(*handle));
+                body.update_kinematics();
+                let multibody = try_continue!(body.downcast_ref::<Multibody<N>>());
+                let parent_id = try_continue!(multibody.link(link_id).and_then(|l| l.parent_id()));
+                let detached = multibody.subtree_copy(link_id);
+                let new_handle = try_continue!(bodies.insert_boxed(Box::new(detached)));
+
+                let body = try_continue!(bodies.get_mut(*handle));
+                let multibody = try_continue!(body.downcast_mut::<Multibody<N>>());
+                let locations = multibody.remove_subtree(link_id);
+
+                for (parent_id, _) in &mut broken {
+                    *parent_id = locations[*parent_id].id();
+                }
+
+                broken.push((locations[parent_id].id(), new_handle));
+
+                let attached = gworld.body_colliders(*handle).unwrap_or(&[]).to_vec();
+
+                for collider_handle in attached {
+                    let collider = try_continue!(colliders.get_mut(collider_handle));
+                    let part_id = match collider.anchor() {
+                        ColliderAnchor::OnBodyPart { body_part, .. } => body_part.1,
+                        ColliderAnchor::OnDeformableBody { .. } => continue,
+                    };
+
+                    match locations[part_id] {
+                        LinkLocation::Kept(id) => {
+                            collider.set_anchor_body_part(BodyPartHandle(*handle, id))
+                        }
+                        LinkLocation::Detached(id) => {
+                            collider.set_anchor_body_part(BodyPartHandle(new_handle, id));
+                            colliders.collider_reattached(collider_handle, *handle);
+                            gworld.collider_reattached(collider_handle, *handle, new_handle);
+                        }
+                    }
+                }
+            }
+
+            for (parent_id, new_handle) in broken {
+                self.events.push(PhysicsEvent::JointBroken(
+                    BodyPartHandle(*handle, parent_id),
+                    BodyPartHandle(new_handle, 0),
+                ));
+            }
         }
     }
 
@@ -821,13 +1759,48 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
                         Interaction::Contact(_, manifold) => {
                             let c1 = colliders.get(ch1).unwrap();
                             let c2 = colliders.get(ch2).unwrap();
-                            contact_manifolds
-                                .push(ColliderContactManifold::new(ch1, c1, ch2, c2, manifold));
+
+                            if !c1.is_contact_solving_enabled() || !c2.is_contact_solving_enabled()
+                            {
+                                continue;
+                            }
+
+                            let mut manifold =
+                                ColliderContactManifold::new(ch1, c1, ch2, c2, manifold);
+
+                            if let Some(max) = parameters.max_contacts_per_manifold {
+                                manifold.reduce(max, parameters.contact_reduction);
+                            }
+
+                            contact_manifolds.push(manifold);
                         }
                         Interaction::Proximity(..) => {}
                     }
                 }
 
+                // Report the impacts resolved by this substep.
+                for toi in &toi_entries {
+                    if toi.is_proximity {
+                        continue;
+                    }
+
+                    if let Some((ch1, _, ch2, _, _, manifold)) =
+                        gworld.contact_pair(&*colliders, toi.c1, toi.c2, false)
+                    {
+                        if let Some(deepest) = manifold.deepest_contact() {
+                            let event = CCDEvent {
+                                collider1: ch1,
+                                collider2: ch2,
+                                toi: toi.toi,
+                                point: deepest.contact.world1,
+                                normal: deepest.contact.normal,
+                            };
+                            self.ccd_events.push(event);
+                            self.events.push(PhysicsEvent::CCD(event));
+                        }
+                    }
+                }
+
                 self.counters.ccd.narrow_phase_time.pause();
 
                 // Solve the system and integrate.
@@ -882,6 +1855,17 @@ impl<N: RealField, Handle: BodyHandle, CollHandle: ColliderHandle>
     }
 }
 
+impl<N: RealField, CollHandle: ColliderHandle> MechanicalWorld<N, DefaultBodyHandle, CollHandle> {
+    /// Removes from `bodies` all the bodies reported by `escaped_bodies`.
+    ///
+    /// The colliders and joint constraints attached to these bodies will be removed by the next step.
+    pub fn remove_escaped_bodies(&mut self, bodies: &mut DefaultBodySet<N>) {
+        for handle in self.escaped_bodies.drain(..) {
+            let _ = bodies.remove(handle);
+        }
+    }
+}
+
 struct TOIEntry<N: RealField, Handle, CollHandle> {
     toi: N,
     c1: CollHandle,
@@ -1018,3 +2002,189 @@ impl<N: RealField, CollHandle, BodyHandle> PartialEq for TOIEntry<N, CollHandle,
 }
 
 impl<N: RealField, CollHandle, BodyHandle> Eq for TOIEntry<N, CollHandle, BodyHandle> {}
+
+#[cfg(test)]
+mod test {
+    use ncollide::bounding_volume::AABB;
+    use ncollide::shape::{Ball, Cuboid, ShapeHandle};
+
+    use crate::analytic_tests::{assert_relative_error, Scenario};
+    use crate::joint::{BallConstraint, RopeConstraint};
+    use crate::math::{Point, Vector};
+    use crate::object::{BodyPartHandle, BodySet, RigidBodyDesc};
+    use crate::world::ScaleSpaceError;
+
+    #[test]
+    fn escaped_bodies_are_removed_only_if_enabled() {
+        for &auto_remove in &[false, true] {
+            let mut scenario = Scenario::new(1.0 / 60.0);
+            let bounds = AABB::new(
+                Point::from(Vector::repeat(-5.0)),
+                Point::from(Vector::repeat(5.0)),
+            );
+            scenario.mechanical_world.set_world_bounds(Some(bounds));
+            scenario
+                .mechanical_world
+                .set_auto_remove_escaped_bodies(auto_remove);
+
+            // Two balls falling together, attached by a joint.
+            let ball = ShapeHandle::new(Ball::new(0.1));
+            let desc = RigidBodyDesc::new().translation(-Vector::x());
+            let left = scenario.add_body(&desc, ball.clone(), 0.0, 0.0);
+            let desc = RigidBodyDesc::new().translation(Vector::x());
+            let right = scenario.add_body(&desc, ball, 0.0, 0.0);
+            let joint = BallConstraint::new(
+                BodyPartHandle(left, 0),
+                BodyPartHandle(right, 0),
+                Point::from(Vector::x()),
+                Point::from(-Vector::x()),
+            );
+            let joint = scenario.joint_constraints.insert(joint);
+
+            while scenario.mechanical_world.escaped_bodies().is_empty() {
+                assert!(scenario.time() < 2.0, "The bodies did not escape.");
+                scenario.step();
+            }
+
+            let escaped = scenario.mechanical_world.escaped_bodies();
+            assert!(escaped.contains(&left) && escaped.contains(&right));
+            assert_eq!(scenario.bodies.contains(left), !auto_remove);
+            assert_eq!(scenario.bodies.contains(right), !auto_remove);
+            let num_colliders = if auto_remove { 0 } else { 2 };
+            assert_eq!(scenario.colliders.iter().count(), num_colliders);
+            assert_eq!(scenario.joint_constraints.contains(joint), !auto_remove);
+        }
+    }
+
+    #[test]
+    fn scaling_space_scales_the_joint_anchors() {
+        let mut scenario = Scenario::new(1.0 / 240.0);
+
+        // A pendulum at rest, hanging below the origin.
+        let length = 1.0;
+        let desc = RigidBodyDesc::new().translation(-Vector::y() * length);
+        let bob = scenario.add_body(&desc, ShapeHandle::new(Ball::new(0.05)), 0.0, 0.0);
+        let joint = BallConstraint::new(
+            BodyPartHandle(scenario.ground, 0),
+            BodyPartHandle(bob, 0),
+            Point::origin(),
+            Point::from(Vector::y() * length),
+        );
+        let joint = scenario.joint_constraints.insert(joint);
+
+        let factor = 2.0;
+        scenario
+            .mechanical_world
+            .scale_space(
+                factor,
+                &mut scenario.bodies,
+                &mut scenario.colliders,
+                &mut scenario.joint_constraints,
+            )
+            .unwrap();
+
+        while scenario.time() < 1.0 {
+            scenario.step();
+        }
+
+        let height = scenario.rigid_body(bob).position().translation.vector.y;
+        assert_relative_error(height, -length * factor, 1.0e-3);
+
+        let error = scenario
+            .joint_constraints
+            .get(joint)
+            .unwrap()
+            .position_error(&scenario.bodies);
+        assert!(error.linear < 1.0e-3);
+
+        // Rope constraints cannot be scaled, so nothing is modified.
+        let rope = RopeConstraint::new(
+            BodyPartHandle(scenario.ground, 0),
+            BodyPartHandle(bob, 0),
+            Point::origin(),
+            Point::origin(),
+            length,
+        );
+        let rope = scenario.joint_constraints.insert(rope);
+        let result = scenario.mechanical_world.scale_space(
+            factor,
+            &mut scenario.bodies,
+            &mut scenario.colliders,
+            &mut scenario.joint_constraints,
+        );
+
+        assert_eq!(result, Err(ScaleSpaceError::UnsupportedJoint(rope)));
+        assert_relative_error(
+            scenario.rigid_body(bob).position().translation.vector.y,
+            height,
+            1.0e-6,
+        );
+    }
+
+    #[test]
+    fn removing_a_body_purges_its_joints_and_contacts() {
+        let mut scenario = Scenario::new(1.0 / 60.0);
+        scenario.add_floor(0.0, 0.5);
+
+        let half_extent = 0.5;
+        let desc = RigidBodyDesc::new().translation(Vector::y() * half_extent);
+        let cuboid = ShapeHandle::new(Cuboid::new(Vector::repeat(half_extent)));
+        let body = scenario.add_body(&desc, cuboid, 0.0, 0.5);
+        let joint = BallConstraint::new(
+            BodyPartHandle(scenario.ground, 0),
+            BodyPartHandle(body, 0),
+            Point::from(Vector::y() * half_extent),
+            Point::origin(),
+        );
+        let joint = scenario.joint_constraints.insert(joint);
+
+        for _ in 0..10 {
+            scenario.step();
+        }
+
+        let collider_of = |scenario: &Scenario, body| {
+            scenario
+                .colliders
+                .iter()
+                .find(|(_, co)| co.body() == body)
+                .map(|(h, _)| h)
+                .unwrap()
+        };
+        let floor_collider = collider_of(&scenario, scenario.ground);
+        let body_collider = collider_of(&scenario, body);
+        assert!(
+            scenario
+                .geometrical_world
+                .contact_age(floor_collider, body_collider)
+                > 0
+        );
+
+        let _ = scenario.bodies.remove(body);
+        let removed = scenario.mechanical_world.maintain(
+            &mut scenario.geometrical_world,
+            &mut scenario.bodies,
+            &mut scenario.colliders,
+            &mut scenario.joint_constraints,
+        );
+
+        assert_eq!(removed, vec![joint]);
+        assert!(!scenario.joint_constraints.contains(joint));
+        assert!(scenario.colliders.get(body_collider).is_none());
+        assert_eq!(
+            scenario
+                .geometrical_world
+                .contact_age(floor_collider, body_collider),
+            0
+        );
+        assert_eq!(
+            scenario
+                .geometrical_world
+                .contact_pairs(&scenario.colliders, false)
+                .count(),
+            0
+        );
+
+        // The next step does not involve the removed body anymore.
+        scenario.step();
+    }
+}
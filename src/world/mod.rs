@@ -1,7 +1,18 @@
 //! The physics world.
 
-pub use self::geometrical_world::{DefaultGeometricalWorld, GeometricalWorld};
-pub use self::mechanical_world::{DefaultMechanicalWorld, MechanicalWorld};
+pub use self::debug_render::{DebugRenderBackend, DebugRenderFlags, DebugRenderObject};
+pub use self::geometrical_world::{
+    DefaultGeometricalWorld, GeometricalWorld, NarrowPhasePairFilter, PredictedImpact,
+};
+pub use self::mechanical_world::{
+    ActivationEvent, CCDEvent, DefaultMechanicalWorld, MechanicalWorld, PhysicsEvent, StepStats,
+    WakeReason,
+};
+pub use self::space_scaling::ScaleSpaceError;
+pub use self::step_hooks::StepHooks;
 
+mod debug_render;
 mod geometrical_world;
 mod mechanical_world;
+mod space_scaling;
+mod step_hooks;
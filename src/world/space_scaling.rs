@@ -0,0 +1,106 @@
+use na::RealField;
+#[cfg(feature = "dim2")]
+use ncollide::shape::ConvexPolygon;
+use ncollide::shape::{
+    Ball, Capsule, Compound, Cuboid, Plane, Polyline, Segment, Shape, ShapeHandle, Triangle,
+};
+#[cfg(feature = "dim3")]
+use ncollide::shape::{ConvexHull, TriMesh};
+
+use crate::math::{Isometry, Point};
+
+/// Error returned by `MechanicalWorld::scale_space` when an object cannot be scaled.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ScaleSpaceError<Handle, CollHandle, JointHandle> {
+    /// The body with this handle is neither a rigid body nor the ground.
+    UnsupportedBody(Handle),
+    /// The collider with this handle has a shape that cannot be scaled.
+    UnsupportedShape(CollHandle),
+    /// The joint constraint with this handle cannot be scaled.
+    UnsupportedJoint(JointHandle),
+}
+
+/// The isometry `pos` with a translation scaled by `factor`.
+pub(crate) fn scaled_isometry<N: RealField>(pos: &Isometry<N>, factor: N) -> Isometry<N> {
+    let mut result = *pos;
+    result.translation.vector *= factor;
+    result
+}
+
+/// A copy of `shape` scaled by `factor`, or `None` if this type of shape cannot be scaled.
+pub(crate) fn scaled_shape<N: RealField>(
+    shape: &ShapeHandle<N>,
+    factor: N,
+) -> Option<ShapeHandle<N>> {
+    let scaled = |pts: &[Point<N>]| pts.iter().map(|pt| pt * factor).collect::<Vec<_>>();
+
+    if shape.as_shape::<Plane<N>>().is_some() {
+        Some(shape.clone())
+    } else if let Some(s) = shape.as_shape::<Ball<N>>() {
+        Some(ShapeHandle::new(Ball::new(s.radius() * factor)))
+    } else if let Some(s) = shape.as_shape::<Cuboid<N>>() {
+        Some(ShapeHandle::new(Cuboid::new(s.half_extents() * factor)))
+    } else if let Some(s) = shape.as_shape::<Capsule<N>>() {
+        Some(ShapeHandle::new(Capsule::new(
+            s.half_height() * factor,
+            s.radius() * factor,
+        )))
+    } else if let Some(s) = shape.as_shape::<Segment<N>>() {
+        Some(ShapeHandle::new(Segment::new(
+            s.a() * factor,
+            s.b() * factor,
+        )))
+    } else if let Some(s) = shape.as_shape::<Triangle<N>>() {
+        Some(ShapeHandle::new(Triangle::new(
+            s.a() * factor,
+            s.b() * factor,
+            s.c() * factor,
+        )))
+    } else if let Some(s) = shape.as_shape::<Polyline<N>>() {
+        let indices = s.edges().iter().map(|e| e.indices).collect();
+        Some(ShapeHandle::new(Polyline::new(
+            scaled(s.points()),
+            Some(indices),
+        )))
+    } else if let Some(s) = shape.as_shape::<Compound<N>>() {
+        let mut shapes = Vec::with_capacity(s.shapes().len());
+
+        for (pos, sub_shape) in s.shapes() {
+            shapes.push((
+                scaled_isometry(pos, factor),
+                scaled_shape(sub_shape, factor)?,
+            ));
+        }
+
+        Some(ShapeHandle::new(Compound::new(shapes)))
+    } else {
+        scaled_polyhedral_shape(&**shape, factor)
+    }
+}
+
+#[cfg(feature = "dim2")]
+fn scaled_polyhedral_shape<N: RealField>(
+    shape: &dyn Shape<N>,
+    factor: N,
+) -> Option<ShapeHandle<N>> {
+    let s = shape.as_shape::<ConvexPolygon<N>>()?;
+    let points: Vec<_> = s.points().iter().map(|pt| pt * factor).collect();
+    ConvexPolygon::try_from_points(&points).map(ShapeHandle::new)
+}
+
+#[cfg(feature = "dim3")]
+fn scaled_polyhedral_shape<N: RealField>(
+    shape: &dyn Shape<N>,
+    factor: N,
+) -> Option<ShapeHandle<N>> {
+    if let Some(s) = shape.as_shape::<ConvexHull<N>>() {
+        let points: Vec<_> = s.points().iter().map(|pt| pt * factor).collect();
+        ConvexHull::try_from_points(&points).map(ShapeHandle::new)
+    } else if let Some(s) = shape.as_shape::<TriMesh<N>>() {
+        let points = s.points().iter().map(|pt| pt * factor).collect();
+        let indices = s.faces().iter().map(|f| f.indices).collect();
+        Some(ShapeHandle::new(TriMesh::new(points, indices, None)))
+    } else {
+        None
+    }
+}
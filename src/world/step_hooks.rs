@@ -0,0 +1,67 @@
+use na::RealField;
+
+use crate::detection::ColliderContactManifold;
+use crate::object::{BodyHandle, BodySet, ColliderHandle, ColliderSet};
+use crate::world::GeometricalWorld;
+
+/// User-defined callbacks executed at well-defined points of `MechanicalWorld::step_with_hooks`.
+///
+/// During one call to `step_with_hooks`, the hooks are executed in the following order:
+///
+/// 1. `pre_step`, before the insertion and removal of objects are handled and before the force
+///    generators are applied.
+/// 2. `pre_solve`, after the contacts have been computed and before the constraints solver is run.
+/// 3. `post_step`, once the bodies have been integrated and the colliders updated with their new positions.
+///
+/// The hooks are not executed again for the substeps performed by the continuous collision detection.
+/// All the methods have an empty default implementation.
+pub trait StepHooks<N, Handle, CollHandle, Colliders>
+where
+    N: RealField,
+    Handle: BodyHandle,
+    CollHandle: ColliderHandle,
+    Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+{
+    /// Executed at the beginning of the step.
+    ///
+    /// Bodies and colliders may be added, removed, or modified here. This is typically where
+    /// custom controllers apply forces or set velocities.
+    fn pre_step(
+        &mut self,
+        _gworld: &mut GeometricalWorld<N, Handle, CollHandle>,
+        _bodies: &mut dyn BodySet<N, Handle = Handle>,
+        _colliders: &mut Colliders,
+    ) {
+    }
+
+    /// Executed once the contacts have been computed, right before they are given to the solver.
+    ///
+    /// The contact manifolds removed from `contact_manifolds` are ignored by the solver for this
+    /// step, e.g., to implement one-way platforms.
+    fn pre_solve<'a>(
+        &mut self,
+        _gworld: &'a GeometricalWorld<N, Handle, CollHandle>,
+        _bodies: &mut dyn BodySet<N, Handle = Handle>,
+        _colliders: &'a Colliders,
+        _contact_manifolds: &mut Vec<ColliderContactManifold<'a, N, Handle, CollHandle>>,
+    ) {
+    }
+
+    /// Executed at the end of the step.
+    fn post_step(
+        &mut self,
+        _gworld: &mut GeometricalWorld<N, Handle, CollHandle>,
+        _bodies: &mut dyn BodySet<N, Handle = Handle>,
+        _colliders: &mut Colliders,
+    ) {
+    }
+}
+
+impl<N, Handle, CollHandle, Colliders> StepHooks<N, Handle, CollHandle, Colliders> for ()
+where
+    N: RealField,
+    Handle: BodyHandle,
+    CollHandle: ColliderHandle,
+    Colliders: ColliderSet<N, Handle, Handle = CollHandle>,
+{
+}
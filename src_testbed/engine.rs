@@ -39,7 +39,7 @@ use nphysics::object::{
     ColliderAnchor, DefaultBodyHandle, DefaultBodyPartHandle, DefaultColliderHandle,
     DefaultColliderSet,
 };
-use nphysics::world::DefaultGeometricalWorld;
+use nphysics::world::{DefaultGeometricalWorld, DefaultMechanicalWorld};
 use rand::{rngs::StdRng, Rng, SeedableRng};
 #[cfg(feature = "fluids")]
 use salva::object::{Boundary, BoundaryHandle, Fluid, FluidHandle};
@@ -266,6 +266,37 @@ impl GraphicsManager {
         }
     }
 
+    pub fn color_islands<N: RealField>(&mut self, mechanical_world: &DefaultMechanicalWorld<N>) {
+        for (handle, ns) in self.b2sn.iter_mut() {
+            let color = match mechanical_world.island_id(*handle) {
+                Some(id) => Self::gen_color(&mut StdRng::seed_from_u64(id as u64)),
+                None => self
+                    .b2color
+                    .get(handle)
+                    .cloned()
+                    .unwrap_or(self.ground_color),
+            };
+
+            for n in ns.iter_mut() {
+                n.set_color(color)
+            }
+        }
+    }
+
+    pub fn reset_body_colors(&mut self) {
+        for (handle, ns) in self.b2sn.iter_mut() {
+            let color = self
+                .b2color
+                .get(handle)
+                .cloned()
+                .unwrap_or(self.ground_color);
+
+            for n in ns.iter_mut() {
+                n.set_color(color)
+            }
+        }
+    }
+
     pub fn set_body_wireframe(&mut self, b: DefaultBodyHandle, enabled: bool) {
         self.b2wireframe.insert(b, enabled);
 
@@ -28,5 +28,7 @@ pub use crate::testbed::Testbed;
 mod box2d_world;
 mod engine;
 pub mod objects;
+mod plot;
+pub mod recording;
 mod testbed;
 mod ui;
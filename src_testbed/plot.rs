@@ -0,0 +1,66 @@
+use std::collections::VecDeque;
+
+use na::RealField;
+use nphysics::object::DefaultBodySet;
+use nphysics::world::DefaultMechanicalWorld;
+
+/// Number of samples displayed by each plot.
+pub const PLOT_WINDOW: usize = 300;
+
+type Probe<N> = Box<dyn FnMut(&DefaultMechanicalWorld<N>, &DefaultBodySet<N>) -> f32>;
+
+/// A scalar quantity sampled at each step and displayed over a rolling time window.
+pub struct Plot<N: RealField> {
+    name: String,
+    probe: Probe<N>,
+    values: VecDeque<f32>,
+}
+
+impl<N: RealField> Plot<N> {
+    pub fn new(
+        name: &str,
+        probe: impl FnMut(&DefaultMechanicalWorld<N>, &DefaultBodySet<N>) -> f32 + 'static,
+    ) -> Self {
+        Plot {
+            name: name.to_string(),
+            probe: Box::new(probe),
+            values: VecDeque::with_capacity(PLOT_WINDOW),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The samples of this plot, from the oldest to the most recent.
+    pub fn values(&self) -> &VecDeque<f32> {
+        &self.values
+    }
+
+    /// The minimum and maximum values of the samples of this plot.
+    pub fn bounds(&self) -> (f32, f32) {
+        let mut min = std::f32::MAX;
+        let mut max = -std::f32::MAX;
+
+        for v in &self.values {
+            min = min.min(*v);
+            max = max.max(*v);
+        }
+
+        if min > max {
+            (0.0, 1.0)
+        } else if max - min < 1.0e-6 {
+            (min - 0.5, max + 0.5)
+        } else {
+            (min, max)
+        }
+    }
+
+    pub fn sample(&mut self, world: &DefaultMechanicalWorld<N>, bodies: &DefaultBodySet<N>) {
+        if self.values.len() == PLOT_WINDOW {
+            let _ = self.values.pop_front();
+        }
+
+        self.values.push_back((self.probe)(world, bodies));
+    }
+}
@@ -0,0 +1,203 @@
+//! Compact binary recording and replay of the collider positions of a simulation.
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+
+use na::{self, RealField};
+#[cfg(feature = "dim3")]
+use na::{Quaternion, UnitQuaternion, Vector3};
+#[cfg(feature = "dim2")]
+use na::Vector2;
+use nphysics::math::{Isometry, DIM};
+use nphysics::object::{DefaultColliderHandle, DefaultColliderSet};
+
+const MAGIC: &[u8; 4] = b"NPHR";
+const VERSION: u32 = 1;
+
+/// Records the collider positions of each simulation step into a file.
+pub struct Recorder {
+    writer: BufWriter<File>,
+}
+
+impl Recorder {
+    /// Creates the recording file and writes its header.
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&[DIM as u8])?;
+
+        Ok(Recorder { writer })
+    }
+
+    /// Appends the current position of every collider as a new frame.
+    pub fn record_frame<N: RealField>(
+        &mut self,
+        colliders: &DefaultColliderSet<N>,
+    ) -> io::Result<()> {
+        let ncolliders = colliders.iter().count() as u32;
+        self.writer.write_all(&ncolliders.to_le_bytes())?;
+
+        for (handle, collider) in colliders.iter() {
+            let (index, generation) = handle.into_raw_parts();
+            let pos: Isometry<f64> = na::convert_unchecked(*collider.position());
+
+            self.writer.write_all(&(index as u64).to_le_bytes())?;
+            self.writer.write_all(&generation.to_le_bytes())?;
+
+            for coord in isometry_coords(&pos).iter() {
+                self.writer.write_all(&coord.to_le_bytes())?;
+            }
+        }
+
+        self.writer.flush()
+    }
+}
+
+/// A recording loaded from a file, replayed frame by frame.
+pub struct Replay {
+    frames: Vec<Vec<(DefaultColliderHandle, Isometry<f64>)>>,
+    curr_frame: usize,
+}
+
+impl Replay {
+    /// Loads a recording generated by a `Recorder`.
+    ///
+    /// Fails with `ErrorKind::InvalidData` if the file is not a recording, or if it has
+    /// been generated by an incompatible version of the testbed.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+
+        if &magic != MAGIC {
+            return Err(io::Error::new(ErrorKind::InvalidData, "Not a testbed recording."));
+        }
+
+        let mut version = [0u8; 4];
+        reader.read_exact(&mut version)?;
+
+        if u32::from_le_bytes(version) != VERSION {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "Unsupported testbed recording version.",
+            ));
+        }
+
+        let mut dim = [0u8; 1];
+        reader.read_exact(&mut dim)?;
+
+        if dim[0] as usize != DIM {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "The recording was made with a testbed of a different dimension.",
+            ));
+        }
+
+        let mut frames = Vec::new();
+        let mut u32_buf = [0u8; 4];
+
+        loop {
+            match reader.read_exact(&mut u32_buf) {
+                Ok(()) => {}
+                Err(ref e) if e.kind() == ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e),
+            }
+
+            let ncolliders = u32::from_le_bytes(u32_buf) as usize;
+            let mut frame = Vec::with_capacity(ncolliders);
+
+            for _ in 0..ncolliders {
+                let index = read_u64(&mut reader)? as usize;
+                let generation = read_u64(&mut reader)?;
+                let mut coords = [0.0; NUM_ISOMETRY_COORDS];
+
+                for coord in coords.iter_mut() {
+                    *coord = f64::from_bits(read_u64(&mut reader)?);
+                }
+
+                let handle = DefaultColliderHandle::from_raw_parts(index, generation);
+                frame.push((handle, isometry_from_coords(&coords)));
+            }
+
+            frames.push(frame);
+        }
+
+        Ok(Replay {
+            frames,
+            curr_frame: 0,
+        })
+    }
+
+    /// The total number of frames of this recording.
+    pub fn num_frames(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// The index of the next frame to be replayed.
+    pub fn curr_frame(&self) -> usize {
+        self.curr_frame
+    }
+
+    /// Sets the index of the next frame to be replayed.
+    pub fn seek(&mut self, frame: usize) {
+        self.curr_frame = frame.min(self.frames.len());
+    }
+
+    /// Moves the colliders to their positions at the current frame, and advances to the next frame.
+    ///
+    /// Returns `false` if the end of the recording has been reached.
+    pub fn apply_frame<N: RealField>(&mut self, colliders: &mut DefaultColliderSet<N>) -> bool {
+        if let Some(frame) = self.frames.get(self.curr_frame) {
+            for (handle, pos) in frame {
+                if let Some(collider) = colliders.get_mut(*handle) {
+                    collider.set_position(na::convert(*pos))
+                }
+            }
+
+            self.curr_frame += 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+#[cfg(feature = "dim2")]
+const NUM_ISOMETRY_COORDS: usize = 3;
+#[cfg(feature = "dim3")]
+const NUM_ISOMETRY_COORDS: usize = 7;
+
+#[cfg(feature = "dim2")]
+fn isometry_coords(pos: &Isometry<f64>) -> [f64; NUM_ISOMETRY_COORDS] {
+    let t = &pos.translation.vector;
+    [t.x, t.y, pos.rotation.angle()]
+}
+
+#[cfg(feature = "dim3")]
+fn isometry_coords(pos: &Isometry<f64>) -> [f64; NUM_ISOMETRY_COORDS] {
+    let t = &pos.translation.vector;
+    let q = &pos.rotation;
+    [t.x, t.y, t.z, q.w, q.i, q.j, q.k]
+}
+
+#[cfg(feature = "dim2")]
+fn isometry_from_coords(coords: &[f64; NUM_ISOMETRY_COORDS]) -> Isometry<f64> {
+    Isometry::new(Vector2::new(coords[0], coords[1]), coords[2])
+}
+
+#[cfg(feature = "dim3")]
+fn isometry_from_coords(coords: &[f64; NUM_ISOMETRY_COORDS]) -> Isometry<f64> {
+    let translation = Vector3::new(coords[0], coords[1], coords[2]);
+    let rotation = UnitQuaternion::new_unchecked(Quaternion::new(
+        coords[3], coords[4], coords[5], coords[6],
+    ));
+    Isometry::from_parts(translation.into(), rotation)
+}
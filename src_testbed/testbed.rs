@@ -2,6 +2,7 @@
 use num::Bounded;
 use std::collections::HashMap;
 use std::env;
+use std::io;
 use std::mem;
 use std::path::Path;
 use std::rc::Rc;
@@ -9,6 +10,8 @@ use std::rc::Rc;
 use crate::engine::{GraphicsManager, GraphicsWindow};
 #[cfg(feature = "fluids")]
 use crate::objects::FluidRenderingMode;
+use crate::plot::Plot;
+use crate::recording::{Recorder, Replay};
 use crate::ui::TestbedUi;
 use kiss3d::camera::Camera;
 use kiss3d::event::Event;
@@ -89,6 +92,7 @@ bitflags! {
         const WIREFRAME = 1 << 8;
         const STATISTICS = 1 << 9;
         const PROFILE = 1 << 10;
+        const ISLANDS = 1 << 11;
     }
 }
 
@@ -141,6 +145,9 @@ pub struct Testbed<N: RealField = f32> {
     callbacks: Callbacks<N>,
     #[cfg(feature = "fluids")]
     callbacks_fluids: CallbacksFluids<N>,
+    callbacks_keys: KeyCallbacks<N>,
+    // The keys currently held down.
+    held_keys: Vec<Key>,
     time: N,
     hide_counters: bool,
     persistant_contacts: HashMap<ContactId, bool>,
@@ -149,6 +156,9 @@ pub struct Testbed<N: RealField = f32> {
     ground_handle: Option<DefaultBodyHandle>,
     ui: TestbedUi,
     state: TestbedState<N>,
+    recorder: Option<Recorder>,
+    replay: Option<Replay>,
+    plots: Vec<Plot<N>>,
     #[cfg(feature = "box2d-backend")]
     box2d: Option<Box2dWorld>,
 }
@@ -166,6 +176,19 @@ type Callbacks<N> = Vec<
     >,
 >;
 
+type KeyCallbacks<N> = Vec<
+    Box<
+        dyn FnMut(
+            &mut DefaultMechanicalWorld<N>,
+            &mut DefaultGeometricalWorld<N>,
+            &mut DefaultBodySet<N>,
+            &mut DefaultColliderSet<N>,
+            &[Key],
+            N,
+        ),
+    >,
+>;
+
 #[cfg(feature = "fluids")]
 type CallbacksFluids<N> = Vec<
     Box<
@@ -239,6 +262,8 @@ impl<N: RealField> Testbed<N> {
             callbacks: Vec::new(),
             #[cfg(feature = "fluids")]
             callbacks_fluids: Vec::new(),
+            callbacks_keys: Vec::new(),
+            held_keys: Vec::new(),
             window: Some(window),
             graphics,
             nsteps: 1,
@@ -251,6 +276,9 @@ impl<N: RealField> Testbed<N> {
             ground_handle: None,
             ui,
             state,
+            recorder: None,
+            replay: None,
+            plots: Vec::new(),
             #[cfg(feature = "box2d-backend")]
             box2d: None,
         }
@@ -290,6 +318,30 @@ impl<N: RealField> Testbed<N> {
         self.nsteps = nsteps
     }
 
+    /// Pauses or resumes the simulation.
+    ///
+    /// Pausing before calling `run` makes the testbed display the initial state of the scene.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.state.running = if paused {
+            RunMode::Stop
+        } else {
+            RunMode::Running
+        };
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.state.running == RunMode::Stop
+    }
+
+    /// Executes a single simulation step at the next frame, and pauses afterwards.
+    pub fn step_once(&mut self) {
+        self.state.running = RunMode::Step;
+    }
+
+    pub fn set_timestep(&mut self, dt: N) {
+        self.mechanical_world.set_timestep(dt);
+    }
+
     pub fn set_ground_handle(&mut self, handle: Option<DefaultBodyHandle>) {
         self.ground_handle = handle;
         self.graphics.set_ground_handle(handle);
@@ -347,6 +399,30 @@ impl<N: RealField> Testbed<N> {
         }
     }
 
+    /// Starts recording the collider positions at each step into the file at `path`.
+    pub fn start_recording(&mut self, path: &Path) -> io::Result<()> {
+        self.recorder = Some(Recorder::create(path)?);
+        Ok(())
+    }
+
+    pub fn stop_recording(&mut self) {
+        self.recorder = None;
+    }
+
+    /// Replays the recording at `path` instead of running the simulation.
+    pub fn load_replay(&mut self, path: &Path) -> io::Result<()> {
+        self.replay = Some(Replay::load(path)?);
+        Ok(())
+    }
+
+    pub fn stop_replay(&mut self) {
+        self.replay = None;
+    }
+
+    pub fn replay_mut(&mut self) -> Option<&mut Replay> {
+        self.replay.as_mut()
+    }
+
     pub fn mechanical_world(&self) -> &DefaultMechanicalWorld<N> {
         &self.mechanical_world
     }
@@ -458,6 +534,8 @@ impl<N: RealField> Testbed<N> {
         self.callbacks.clear();
         #[cfg(feature = "fluids")]
         self.callbacks_fluids.clear();
+        self.callbacks_keys.clear();
+        self.plots.clear();
         self.persistant_contacts.clear();
         self.ground_handle = None;
         self.state.grabbed_object = None;
@@ -482,6 +560,35 @@ impl<N: RealField> Testbed<N> {
         self.callbacks.push(Box::new(callback));
     }
 
+    /// Adds a callback called after each step with the keys currently held down.
+    ///
+    /// While such a callback is registered, the `S` key no longer switches the testbed to
+    /// step-by-step mode so it can be used to control the example, e.g., with WASD.
+    pub fn add_callback_with_keys<
+        F: FnMut(
+                &mut DefaultMechanicalWorld<N>,
+                &mut DefaultGeometricalWorld<N>,
+                &mut DefaultBodySet<N>,
+                &mut DefaultColliderSet<N>,
+                &[Key],
+                N,
+            ) + 'static,
+    >(
+        &mut self,
+        callback: F,
+    ) {
+        self.callbacks_keys.push(Box::new(callback));
+    }
+
+    /// Displays a graph of the value returned by `probe` after each step.
+    pub fn add_plot(
+        &mut self,
+        name: &str,
+        probe: impl FnMut(&DefaultMechanicalWorld<N>, &DefaultBodySet<N>) -> f32 + 'static,
+    ) {
+        self.plots.push(Plot::new(name, probe));
+    }
+
     #[cfg(feature = "fluids")]
     pub fn add_callback_with_fluids<
         F: FnMut(
@@ -529,7 +636,9 @@ impl<N: RealField> Testbed<N> {
                     self.state.running = RunMode::Stop;
                 }
             }
-            WindowEvent::Key(Key::S, Action::Release, _) => self.state.running = RunMode::Step,
+            WindowEvent::Key(Key::S, Action::Release, _) if self.callbacks_keys.is_empty() => {
+                self.state.running = RunMode::Step
+            }
             WindowEvent::Key(Key::R, Action::Release, _) => self
                 .state
                 .action_flags
@@ -925,8 +1034,12 @@ impl<N: RealField> State for Testbed<N> {
     }
 
     fn step(&mut self, window: &mut Window) {
-        self.ui
-            .update(window, &mut self.mechanical_world, &mut self.state);
+        self.ui.update(
+            window,
+            &mut self.mechanical_world,
+            &mut self.state,
+            &self.plots,
+        );
 
         // Handle UI actions.
         {
@@ -1062,6 +1175,12 @@ impl<N: RealField> State for Testbed<N> {
                 }
             }
 
+            if self.state.prev_flags.contains(TestbedStateFlags::ISLANDS)
+                && !self.state.flags.contains(TestbedStateFlags::ISLANDS)
+            {
+                self.graphics.reset_body_colors()
+            }
+
             if self
                 .state
                 .prev_flags
@@ -1078,6 +1197,17 @@ impl<N: RealField> State for Testbed<N> {
         self.state.prev_flags = self.state.flags;
 
         for event in window.events().iter() {
+            if let WindowEvent::Key(key, action, _) = event.value {
+                match action {
+                    Action::Press => {
+                        if !self.held_keys.contains(&key) {
+                            self.held_keys.push(key)
+                        }
+                    }
+                    Action::Release => self.held_keys.retain(|k| *k != key),
+                }
+            }
+
             let event = self.handle_common_event(event);
             self.handle_special_event(window, event);
         }
@@ -1087,6 +1217,12 @@ impl<N: RealField> State for Testbed<N> {
 
         if self.state.running != RunMode::Stop {
             for _ in 0..self.nsteps {
+                if let Some(replay) = &mut self.replay {
+                    // Move the colliders instead of simulating.
+                    let _ = replay.apply_frame(&mut self.colliders);
+                    continue;
+                }
+
                 if self.state.selected_backend == NPHYSICS_BACKEND {
                     self.mechanical_world.step(
                         &mut self.geometrical_world,
@@ -1140,6 +1276,17 @@ impl<N: RealField> State for Testbed<N> {
                     )
                 }
 
+                for f in &mut self.callbacks_keys {
+                    f(
+                        &mut self.mechanical_world,
+                        &mut self.geometrical_world,
+                        &mut self.bodies,
+                        &mut self.colliders,
+                        &self.held_keys,
+                        self.time,
+                    )
+                }
+
                 #[cfg(feature = "fluids")]
                 {
                     if let Some(fluid_state) = &mut self.fluids {
@@ -1158,6 +1305,24 @@ impl<N: RealField> State for Testbed<N> {
                     }
                 }
 
+                for plot in &mut self.plots {
+                    plot.sample(&self.mechanical_world, &self.bodies);
+                }
+
+                let recording_failed = self
+                    .recorder
+                    .as_mut()
+                    .map(|r| r.record_frame(&self.colliders).is_err())
+                    .unwrap_or(false);
+
+                if recording_failed {
+                    #[cfg(not(feature = "log"))]
+                    println!("Failed to write the recording file, recording stopped.");
+                    #[cfg(feature = "log")]
+                    error!("Failed to write the recording file, recording stopped.");
+                    self.recorder = None;
+                }
+
                 if !self.hide_counters {
                     #[cfg(not(feature = "log"))]
                     println!("{}", self.mechanical_world.counters);
@@ -1168,6 +1333,10 @@ impl<N: RealField> State for Testbed<N> {
             }
         }
 
+        if self.state.flags.contains(TestbedStateFlags::ISLANDS) {
+            self.graphics.color_islands(&self.mechanical_world);
+        }
+
         self.graphics
             .draw(&self.geometrical_world, &self.colliders, window);
 
@@ -1,6 +1,7 @@
 use kiss3d::conrod::{self, Borderable, Colorable, Labelable, Positionable, Sizeable, Widget};
 use kiss3d::window::Window;
 
+use crate::plot::{Plot, PLOT_WINDOW};
 use crate::testbed::{RunMode, TestbedActionFlags, TestbedState, TestbedStateFlags};
 use na::RealField;
 use nphysics::world::DefaultMechanicalWorld;
@@ -12,6 +13,8 @@ const VSPACE: f64 = 4.0;
 const TITLE_VSPACE: f64 = 4.0;
 const LEFT_MARGIN: f64 = 10.0;
 const ALPHA: f32 = 0.9;
+const PLOT_W: f64 = 300.0;
+const PLOT_H: f64 = 100.0;
 
 widget_ids! {
     pub struct ConrodIds {
@@ -42,6 +45,7 @@ widget_ids! {
         toggle_shapes,
         toggle_joints,
         toggle_aabbs,
+        toggle_islands,
         toggle_contact_points,
         toggle_contact_normals,
         toggle_center_of_masses,
@@ -56,6 +60,9 @@ widget_ids! {
 
 pub struct TestbedUi {
     ids: ConrodIds,
+    plot_backgrounds: conrod::widget::id::List,
+    plot_paths: conrod::widget::id::List,
+    plot_labels: conrod::widget::id::List,
 }
 
 impl TestbedUi {
@@ -84,6 +91,9 @@ impl TestbedUi {
 
         Self {
             ids: ConrodIds::new(ui.widget_id_generator()),
+            plot_backgrounds: conrod::widget::id::List::new(),
+            plot_paths: conrod::widget::id::List::new(),
+            plot_labels: conrod::widget::id::List::new(),
         }
     }
 
@@ -92,6 +102,7 @@ impl TestbedUi {
         window: &mut Window,
         world: &mut DefaultMechanicalWorld<N>,
         state: &mut TestbedState<N>,
+        plots: &[Plot<N>],
     ) {
         let ui_root = window.conrod_ui().window;
         let mut ui = window.conrod_ui_mut().set_widgets();
@@ -319,6 +330,11 @@ impl TestbedUi {
             //            ("Shapes", self.ids.toggle_shapes, TestbedStateFlags::SHAPES),
             //            ("Joints", self.ids.toggle_joints, TestbedStateFlags::JOINTS),
             ("AABBs", self.ids.toggle_aabbs, TestbedStateFlags::AABBS),
+            (
+                "Islands",
+                self.ids.toggle_islands,
+                TestbedStateFlags::ISLANDS,
+            ),
             (
                 "Contacts",
                 self.ids.toggle_contact_points,
@@ -399,6 +415,64 @@ impl TestbedUi {
         {
             state.running = RunMode::Quit
         }
+
+        self.update_plots(ui_root, plots, &mut ui);
+    }
+
+    fn update_plots<N: RealField>(
+        &mut self,
+        ui_root: conrod::widget::Id,
+        plots: &[Plot<N>],
+        ui: &mut conrod::UiCell,
+    ) {
+        self.plot_backgrounds
+            .resize(plots.len(), &mut ui.widget_id_generator());
+        self.plot_paths
+            .resize(plots.len(), &mut ui.widget_id_generator());
+        self.plot_labels
+            .resize(plots.len(), &mut ui.widget_id_generator());
+
+        for (i, plot) in plots.iter().enumerate() {
+            let background = self.plot_backgrounds[i];
+            let (min, max) = plot.bounds();
+            let last = plot.values().back().cloned().unwrap_or(0.0);
+
+            conrod::widget::Rectangle::fill([PLOT_W, PLOT_H])
+                .bottom_left_with_margins_on(ui_root, 10.0 + i as f64 * (PLOT_H + 10.0), 10.0)
+                .color(conrod::color::DARK_CHARCOAL.alpha(ALPHA))
+                .set(background, ui);
+
+            conrod::widget::Text::new(&format!(
+                "{}: {:.3} [{:.3}, {:.3}]",
+                plot.name(),
+                last,
+                min,
+                max
+            ))
+            .top_left_with_margins_on(background, 4.0, 4.0)
+            .color(conrod::color::WHITE)
+            .set(self.plot_labels[i], ui);
+
+            if plot.values().len() > 1 {
+                // Autoscale the samples so they fill the whole plot area.
+                let points: Vec<_> = plot
+                    .values()
+                    .iter()
+                    .enumerate()
+                    .map(|(k, v)| {
+                        let x = (k as f64 / (PLOT_WINDOW - 1) as f64 - 0.5) * PLOT_W;
+                        let y = ((*v - min) / (max - min) - 0.5) as f64 * PLOT_H;
+                        [x, y]
+                    })
+                    .collect();
+
+                conrod::widget::PointPath::centred(points)
+                    .middle_of(background)
+                    .wh_of(background)
+                    .color(conrod::color::LIGHT_GREEN)
+                    .set(self.plot_paths[i], ui);
+            }
+        }
     }
 }
 